@@ -16,6 +16,9 @@ pub struct Cli {
     /// Display logs and log at the trace level.
     #[arg(short, long, action)]
     pub verbose: bool,
+    /// Format of log lines, overriding the configured format.
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    pub log_format: Option<LogFormat>,
     /// The command mode for slipstream.
     #[command(subcommand)]
     pub command: CommandMode,
@@ -44,24 +47,7 @@ impl Cli {
                 );
             }
         }
-        // Read file.
-        let config_data = match std::fs::read_to_string(&config_path) {
-            Ok(data) => data,
-            Err(e) => {
-                bail!(
-                    "Unable to read data from config file {:?}: {}.",
-                    config_path,
-                    e
-                );
-            }
-        };
-        // Parse.
-        match toml::from_str(&config_data) {
-            Ok(config) => Ok(config),
-            Err(e) => {
-                bail!("Configuration file is not valid: {}.", e);
-            }
-        }
+        Config::from_path(&config_path)
     }
 
     /// Get config path.
@@ -93,11 +79,26 @@ pub enum CommandMode {
         #[arg(short, long, value_name = "PORT")]
         port: Option<u16>,
         /// The address to listen on (default="0.0.0.0").
-        #[arg(short, long)]
-        address: Option<String>,
+        #[arg(short, long, visible_alias = "bind")]
+        address: Option<std::net::IpAddr>,
     },
     /// Read feeds in a local tui.
     Read,
+    /// Check that a bundled feed can be parsed, stored, and served.
+    SelfTest,
+    /// Fetch and parse a feed once, showing which entries would be kept.
+    DebugFeed {
+        /// Name of a configured feed, or a feed url.
+        feed: String,
+        /// Number of entries shown.
+        #[arg(short = 'n', long, default_value_t = 5)]
+        entries: usize,
+    },
+    /// List configured feeds.
+    Feeds {
+        #[command(subcommand)]
+        feeds_mode: FeedsMode,
+    },
     /// Read feeds in a local tui.
     Config {
         #[command(subcommand)]
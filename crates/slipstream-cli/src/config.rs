@@ -7,6 +7,11 @@ use slipfeed::Tag;
 const DEFAULT_FEED_STEP: u8 = 3;
 const DEFAULT_FEED_AGG_STEP: u8 = 5;
 const DEFAULT_FEED_TAG_STEP: u8 = 7;
+const DEFAULT_USER_AGENT: &str =
+    concat!("slipstream/", env!("CARGO_PKG_VERSION"));
+const USER_AGENT_HEADER: &str = "user-agent";
+/// Keys of secrets, which are never shown in the exported config.
+const SECRET_KEYS: &[&str] = &["auth_token", "token"];
 
 /// Configuration for slipstream.
 /// This is parsed from the toml slipstream configuration file.
@@ -22,8 +27,14 @@ pub struct Config {
     /// Timezone (default UTC).
     #[serde(default, alias = "time-zone", alias = "tz")]
     pub timezone: TimeZone,
-    /// Log file.
+    /// Format of displayed dates (default "%Y-%m-%d %H:%M").
+    #[serde(default, alias = "date-format")]
+    pub date_format: Option<DateFormat>,
+    /// Log file (see `logging.file`).
     pub log: Option<String>,
+    /// Logging configuration.
+    #[serde(default)]
+    pub logging: LoggingConfig,
     /// Maximum entry storage size.
     pub storage: Option<u16>,
     /// Database cache file.
@@ -39,6 +50,13 @@ pub struct Config {
     // Read configuration.
     #[serde(default)]
     pub read: ReadConfig,
+    /// Notification configuration.
+    #[serde(default)]
+    pub notify: NotifyConfig,
+    /// Strings of the config file that had environment variables
+    /// substituted, as their source and value.
+    #[serde(skip)]
+    pub substituted: Vec<(String, String)>,
 }
 
 impl Default for Config {
@@ -47,25 +65,91 @@ impl Default for Config {
             freq: None,
             workers: None,
             timezone: TimeZone::default(),
+            date_format: None,
             feeds: None,
             storage: None,
             database: None,
             global: GlobalConfig::default(),
             log: None,
+            logging: LoggingConfig::default(),
             serve: ServeConfig::default(),
             read: ReadConfig::default(),
+            notify: NotifyConfig::default(),
+            substituted: Vec::new(),
         }
     }
 }
 
 impl Config {
+    /// Format a date for display, in the configured timezone and format.
+    pub fn format_date(&self, dt: &slipfeed::DateTime) -> String {
+        match &self.date_format {
+            Some(format) => self.timezone.format_with(dt, &format.0),
+            None => self.timezone.format(dt),
+        }
+    }
+
+    /// Read and validate a configuration file.
+    pub fn from_path(config_path: &std::path::Path) -> Result<Self> {
+        // Read file.
+        let config_data = match std::fs::read_to_string(config_path) {
+            Ok(data) => data,
+            Err(e) => {
+                bail!(
+                    "Unable to read data from config file {:?}: {}.",
+                    config_path,
+                    e
+                );
+            }
+        };
+        let (config_data, substituted) =
+            match substitute_env_strings(&config_data, |name| {
+                std::env::var(name).ok()
+            }) {
+                Ok(substituted) => substituted,
+                Err(e) => bail!("{e} in config file {:?}.", config_path),
+            };
+        // Parse.
+        match toml::from_str::<Config>(&config_data) {
+            Ok(mut config) => {
+                config.substituted = substituted;
+                if let Err(e) = config.validate() {
+                    bail!("Configuration file is not valid: {}", e);
+                }
+                Ok(config)
+            }
+            Err(e) => {
+                bail!("Configuration file is not valid: {}.", e);
+            }
+        }
+    }
+
+    /// Export the config as toml that is safe to share. Secrets are removed,
+    /// and strings with substituted environment variables are shown as they
+    /// were written, with `${VAR}`.
+    pub fn export_public(&self) -> std::result::Result<String, String> {
+        let mut table =
+            toml::Table::try_from(self).map_err(|e| e.to_string())?;
+        redact_table(&mut table, &self.substituted);
+        toml::to_string_pretty(&table).map_err(|e| e.to_string())
+    }
+
     /// Create a slipstream updater from the parsed configuration.
     pub async fn updater(&self) -> Result<Updater> {
-        let entry_db = Database::new(match &self.database {
+        let mut entry_db = Database::new(match &self.database {
             Some(db) => db.as_str(),
             None => ":memory:",
         })
         .await?;
+        entry_db.set_dedup_by_link(self.global.dedup_by_link());
+        self.updater_with_database(Arc::new(entry_db)).await
+    }
+
+    /// Create a slipstream updater that stores entries in an open database.
+    pub async fn updater_with_database(
+        &self,
+        entry_db: Arc<Database>,
+    ) -> Result<Updater> {
         let mut updater = Updater::default();
         updater.updater = Arc::new(RwLock::new({
             let mut updater = slipfeed::Updater::new(
@@ -78,114 +162,36 @@ impl Config {
             if let Some(workers) = self.workers {
                 updater.set_workers(workers);
             }
+            updater.set_feed_freq(Some(FeedOptions::default_freq()));
+            updater.set_retries(
+                self.global.fetch_retries(),
+                self.global.retry_delay(),
+            );
             updater
         }));
-        updater.entry_db = Some(Arc::new(entry_db));
+        updater.entry_db = Some(entry_db);
 
         if let Some(feeds) = &self.feeds {
-            // Add raw feeds.
-            for (name, feed_def) in feeds {
-                let mut attr = slipfeed::FeedAttributes::new();
-                attr.display_name = Arc::new(name.clone());
-
-                // Build options from global, overriding with feed-specific options.
-                let mut options = self.global.limits.clone();
-                options.merge(feed_def.options());
-
-                attr.freq = Some(options.freq_or_default());
-                attr.timeout = options.oldest();
-                attr.headers = options.headers().clone();
-                attr.keep_empty = options.keep_empty();
-                attr.apply_tags = options.apply_tags();
-                feed_def
-                    .tags()
-                    .clone()
-                    .unwrap_or_else(|| Vec::new())
-                    .iter()
-                    .for_each(|tag| attr.add_tag(tag.clone().into()));
-                feed_def
-                    .filters()
-                    .get_filters()
-                    .iter()
-                    .for_each(|f| attr.add_filter(f.clone()));
-
-                match feed_def.feed() {
-                    RawFeed::Raw { url } => {
-                        attr.step = options.step(DEFAULT_FEED_STEP);
-                        let feed = StandardFeed::new(url);
-                        let mut inner_updater = updater.updater.write().await;
-                        let id = inner_updater.add_feed(feed, attr);
-                        updater.feeds.insert(name.clone(), id);
-                        updater.feeds_ids.insert(id, name.clone());
-                        tracing::debug!("Added standard feed {}.", name);
-                    }
-                    RawFeed::Aggregate { .. } => {
-                        attr.step = options.step(DEFAULT_FEED_AGG_STEP);
-                        let feed = AggregateFeed::new();
-                        let mut inner_updater = updater.updater.write().await;
-                        let id = inner_updater.add_feed(feed, attr);
-                        updater.feeds.insert(name.clone(), id);
-                        updater.feeds_ids.insert(id, name.clone());
-                        tracing::debug!("Added aggregate feed {}.", name);
-                    }
-                    RawFeed::AggregateTag {
-                        tag_allowlist,
-                        tag_blocklist,
-                    } => {
-                        attr.step = options.step(DEFAULT_FEED_TAG_STEP);
-                        let mut feed = AggregateTagFeed::new();
-                        feed.allowlist = tag_allowlist
-                            .into_iter()
-                            .map(|t| slipfeed::Tag::from(t.as_str()))
-                            .collect();
-                        feed.blocklist = tag_blocklist
-                            .into_iter()
-                            .map(|t| slipfeed::Tag::from(t.as_str()))
-                            .collect();
-                        let mut inner_updater = updater.updater.write().await;
-                        let id = inner_updater.add_feed(feed, attr);
-                        updater.feeds.insert(name.clone(), id);
-                        updater.feeds_ids.insert(id, name.clone());
-                        tracing::debug!("Added aggregate tag feed {}.", name);
-                    }
-                    RawFeed::MastodonStatuses {
-                        mastodon,
-                        feed_type,
-                        token,
-                    } => {
-                        attr.step = options.step(DEFAULT_FEED_STEP);
-                        let feed = slipfeed::MastodonFeed::new(
-                            mastodon,
-                            feed_type.into(),
-                            token.clone(),
-                        );
-                        let mut inner_updater = updater.updater.write().await;
-                        let id = inner_updater.add_feed(feed, attr);
-                        updater.feeds.insert(name.clone(), id);
-                        updater.feeds_ids.insert(id, name.clone());
-                        tracing::debug!("Added mastodon feed {}.", name);
-                    }
-                    RawFeed::MastodonUserStatuses {
-                        mastodon,
-                        user,
-                        token,
-                    } => {
-                        attr.step = options.step(DEFAULT_FEED_STEP);
-                        let feed = slipfeed::MastodonFeed::new(
-                            mastodon,
-                            slipfeed::MastodonFeedType::UserStatuses {
-                                user: user.clone(),
-                                id: None,
-                            },
-                            token.clone(),
-                        );
-                        let mut inner_updater = updater.updater.write().await;
-                        let id = inner_updater.add_feed(feed, attr);
-                        updater.feeds.insert(name.clone(), id);
-                        updater.feeds_ids.insert(id, name.clone());
-                        tracing::debug!("Added mastodon feed {}.", name);
-                    }
-                };
+            // Add raw feeds. Feeds tag entries in the order they are added, so
+            // aggregates come after their sources, and tag aggregates come
+            // last to see the tags of every other feed.
+            let mut ordered: Vec<_> = feeds.iter().collect();
+            ordered.sort_by_key(|(_, feed_def)| match feed_def.feed() {
+                RawFeed::Aggregate { .. } => 1,
+                RawFeed::AggregateTag { .. } => 2,
+                _ => 0,
+            });
+            for (name, feed_def) in ordered {
+                let (feed, attr) = self.build_feed(name, feed_def);
+                let mut inner_updater = updater.updater.write().await;
+                let id = inner_updater.add_feed(feed, attr);
+                updater.feeds.insert(name.clone(), id);
+                updater.feeds_ids.insert(id, name.clone());
+                tracing::debug!(
+                    "Added {} feed {}.",
+                    feed_def.feed().kind(),
+                    name
+                );
             }
 
             // Add reference to child feeds for aggregate feeds.
@@ -264,9 +270,245 @@ impl Config {
                 .for_each(|t| inner_updater.add_transform(t.clone()));
         }
 
+        // Restore feed state, so fetches after a restart are conditional.
+        if let Some(entry_db) = &updater.entry_db {
+            let mut inner_updater = updater.updater.write().await;
+            for id in updater.feeds.values() {
+                let Some(url) = inner_updater.feed_url(*id).await else {
+                    continue;
+                };
+                if let Some(state) = entry_db.feed_state(&url).await {
+                    inner_updater.restore_feed_state(*id, state).await;
+                }
+            }
+        }
+
         Ok(updater)
     }
 
+    /// Check that the configuration can be used to build an updater.
+    pub fn validate(&self) -> Result<()> {
+        self.serve.bind_address()?;
+        self.serve.cors_origins()?;
+        for (name, feed_def) in self.feeds.iter().flatten() {
+            for (header, value) in self.feed_headers(feed_def) {
+                if let Err(e) =
+                    reqwest::header::HeaderName::from_bytes(header.as_bytes())
+                {
+                    bail!(
+                        "Feed {name} has invalid header name `{header}`: {e}."
+                    );
+                }
+                if let Err(e) = reqwest::header::HeaderValue::from_str(&value) {
+                    bail!(
+                        "Feed {name} has invalid value for header `{header}`: {e}."
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Build a configured feed, with its attributes.
+    pub fn build_feed(
+        &self,
+        name: &str,
+        feed_def: &FeedDefinition,
+    ) -> (Box<dyn slipfeed::Feed>, slipfeed::FeedAttributes) {
+        let mut attr = slipfeed::FeedAttributes::new();
+        attr.display_name = Arc::new(name.to_string());
+
+        let options = self.feed_options(feed_def);
+
+        attr.freq = options.freq();
+        attr.timeout = options.oldest();
+        attr.headers = self.feed_headers(feed_def);
+        attr.keep_empty = options.keep_empty();
+        attr.apply_tags = options.apply_tags();
+        attr.format = options.force_format();
+        attr.charset = options.force_charset().cloned();
+        attr.request_timeout = options.request_timeout();
+        attr.titles_only = options.titles_only();
+        attr.max_body_size = options.max_body_size();
+        attr.full_content = options.full_content();
+        feed_def
+            .tags()
+            .clone()
+            .unwrap_or_default()
+            .iter()
+            .for_each(|tag| attr.add_tag(tag.clone().into()));
+        feed_def
+            .filters()
+            .get_filters()
+            .iter()
+            .for_each(|f| attr.add_filter(f.clone()));
+
+        let feed: Box<dyn slipfeed::Feed> = match feed_def.feed() {
+            RawFeed::Json { url, .. } => {
+                attr.step = options.step(DEFAULT_FEED_STEP);
+                slipfeed::JsonFeed::new(url)
+            }
+            RawFeed::Raw { url } => {
+                attr.step = options.step(DEFAULT_FEED_STEP);
+                StandardFeed::new(url)
+            }
+            RawFeed::YouTube { channel } => {
+                attr.step = options.step(DEFAULT_FEED_STEP);
+                slipfeed::YouTubeFeed::new(channel)
+            }
+            RawFeed::Reddit { subreddit, sort } => {
+                attr.step = options.step(DEFAULT_FEED_STEP);
+                slipfeed::RedditFeed::new(subreddit, sort.unwrap_or_default())
+            }
+            RawFeed::Aggregate { .. } => {
+                attr.step = options.step(DEFAULT_FEED_AGG_STEP);
+                AggregateFeed::new()
+            }
+            RawFeed::AggregateTag {
+                tag_allowlist,
+                tag_blocklist,
+            } => {
+                attr.step = options.step(DEFAULT_FEED_TAG_STEP);
+                let mut feed = AggregateTagFeed::new();
+                feed.allowlist = tag_allowlist
+                    .iter()
+                    .map(|t| slipfeed::Tag::from(t.as_str()))
+                    .collect();
+                feed.blocklist = tag_blocklist
+                    .iter()
+                    .map(|t| slipfeed::Tag::from(t.as_str()))
+                    .collect();
+                feed
+            }
+            RawFeed::MastodonStatuses {
+                mastodon,
+                feed_type,
+                token,
+            } => {
+                attr.step = options.step(DEFAULT_FEED_STEP);
+                let mut feed = slipfeed::MastodonFeed::new(
+                    mastodon,
+                    feed_type.into(),
+                    token.clone(),
+                );
+                if let Some(pages) = options.pages() {
+                    feed.set_max_pages(pages);
+                }
+                feed
+            }
+            RawFeed::MastodonUserStatuses {
+                mastodon,
+                user,
+                token,
+            } => {
+                attr.step = options.step(DEFAULT_FEED_STEP);
+                let mut feed = slipfeed::MastodonFeed::new(
+                    mastodon,
+                    slipfeed::MastodonFeedType::UserStatuses {
+                        user: user.clone(),
+                        id: None,
+                    },
+                    token.clone(),
+                );
+                if let Some(pages) = options.pages() {
+                    feed.set_max_pages(pages);
+                }
+                feed
+            }
+            RawFeed::MastodonHashtag {
+                mastodon,
+                hashtag,
+                token,
+            } => {
+                attr.step = options.step(DEFAULT_FEED_STEP);
+                let mut feed = slipfeed::MastodonFeed::new(
+                    mastodon,
+                    slipfeed::MastodonFeedType::HashtagTimeline {
+                        tag: hashtag.clone(),
+                    },
+                    token.clone(),
+                );
+                if let Some(pages) = options.pages() {
+                    feed.set_max_pages(pages);
+                }
+                feed
+            }
+            RawFeed::MastodonList {
+                mastodon,
+                list,
+                token,
+            } => {
+                if token.is_none() {
+                    tracing::warn!(
+                        "Mastodon list feed {} needs a token.",
+                        name
+                    );
+                }
+                attr.step = options.step(DEFAULT_FEED_STEP);
+                let mut feed = slipfeed::MastodonFeed::new(
+                    mastodon,
+                    slipfeed::MastodonFeedType::ListTimeline {
+                        list_id: list.clone(),
+                    },
+                    token.clone(),
+                );
+                if let Some(pages) = options.pages() {
+                    feed.set_max_pages(pages);
+                }
+                feed
+            }
+        };
+        (feed, attr)
+    }
+
+    /// Build feed options from global, overriding with feed-specific options.
+    pub fn feed_options(&self, feed_def: &FeedDefinition) -> FeedOptions {
+        let mut options = self.global.limits.clone();
+        options.merge(feed_def.options());
+        options
+    }
+
+    /// Check if duplicate entries are merged when collecting a feed.
+    /// Only aggregate feeds, which gather entries from other feeds, merge.
+    pub fn merges_duplicates(&self, feed_def: &FeedDefinition) -> bool {
+        feed_def.is_aggregate() && self.feed_options(feed_def).dedup()
+    }
+
+    /// Get the request headers of a feed, including the user agent.
+    /// Reddit feeds without a configured user agent use slipfeed's, since
+    /// reddit rate limits generic user agents.
+    pub fn feed_headers(
+        &self,
+        feed_def: &FeedDefinition,
+    ) -> BTreeMap<String, String> {
+        let options = self.feed_options(feed_def);
+        let mut headers = options.headers().clone();
+        let has_user_agent = headers
+            .keys()
+            .any(|h| h.eq_ignore_ascii_case(USER_AGENT_HEADER));
+        match (options.user_agent(), has_user_agent) {
+            (Some(user_agent), _) => {
+                headers
+                    .retain(|h, _| !h.eq_ignore_ascii_case(USER_AGENT_HEADER));
+                headers.insert(USER_AGENT_HEADER.into(), user_agent.clone());
+            }
+            (None, true) => {}
+            (None, false)
+                if self.global.user_agent.is_none()
+                    && matches!(feed_def.feed(), RawFeed::Reddit { .. }) => {}
+            (None, false) => {
+                headers.insert(
+                    USER_AGENT_HEADER.into(),
+                    match &self.global.user_agent {
+                        Some(user_agent) => user_agent.clone(),
+                        None => DEFAULT_USER_AGENT.into(),
+                    },
+                );
+            }
+        }
+        headers
+    }
+
     /// Find a feed by name.
     pub fn feed(&self, feed: impl AsRef<str>) -> Option<&FeedDefinition> {
         if let Some(feeds) = self.feeds.as_ref() {
@@ -275,6 +517,54 @@ impl Config {
         None
     }
 
+    /// Check if an entry should be marked important when it is stored.
+    pub fn marks_important(&self, entry: &slipfeed::Entry) -> bool {
+        if let Some(rule) = &self.global.important {
+            if rule.matches(entry) {
+                return true;
+            }
+        }
+        entry.feeds().iter().any(|feed_ref| {
+            self.feed(feed_ref.name.as_str())
+                .and_then(|feed_def| feed_def.important())
+                .is_some_and(|rule| rule.matches(entry))
+        })
+    }
+
+    /// Get the configured tags of a feed.
+    pub fn feed_tags(
+        &self,
+        feed: impl AsRef<str>,
+    ) -> Option<Vec<slipfeed::Tag>> {
+        let feed_def = self.feed(feed)?;
+        Some(
+            feed_def
+                .tags()
+                .iter()
+                .flatten()
+                .map(slipfeed::Tag::new)
+                .collect(),
+        )
+    }
+
+    /// Get the feed names, in display order.
+    pub fn feed_names(&self) -> Vec<&String> {
+        let mut names: Vec<(&String, &FeedDefinition)> = match &self.feeds {
+            Some(feeds) => feeds.iter().collect(),
+            None => Vec::new(),
+        };
+        names.sort_by_key(|(name, feed_def)| (feed_def.priority(), *name));
+        names.into_iter().map(|(name, _)| name).collect()
+    }
+
+    /// Get the display rank of a feed, where unknown feeds are last.
+    pub fn feed_rank(&self, feed: impl AsRef<str>) -> (bool, i64) {
+        match self.feed(feed) {
+            Some(feed_def) => (false, feed_def.priority()),
+            None => (true, 0),
+        }
+    }
+
     /// Add a feed, directly.
     pub fn add_feed(
         &mut self,
@@ -291,6 +581,148 @@ impl Config {
     }
 }
 
+/// Replace `${VAR}` in the strings of toml data with the value of each
+/// variable, and `$$` with `$`. Keys and comments are left as they are.
+pub fn substitute_env(
+    data: &str,
+    var: impl Fn(&str) -> Option<String>,
+) -> std::result::Result<String, String> {
+    substitute_env_strings(data, var).map(|(data, _)| data)
+}
+
+/// Replace `${VAR}` in the strings of toml data, like [`substitute_env`].
+/// This also returns the strings that had variables substituted, as their
+/// source and value.
+pub fn substitute_env_strings(
+    data: &str,
+    var: impl Fn(&str) -> Option<String>,
+) -> std::result::Result<(String, Vec<(String, String)>), String> {
+    let mut substituted = String::with_capacity(data.len());
+    let mut strings = Vec::new();
+    // Closing delimiter of the string being read.
+    let mut quote: Option<&str> = None;
+    // Where the string being read starts, in the data and the substituted
+    // data, and whether it had a variable substituted.
+    let mut string_start = (0, 0);
+    let mut string_substituted = false;
+    let mut rest = data;
+    'data: while let Some(c) = rest.chars().next() {
+        let Some(delimiter) = quote else {
+            if c == '#' {
+                let end = rest.find('\n').unwrap_or(rest.len());
+                substituted.push_str(&rest[..end]);
+                rest = &rest[end..];
+                continue;
+            }
+            for delimiter in ["\"\"\"", "'''", "\"", "'"] {
+                if let Some(after) = rest.strip_prefix(delimiter) {
+                    string_start = (data.len() - rest.len(), substituted.len());
+                    string_substituted = false;
+                    substituted.push_str(delimiter);
+                    quote = Some(delimiter);
+                    rest = after;
+                    continue 'data;
+                }
+            }
+            substituted.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        };
+
+        let basic = delimiter.starts_with('"');
+        if let Some(after) = rest.strip_prefix(delimiter) {
+            substituted.push_str(delimiter);
+            quote = None;
+            rest = after;
+            if string_substituted {
+                let source = &data[string_start.0..data.len() - rest.len()];
+                let value = &substituted[string_start.1..];
+                if let (Some(source), Some(value)) =
+                    (toml_string(source), toml_string(value))
+                {
+                    strings.push((source, value));
+                }
+            }
+        } else if basic && c == '\\' {
+            let len = 1 + rest[1..].chars().next().map_or(0, char::len_utf8);
+            substituted.push_str(&rest[..len]);
+            rest = &rest[len..];
+        } else if let Some(after) = rest.strip_prefix("$$") {
+            substituted.push('$');
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("${") {
+            let Some(end) = after.find('}') else {
+                return Err("Unterminated environment variable".into());
+            };
+            let name = &after[..end];
+            let Some(value) = var(name) else {
+                return Err(format!("Environment variable {name} is not set"));
+            };
+            string_substituted = true;
+            if basic {
+                for c in value.chars() {
+                    match c {
+                        '\\' => substituted.push_str("\\\\"),
+                        '"' => substituted.push_str("\\\""),
+                        '\n' => substituted.push_str("\\n"),
+                        '\r' => substituted.push_str("\\r"),
+                        '\t' => substituted.push_str("\\t"),
+                        c => substituted.push(c),
+                    }
+                }
+            } else {
+                substituted.push_str(&value);
+            }
+            rest = &after[end + 1..];
+        } else {
+            substituted.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    Ok((substituted, strings))
+}
+
+/// Parse a toml string literal.
+fn toml_string(literal: &str) -> Option<String> {
+    let mut table: toml::Table =
+        toml::from_str(&format!("value = {literal}")).ok()?;
+    match table.remove("value")? {
+        toml::Value::String(string) => Some(string),
+        _ => None,
+    }
+}
+
+/// Remove secrets from exported toml, and replace substituted strings with
+/// their source.
+fn redact_table(table: &mut toml::Table, substituted: &[(String, String)]) {
+    table.retain(|key, _| !SECRET_KEYS.contains(&key));
+    for (_, value) in table.iter_mut() {
+        redact_value(value, substituted);
+    }
+}
+
+/// Redact a value of exported toml.
+/// Only whole strings are replaced, so short values don't rewrite unrelated
+/// strings that contain them.
+fn redact_value(value: &mut toml::Value, substituted: &[(String, String)]) {
+    match value {
+        toml::Value::String(string) => {
+            if let Some((source, _)) =
+                substituted.iter().find(|(_, value)| value == string)
+            {
+                *string = source.clone();
+            }
+        }
+        toml::Value::Array(array) => {
+            for value in array {
+                redact_value(value, substituted);
+            }
+        }
+        toml::Value::Table(table) => redact_table(table, substituted),
+        _ => {}
+    }
+}
+
 /// Global feed configuration.
 #[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct GlobalConfig {
@@ -304,9 +736,63 @@ pub struct GlobalConfig {
     #[serde(default)]
     pub transforms: TransformsConfig,
     /// The user agent used for StandardSyndication HTTP requests.
-    /// Without specifying, no user agent is used.
+    /// Without specifying, `slipstream/<version>` is used.
     #[serde(default, alias = "user-agent")]
     pub user_agent: Option<String>,
+    /// Number of times to retry transient fetch failures (default 2).
+    #[serde(default, alias = "fetch-retries")]
+    pub fetch_retries: Option<usize>,
+    /// Delay between fetch retries (default 5s).
+    #[serde(default, alias = "retry-delay", with = "humantime_serde::option")]
+    pub retry_delay: Option<std::time::Duration>,
+    /// Whether served feeds are filtered before or after limiting.
+    #[serde(default, alias = "collection-order")]
+    pub collection_order: CollectionOrder,
+    /// Rule that marks new entries from any feed important.
+    #[serde(default)]
+    pub important: Option<ImportanceRule>,
+    /// Daily range in which feeds aren't fetched and notifications aren't
+    /// sent.
+    #[serde(default, alias = "quiet-hours")]
+    pub quiet_hours: Option<QuietHours>,
+    /// Whether entries sharing a link (host, path, and query, without
+    /// tracking parameters) are stored once, with every feed they came from.
+    #[serde(default, alias = "dedup-by-link")]
+    pub dedup_by_link: Option<bool>,
+}
+
+impl GlobalConfig {
+    /// Number of times to retry transient fetch failures.
+    pub fn fetch_retries(&self) -> usize {
+        self.fetch_retries.unwrap_or(2)
+    }
+
+    /// Delay between fetch retries.
+    pub fn retry_delay(&self) -> slipfeed::Duration {
+        match self.retry_delay {
+            Some(delay) => slipfeed::Duration::from_std(delay),
+            None => slipfeed::Duration::from_seconds(5),
+        }
+    }
+
+    /// Whether entries sharing a link are stored once.
+    pub fn dedup_by_link(&self) -> bool {
+        self.dedup_by_link.unwrap_or(false)
+    }
+}
+
+/// Order of collecting and filtering entries for served feeds.
+#[derive(
+    Clone, Copy, Default, Debug, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum CollectionOrder {
+    /// Collect `max` entries, then filter them. This may return fewer than
+    /// `max` entries.
+    #[default]
+    CollectThenFilter,
+    /// Filter entries, paging the database until `max` entries are found.
+    FilterThenLimit,
 }
 
 #[derive(Clone, Debug)]
@@ -362,6 +848,13 @@ impl<'de> Deserialize<'de> for TimeZone {
             });
         }
 
+        if let Ok(tz) = text.trim().parse::<chrono_tz::Tz>() {
+            return Ok(Self {
+                timezone: text.trim().into(),
+                inner: TimeZoneInner::RealTimeZone(tz),
+            });
+        }
+
         let upper_text = text.trim().to_uppercase();
         let text = format!("\"{upper_text}\"");
         let de = match toml::de::ValueDeserializer::parse(&text) {
@@ -388,6 +881,32 @@ enum TimeZoneInner {
 }
 
 impl TimeZone {
+    /// Get the time of day of a DateTime in this timezone.
+    pub fn time_of_day(&self, dt: &slipfeed::DateTime) -> chrono::NaiveTime {
+        match &self.inner {
+            TimeZoneInner::RealTimeZone(tz) => {
+                dt.to_chrono().with_timezone(tz).time()
+            }
+            TimeZoneInner::Utc => dt.to_chrono().time(),
+            TimeZoneInner::Local => {
+                dt.to_chrono().with_timezone(&chrono::Local).time()
+            }
+        }
+    }
+
+    /// Get the date of a DateTime in this timezone.
+    pub fn date(&self, dt: &slipfeed::DateTime) -> chrono::NaiveDate {
+        match &self.inner {
+            TimeZoneInner::RealTimeZone(tz) => {
+                dt.to_chrono().with_timezone(tz).date_naive()
+            }
+            TimeZoneInner::Utc => dt.to_chrono().date_naive(),
+            TimeZoneInner::Local => {
+                dt.to_chrono().with_timezone(&chrono::Local).date_naive()
+            }
+        }
+    }
+
     pub fn format(&self, dt: &slipfeed::DateTime) -> String {
         let c = dt.to_chrono().with_timezone(match &self.inner {
             TimeZoneInner::RealTimeZone(tz) => {
@@ -403,6 +922,36 @@ impl TimeZone {
 
         return c.format("%Y-%m-%d %H:%M").to_string();
     }
+
+    /// Format a DateTime in this timezone with a strftime-style format.
+    pub fn format_with(&self, dt: &slipfeed::DateTime, format: &str) -> String {
+        match &self.inner {
+            TimeZoneInner::RealTimeZone(tz) => dt.format_in(tz, format),
+            TimeZoneInner::Utc => dt.format_in(&chrono::Utc, format),
+            TimeZoneInner::Local => dt.format_in(&chrono::Local, format),
+        }
+    }
+}
+
+/// A strftime-style date format, e.g. "%a %d %b %H:%M".
+#[derive(Clone, Debug, Serialize)]
+pub struct DateFormat(String);
+
+impl<'de> Deserialize<'de> for DateFormat {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let format = String::deserialize(deserializer)?;
+        if chrono::format::StrftimeItems::new(&format)
+            .any(|item| item == chrono::format::Item::Error)
+        {
+            return Err(<D::Error as serde::de::Error>::custom(format!(
+                "invalid date format `{format}`"
+            )));
+        }
+        Ok(Self(format))
+    }
 }
 
 /// Tag config.
@@ -6,7 +6,7 @@ use std::path::PathBuf;
 
 use resolve_path::PathResolveExt;
 use sqlx::{
-    Execute, Row, SqlitePool,
+    Execute, Row, SqliteConnection, SqlitePool,
     sqlite::{SqliteConnectOptions, SqlitePoolOptions},
 };
 
@@ -20,6 +20,10 @@ pub struct Database {
     path: String,
     /// Connection to the sqlite database.
     pool: SqlitePool,
+    /// Whether the full-text search table is available.
+    fts: bool,
+    /// Whether entries with the same canonical link are merged.
+    dedup_by_link: bool,
 }
 
 impl Database {
@@ -61,8 +65,167 @@ impl Database {
 
         // Initialize database.
         Database::initialize(&pool).await?;
+        let fts = Database::has_fts(&pool).await;
+        if !fts {
+            tracing::warn!("Full-text search is unavailable, using LIKE.");
+        }
+
+        Ok(Self {
+            path,
+            pool,
+            fts,
+            dedup_by_link: false,
+        })
+    }
+
+    /// Set whether entries with the same canonical link are merged on
+    /// insertion, across all feeds.
+    pub fn set_dedup_by_link(&mut self, dedup: bool) {
+        self.dedup_by_link = dedup;
+    }
+
+    /// Normalize a link to its host, path, and query, so copies of a story
+    /// share it. Schemes, `www.`, ports, fragments, trailing slashes, and
+    /// tracking parameters are ignored, and the query is sorted.
+    pub fn canonical_link(link: &str) -> Option<String> {
+        let mut url = reqwest::Url::parse(link.trim()).ok()?;
+        let host = url.host_str()?.to_lowercase();
+        let host = host.strip_prefix("www.").unwrap_or(&host);
+        let path = url.path().trim_end_matches('/').to_string();
+        let mut query: Vec<(String, String)> = url
+            .query_pairs()
+            .filter(|(key, _)| !is_tracking_param(key))
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect();
+        if query.is_empty() {
+            return Some(format!("{host}{path}"));
+        }
+        query.sort();
+        url.set_query(None);
+        url.query_pairs_mut().extend_pairs(query);
+        Some(format!("{host}{path}?{}", url.query().unwrap_or_default()))
+    }
+
+    /// Set the canonical link of every stored entry with a link.
+    async fn backfill_canonical_links(
+        conn: &mut SqliteConnection,
+    ) -> std::result::Result<(), sqlx::Error> {
+        let links: Vec<(EntryDbId, String)> = sqlx::query_as(
+            "SELECT id, link FROM entries WHERE link IS NOT NULL",
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+        for (id, link) in links {
+            sqlx::query("UPDATE entries SET canonical_link = ? WHERE id = ?")
+                .bind(Database::canonical_link(&link))
+                .bind(id)
+                .execute(&mut *conn)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Record the version of a migration.
+    async fn record_version(
+        conn: &mut SqliteConnection,
+        version: &semver::Version,
+    ) -> std::result::Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO version_history(version, timestamp) VALUES(?, unixepoch(?));",
+        )
+        .bind(version.to_string())
+        .bind(slipfeed::DateTime::now().to_chrono())
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
 
-        Ok(Self { path, pool })
+    /// Run a migration in one transaction, recording its version last.
+    async fn migrate(
+        pool: &SqlitePool,
+        version: &semver::Version,
+        sql: &'static str,
+    ) -> std::result::Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::raw_sql(sql).execute(&mut *tx).await?;
+        Database::record_version(&mut tx, version).await?;
+        tx.commit().await
+    }
+
+    /// Upgrade the database to v2.24.0.
+    /// The upgrade runs in one transaction and records the version last, so a
+    /// failed upgrade is retried from the start.
+    async fn upgrade_2_24(
+        pool: &SqlitePool,
+    ) -> std::result::Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::raw_sql(
+            "
+            CREATE TABLE IF NOT EXISTS flags(
+                id INTEGER PRIMARY KEY,
+                -- Entry id.
+                entry_id INTEGER NOT NULL UNIQUE REFERENCES entries(id),
+                -- Whether the entry has been read.
+                read INTEGER NOT NULL DEFAULT 0,
+                -- Whether the entry is important.
+                important INTEGER NOT NULL DEFAULT 0,
+                -- Whether the entry is saved to read later.
+                saved INTEGER NOT NULL DEFAULT 0
+            ) STRICT;
+            CREATE INDEX IF NOT EXISTS flags_entry_id_idx ON flags(entry_id);
+
+            ALTER TABLE entries ADD COLUMN canonical_link TEXT DEFAULT NULL;
+            CREATE INDEX IF NOT EXISTS entries_canonical_link_idx ON entries(canonical_link);
+
+            CREATE TABLE IF NOT EXISTS feed_state(
+                -- The feed url.
+                url TEXT PRIMARY KEY,
+                -- When the feed was last updated successfully.
+                last_update INTEGER DEFAULT NULL,
+                -- The ETag of the feed's last successful response.
+                etag TEXT DEFAULT NULL
+            ) STRICT;
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // Sqlite may be built without fts5, in which case searches fall back
+        // to LIKE.
+        let res = sqlx::raw_sql(
+            "
+            CREATE VIRTUAL TABLE IF NOT EXISTS entries_fts USING fts5(
+                title, content, author, content='entries', content_rowid='id'
+            );
+            CREATE TRIGGER IF NOT EXISTS entries_fts_insert AFTER INSERT ON entries BEGIN
+                INSERT INTO entries_fts(rowid, title, content, author)
+                VALUES (new.id, new.title, new.content, new.author);
+            END;
+            CREATE TRIGGER IF NOT EXISTS entries_fts_delete AFTER DELETE ON entries BEGIN
+                INSERT INTO entries_fts(entries_fts, rowid, title, content, author)
+                VALUES ('delete', old.id, old.title, old.content, old.author);
+            END;
+            CREATE TRIGGER IF NOT EXISTS entries_fts_update AFTER UPDATE ON entries BEGIN
+                INSERT INTO entries_fts(entries_fts, rowid, title, content, author)
+                VALUES ('delete', old.id, old.title, old.content, old.author);
+                INSERT INTO entries_fts(rowid, title, content, author)
+                VALUES (new.id, new.title, new.content, new.author);
+            END;
+            INSERT INTO entries_fts(entries_fts) VALUES ('rebuild');
+            ",
+        )
+        .execute(&mut *tx)
+        .await;
+        if let Err(e) = res {
+            tracing::warn!("Failed to create full-text search: {e}");
+        }
+
+        // Links are normalized in rust, so stored entries are backfilled here.
+        Database::backfill_canonical_links(&mut tx).await?;
+
+        Database::record_version(&mut tx, &semver::Version::new(2, 24, 0))
+            .await?;
+        tx.commit().await
     }
 
     async fn database_version(pool: &SqlitePool) -> Option<semver::Version> {
@@ -91,6 +254,16 @@ impl Database {
         }
     }
 
+    /// Check whether the full-text search table exists.
+    async fn has_fts(pool: &SqlitePool) -> bool {
+        let res: Result<(i64,), _> = sqlx::query_as(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='entries_fts'",
+        )
+        .fetch_one(pool)
+        .await;
+        matches!(res, Ok((1,)))
+    }
+
     /// Initialize the database.
     /// This handles all upgrades and migrations.
     async fn initialize(pool: &SqlitePool) -> Result<()> {
@@ -185,18 +358,15 @@ impl Database {
             }
 
             if current_version < semver::Version::new(2, 10, 0) {
-                let res = sqlx::query(
+                let res = Database::migrate(
+                    pool,
+                    &semver::Version::new(2, 10, 0),
                     "
-                    INSERT INTO version_history(version, timestamp) VALUES(?, unixepoch(?));
-
                     ALTER TABLE entries ADD COLUMN modified_timestamp INTEGER NOT NULL DEFAULT 0;
                     CREATE INDEX IF NOT EXISTS entries_modified_timestamp_idx ON entries(modified_timestamp);
                     UPDATE entries SET modified_timestamp = timestamp WHERE modified_timestamp = 0;
                     ",
                 )
-                .bind(&semver::Version::new(2, 10, 0).to_string())
-                .bind(&slipfeed::DateTime::now().to_chrono())
-                .execute(pool)
                 .await;
 
                 if let Err(e) = res {
@@ -208,19 +378,16 @@ impl Database {
             }
 
             if current_version < semver::Version::new(2, 23, 0) {
-                let res = sqlx::query(
+                let res = Database::migrate(
+                    pool,
+                    &semver::Version::new(2, 23, 0),
                     "
-                    INSERT INTO version_history(version, timestamp) VALUES(?, unixepoch(?));
-
                     ALTER TABLE entries ADD COLUMN source_feed TEXT DEFAULT NULL;
                     ALTER TABLE entries ADD COLUMN link TEXT DEFAULT NULL;
                     CREATE INDEX IF NOT EXISTS entries_source_feed_idx ON entries(source_feed);
                     CREATE INDEX IF NOT EXISTS entries_link_idx ON entries(link);
                     ",
                 )
-                .bind(&semver::Version::new(2, 23, 0).to_string())
-                .bind(&slipfeed::DateTime::now().to_chrono())
-                .execute(pool)
                 .await;
 
                 if let Err(e) = res {
@@ -231,6 +398,15 @@ impl Database {
                 continue;
             }
 
+            if current_version < semver::Version::new(2, 24, 0) {
+                if let Err(e) = Database::upgrade_2_24(pool).await {
+                    bail!("Failed to upgrade database to v2.24.0: {e}");
+                }
+
+                current_version = semver::Version::new(2, 24, 0);
+                continue;
+            }
+
             tracing::debug!("Database is already up-to-date.");
             break;
         }
@@ -242,12 +418,24 @@ impl Database {
     pub async fn insert_slipfeed_entry(
         &self,
         entry: &slipfeed::Entry,
-    ) -> EntryDbId {
+    ) -> EntryInsertion {
         let entry_v1 = EntryV1::from(entry);
         let serialized_entry = SerializedEntry::V1(entry_v1.clone());
-        let entry_id: EntryDbId = {
+        let canonical_link = Database::canonical_link(&entry.source().url);
+        let (entry_id, new): (EntryDbId, bool) = {
             // Find existing id.
             let mut id: (Option<EntryDbId>,) = (None,);
+            // Search by primary-feed+source_id, which survives edits.
+            if let Some(source_id) = entry.source_id() {
+                id = sqlx::query_as(
+                    "SELECT id FROM entries WHERE source_feed = ? AND source_id = ?",
+                )
+                .bind(entry.primary_feed().name.as_str())
+                .bind(source_id)
+                .fetch_one(&self.pool)
+                .await
+                .unwrap_or((None,));
+            }
             // Search by entry.
             if id.0.is_none() {
                 id = sqlx::query_as("SELECT id FROM entries WHERE entry = ?")
@@ -267,6 +455,18 @@ impl Database {
                 .await
                 .unwrap_or_else(|_| (None,));
             }
+            // Search by canonical link, from any feed.
+            if id.0.is_none() && self.dedup_by_link {
+                if let Some(canonical_link) = &canonical_link {
+                    id = sqlx::query_as(
+                        "SELECT id FROM entries WHERE canonical_link = ?",
+                    )
+                    .bind(canonical_link)
+                    .fetch_one(&self.pool)
+                    .await
+                    .unwrap_or((None,));
+                }
+            }
             // Search by title+author.
             if id.0.is_none()
                 && !entry.title().is_empty()
@@ -302,14 +502,14 @@ impl Database {
                         "No insertion, found existing entry {}.",
                         id
                     );
-                    id
+                    (id, false)
                 }
                 (None,) => {
                     let id_res: Result<(Option<EntryDbId>,), _> =
                         sqlx::query_as(
                         "
-                        INSERT INTO entries (timestamp, modified_timestamp, entry, title, author, link, content, source_feed, source_id)
-                        VALUES (unixepoch(?), unixepoch(?), ?, ?, ?, ?, ?, ?, ?)
+                        INSERT INTO entries (timestamp, modified_timestamp, entry, title, author, link, content, source_feed, source_id, canonical_link)
+                        VALUES (unixepoch(?), unixepoch(?), ?, ?, ?, ?, ?, ?, ?, ?)
                         RETURNING id
                         ",
                         )
@@ -322,22 +522,23 @@ impl Database {
                         .bind(entry.content())
                         .bind(entry.primary_feed().name.as_str())
                         .bind(entry.source_id())
+                        .bind(&canonical_link)
                         .fetch_one(&self.pool)
                         .await;
                     match id_res {
                         Ok(maybe_id) => match maybe_id.0 {
                             Some(id) => {
                                 tracing::trace!("Insertion, new entry {}.", id);
-                                id
+                                (id, true)
                             }
                             None => {
                                 tracing::error!("Failed to insert entry");
-                                return 0;
+                                return EntryInsertion::Failed;
                             }
                         },
                         Err(e) => {
                             tracing::error!("Failed: {}", e);
-                            return 0;
+                            return EntryInsertion::Failed;
                         }
                     }
                 }
@@ -366,26 +567,68 @@ impl Database {
             }
         }
 
-        return entry_id;
+        match new {
+            true => EntryInsertion::New(entry_id),
+            false => EntryInsertion::Existing(entry_id),
+        }
     }
 
+    /// Get entries in an order.
+    /// Pages before or after a date are the entries next to the date.
     pub async fn get_entries(
         &self,
         criteria: Vec<DatabaseSearch>,
         max_length: usize,
         cursor: OffsetCursor,
+        order: EntryOrder,
+    ) -> DatabaseEntryList {
+        let reverse = matches!(
+            (order, &cursor),
+            (EntryOrder::Newest, OffsetCursor::After(..))
+                | (EntryOrder::Oldest, OffsetCursor::Before(..))
+        );
+        let mut entries = self
+            .get_entries_page(criteria, max_length, cursor, 0, order)
+            .await;
+        if reverse {
+            entries.reverse();
+        }
+        entries
+    }
+
+    /// Get entries, skipping the first `offset` matches.
+    /// Entries after a date are found oldest first, and entries before a date
+    /// newest first, so that pages are next to the date.
+    pub async fn get_entries_page(
+        &self,
+        criteria: Vec<DatabaseSearch>,
+        max_length: usize,
+        cursor: OffsetCursor,
+        offset: usize,
+        order: EntryOrder,
     ) -> DatabaseEntryList {
         use sqlx::QueryBuilder;
-        let mut query = QueryBuilder::new(
+        let mut query = QueryBuilder::new("");
+        // Pages by source are the newest entries, then sorted by source.
+        if order == EntryOrder::Source {
+            query.push("SELECT * FROM (");
+        }
+        query.push(
             "
             SELECT
                 entries.id,
                 entries.entry,
                 json_group_array(sources.source) AS sources,
                 json_group_array(tags.tag) AS tags,
-                json_group_object(commands.name, commands.result) AS commands
+                json_group_object(commands.name, commands.result) AS commands,
+                COALESCE(flags.read, 0) AS read,
+                COALESCE(flags.important, 0) AS important,
+                COALESCE(flags.saved, 0) AS saved,
+                entries.source_feed,
+                entries.timestamp
             FROM
                 entries
+                LEFT JOIN flags ON entries.id = flags.entry_id
                 LEFT JOIN sources ON entries.id = sources.entry_id
                 LEFT JOIN tags ON entries.id = tags.entry_id
                 LEFT JOIN commands ON entries.id = commands.entry_id
@@ -395,78 +638,129 @@ impl Database {
 
         query.push(" TRUE = TRUE");
 
-        let mut order_clause =
-            String::from(" ORDER BY entries.timestamp DESC, entries.id DESC");
+        let mut order_clause = match order {
+            EntryOrder::Oldest => " ORDER BY entries.timestamp, entries.id",
+            _ => " ORDER BY entries.timestamp DESC, entries.id DESC",
+        };
         for crit in &criteria {
             match crit {
                 DatabaseSearch::Latest => {}
                 DatabaseSearch::Live => {
-                    order_clause = " ORDER BY entries.id DESC".into();
+                    order_clause = match order {
+                        EntryOrder::Oldest => " ORDER BY entries.id",
+                        _ => " ORDER BY entries.id DESC",
+                    };
                 }
                 DatabaseSearch::Raw(raw_clause) => {
                     query.push(format!(" AND {}", raw_clause));
                 }
+                DatabaseSearch::Search(search)
+                    if self.fts && !search.trim().is_empty() =>
+                {
+                    query.push(
+                        " AND entries.id IN (SELECT rowid FROM entries_fts WHERE entries_fts MATCH ",
+                    );
+                    query.push_bind(Database::fts_query(search));
+                    query.push(")");
+                }
                 DatabaseSearch::Search(search) => {
-                    let search = search.to_lowercase();
-                    query.push(" AND (entries.title LIKE CONCAT('%',");
+                    let search = Database::like_pattern(&search.to_lowercase());
+                    query.push(" AND (entries.title LIKE ");
+                    query.push_bind(search.clone());
+                    query.push(" ESCAPE '\\' OR entries.content LIKE ");
                     query.push_bind(search.clone());
-                    query.push(",'%') OR entries.author LIKE CONCAT('%',");
+                    query.push(" ESCAPE '\\' OR entries.author LIKE ");
                     query.push_bind(search);
-                    query.push(",'%'))");
+                    query.push(" ESCAPE '\\')");
                 }
                 DatabaseSearch::Tag(tag) => {
                     query.push(
-                        " AND EXISTS(SELECT id FROM tags WHERE tags.tag LIKE CONCAT('%',",
+                        " AND EXISTS(SELECT id FROM tags WHERE tags.tag = ",
                     );
                     query.push_bind(tag);
-                    query.push(",'%') AND tags.entry_id = entries.id)");
+                    query.push(
+                        " COLLATE NOCASE AND tags.entry_id = entries.id)",
+                    );
+                }
+                DatabaseSearch::TagSubstring(tag) => {
+                    query.push(
+                        " AND EXISTS(SELECT id FROM tags WHERE tags.tag LIKE ",
+                    );
+                    query.push_bind(Database::like_pattern(tag));
+                    query.push(" ESCAPE '\\' AND tags.entry_id = entries.id)");
                 }
                 DatabaseSearch::NotTag(tag) => {
                     query.push(
-                        " AND NOT EXISTS(SELECT id FROM tags WHERE tags.tag LIKE CONCAT('%',",
+                        " AND NOT EXISTS(SELECT id FROM tags WHERE tags.tag = ",
                     );
                     query.push_bind(tag);
-                    query.push(",'%') AND tags.entry_id = entries.id)");
+                    query.push(
+                        " COLLATE NOCASE AND tags.entry_id = entries.id)",
+                    );
                 }
                 DatabaseSearch::Feed(feed) => {
-                    query.push(" AND EXISTS(SELECT id FROM tags WHERE sources.source LIKE CONCAT('%',");
+                    query.push(" AND EXISTS(SELECT id FROM sources WHERE sources.source = ");
                     query.push_bind(feed);
-                    query.push(",'%') AND sources.entry_id = entries.id)");
+                    query.push(
+                        " COLLATE NOCASE AND sources.entry_id = entries.id)",
+                    );
+                }
+                DatabaseSearch::FeedSubstring(feed) => {
+                    query.push(" AND EXISTS(SELECT id FROM sources WHERE sources.source LIKE ");
+                    query.push_bind(Database::like_pattern(feed));
+                    query.push(
+                        " ESCAPE '\\' AND sources.entry_id = entries.id)",
+                    );
                 }
                 DatabaseSearch::NotFeed(feed) => {
-                    query.push(" AND NOT EXISTS(SELECT id FROM tags WHERE sources.source LIKE CONCAT('%',");
+                    query.push(" AND NOT EXISTS(SELECT id FROM sources WHERE sources.source = ");
                     query.push_bind(feed);
-                    query.push(",'%') AND sources.entry_id = entries.id)");
+                    query.push(
+                        " COLLATE NOCASE AND sources.entry_id = entries.id)",
+                    );
                 }
                 DatabaseSearch::Command(command) => {
-                    query.push(" AND EXISTS(SELECT id FROM commands WHERE commands.name LIKE CONCAT('%',");
-                    query.push_bind(command);
-                    query.push(",'%') AND commands.entry_id = entries.id)");
+                    query.push(" AND EXISTS(SELECT id FROM commands WHERE commands.name LIKE ");
+                    query.push_bind(Database::like_pattern(command));
+                    query.push(
+                        " ESCAPE '\\' AND commands.entry_id = entries.id)",
+                    );
                 }
                 DatabaseSearch::NotCommand(command) => {
-                    query.push(" AND NOT EXISTS(SELECT id FROM commands WHERE commands.name LIKE CONCAT('%',");
-                    query.push_bind(command);
-                    query.push(",'%') AND commands.entry_id = entries.id)");
+                    query.push(" AND NOT EXISTS(SELECT id FROM commands WHERE commands.name LIKE ");
+                    query.push_bind(Database::like_pattern(command));
+                    query.push(
+                        " ESCAPE '\\' AND commands.entry_id = entries.id)",
+                    );
+                }
+                DatabaseSearch::Unread => {
+                    query.push(" AND COALESCE(flags.read, 0) = 0");
+                }
+                DatabaseSearch::Saved => {
+                    query.push(" AND COALESCE(flags.saved, 0) = 1");
+                }
+                DatabaseSearch::Id(id) => {
+                    query.push(" AND entries.id = ");
+                    query.push_bind(*id);
                 }
             };
         }
         match cursor {
             OffsetCursor::LatestTimestamp => {}
             OffsetCursor::LatestId => {}
-            OffsetCursor::Before(dt) => {
-                query.push(" AND entries.timestamp < unixepoch(");
-                query.push_bind(dt.to_chrono());
-                query.push(")");
+            OffsetCursor::Before(dt, id) => {
+                if order == EntryOrder::Oldest {
+                    order_clause =
+                        " ORDER BY entries.timestamp DESC, entries.id DESC";
+                }
+                Database::push_page_key(&mut query, "<", &dt, id);
             }
-            OffsetCursor::After(dt) => {
-                query.push(" AND entries.timestamp > unixepoch(");
-                query.push_bind(dt.to_chrono());
-                query.push(")");
+            OffsetCursor::After(dt, id) => {
+                order_clause = " ORDER BY entries.timestamp, entries.id";
+                Database::push_page_key(&mut query, ">", &dt, id);
             }
             OffsetCursor::ModifiedAfter(dt) => {
-                order_clause = String::from(
-                    " ORDER BY entries.modified_timestamp DESC, entries.id DESC",
-                );
+                order_clause = " ORDER BY entries.modified_timestamp DESC, entries.id DESC";
                 query.push(" AND entries.modified_timestamp > unixepoch(");
                 query.push_bind(dt.to_chrono());
                 query.push(")");
@@ -476,6 +770,13 @@ impl Database {
         query.push(order_clause);
         query.push(" LIMIT ");
         query.push_bind(max_length as u32);
+        query.push(" OFFSET ");
+        query.push_bind(offset as u32);
+        if order == EntryOrder::Source {
+            query.push(
+                ") ORDER BY source_feed COLLATE NOCASE, timestamp DESC, id DESC",
+            );
+        }
 
         let query = query.build();
         tracing::trace!("Query: {}", query.sql());
@@ -542,6 +843,11 @@ impl Database {
                         }
                     }
 
+                    // Parse flags.
+                    entry.read = row.get::<bool, usize>(5);
+                    entry.important = row.get::<bool, usize>(6);
+                    entry.saved = row.get::<bool, usize>(7);
+
                     set.add(entry).ok();
                 }
             }
@@ -554,6 +860,254 @@ impl Database {
         set
     }
 
+    /// Only match entries before or after a date, in the order of
+    /// `(timestamp, id)` when the id of the entry at the date is known.
+    fn push_page_key(
+        query: &mut sqlx::QueryBuilder<'_, sqlx::Sqlite>,
+        comparison: &str,
+        dt: &slipfeed::DateTime,
+        id: Option<EntryDbId>,
+    ) {
+        query.push(format!(" AND (entries.timestamp {comparison} unixepoch("));
+        query.push_bind(dt.to_chrono());
+        query.push(")");
+        if let Some(id) = id {
+            query.push(" OR (entries.timestamp = unixepoch(");
+            query.push_bind(dt.to_chrono());
+            query.push(format!(") AND entries.id {comparison} "));
+            query.push_bind(id);
+            query.push(")");
+        }
+        query.push(")");
+    }
+
+    /// Convert search text into an fts5 query.
+    /// Each word is quoted, so operators and punctuation are matched
+    /// literally, and treated as a prefix. Words ending in punctuation, like
+    /// `50%`, already end their last token, so they aren't prefixes.
+    fn fts_query(search: &str) -> String {
+        search
+            .split_whitespace()
+            .map(|word| {
+                let quoted = format!("\"{}\"", word.replace('"', "\"\""));
+                match word.ends_with(|c: char| c.is_alphanumeric()) {
+                    true => quoted + "*",
+                    false => quoted,
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Convert text into a LIKE pattern matching it anywhere, where
+    /// wildcards in the text are escaped.
+    fn like_pattern(text: &str) -> String {
+        let escaped = text
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        format!("%{escaped}%")
+    }
+
+    /// Count the stored entries.
+    pub async fn entry_count(&self) -> u64 {
+        let res: Result<(i64,), _> =
+            sqlx::query_as("SELECT COUNT(*) FROM entries")
+                .fetch_one(&self.pool)
+                .await;
+        match res {
+            Ok((count,)) => count as u64,
+            Err(e) => {
+                tracing::error!("Failed to count entries: {}", e);
+                0
+            }
+        }
+    }
+
+    /// Close the connection, waiting for queries in progress to finish.
+    pub async fn close(&self) {
+        self.pool.close().await;
+    }
+
+    /// Count the stored entries from each feed.
+    pub async fn feed_entry_counts(&self) -> BTreeMap<String, u64> {
+        let res: Result<Vec<(String, i64)>, _> = sqlx::query_as(
+            "SELECT source, COUNT(*) FROM sources GROUP BY source",
+        )
+        .fetch_all(&self.pool)
+        .await;
+        match res {
+            Ok(counts) => counts
+                .into_iter()
+                .map(|(feed, count)| (feed, count as u64))
+                .collect(),
+            Err(e) => {
+                tracing::error!("Failed to count feed entries: {}", e);
+                BTreeMap::new()
+            }
+        }
+    }
+
+    /// Count the stored entries from each feed, with the date of the newest.
+    pub async fn feed_summaries(&self) -> BTreeMap<String, FeedSummary> {
+        let res: Result<Vec<(String, i64, Option<i64>)>, _> = sqlx::query_as(
+            "
+            SELECT
+                sources.source,
+                COUNT(DISTINCT sources.entry_id),
+                MAX(entries.timestamp)
+            FROM
+                sources
+                JOIN entries ON sources.entry_id = entries.id
+            GROUP BY sources.source
+            ",
+        )
+        .fetch_all(&self.pool)
+        .await;
+        match res {
+            Ok(summaries) => summaries
+                .into_iter()
+                .map(|(feed, count, newest)| {
+                    let summary = FeedSummary {
+                        entries: count as u64,
+                        newest: newest
+                            .and_then(|timestamp| {
+                                chrono::DateTime::from_timestamp(timestamp, 0)
+                            })
+                            .map(slipfeed::DateTime::from_chrono),
+                    };
+                    (feed, summary)
+                })
+                .collect(),
+            Err(e) => {
+                tracing::error!("Failed to summarize feeds: {}", e);
+                BTreeMap::new()
+            }
+        }
+    }
+
+    /// Count the unread entries of each feed, then of each tag.
+    pub async fn unread_counts(&self) -> Vec<UnreadCount> {
+        let feeds = self.count_unread("sources", "source").await;
+        let tags = self.count_unread("tags", "tag").await;
+        feeds
+            .into_iter()
+            .map(|(feed, unread)| UnreadCount {
+                search: DatabaseSearch::Feed(feed),
+                unread,
+            })
+            .chain(tags.into_iter().map(|(tag, unread)| UnreadCount {
+                search: DatabaseSearch::Tag(tag),
+                unread,
+            }))
+            .collect()
+    }
+
+    /// Count the unread entries with each value of a column, by name.
+    async fn count_unread(
+        &self,
+        table: &str,
+        column: &str,
+    ) -> Vec<(String, u64)> {
+        let res: Result<Vec<(String, i64)>, _> = sqlx::query_as(&format!(
+            "
+            SELECT
+                {table}.{column},
+                COUNT(DISTINCT CASE WHEN COALESCE(flags.read, 0) = 0
+                    THEN {table}.entry_id END)
+            FROM
+                {table}
+                LEFT JOIN flags ON {table}.entry_id = flags.entry_id
+            GROUP BY {table}.{column}
+            ORDER BY {table}.{column} COLLATE NOCASE
+            "
+        ))
+        .fetch_all(&self.pool)
+        .await;
+        match res {
+            Ok(counts) => counts
+                .into_iter()
+                .map(|(name, count)| (name, count as u64))
+                .collect(),
+            Err(e) => {
+                tracing::error!("Failed to count unread entries: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Get the state stored for the feed at a url.
+    /// Missing or unreadable state is ignored, so the feed is fetched fresh.
+    pub async fn feed_state(&self, url: &str) -> Option<slipfeed::FeedState> {
+        let res: Result<Option<(Option<i64>, Option<String>)>, _> =
+            sqlx::query_as(
+                "SELECT last_update, etag FROM feed_state WHERE url = ?",
+            )
+            .bind(url)
+            .fetch_optional(&self.pool)
+            .await;
+        match res {
+            Ok(Some((last_update, etag))) => Some(slipfeed::FeedState {
+                last_update: last_update
+                    .and_then(|timestamp| {
+                        chrono::DateTime::from_timestamp(timestamp, 0)
+                    })
+                    .map(slipfeed::DateTime::from_chrono),
+                etag,
+            }),
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!("Failed to read feed state for {url}: {e}");
+                None
+            }
+        }
+    }
+
+    /// Store the state of the feed at a url.
+    pub async fn store_feed_state(
+        &self,
+        url: &str,
+        state: &slipfeed::FeedState,
+    ) {
+        let res = sqlx::query(
+            "
+            INSERT INTO feed_state (url, last_update, etag) VALUES(?, ?, ?)
+                ON CONFLICT(url) DO UPDATE SET
+                    last_update = excluded.last_update,
+                    etag = excluded.etag
+            ",
+        )
+        .bind(url)
+        .bind(
+            state
+                .last_update
+                .as_ref()
+                .map(|last_update| last_update.to_chrono().timestamp()),
+        )
+        .bind(&state.etag)
+        .execute(&self.pool)
+        .await;
+
+        if let Err(e) = res {
+            tracing::error!("Failed to store feed state for {url}: {e}");
+        }
+    }
+
+    /// Get every distinct tag, sorted.
+    pub async fn all_tags(&self) -> Vec<String> {
+        let res: Result<Vec<(String,)>, _> =
+            sqlx::query_as("SELECT DISTINCT tag FROM tags ORDER BY tag")
+                .fetch_all(&self.pool)
+                .await;
+        match res {
+            Ok(tags) => tags.into_iter().map(|(tag,)| tag).collect(),
+            Err(e) => {
+                tracing::error!("Failed to get tags: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
     pub async fn update_tags(
         &self,
         entry_id: EntryDbId,
@@ -581,6 +1135,111 @@ impl Database {
         }
     }
 
+    pub async fn toggle_read(&self, entry_id: EntryDbId, read: bool) {
+        let res = sqlx::query(
+            "
+            INSERT INTO flags (entry_id, read) VALUES(?, ?)
+                ON CONFLICT(entry_id) DO UPDATE SET read = excluded.read
+            ",
+        )
+        .bind(entry_id)
+        .bind(read)
+        .execute(&self.pool)
+        .await;
+
+        if let Err(e) = res {
+            tracing::error!("Failed to update read flag: {}", e);
+        }
+    }
+
+    /// Set whether many entries have been read, in a single transaction.
+    pub async fn toggle_read_many(&self, entry_ids: &[EntryDbId], read: bool) {
+        let res: std::result::Result<(), sqlx::Error> = async {
+            let mut tx = self.pool.begin().await?;
+            for entry_id in entry_ids {
+                sqlx::query(
+                    "
+                    INSERT INTO flags (entry_id, read) VALUES(?, ?)
+                        ON CONFLICT(entry_id) DO UPDATE SET read = excluded.read
+                    ",
+                )
+                .bind(entry_id)
+                .bind(read)
+                .execute(&mut *tx)
+                .await?;
+            }
+            tx.commit().await
+        }
+        .await;
+
+        if let Err(e) = res {
+            tracing::error!("Failed to update read flags: {}", e);
+        }
+    }
+
+    pub async fn toggle_important(&self, entry_id: EntryDbId, important: bool) {
+        let res = sqlx::query(
+            "
+            INSERT INTO flags (entry_id, important) VALUES(?, ?)
+                ON CONFLICT(entry_id) DO UPDATE SET important = excluded.important
+            ",
+        )
+        .bind(entry_id)
+        .bind(important)
+        .execute(&self.pool)
+        .await;
+
+        if let Err(e) = res {
+            tracing::error!("Failed to update important flag: {}", e);
+        }
+    }
+
+    pub async fn toggle_saved(&self, entry_id: EntryDbId, saved: bool) {
+        let res = sqlx::query(
+            "
+            INSERT INTO flags (entry_id, saved) VALUES(?, ?)
+                ON CONFLICT(entry_id) DO UPDATE SET saved = excluded.saved
+            ",
+        )
+        .bind(entry_id)
+        .bind(saved)
+        .execute(&self.pool)
+        .await;
+
+        if let Err(e) = res {
+            tracing::error!("Failed to update saved flag: {}", e);
+        }
+    }
+
+    /// Add tags to every entry from a source, returning the number of tags
+    /// added.
+    pub async fn add_source_tags(
+        &self,
+        source: &str,
+        tags: &[slipfeed::Tag],
+    ) -> u64 {
+        let mut added = 0;
+        for tag in tags {
+            let res = sqlx::query(
+                "
+                INSERT INTO tags (entry_id, tag)
+                SELECT entry_id, ? FROM sources WHERE source = ?
+                ON CONFLICT DO NOTHING
+                ",
+            )
+            .bind(String::from(tag))
+            .bind(source)
+            .execute(&self.pool)
+            .await;
+
+            match res {
+                Ok(res) => added += res.rows_affected(),
+                Err(e) => tracing::error!("Failed to add source tag: {}", e),
+            }
+        }
+        added
+    }
+
     pub async fn store_command_result(
         &self,
         entry_id: EntryDbId,
@@ -602,6 +1261,12 @@ impl Database {
     }
 }
 
+/// Whether a query parameter only tracks where a link was shared.
+fn is_tracking_param(key: &str) -> bool {
+    key.starts_with("utm_")
+        || matches!(key, "fbclid" | "gclid" | "mc_cid" | "mc_eid")
+}
+
 /// Message used to communicate with the database handler.
 #[derive(Debug, Clone)]
 pub enum DatabaseSearch {
@@ -615,21 +1280,118 @@ pub enum DatabaseSearch {
     Search(String),
     /// Search where a tag is present.
     Tag(String),
+    /// Search where a tag containing text is present.
+    TagSubstring(String),
     /// Search where a tag is not present.
     NotTag(String),
     /// Search from a feed.
     Feed(String),
+    /// Search from a feed whose name contains text.
+    FeedSubstring(String),
     /// Search not from a feed.
     NotFeed(String),
     /// Search where a command has been run.
     Command(String),
     /// Search where a command has not been run.
     NotCommand(String),
+    /// Search where the entry has not been read.
+    Unread,
+    /// Search where the entry is saved.
+    Saved,
+    /// Search for a single entry.
+    Id(EntryDbId),
+}
+
+/// How searched tags and feeds match those of entries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SearchMatch {
+    /// Names equal the searched name, ignoring case.
+    #[default]
+    Exact,
+    /// Names contain the searched text.
+    Substring,
+}
+
+impl SearchMatch {
+    /// Search where a tag is present.
+    pub fn tag(&self, tag: impl Into<String>) -> DatabaseSearch {
+        match self {
+            SearchMatch::Exact => DatabaseSearch::Tag(tag.into()),
+            SearchMatch::Substring => DatabaseSearch::TagSubstring(tag.into()),
+        }
+    }
+
+    /// Search from a feed.
+    pub fn feed(&self, feed: impl Into<String>) -> DatabaseSearch {
+        match self {
+            SearchMatch::Exact => DatabaseSearch::Feed(feed.into()),
+            SearchMatch::Substring => {
+                DatabaseSearch::FeedSubstring(feed.into())
+            }
+        }
+    }
+
+    /// Search for entries with every tag in a comma-separated list.
+    pub fn tags(&self, tags: &str) -> Vec<DatabaseSearch> {
+        tags.split(',')
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(|tag| self.tag(tag))
+            .collect()
+    }
+}
+
+/// Stored entries of a feed.
+#[derive(Debug, Clone)]
+pub struct FeedSummary {
+    /// Number of stored entries.
+    pub entries: u64,
+    /// Date of the newest stored entry.
+    pub newest: Option<slipfeed::DateTime>,
+}
+
+/// Unread entries of a feed or tag.
+#[derive(Debug, Clone)]
+pub struct UnreadCount {
+    /// Search for the entries of the feed or tag.
+    pub search: DatabaseSearch,
+    /// Number of unread entries.
+    pub unread: u64,
+}
+
+impl UnreadCount {
+    /// Name of the feed, or the tag with a `#`.
+    pub fn label(&self) -> String {
+        match &self.search {
+            DatabaseSearch::Feed(feed) => feed.clone(),
+            DatabaseSearch::Tag(tag) => format!("#{tag}"),
+            search => format!("{search:?}"),
+        }
+    }
 }
 
 /// Database identifier for entries.
 pub(crate) type EntryDbId = u32;
 
+/// Result of inserting an entry into the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryInsertion {
+    /// The entry did not exist and was inserted.
+    New(EntryDbId),
+    /// The entry already existed and was updated.
+    Existing(EntryDbId),
+    /// The entry could not be inserted.
+    Failed,
+}
+
+impl EntryInsertion {
+    /// Whether or not the entry is new.
+    pub fn is_new(&self) -> bool {
+        matches!(self, EntryInsertion::New(_))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) enum SerializedEntry {
     V1(EntryV1),
@@ -654,6 +1416,10 @@ pub struct EntryV1 {
     other_links: Vec<slipfeed::Link>,
     #[serde(default = "String::default")]
     icon: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    language: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source_id: Option<String>,
 }
 
 impl From<&EntryV1> for slipfeed::Entry {
@@ -667,6 +1433,12 @@ impl From<&EntryV1> for slipfeed::Entry {
             .source(&value.source.url)
             .comments(&value.comments.url)
             .icon(&value.icon);
+        if let Some(language) = &value.language {
+            entry.language(language);
+        }
+        if let Some(source_id) = &value.source_id {
+            entry.source_id(source_id);
+        }
         for link in &value.other_links {
             entry.other_link(link.clone());
         }
@@ -688,16 +1460,56 @@ impl From<&slipfeed::Entry> for EntryV1 {
                 Some(icon) => icon.url.clone(),
                 None => String::default(),
             },
+            language: value.language().map(String::from),
+            source_id: value.source_id().map(String::from),
+        }
+    }
+}
+
+/// Order of entries found by a search.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    clap::ValueEnum,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum EntryOrder {
+    /// Newest entries first.
+    #[default]
+    Newest,
+    /// Oldest entries first.
+    Oldest,
+    /// By source feed name, newest entries first within each feed.
+    Source,
+}
+
+impl EntryOrder {
+    /// The next order to cycle through.
+    pub fn next(self) -> Self {
+        match self {
+            EntryOrder::Newest => EntryOrder::Oldest,
+            EntryOrder::Oldest => EntryOrder::Source,
+            EntryOrder::Source => EntryOrder::Newest,
         }
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub enum OffsetCursor {
+    #[default]
     LatestTimestamp,
     LatestId,
-    Before(slipfeed::DateTime),
-    After(slipfeed::DateTime),
+    /// Entries before a date, or before an entry at the date when its id is
+    /// known, so entries sharing the date aren't skipped.
+    Before(slipfeed::DateTime, Option<EntryDbId>),
+    /// Entries after a date, or after an entry at the date.
+    After(slipfeed::DateTime, Option<EntryDbId>),
     ModifiedAfter(slipfeed::DateTime),
 }
 
@@ -708,4 +1520,21 @@ impl OffsetCursor {
             None => OffsetCursor::LatestTimestamp,
         }
     }
+
+    /// Get the query parameters for a page starting at the cursor, if it
+    /// starts before or after a date.
+    pub fn page_query(&self) -> Option<String> {
+        let (key, dt, id) = match self {
+            OffsetCursor::Before(dt, id) => ("before", dt, id),
+            OffsetCursor::After(dt, id) => ("after", dt, id),
+            _ => return None,
+        };
+        let dt = dt
+            .to_chrono()
+            .to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        Some(match id {
+            Some(id) => format!("{key}={dt}&{key}_id={id}"),
+            None => format!("{key}={dt}"),
+        })
+    }
 }
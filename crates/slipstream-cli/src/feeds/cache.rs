@@ -1,61 +1,219 @@
 //! Cache.
 
+use std::sync::atomic::{AtomicU64, Ordering};
+
 use super::*;
 
 /// Cache for requests.
-pub struct Cache {
-    cache: HashMap<String, CacheEntry>,
-    duration: slipfeed::Duration,
+/// Only one entry is created at a time per key, so concurrent requests for an
+/// expired key await a single rebuild.
+pub struct Cache<T = String> {
+    cache: std::sync::Mutex<HashMap<String, CacheEntry<T>>>,
+    flights: std::sync::Mutex<HashMap<String, Arc<Mutex<()>>>>,
+    durations: CacheDurations,
+    /// Entries created before the current generation are expired.
+    generation: Arc<AtomicU64>,
 }
 
-impl Cache {
-    pub fn new(duration: slipfeed::Duration) -> Self {
+impl<T: Clone> Cache<T> {
+    pub fn new(durations: impl Into<CacheDurations>) -> Self {
         Self {
-            cache: HashMap::new(),
-            duration,
+            cache: std::sync::Mutex::new(HashMap::new()),
+            flights: std::sync::Mutex::new(HashMap::new()),
+            durations: durations.into(),
+            generation: Arc::default(),
         }
     }
 
+    /// Expire every entry whenever the generation changes, such as when new
+    /// entries are stored.
+    pub fn invalidated_by(mut self, generation: Arc<AtomicU64>) -> Self {
+        self.generation = generation;
+        self
+    }
+
     pub async fn get(
-        &mut self,
+        &self,
         // Key for the cache.
         uri: impl AsRef<str>,
         // Future to create the entry if not present.
-        create: impl Future<Output = String>,
+        create: impl Future<Output = T>,
         // Whether or not to write the result
         behavior: CacheBehavior,
-    ) -> String {
+    ) -> T {
+        self.try_get(uri, async { Ok(create.await) }, behavior)
+            .await
+    }
+
+    /// Get an entry from the cache, where failed creations are returned but
+    /// not written to the cache.
+    pub async fn try_get(
+        &self,
+        uri: impl AsRef<str>,
+        create: impl Future<Output = std::result::Result<T, T>>,
+        behavior: CacheBehavior,
+    ) -> T {
         // If skipping the cache, just return the result.
         if let CacheBehavior::Skip = behavior {
-            return create.await;
+            return match create.await {
+                Ok(entry) => entry,
+                Err(entry) => entry,
+            };
         }
 
-        let now = slipfeed::DateTime::now();
-
         // Check and use cache.
-        if let Some(entry) = self.cache.get(uri.as_ref()) {
-            if entry.creation.clone() + self.duration.clone() > now {
-                tracing::debug!("Using entry from cache.");
-                return entry.entry.clone();
-            }
+        if let Some(entry) = self.fresh(uri.as_ref()) {
+            tracing::debug!("Using entry from cache.");
+            return entry;
+        }
+
+        // Wait for any in-flight creation of the same key.
+        let flight = Flight::join(&self.flights, uri.as_ref());
+        let _creating = flight.lock.lock().await;
+        if let Some(entry) = self.fresh(uri.as_ref()) {
+            tracing::debug!("Using entry from cache after waiting.");
+            return entry;
         }
 
         // Create entry.
+        // The generation is read first, so changes while creating expire it.
         tracing::debug!("Creating new entry for cache.");
+        let generation = self.generation.load(Ordering::Acquire);
         let entry = CacheEntry {
-            creation: now,
-            entry: create.await,
+            creation: slipfeed::DateTime::now(),
+            generation,
+            entry: match create.await {
+                Ok(entry) => entry,
+                Err(entry) => return entry,
+            },
         };
-        self.cache.insert(uri.as_ref().to_string(), entry.clone());
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(uri.as_ref().to_string(), entry.clone());
         entry.entry
     }
+
+    /// Number of keys with requests creating or waiting on them.
+    #[cfg(test)]
+    pub(crate) fn in_flight(&self) -> usize {
+        self.flights.lock().unwrap().len()
+    }
+
+    /// Get an unexpired entry.
+    fn fresh(&self, uri: &str) -> Option<T> {
+        let cache = self.cache.lock().unwrap();
+        let entry = cache.get(uri)?;
+        if entry.generation != self.generation.load(Ordering::Acquire) {
+            return None;
+        }
+        match entry.creation.clone() + self.durations.get(uri).clone()
+            > slipfeed::DateTime::now()
+        {
+            true => Some(entry.entry.clone()),
+            false => None,
+        }
+    }
+}
+
+/// A request creating, or waiting on the creation of, a key.
+/// The key is removed from the flights when its last request finishes.
+struct Flight<'a> {
+    flights: &'a std::sync::Mutex<HashMap<String, Arc<Mutex<()>>>>,
+    key: String,
+    lock: Arc<Mutex<()>>,
+}
+
+impl<'a> Flight<'a> {
+    fn join(
+        flights: &'a std::sync::Mutex<HashMap<String, Arc<Mutex<()>>>>,
+        key: &str,
+    ) -> Self {
+        let lock = flights
+            .lock()
+            .unwrap()
+            .entry(key.to_string())
+            .or_default()
+            .clone();
+        Self {
+            flights,
+            key: key.to_string(),
+            lock,
+        }
+    }
+}
+
+impl Drop for Flight<'_> {
+    fn drop(&mut self) {
+        // Flights are only cloned while the map is locked, so the count can't
+        // change underneath this check.
+        let mut flights = self.flights.lock().unwrap();
+        if Arc::strong_count(&self.lock) == 2 {
+            flights.remove(&self.key);
+        }
+    }
+}
+
+/// How long cache entries last, by the uri they are cached under.
+#[derive(Clone, Debug)]
+pub struct CacheDurations {
+    /// Duration of entries without a matching route.
+    default: slipfeed::Duration,
+    /// Durations by uri prefix, longest prefix first.
+    routes: Vec<(String, slipfeed::Duration)>,
+}
+
+impl CacheDurations {
+    pub fn new(default: slipfeed::Duration) -> Self {
+        Self {
+            default,
+            routes: Vec::new(),
+        }
+    }
+
+    /// Set the duration of entries under a uri prefix.
+    pub fn route(
+        mut self,
+        prefix: impl Into<String>,
+        duration: slipfeed::Duration,
+    ) -> Self {
+        self.routes.push((prefix.into(), duration));
+        self.routes
+            .sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+        self
+    }
+
+    /// Get the duration of an entry, from the longest matching prefix.
+    /// Prefixes match whole path segments, so `/tag/go` doesn't match
+    /// `/tag/golang`.
+    pub fn get(&self, uri: &str) -> &slipfeed::Duration {
+        self.routes
+            .iter()
+            .find(|(prefix, _)| match uri.strip_prefix(prefix.as_str()) {
+                Some(rest) => {
+                    prefix.ends_with('/')
+                        || rest.is_empty()
+                        || rest.starts_with(['/', '?', '.'])
+                }
+                None => false,
+            })
+            .map(|(_, duration)| duration)
+            .unwrap_or(&self.default)
+    }
+}
+
+impl From<slipfeed::Duration> for CacheDurations {
+    fn from(default: slipfeed::Duration) -> Self {
+        Self::new(default)
+    }
 }
 
 /// An entry in the cache.
 #[derive(Clone, Debug)]
-struct CacheEntry {
+struct CacheEntry<T> {
     creation: slipfeed::DateTime,
-    entry: String,
+    generation: u64,
+    entry: T,
 }
 
 /// Behavior for utilizing cache.
@@ -22,9 +22,42 @@ pub struct FeedOptions {
     /// Custom headers for this feed.
     #[serde(default)]
     headers: BTreeMap<String, String>,
+    /// User agent for this feed, overriding any `user-agent` header.
+    #[serde(default, alias = "user-agent")]
+    user_agent: Option<String>,
     /// Feed update step (lower updates first).
     #[serde(default)]
     step: Option<usize>,
+    /// Format to parse the feed as, instead of detecting it.
+    #[serde(default, alias = "force-format")]
+    force_format: Option<slipfeed::FeedFormat>,
+    /// Charset to decode the feed with, instead of detecting it.
+    #[serde(default, alias = "force-charset")]
+    force_charset: Option<String>,
+    /// Timeout for each request to the feed.
+    #[serde(
+        default,
+        alias = "timeout",
+        alias = "request-timeout",
+        with = "humantime_serde::option"
+    )]
+    request_timeout: Option<std::time::Duration>,
+    /// Whether aggregate feeds merge identical entries from their feeds.
+    #[serde(default)]
+    dedup: Option<bool>,
+    /// Maximum pages fetched per update, for feeds that page (mastodon).
+    #[serde(default, alias = "max-pages")]
+    pages: Option<usize>,
+    /// Whether to only keep titles, discarding content and extra links.
+    #[serde(default, alias = "titles-only")]
+    titles_only: Option<bool>,
+    /// Largest body read from the feed, in bytes.
+    #[serde(default, alias = "max-body-size")]
+    max_body_size: Option<usize>,
+    /// Whether to replace entry content with the full article from its
+    /// source.
+    #[serde(default, alias = "full-content")]
+    full_content: Option<bool>,
 }
 
 impl FeedOptions {
@@ -39,13 +72,6 @@ impl FeedOptions {
         }
     }
 
-    pub fn freq_or_default(&self) -> slipfeed::Duration {
-        match self.freq {
-            Some(freq) => slipfeed::Duration::from_std(freq),
-            None => FeedOptions::default_freq(),
-        }
-    }
-
     pub fn oldest(&self) -> slipfeed::Duration {
         match self.oldest {
             Some(oldest) => slipfeed::Duration::from_std(oldest),
@@ -69,11 +95,48 @@ impl FeedOptions {
         &self.headers
     }
 
+    pub fn user_agent(&self) -> Option<&String> {
+        self.user_agent.as_ref()
+    }
+
+    pub fn force_format(&self) -> Option<slipfeed::FeedFormat> {
+        self.force_format
+    }
+
+    pub fn force_charset(&self) -> Option<&String> {
+        self.force_charset.as_ref()
+    }
+
+    pub fn request_timeout(&self) -> Option<slipfeed::Duration> {
+        self.request_timeout.map(slipfeed::Duration::from_std)
+    }
+
+    pub fn dedup(&self) -> bool {
+        self.dedup.unwrap_or(true)
+    }
+
+    pub fn pages(&self) -> Option<usize> {
+        self.pages
+    }
+
+    pub fn titles_only(&self) -> bool {
+        self.titles_only.unwrap_or(false)
+    }
+
+    pub fn max_body_size(&self) -> usize {
+        self.max_body_size
+            .unwrap_or(slipfeed::DEFAULT_MAX_BODY_SIZE)
+    }
+
+    pub fn full_content(&self) -> bool {
+        self.full_content.unwrap_or(false)
+    }
+
     pub fn too_old(&self, dt: &slipfeed::DateTime) -> bool {
         slipfeed::DateTime::now() > dt.clone() + self.oldest()
     }
 
-    fn default_freq() -> slipfeed::Duration {
+    pub fn default_freq() -> slipfeed::Duration {
         slipfeed::Duration::from_seconds(7200)
     }
 
@@ -102,6 +165,33 @@ impl FeedOptions {
         if let Some(step) = &other.step {
             self.step = Some(step.clone());
         }
+        if let Some(force_format) = &other.force_format {
+            self.force_format = Some(*force_format);
+        }
+        if let Some(force_charset) = &other.force_charset {
+            self.force_charset = Some(force_charset.clone());
+        }
+        if let Some(request_timeout) = &other.request_timeout {
+            self.request_timeout = Some(*request_timeout);
+        }
+        if let Some(user_agent) = &other.user_agent {
+            self.user_agent = Some(user_agent.clone());
+        }
+        if let Some(dedup) = &other.dedup {
+            self.dedup = Some(*dedup);
+        }
+        if let Some(pages) = &other.pages {
+            self.pages = Some(*pages);
+        }
+        if let Some(titles_only) = &other.titles_only {
+            self.titles_only = Some(*titles_only);
+        }
+        if let Some(max_body_size) = &other.max_body_size {
+            self.max_body_size = Some(*max_body_size);
+        }
+        if let Some(full_content) = &other.full_content {
+            self.full_content = Some(*full_content);
+        }
         self.keep_empty = other.keep_empty;
         self.apply_tags = other.apply_tags;
         for (header, value) in &other.headers {
@@ -120,6 +210,15 @@ impl Default for FeedOptions {
             keep_empty: Self::default_keep_empty(),
             apply_tags: Self::default_apply_tags(),
             headers: BTreeMap::new(),
+            user_agent: None,
+            force_format: None,
+            force_charset: None,
+            request_timeout: None,
+            dedup: None,
+            pages: None,
+            titles_only: None,
+            max_body_size: None,
+            full_content: None,
         }
     }
 }
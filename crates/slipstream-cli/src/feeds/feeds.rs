@@ -12,6 +12,13 @@ pub struct FeedDefinition {
     filters: Filters,
     #[serde(default, flatten)]
     options: FeedOptions,
+    /// Display order (lower first). Feeds with equal priority are ordered by
+    /// name.
+    #[serde(default, alias = "order")]
+    priority: Option<i64>,
+    /// Rule that marks new entries from this feed important.
+    #[serde(default)]
+    important: Option<ImportanceRule>,
 }
 
 impl FeedDefinition {
@@ -22,6 +29,8 @@ impl FeedDefinition {
             tags: None,
             filters: Filters::default(),
             options: FeedOptions::default(),
+            priority: None,
+            important: None,
         }
     }
 
@@ -33,6 +42,12 @@ impl FeedDefinition {
         &self.tags
     }
 
+    /// Set the tags of a feed.
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = Some(tags);
+        self
+    }
+
     pub fn filters(&self) -> &Filters {
         &self.filters
     }
@@ -40,19 +55,57 @@ impl FeedDefinition {
     pub fn options(&self) -> &FeedOptions {
         &self.options
     }
+
+    pub fn priority(&self) -> i64 {
+        self.priority.unwrap_or(0)
+    }
+
+    pub fn important(&self) -> Option<&ImportanceRule> {
+        self.important.as_ref()
+    }
+
+    /// Check if this gathers entries from other feeds.
+    pub fn is_aggregate(&self) -> bool {
+        matches!(
+            self.feed,
+            RawFeed::Aggregate { .. } | RawFeed::AggregateTag { .. }
+        )
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum RawFeed {
+    Json {
+        url: String,
+        #[serde(rename = "type")]
+        feed_type: JsonFeedType,
+    },
     Raw {
         url: String,
     },
+    YouTube {
+        /// Channel url, `@handle`, or `UC...` id.
+        #[serde(alias = "youtube")]
+        channel: String,
+    },
+    Reddit {
+        /// Subreddit name, like `rust` or `r/rust`.
+        subreddit: String,
+        /// Listing sort (default hot).
+        #[serde(default)]
+        sort: Option<slipfeed::RedditSort>,
+    },
     Aggregate {
         feeds: Vec<String>,
     },
+    /// Every entry, from any feed, carrying one of the allowed tags.
     AggregateTag {
+        /// Tags that add an entry. If empty, every entry is added.
+        #[serde(alias = "tag-allowlist")]
         tag_allowlist: Vec<String>,
+        /// Tags that keep an entry out, even if it has an allowed tag.
+        #[serde(default, alias = "tag-blocklist")]
         tag_blocklist: Vec<String>,
     },
     MastodonStatuses {
@@ -67,9 +120,67 @@ pub enum RawFeed {
         user: String,
         token: Option<String>,
     },
+    MastodonHashtag {
+        mastodon: String,
+        /// Hashtag, with or without the `#`.
+        hashtag: String,
+        token: Option<String>,
+    },
+    MastodonList {
+        mastodon: String,
+        /// List id. Lists are private, so a token is required.
+        #[serde(alias = "list-id", alias = "list_id")]
+        list: String,
+        token: Option<String>,
+    },
+}
+
+impl RawFeed {
+    /// Get the name of the feed's type.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RawFeed::Raw { .. } => "feed",
+            RawFeed::Json { .. } => "json",
+            RawFeed::YouTube { .. } => "youtube",
+            RawFeed::Reddit { .. } => "reddit",
+            RawFeed::Aggregate { .. } => "aggregate",
+            RawFeed::AggregateTag { .. } => "aggregate-tag",
+            RawFeed::MastodonStatuses { .. }
+            | RawFeed::MastodonUserStatuses { .. }
+            | RawFeed::MastodonHashtag { .. }
+            | RawFeed::MastodonList { .. } => "mastodon",
+        }
+    }
+
+    /// Get the url the feed is read from, if it has one.
+    pub fn url(&self) -> Option<String> {
+        match self {
+            RawFeed::Raw { url } | RawFeed::Json { url, .. } => {
+                Some(url.clone())
+            }
+            RawFeed::YouTube { channel } => Some(channel.clone()),
+            RawFeed::Reddit { subreddit, sort } => {
+                Some(slipfeed::RedditFeed::listing_url(
+                    subreddit,
+                    sort.unwrap_or_default(),
+                ))
+            }
+            RawFeed::Aggregate { .. } | RawFeed::AggregateTag { .. } => None,
+            RawFeed::MastodonStatuses { mastodon, .. }
+            | RawFeed::MastodonUserStatuses { mastodon, .. }
+            | RawFeed::MastodonHashtag { mastodon, .. }
+            | RawFeed::MastodonList { mastodon, .. } => Some(mastodon.clone()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum JsonFeedType {
+    #[serde(rename = "json", alias = "json-feed", alias = "jsonfeed")]
+    Json,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum MastodonFeedType {
     #[serde(alias = "public-timeline", alias = "public")]
     PublicTimeline,
@@ -92,13 +203,84 @@ impl From<&MastodonFeedType> for slipfeed::MastodonFeedType {
 
 pub trait EntryExt {
     fn to_atom(&self, config: &Config) -> atom::Entry;
+    fn to_rss(&self, config: &Config) -> rss::Item;
+}
+
+/// Format of syndicated feeds.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum SyndicationFormat {
+    #[default]
+    Atom,
+    Rss,
+}
+
+/// Title of a syndicated entry.
+fn syndication_title(entry: &slipfeed::Entry, config: &Config) -> String {
+    if config.serve.show_source_in_title && !entry.feeds().is_empty() {
+        return format!(
+            "[{}] {}",
+            entry
+                .feeds()
+                .iter()
+                .map(|f| (*f.name).clone())
+                .collect::<Vec<String>>()
+                .join(", "),
+            entry.title()
+        );
+    }
+    entry.title().clone()
+}
+
+/// Maximum characters in a syndicated summary.
+const SUMMARY_LENGTH: usize = 280;
+
+/// Short plain-text summary of an entry's content.
+/// Html is stripped first, so tags are never cut in half.
+fn syndication_summary(entry: &slipfeed::Entry) -> String {
+    let text = strip_html(entry.content());
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= SUMMARY_LENGTH {
+        return text;
+    }
+    let mut summary: String = text.chars().take(SUMMARY_LENGTH).collect();
+    // Don't cut words in half.
+    if let Some(end) = summary.rfind(' ') {
+        summary.truncate(end);
+    }
+    summary.push('…');
+    summary
+}
+
+/// Block-level html tags, which separate the words around them.
+static BLOCK_TAGS: std::sync::LazyLock<regex::Regex> = std::sync::LazyLock::new(
+    || {
+        regex::Regex::new(
+            r"(?i)</?(p|div|br|li|ul|ol|h[1-6]|blockquote|pre|tr|td|th)\b[^>]*>",
+        )
+        .unwrap()
+    },
+);
+
+/// Strip html tags from text, keeping the text a reader would see.
+fn strip_html(content: &str) -> String {
+    let content = BLOCK_TAGS.replace_all(content, "$0 ");
+    ammonia::Builder::empty()
+        .clean_content_tags(["script", "style"].into())
+        .clean(&content)
+        .to_string()
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
 }
 
 impl EntryExt for slipfeed::Entry {
     fn to_atom(&self, config: &Config) -> atom::Entry {
         let mut atom_entry = atom::EntryBuilder::default();
         atom_entry
-            .summary(Some(self.content().clone().into()))
             // .published(Some(self.date().clone().to_chrono()))
             .updated(self.date().clone().to_chrono())
             .author(
@@ -107,45 +289,35 @@ impl EntryExt for slipfeed::Entry {
                     .build(),
             );
 
-        // Content can either be html or markdown.
-        match config.serve.export_format {
-            ExportFormat::HTML => {
-                atom_entry.content(atom::Content {
-                    base: None,
-                    lang: None,
-                    value: Some(markdown::to_html(self.content().as_str())),
-                    src: None,
-                    content_type: Some("html".into()),
+        // Summaries are plain text and full content can either be html or
+        // markdown.
+        match config.serve.content_in_feed {
+            ContentInFeed::Full => {
+                atom_entry.summary(Some(self.content().clone().into()));
+                atom_entry.content(match config.serve.export_format {
+                    ExportFormat::HTML => atom::Content {
+                        base: None,
+                        lang: self.language().map(String::from),
+                        value: Some(markdown::to_html(self.content().as_str())),
+                        src: None,
+                        content_type: Some("html".into()),
+                    },
+                    ExportFormat::Markdown => atom::Content {
+                        base: None,
+                        lang: self.language().map(String::from),
+                        value: Some(self.content().clone()),
+                        src: None,
+                        content_type: Some("text".into()),
+                    },
                 });
             }
-            ExportFormat::Markdown => {
-                atom_entry.content(atom::Content {
-                    base: None,
-                    lang: None,
-                    value: Some(self.content().clone()),
-                    src: None,
-                    content_type: Some("text".into()),
-                });
+            ContentInFeed::Summary => {
+                atom_entry.summary(Some(syndication_summary(self).into()));
             }
+            ContentInFeed::None => {}
         }
 
-        if config.serve.show_source_in_title {
-            if self.feeds().len() > 0 {
-                atom_entry.title(format!(
-                    "[{}] {}",
-                    self.feeds()
-                        .iter()
-                        .map(|f| (*f.name).clone())
-                        .collect::<Vec<String>>()
-                        .join(", "),
-                    self.title()
-                ));
-            } else {
-                atom_entry.title(self.title().clone());
-            }
-        } else {
-            atom_entry.title(self.title().clone());
-        }
+        atom_entry.title(syndication_title(self, config));
         if self.source().url != "" {
             atom_entry.link(
                 atom::LinkBuilder::default()
@@ -170,6 +342,7 @@ impl EntryExt for slipfeed::Entry {
                     .href(&link.url)
                     .title(Some(link.title.clone()))
                     .mime_type(link.mime_type.clone())
+                    .length(link.length.map(|length| length.to_string()))
                     .build(),
             );
         }
@@ -197,6 +370,53 @@ impl EntryExt for slipfeed::Entry {
 
         atom_entry.build()
     }
+
+    fn to_rss(&self, config: &Config) -> rss::Item {
+        let mut rss_item = rss::ItemBuilder::default();
+        rss_item
+            .title(Some(syndication_title(self, config)))
+            .description(match config.serve.content_in_feed {
+                ContentInFeed::Full => Some(match config.serve.export_format {
+                    ExportFormat::HTML => {
+                        markdown::to_html(self.content().as_str())
+                    }
+                    ExportFormat::Markdown => self.content().clone(),
+                }),
+                ContentInFeed::Summary => Some(syndication_summary(self)),
+                ContentInFeed::None => None,
+            })
+            .pub_date(Some(self.date().to_chrono().to_rfc2822()));
+        if !self.author().is_empty() {
+            rss_item.author(Some(self.author().clone()));
+        }
+        if !self.source().url.is_empty() {
+            rss_item.link(Some(self.source().url.clone()));
+        }
+        if !self.comments().url.is_empty() {
+            rss_item.comments(Some(self.comments().url.clone()));
+        }
+
+        // Add tags.
+        for tag in self.tags() {
+            rss_item.category(
+                rss::CategoryBuilder::default()
+                    .name(String::from(tag))
+                    .build(),
+            );
+        }
+
+        // Use original id.
+        if let Some(source_id) = self.source_id() {
+            rss_item.guid(Some(
+                rss::GuidBuilder::default()
+                    .value(source_id)
+                    .permalink(false)
+                    .build(),
+            ));
+        }
+
+        rss_item.build()
+    }
 }
 
 pub use slipfeed::StandardSyndication as StandardFeed;
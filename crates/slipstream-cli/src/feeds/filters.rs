@@ -22,6 +22,24 @@ pub struct Filters {
     pub include_tags: Option<Vec<String>>,
     #[serde(alias = "include-tags-strict")]
     pub include_tags_strict: Option<Vec<String>>,
+    #[serde(default)]
+    pub regex: Option<Vec<RegexFilter>>,
+    /// Languages (ISO 639 codes) to keep. Entries of unknown language are
+    /// kept.
+    #[serde(default, alias = "language")]
+    pub lang: Option<Vec<String>>,
+    /// Languages (ISO 639 codes) to drop.
+    #[serde(default, alias = "exclude-lang", alias = "exclude-language")]
+    pub exclude_lang: Option<Vec<String>>,
+    /// Groups where at least one must pass.
+    #[serde(default)]
+    pub any: Option<Vec<Filters>>,
+    /// Groups where all must pass.
+    #[serde(default)]
+    pub all: Option<Vec<Filters>>,
+    /// Groups that drop entries passing any of them.
+    #[serde(default)]
+    pub not: Option<Vec<Filters>>,
 }
 
 impl Filters {
@@ -59,8 +77,43 @@ impl Filters {
         if let Some(filter) = include_tags_strict(&self.include_tags_strict) {
             filters.push(filter);
         }
+        if let Some(regexes) = &self.regex {
+            filters.extend(regexes.iter().map(RegexFilter::get_filter));
+        }
+        if let Some(filter) = include_languages(&self.lang) {
+            filters.push(filter);
+        }
+        if let Some(filter) = exclude_languages(&self.exclude_lang) {
+            filters.push(filter);
+        }
+        if let Some(groups) = &self.any {
+            let groups = Filters::get_groups(groups);
+            filters.push(Arc::new(move |feed, entry| {
+                groups.iter().any(|group| passes_all(group, feed, entry))
+            }));
+        }
+        if let Some(groups) = &self.all {
+            let groups = Filters::get_groups(groups);
+            filters.push(Arc::new(move |feed, entry| {
+                groups.iter().all(|group| passes_all(group, feed, entry))
+            }));
+        }
+        if let Some(groups) = &self.not {
+            // Empty groups have nothing to exclude, rather than matching
+            // every entry.
+            let mut groups = Filters::get_groups(groups);
+            groups.retain(|group| !group.is_empty());
+            filters.push(Arc::new(move |feed, entry| {
+                !groups.iter().any(|group| passes_all(group, feed, entry))
+            }));
+        }
         filters
     }
+
+    /// Get the filters of each group.
+    fn get_groups(groups: &[Filters]) -> Vec<Vec<slipfeed::Filter>> {
+        groups.iter().map(Filters::get_filters).collect()
+    }
 }
 
 impl Default for Filters {
@@ -75,10 +128,98 @@ impl Default for Filters {
             exclude_tags_strict: None,
             include_tags: None,
             include_tags_strict: None,
+            regex: None,
+            lang: None,
+            exclude_lang: None,
+            any: None,
+            all: None,
+            not: None,
         }
     }
 }
 
+/// Check if an entry passes all filters of a group.
+fn passes_all(
+    filters: &[slipfeed::Filter],
+    feed: &dyn slipfeed::Feed,
+    entry: &slipfeed::Entry,
+) -> bool {
+    filters.iter().all(|filter| filter(feed, entry))
+}
+
+/// A filter that matches a field of an entry against a regex.
+/// Entries must match unless `exclude` is set, in which case matching entries
+/// are dropped.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RegexFilter {
+    pub regex: RegexPattern,
+    #[serde(default)]
+    pub field: RegexField,
+    #[serde(default)]
+    pub exclude: bool,
+}
+
+impl RegexFilter {
+    fn get_filter(&self) -> slipfeed::Filter {
+        let regex = self.regex.0.clone();
+        let field = self.field;
+        let exclude = self.exclude;
+        Arc::new(move |_feed, entry| {
+            let text = match field {
+                RegexField::Title => entry.title(),
+                RegexField::Content => entry.content(),
+                RegexField::Author => entry.author(),
+            };
+            regex.is_match(text) != exclude
+        })
+    }
+}
+
+/// Entry field matched by a regex filter.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RegexField {
+    #[default]
+    Title,
+    Content,
+    Author,
+}
+
+/// A regex, compiled when the config is loaded.
+#[derive(Clone, Debug)]
+pub struct RegexPattern(regex::Regex);
+
+impl RegexPattern {
+    /// Check whether the regex matches text.
+    pub fn is_match(&self, text: &str) -> bool {
+        self.0.is_match(text)
+    }
+}
+
+impl Serialize for RegexPattern {
+    fn serialize<S>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.0.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for RegexPattern {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let pattern = String::deserialize(deserializer)?;
+        regex::Regex::new(&pattern)
+            .map(RegexPattern)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
 fn exclude_title_words(
     exclusions: &Option<Vec<String>>,
 ) -> Option<slipfeed::Filter> {
@@ -265,3 +406,35 @@ fn include_tags_strict(
     }
     None
 }
+
+fn include_languages(
+    inclusions: &Option<Vec<String>>,
+) -> Option<slipfeed::Filter> {
+    if let Some(inclusions) = inclusions {
+        let inclusions: Vec<String> = inclusions
+            .iter()
+            .map(slipfeed::normalize_language)
+            .collect();
+        return Some(Arc::new(move |_feed, entry| match entry.language() {
+            Some(language) => inclusions.iter().any(|inc| inc == language),
+            None => true,
+        }));
+    }
+    None
+}
+
+fn exclude_languages(
+    exclusions: &Option<Vec<String>>,
+) -> Option<slipfeed::Filter> {
+    if let Some(exclusions) = exclusions {
+        let exclusions: Vec<String> = exclusions
+            .iter()
+            .map(slipfeed::normalize_language)
+            .collect();
+        return Some(Arc::new(move |_feed, entry| match entry.language() {
+            Some(language) => exclusions.iter().all(|exc| exc != language),
+            None => true,
+        }));
+    }
+    None
+}
@@ -0,0 +1,48 @@
+//! Importance rules.
+
+use super::*;
+
+/// Criteria that mark new entries important when they are stored.
+/// An entry matches if it meets any of the criteria.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ImportanceRule {
+    /// Substrings of the title or content (case-insensitive).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub substrings: Vec<String>,
+    /// Words in the title (case-insensitive).
+    #[serde(
+        default,
+        alias = "title-words",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub title_words: Vec<String>,
+    /// Tags of the entry.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Authors of the entry (case-insensitive).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<String>,
+}
+
+impl ImportanceRule {
+    /// Check if an entry matches the rule.
+    pub fn matches(&self, entry: &slipfeed::Entry) -> bool {
+        let title = entry.title().to_lowercase();
+        let content = entry.content().to_lowercase();
+        let author = entry.author().to_lowercase();
+
+        self.substrings.iter().any(|substring| {
+            let substring = substring.to_lowercase();
+            title.contains(&substring) || content.contains(&substring)
+        }) || self.title_words.iter().any(|word| {
+            let word = word.to_lowercase();
+            title
+                .split_whitespace()
+                .any(|title_word| title_word == word)
+        }) || self.tags.iter().any(|tag| entry.has_tag(tag))
+            || self
+                .authors
+                .iter()
+                .any(|other| other.to_lowercase() == author)
+    }
+}
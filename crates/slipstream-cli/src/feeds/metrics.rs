@@ -0,0 +1,33 @@
+//! Updater metrics.
+
+use super::*;
+
+/// Statistics kept by the update loop.
+#[derive(Clone, Debug, Default)]
+pub struct UpdateStats {
+    /// Number of updates run.
+    pub updates: u64,
+    /// When each feed was last updated successfully, by feed name.
+    pub last_successes: BTreeMap<String, slipfeed::DateTime>,
+    /// How long the latest update took.
+    pub last_update_duration: Option<slipfeed::Duration>,
+    /// Whether an update cycle has finished, even if quiet hours skipped it.
+    pub ready: bool,
+    /// Bumped whenever an update stores new entries, to expire caches.
+    pub generation: Arc<std::sync::atomic::AtomicU64>,
+}
+
+/// Metrics about stored entries and updates.
+#[derive(Clone, Debug, Default)]
+pub struct UpdaterMetrics {
+    /// Entries in the database.
+    pub entries: u64,
+    /// Entries in the database from each feed.
+    pub feed_entries: BTreeMap<String, u64>,
+    /// When each feed was last updated successfully.
+    pub feed_last_update: BTreeMap<String, slipfeed::DateTime>,
+    /// Number of updates run.
+    pub updates: u64,
+    /// How long the latest update took.
+    pub last_update_duration: Option<slipfeed::Duration>,
+}
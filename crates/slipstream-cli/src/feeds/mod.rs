@@ -6,10 +6,16 @@ mod cache;
 mod feed_options;
 mod feeds;
 mod filters;
+mod importance;
+mod metrics;
+mod quiet_hours;
 mod updater;
 
 pub use cache::*;
 pub use feed_options::*;
 pub use feeds::*;
 pub use filters::*;
+pub use importance::*;
+pub use metrics::*;
+pub use quiet_hours::*;
 pub use updater::*;
@@ -0,0 +1,101 @@
+//! Quiet hours.
+
+use super::*;
+
+use chrono::NaiveTime;
+
+/// Daily time range in which feeds aren't fetched and notifications aren't
+/// sent. Ranges past midnight (e.g. 22:00 to 07:00) wrap around.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QuietHours {
+    /// Start of quiet hours, as `HH:MM`.
+    #[serde(with = "time_of_day")]
+    pub start: NaiveTime,
+    /// End of quiet hours, as `HH:MM`.
+    #[serde(with = "time_of_day")]
+    pub end: NaiveTime,
+    /// Timezone of the range (defaults to the config's timezone).
+    #[serde(default, alias = "time-zone", alias = "tz")]
+    pub timezone: Option<TimeZone>,
+    /// Whether notifications are held until quiet hours end, instead of
+    /// being dropped (default true).
+    #[serde(default)]
+    pub queue: Option<bool>,
+}
+
+impl QuietHours {
+    /// Check if a time is within quiet hours.
+    pub fn contains(
+        &self,
+        now: &slipfeed::DateTime,
+        default_timezone: &TimeZone,
+    ) -> bool {
+        let time = self
+            .timezone
+            .as_ref()
+            .unwrap_or(default_timezone)
+            .time_of_day(now);
+        match self.start <= self.end {
+            true => self.start <= time && time < self.end,
+            false => self.start <= time || time < self.end,
+        }
+    }
+
+    /// Time left until quiet hours end.
+    pub fn remaining(
+        &self,
+        now: &slipfeed::DateTime,
+        default_timezone: &TimeZone,
+    ) -> std::time::Duration {
+        let time = self
+            .timezone
+            .as_ref()
+            .unwrap_or(default_timezone)
+            .time_of_day(now);
+        let remaining = match self.end > time {
+            true => self.end - time,
+            false => chrono::TimeDelta::days(1) - (time - self.end),
+        };
+        remaining.to_std().unwrap_or_default()
+    }
+
+    /// Whether notifications are held until quiet hours end.
+    pub fn queue(&self) -> bool {
+        self.queue.unwrap_or(true)
+    }
+}
+
+/// (De)serialize times of day as `HH:MM` or `HH:MM:SS`.
+mod time_of_day {
+    use super::*;
+
+    use chrono::Timelike;
+
+    pub fn serialize<S>(
+        time: &NaiveTime,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let format = match time.second() {
+            0 => "%H:%M",
+            _ => "%H:%M:%S",
+        };
+        serializer.serialize_str(&time.format(format).to_string())
+    }
+
+    pub fn deserialize<'de, D>(
+        deserializer: D,
+    ) -> std::result::Result<NaiveTime, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let text = String::deserialize(deserializer)?;
+        NaiveTime::parse_from_str(text.trim(), "%H:%M")
+            .or_else(|_| NaiveTime::parse_from_str(text.trim(), "%H:%M:%S"))
+            .map_err(|e| {
+                serde::de::Error::custom(format!("invalid time {text}: {e}"))
+            })
+    }
+}
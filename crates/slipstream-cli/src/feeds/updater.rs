@@ -4,55 +4,415 @@ use super::*;
 
 use tokio::sync::oneshot;
 
+/// Longest wait before checking if quiet hours are over.
+const QUIET_HOURS_RECHECK: std::time::Duration =
+    std::time::Duration::from_secs(60);
+
+/// Longest wait for fetched entries to be stored when quitting.
+const STORE_ON_QUIT_TIMEOUT: std::time::Duration =
+    std::time::Duration::from_secs(10);
+
 /// Run the slipstream updater.
 pub async fn update(
     mut updater: Updater,
-    config: Arc<Config>,
+    mut config: Arc<Config>,
     cancel_token: CancellationToken,
 ) -> Result<()> {
+    let running = Arc::new(std::sync::Mutex::new(RunningFeeds {
+        config: config.clone(),
+        feeds: updater.feeds.clone(),
+        reloaded: None,
+    }));
+
     // We don't want to cancel the updater's updater update future working on other
     // jobs. We convert this loop into a task and only cancel on quit.
-    let updater_task: tokio::task::JoinHandle<()> = {
+    let mut updater_task: tokio::task::JoinHandle<()> = {
         let entry_db = updater.entry_db.clone();
+        let clock = updater.clock.clone();
+        let stats = updater.stats.clone();
         let updater = updater.updater.clone();
+        let running = running.clone();
         let cancel_token = cancel_token.clone();
-        tokio::task::spawn(run_updater(updater, entry_db, cancel_token))
+        let notifier = Notifier::new(&config.notify)?;
+        tokio::task::spawn(run_updater(
+            updater,
+            entry_db,
+            running,
+            notifier,
+            clock,
+            stats,
+            cancel_token,
+        ))
     };
 
     // Continue updating and responding to requests until cancelled.
     'update: loop {
         tokio::select! {
-            command = updater.to_updater_receiver.recv() => {
-                if let Some(command) = command {
-                    updater.handle_command(command, &config).await;
+            command = updater.to_updater_receiver.recv() => match command {
+                Some(UpdaterRequest::Reload { tx, config: reloaded }) => {
+                    let result = updater.reload(&reloaded, &running).await;
+                    if result.is_ok() {
+                        config = reloaded;
+                    }
+                    tx.send(result).ok();
                 }
+                Some(command) => updater.handle_command(command, &config).await,
+                None => {}
             },
             _ = cancel_token.cancelled() => break 'update,
         }
     }
 
-    updater_task.abort();
+    // Let fetched entries finish being stored before closing the database.
+    if tokio::time::timeout(STORE_ON_QUIT_TIMEOUT, &mut updater_task)
+        .await
+        .is_err()
+    {
+        tracing::warn!(
+            "Stopped storing entries after {STORE_ON_QUIT_TIMEOUT:?}."
+        );
+        updater_task.abort();
+    }
+    if let Some(entry_db) = &updater.entry_db {
+        entry_db.close().await;
+    }
 
     Ok(())
 }
 
+/// Reload the config whenever SIGHUP is received.
+/// Configs that can't be read are logged and the current config is kept.
+#[cfg(unix)]
+pub async fn reload_on_hangup(
+    config_path: PathBuf,
+    handle: UpdaterHandle,
+    cancel_token: CancellationToken,
+) -> Result<()> {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut hangup = signal(SignalKind::hangup())?;
+    loop {
+        tokio::select! {
+            _ = cancel_token.cancelled() => return Ok(()),
+            Some(()) = hangup.recv() => {
+                tracing::info!("Reloading config {:?}.", config_path);
+                if let Err(e) = reload_config(&config_path, &handle).await {
+                    tracing::error!("Keeping the current config: {e}");
+                }
+            }
+        }
+    }
+}
+
+/// Reload feeds and filters from a config file.
+pub async fn reload_config(
+    config_path: &std::path::Path,
+    handle: &UpdaterHandle,
+) -> Result<()> {
+    let config = Config::from_path(config_path)?;
+    handle.reload(Arc::new(config)).await
+}
+
 async fn run_updater(
     internal_updater: Arc<RwLock<slipfeed::Updater>>,
     entry_db: Option<Arc<Database>>,
+    running: Arc<std::sync::Mutex<RunningFeeds>>,
+    mut notifier: Notifier,
+    clock: Arc<dyn slipfeed::Clock>,
+    stats: Arc<std::sync::Mutex<UpdateStats>>,
     cancel_token: CancellationToken,
 ) {
     while !cancel_token.is_cancelled() {
-        let entries = {
-            let mut slipfeed_updater = internal_updater.write().await;
-            slipfeed_updater.update().await
+        let config =
+            run_reloaded_feeds(&internal_updater, &running, &stats).await;
+        // Fetching can be dropped when quitting, but storing is finished.
+        let fetched = tokio::select! {
+            fetched = fetch_step(
+                &internal_updater,
+                &config,
+                &mut notifier,
+                &*clock,
+            ) => fetched,
+            _ = cancel_token.cancelled() => break,
+        };
+        let stored = store_step(
+            fetched,
+            entry_db.as_deref(),
+            &config,
+            &mut notifier,
+            &*clock,
+        )
+        .await;
+        let quiet = match stored {
+            Stored::Entries(new) => {
+                // Ready once feeds have been fetched and stored, not while
+                // quiet hours skip the first update.
+                stats.lock().unwrap().ready = true;
+                if new > 0 {
+                    tracing::debug!(new, "Stored new entries.");
+                    stats
+                        .lock()
+                        .unwrap()
+                        .generation
+                        .fetch_add(1, std::sync::atomic::Ordering::Release);
+                }
+                None
+            }
+            Stored::Quiet(remaining) => Some(remaining),
+        };
+        if quiet.is_none() {
+            let internal_updater = internal_updater.read().await;
+            let feeds = running.lock().unwrap().feeds.clone();
+            let mut stats = stats.lock().unwrap();
+            stats.updates += 1;
+            stats.last_successes = named_successes(&internal_updater, &feeds);
+            stats.last_update_duration =
+                internal_updater.last_update_duration().cloned();
+        }
+        if let Some(remaining) = quiet {
+            // Check again regularly, in case the clock jumps.
+            let recheck = remaining.min(QUIET_HOURS_RECHECK);
+            tokio::select! {
+                _ = tokio::time::sleep(recheck) => {},
+                _ = cancel_token.cancelled() => break,
+            }
+        }
+    }
+}
+
+/// Feeds run by the update task.
+struct RunningFeeds {
+    /// Config of the running feeds.
+    config: Arc<Config>,
+    /// Running feeds by name.
+    feeds: HashMap<String, slipfeed::FeedId>,
+    /// Feeds of a reloaded config, which replace the running feeds before the
+    /// next update.
+    reloaded: Option<ReloadedFeeds>,
+}
+
+/// Feeds of a reloaded config.
+struct ReloadedFeeds {
+    /// Config the feeds were reloaded from.
+    config: Arc<Config>,
+    /// Reloaded feeds by name.
+    feeds: HashMap<String, slipfeed::FeedId>,
+    /// Updater of the reloaded feeds.
+    updater: slipfeed::Updater,
+}
+
+/// Replace the running feeds with reloaded ones, if there are any, and get
+/// the config of the running feeds.
+/// Feeds that are unchanged keep their update state.
+async fn run_reloaded_feeds(
+    internal_updater: &RwLock<slipfeed::Updater>,
+    running: &std::sync::Mutex<RunningFeeds>,
+    stats: &std::sync::Mutex<UpdateStats>,
+) -> Arc<Config> {
+    let (reloaded, config, feeds) = {
+        let mut running = running.lock().unwrap();
+        match running.reloaded.take() {
+            Some(reloaded) => {
+                (reloaded, running.config.clone(), running.feeds.clone())
+            }
+            None => return running.config.clone(),
+        }
+    };
+
+    let ReloadedFeeds {
+        config: next_config,
+        feeds: next_feeds,
+        updater: mut next,
+    } = reloaded;
+    let mut current = internal_updater.write().await;
+    let mut unchanged = 0;
+    for (name, id) in &next_feeds {
+        let Some(previous_id) = feeds.get(name) else {
+            continue;
+        };
+        let previous_feed = config.feed(name).map(|f| f.feed());
+        if previous_feed == next_config.feed(name).map(|f| f.feed()) {
+            next.carry_over_feed(*id, &current, *previous_id).await;
+            unchanged += 1;
+        }
+    }
+    stats.lock().unwrap().last_successes = named_successes(&next, &next_feeds);
+    *current = next;
+    tracing::info!(
+        "Running {} reloaded feeds ({unchanged} unchanged).",
+        next_feeds.len()
+    );
+
+    let mut running = running.lock().unwrap();
+    running.config = next_config.clone();
+    running.feeds = next_feeds;
+    next_config
+}
+
+/// Get when each feed was last updated successfully, by feed name.
+fn named_successes(
+    slipfeed_updater: &slipfeed::Updater,
+    feeds: &HashMap<String, slipfeed::FeedId>,
+) -> BTreeMap<String, slipfeed::DateTime> {
+    feeds
+        .iter()
+        .filter_map(|(name, id)| {
+            let last_success = slipfeed_updater.last_successes().get(id)?;
+            Some((name.clone(), last_success.clone()))
+        })
+        .collect()
+}
+
+/// Update feeds, storing and notifying new entries.
+/// During quiet hours nothing is fetched or sent, and the time left until
+/// quiet hours end is returned instead.
+pub async fn update_step(
+    internal_updater: &RwLock<slipfeed::Updater>,
+    entry_db: Option<&Database>,
+    config: &Config,
+    notifier: &mut Notifier,
+    clock: &dyn slipfeed::Clock,
+) -> Option<std::time::Duration> {
+    let fetched = fetch_step(internal_updater, config, notifier, clock).await;
+    match store_step(fetched, entry_db, config, notifier, clock).await {
+        Stored::Entries(_) => None,
+        Stored::Quiet(remaining) => Some(remaining),
+    }
+}
+
+/// Entries and feed states fetched by an update, or the time left in quiet
+/// hours.
+enum Fetched {
+    Updates(slipfeed::EntrySet, Vec<(String, slipfeed::FeedState)>),
+    Quiet(std::time::Duration),
+}
+
+/// Number of new entries stored by an update, or the time left in quiet hours.
+enum Stored {
+    Entries(usize),
+    Quiet(std::time::Duration),
+}
+
+/// Fetch feeds, unless it's quiet hours.
+/// This can be dropped, such as when quitting, without losing anything.
+async fn fetch_step(
+    internal_updater: &RwLock<slipfeed::Updater>,
+    config: &Config,
+    notifier: &mut Notifier,
+    clock: &dyn slipfeed::Clock,
+) -> Fetched {
+    let now = clock.now();
+    if let Some(quiet_hours) = quiet_hours_at(config, &now) {
+        tracing::debug!("Skipping update during quiet hours.");
+        if !quiet_hours.queue() {
+            notifier.discard();
+        }
+        return Fetched::Quiet(quiet_hours.remaining(&now, &config.timezone));
+    }
+
+    // The updater stays locked while feeds and any full articles are fetched.
+    let mut slipfeed_updater = internal_updater.write().await;
+    let previous = slipfeed_updater.last_successes().clone();
+    let entries = slipfeed_updater.update().await;
+    let states = updated_feed_states(&slipfeed_updater, &previous).await;
+    Fetched::Updates(entries, states)
+}
+
+/// Get the quiet hours a time is in, if any.
+fn quiet_hours_at<'a>(
+    config: &'a Config,
+    now: &slipfeed::DateTime,
+) -> Option<&'a QuietHours> {
+    config
+        .global
+        .quiet_hours
+        .as_ref()
+        .filter(|quiet_hours| quiet_hours.contains(now, &config.timezone))
+}
+
+/// Store and notify fetched entries.
+/// Fetching can wait on feeds until quiet hours have begun, so they're
+/// checked again before notifying.
+async fn store_step(
+    fetched: Fetched,
+    entry_db: Option<&Database>,
+    config: &Config,
+    notifier: &mut Notifier,
+    clock: &dyn slipfeed::Clock,
+) -> Stored {
+    let (entries, states) = match fetched {
+        Fetched::Updates(entries, states) => (entries, states),
+        Fetched::Quiet(remaining) => return Stored::Quiet(remaining),
+    };
+    if let Some(entry_db) = entry_db {
+        for (url, state) in &states {
+            entry_db.store_feed_state(url, state).await;
+        }
+    }
+    let now = clock.now();
+    let quiet = quiet_hours_at(config, &now).map(QuietHours::queue);
+    let mut new = 0;
+    for entry in entries.as_slice() {
+        // Without a database, every entry is re-processed each update, so
+        // only the dedup window prevents repeat notifications.
+        let is_new = match entry_db {
+            Some(entry_db) => {
+                store_entry(entry_db, config, entry).await.is_new()
+            }
+            None => true,
         };
-        for entry in entries.as_slice() {
-            if let Some(entry_db) = &entry_db {
-                entry_db.insert_slipfeed_entry(entry).await;
+        if is_new {
+            new += 1;
+            if quiet != Some(false) {
+                notifier.notify(entry, &now);
             }
         }
     }
-    ()
+    match quiet {
+        Some(_) => tracing::debug!("Holding notifications during quiet hours."),
+        None => {
+            if let Err(e) = notifier.flush(&now).await {
+                tracing::warn!("Failed to send notifications: {e}");
+            }
+        }
+    }
+    Stored::Entries(new)
+}
+
+/// Get the state of feeds updated successfully since the previous successes,
+/// by url.
+async fn updated_feed_states(
+    slipfeed_updater: &slipfeed::Updater,
+    previous: &BTreeMap<slipfeed::FeedId, slipfeed::DateTime>,
+) -> Vec<(String, slipfeed::FeedState)> {
+    let mut states = Vec::new();
+    for (id, last_success) in slipfeed_updater.last_successes() {
+        if previous.get(id) == Some(last_success) {
+            continue;
+        }
+        let Some(url) = slipfeed_updater.feed_url(*id).await else {
+            continue;
+        };
+        if let Some(state) = slipfeed_updater.feed_state(*id).await {
+            states.push((url, state));
+        }
+    }
+    states
+}
+
+/// Store an entry, marking new entries important if they match a rule.
+pub async fn store_entry(
+    entry_db: &Database,
+    config: &Config,
+    entry: &slipfeed::Entry,
+) -> EntryInsertion {
+    let insertion = entry_db.insert_slipfeed_entry(entry).await;
+    if let EntryInsertion::New(id) = &insertion {
+        if config.marks_important(entry) {
+            entry_db.toggle_important(*id, true).await;
+        }
+    }
+    insertion
 }
 
 /// Slipstream updater.
@@ -70,6 +430,10 @@ pub struct Updater {
     /// The entry database.
     /// This allows persistance between slipstream sessions.
     pub entry_db: Option<Arc<Database>>,
+    /// Source of the current time.
+    pub clock: Arc<dyn slipfeed::Clock>,
+    /// Statistics about completed updates.
+    stats: Arc<std::sync::Mutex<UpdateStats>>,
     /// Handle's sender.
     to_updater_sender: Sender<UpdaterRequest>,
     /// Updater's receiver.
@@ -81,9 +445,16 @@ impl Updater {
     pub fn handle(&mut self) -> Result<UpdaterHandle> {
         Ok(UpdaterHandle {
             to_updater_sender: self.to_updater_sender.clone(),
+            stats: self.stats.clone(),
         })
     }
 
+    /// Set the clock used for updates and quiet hours.
+    pub async fn set_clock(&mut self, clock: Arc<dyn slipfeed::Clock>) {
+        self.updater.write().await.set_clock(clock.clone());
+        self.clock = clock;
+    }
+
     /// Handle command.
     async fn handle_command(
         &self,
@@ -91,11 +462,31 @@ impl Updater {
         config: &Arc<Config>,
     ) {
         match command {
-            UpdaterRequest::EntryUpdate { entry_id, tags } => {
+            UpdaterRequest::EntryUpdate {
+                entry_id,
+                tags,
+                read,
+                important,
+                saved,
+            } => {
                 if let Some(entry_db) = &self.entry_db {
                     if let Some(tags) = tags {
                         entry_db.update_tags(entry_id, tags).await;
                     }
+                    if let Some(read) = read {
+                        entry_db.toggle_read(entry_id, read).await;
+                    }
+                    if let Some(important) = important {
+                        entry_db.toggle_important(entry_id, important).await;
+                    }
+                    if let Some(saved) = saved {
+                        entry_db.toggle_saved(entry_id, saved).await;
+                    }
+                }
+            }
+            UpdaterRequest::EntriesRead { entry_ids, read } => {
+                if let Some(entry_db) = &self.entry_db {
+                    entry_db.toggle_read_many(&entry_ids, read).await;
                 }
             }
             UpdaterRequest::CommandUpdate {
@@ -119,127 +510,141 @@ impl Updater {
                 tx,
                 criteria,
                 offset,
+                order,
             } => {
                 if let Some(entry_db) = &self.entry_db {
                     // TODO: custom search count.
-                    tx.send(entry_db.get_entries(criteria, 128, offset).await)
-                        .ok();
+                    tx.send(
+                        entry_db
+                            .get_entries(criteria, 128, offset, order)
+                            .await,
+                    )
+                    .ok();
                 };
             }
-            UpdaterRequest::FeedFetch { tx, options } => {
+            UpdaterRequest::FeedFetch { tx, options, limit } => {
                 if let Some(entry_db) = &self.entry_db {
+                    let order = config.global.collection_order;
+                    let window = limit.unwrap_or(config.global.limits.max());
                     let entries = match options {
-                        FeedFetchOptions::All {
-                            since,
-                            modified_since,
-                        } => {
-                            let unfiltered_entries = entry_db
-                                .get_entries(
-                                    vec![DatabaseSearch::Latest],
-                                    config.global.limits.max(),
-                                    match (since, modified_since) {
-                                        (Some(since), None) => {
-                                            OffsetCursor::After(since)
-                                        }
-                                        (None, Some(modified_since)) => {
-                                            OffsetCursor::ModifiedAfter(
-                                                modified_since,
-                                            )
-                                        }
-                                        (Some(_), Some(_)) => {
-                                            OffsetCursor::LatestTimestamp
-                                        }
-                                        (None, None) => {
-                                            OffsetCursor::LatestTimestamp
-                                        }
-                                    },
-                                )
-                                .await;
-                            let mut entries = DatabaseEntryList::new(
-                                config.global.limits.max(),
-                            );
-                            for entry in unfiltered_entries.iter() {
-                                if config.global.limits.too_old(entry.date()) {
-                                    continue;
-                                }
-                                if !self.passes_global_filters(&entry) {
-                                    continue;
-                                }
-                                if !self.passes_all_filters(&entry) {
-                                    continue;
-                                }
-                                entries.add(entry.clone()).ok();
-                            }
-                            entries
+                        FeedFetchOptions::All { cursor } => {
+                            Updater::collect_entries(
+                                entry_db,
+                                EntryCollection {
+                                    criteria: vec![DatabaseSearch::Latest],
+                                    cursor,
+                                    window,
+                                    max: limit
+                                        .unwrap_or(config.global.limits.max()),
+                                    order,
+                                    dedup: false,
+                                },
+                                |entry| {
+                                    !config.global.limits.too_old(entry.date())
+                                        && !config.serve.too_old(entry.date())
+                                        && self.passes_global_filters(entry)
+                                        && self.passes_all_filters(entry)
+                                },
+                            )
+                            .await
+                        }
+                        FeedFetchOptions::Saved { cursor } => {
+                            // Saved entries are kept however old they are.
+                            Updater::collect_entries(
+                                entry_db,
+                                EntryCollection {
+                                    criteria: vec![DatabaseSearch::Saved],
+                                    cursor,
+                                    window,
+                                    max: limit
+                                        .unwrap_or(config.global.limits.max()),
+                                    order,
+                                    dedup: false,
+                                },
+                                |entry| self.passes_global_filters(entry),
+                            )
+                            .await
                         }
                         FeedFetchOptions::Tag {
                             tag,
-                            modified_since,
+                            matching,
+                            cursor,
                         } => {
-                            let unfiltered_entries = entry_db
-                                .get_entries(
-                                    vec![DatabaseSearch::Tag(tag)],
-                                    config.global.limits.max(),
-                                    OffsetCursor::modified_since(
-                                        modified_since,
-                                    ),
-                                )
-                                .await;
-                            let mut entries = DatabaseEntryList::new(
-                                config.global.limits.max(),
-                            );
-                            for entry in unfiltered_entries.iter() {
-                                if config.global.limits.too_old(entry.date()) {
-                                    continue;
-                                }
-                                if !self.passes_global_filters(&entry) {
-                                    continue;
-                                }
-                                entries.add(entry.clone()).ok();
-                            }
-                            entries
+                            Updater::collect_entries(
+                                entry_db,
+                                EntryCollection {
+                                    criteria: matching.tags(&tag),
+                                    cursor,
+                                    window,
+                                    max: limit
+                                        .unwrap_or(config.global.limits.max()),
+                                    order,
+                                    dedup: false,
+                                },
+                                |entry| {
+                                    !config.global.limits.too_old(entry.date())
+                                        && !config.serve.too_old(entry.date())
+                                        && self.passes_global_filters(entry)
+                                },
+                            )
+                            .await
                         }
-                        FeedFetchOptions::Feed {
-                            feed,
-                            modified_since,
-                        } => {
+                        FeedFetchOptions::Search { criteria, cursor } => {
+                            Updater::collect_entries(
+                                entry_db,
+                                EntryCollection {
+                                    criteria,
+                                    cursor,
+                                    window,
+                                    max: limit
+                                        .unwrap_or(config.global.limits.max()),
+                                    order,
+                                    dedup: false,
+                                },
+                                |entry| {
+                                    !config.global.limits.too_old(entry.date())
+                                        && !config.serve.too_old(entry.date())
+                                        && self.passes_global_filters(entry)
+                                },
+                            )
+                            .await
+                        }
+                        FeedFetchOptions::Feed { feed, cursor } => {
                             if let (Some(_feed_id), Some(feed_def)) =
                                 (self.feeds.get(&feed), config.feed(&feed))
                             {
-                                let unfiltered_entries = entry_db
-                                    .get_entries(
-                                        vec![DatabaseSearch::Feed(
+                                Updater::collect_entries(
+                                    entry_db,
+                                    EntryCollection {
+                                        criteria: vec![DatabaseSearch::Feed(
                                             feed.clone(),
                                         )],
-                                        config.global.limits.max(),
-                                        OffsetCursor::modified_since(
-                                            modified_since,
+                                        cursor,
+                                        window,
+                                        max: limit.unwrap_or(
+                                            feed_def.options().max(),
                                         ),
-                                    )
-                                    .await;
-                                let mut entries = DatabaseEntryList::new(
-                                    feed_def.options().max(),
-                                );
-                                for entry in unfiltered_entries.iter() {
-                                    if config
-                                        .global
-                                        .limits
-                                        .too_old(entry.date())
-                                    {
-                                        continue;
-                                    }
-                                    if feed_def.options().too_old(entry.date())
-                                    {
-                                        continue;
-                                    }
-                                    if !self.passes_global_filters(&entry) {
-                                        continue;
-                                    }
+                                        order,
+                                        dedup: config
+                                            .merges_duplicates(feed_def),
+                                    },
                                     // NOTE: Individual feed filters are already checked by the underlying
                                     // slipfeed updater.
-                                    entries.add(entry.clone()).ok();
-                                }
-                                entries
+                                    |entry| {
+                                        !config
+                                            .global
+                                            .limits
+                                            .too_old(entry.date())
+                                            && !config
+                                                .serve
+                                                .too_old(entry.date())
+                                            && !feed_def
+                                                .options()
+                                                .too_old(entry.date())
+                                            && self.passes_global_filters(entry)
+                                    },
+                                )
+                                .await
                             } else {
                                 DatabaseEntryList::new(0)
                             }
@@ -248,11 +653,133 @@ impl Updater {
                     tx.send(entries).ok();
                 };
             }
+            UpdaterRequest::Retag { tx, feed } => {
+                let tags = config.feed_tags(&feed);
+                if let (Some(entry_db), Some(tags)) = (&self.entry_db, &tags) {
+                    let added = entry_db.add_source_tags(&feed, tags).await;
+                    tracing::info!("Retagged {feed}, adding {added} tags.");
+                }
+                tx.send(tags).ok();
+            }
+            UpdaterRequest::AllTags { tx } => {
+                let tags = match &self.entry_db {
+                    Some(entry_db) => entry_db.all_tags().await,
+                    None => Vec::new(),
+                };
+                tx.send(tags).ok();
+            }
+            UpdaterRequest::UnreadCounts { tx } => {
+                let counts = match &self.entry_db {
+                    Some(entry_db) => entry_db.unread_counts().await,
+                    None => Vec::new(),
+                };
+                tx.send(counts).ok();
+            }
+            UpdaterRequest::Metrics { tx } => {
+                let mut metrics = UpdaterMetrics::default();
+                if let Some(entry_db) = &self.entry_db {
+                    metrics.entries = entry_db.entry_count().await;
+                    metrics.feed_entries = entry_db.feed_entry_counts().await;
+                }
+                {
+                    let stats = self.stats.lock().unwrap();
+                    metrics.updates = stats.updates;
+                    metrics.last_update_duration =
+                        stats.last_update_duration.clone();
+                    metrics.feed_last_update = stats.last_successes.clone();
+                }
+                tx.send(metrics).ok();
+            }
             UpdaterRequest::FeedName { tx, feed } => {
                 // config.feed(feed)
                 tx.send(self.feeds_ids.get(&feed).map(|f| f.clone())).ok();
             }
+            UpdaterRequest::Reload { .. } => {
+                // Reloads replace the config, so the update loop handles them.
+            }
+        }
+    }
+
+    /// Replace feeds and filters with those of a reloaded config.
+    /// The database and handles are kept, and the update task runs the
+    /// reloaded feeds from its next update.
+    async fn reload(
+        &mut self,
+        config: &Arc<Config>,
+        running: &std::sync::Mutex<RunningFeeds>,
+    ) -> Result<()> {
+        let Some(entry_db) = self.entry_db.clone() else {
+            bail!("Unable to reload without a database.");
+        };
+        let reloaded = config.updater_with_database(entry_db).await?;
+        let mut next = std::mem::take(&mut *reloaded.updater.write().await);
+        next.set_clock(self.clock.clone());
+        running.lock().unwrap().reloaded = Some(ReloadedFeeds {
+            config: config.clone(),
+            feeds: reloaded.feeds.clone(),
+            updater: next,
+        });
+        tracing::info!("Reloaded config with {} feeds.", reloaded.feeds.len());
+
+        self.feeds = reloaded.feeds;
+        self.feeds_ids = reloaded.feeds_ids;
+        self.global_filters = reloaded.global_filters;
+        self.all_filters = reloaded.all_filters;
+        Ok(())
+    }
+
+    /// Collect up to `max` entries that are kept, reading `window` entries
+    /// from the database at a time.
+    pub async fn collect_entries(
+        entry_db: &Database,
+        collection: EntryCollection,
+        keep: impl Fn(&slipfeed::Entry) -> bool,
+    ) -> DatabaseEntryList {
+        let EntryCollection {
+            criteria,
+            cursor,
+            window,
+            max,
+            order,
+            dedup,
+        } = collection;
+        let newer = matches!(cursor, OffsetCursor::After(..));
+        let mut entries = DatabaseEntryList::new(max);
+        let mut offset = 0;
+        loop {
+            let page = entry_db
+                .get_entries_page(
+                    criteria.clone(),
+                    window,
+                    cursor.clone(),
+                    offset,
+                    EntryOrder::Newest,
+                )
+                .await;
+            for entry in page.iter() {
+                if !keep(entry) {
+                    continue;
+                }
+                match dedup {
+                    true => entries.add_or_merge(entry.clone()).ok(),
+                    false => entries.add(entry.clone()).ok(),
+                };
+            }
+
+            // Only keep paging while there may be more entries to find.
+            if order == CollectionOrder::CollectThenFilter
+                || entries.len() >= max
+                || page.len() < window.max(1)
+            {
+                break;
+            }
+            offset += page.len();
         }
+        // Entries after a date are collected oldest first.
+        if newer {
+            entries.reverse();
+        }
+        entries
     }
 
     /// Check if entry passes the global filters.
@@ -278,6 +805,8 @@ impl Default for Updater {
             global_filters: Vec::default(),
             all_filters: Vec::default(),
             entry_db: None,
+            clock: Arc::new(slipfeed::SystemClock),
+            stats: Arc::default(),
             to_updater_sender,
             to_updater_receiver,
         }
@@ -290,51 +819,221 @@ enum UpdaterRequest {
     EntryUpdate {
         entry_id: EntryDbId,
         tags: Option<Vec<slipfeed::Tag>>,
+        read: Option<bool>,
+        important: Option<bool>,
+        saved: Option<bool>,
+    },
+    EntriesRead {
+        entry_ids: Vec<EntryDbId>,
+        read: bool,
     },
     EntriesSearch {
         tx: oneshot::Sender<DatabaseEntryList>,
         criteria: Vec<DatabaseSearch>,
         offset: OffsetCursor,
+        order: EntryOrder,
     },
     FeedFetch {
         tx: oneshot::Sender<DatabaseEntryList>,
         options: FeedFetchOptions,
+        limit: Option<usize>,
     },
     FeedName {
         tx: oneshot::Sender<Option<String>>,
         feed: slipfeed::FeedId,
     },
+    AllTags {
+        tx: oneshot::Sender<Vec<String>>,
+    },
+    UnreadCounts {
+        tx: oneshot::Sender<Vec<UnreadCount>>,
+    },
+    Metrics {
+        tx: oneshot::Sender<UpdaterMetrics>,
+    },
+    Retag {
+        tx: oneshot::Sender<Option<Vec<slipfeed::Tag>>>,
+        feed: String,
+    },
     CommandUpdate {
         entry_id: EntryDbId,
         command: String,
         result: i32,
         output: String,
     },
+    Reload {
+        tx: oneshot::Sender<Result<()>>,
+        config: Arc<Config>,
+    },
 }
 
 #[derive(Debug, Clone)]
 enum FeedFetchOptions {
     All {
-        since: Option<slipfeed::DateTime>,
-        modified_since: Option<slipfeed::DateTime>,
+        cursor: OffsetCursor,
     },
     Feed {
         feed: String,
-        modified_since: Option<slipfeed::DateTime>,
+        cursor: OffsetCursor,
     },
     Tag {
         tag: String,
-        modified_since: Option<slipfeed::DateTime>,
+        matching: SearchMatch,
+        cursor: OffsetCursor,
+    },
+    Search {
+        criteria: Vec<DatabaseSearch>,
+        cursor: OffsetCursor,
     },
+    Saved {
+        cursor: OffsetCursor,
+    },
+}
+
+/// A syndicated feed.
+#[derive(Clone, Debug, Default)]
+pub struct Syndication {
+    /// The serialized feed.
+    pub body: String,
+    /// Date of the newest entry, if there are any.
+    pub last_modified: Option<slipfeed::DateTime>,
+}
+
+impl Syndication {
+    /// Syndicate a list of entries.
+    pub fn new(
+        entries: &DatabaseEntryList,
+        name: impl AsRef<str>,
+        config: &Config,
+        format: SyndicationFormat,
+        page: &FeedPage,
+    ) -> Self {
+        Self {
+            body: entries.syndicate_page(
+                name,
+                config,
+                format,
+                &page.links(entries),
+            ),
+            last_modified: entries
+                .iter_entries()
+                .map(|e| e.date())
+                .max()
+                .cloned(),
+        }
+    }
+}
+
+/// A page of a served feed.
+#[derive(Clone, Debug, Default)]
+pub struct FeedPage {
+    /// Where the page starts.
+    pub cursor: OffsetCursor,
+    /// Link to the feed, which neighbouring pages are linked from (default
+    /// none).
+    pub link: Option<String>,
+    /// Most entries on the page, instead of the feed's limit (default none).
+    pub limit: Option<usize>,
+}
+
+impl FeedPage {
+    /// Whether this is an older or newer page, rather than the latest.
+    pub fn is_paged(&self) -> bool {
+        self.cursor.page_query().is_some()
+    }
+
+    /// Get the link to this page, if the feed has one.
+    pub fn page_link(&self) -> Option<String> {
+        let link = self.link.as_ref()?;
+        Some(match self.cursor.page_query() {
+            Some(query) => match link.contains('?') {
+                true => format!("{link}&{query}"),
+                false => format!("{link}?{query}"),
+            },
+            None => link.clone(),
+        })
+    }
+
+    /// Get the links to the pages before and after a page's entries.
+    /// Entries by id aren't paged.
+    pub fn links(&self, entries: &DatabaseEntryList) -> PageLinks {
+        let (Some(first), Some(last)) = (entries.first(), entries.last())
+        else {
+            return PageLinks::default();
+        };
+        if matches!(self.cursor, OffsetCursor::LatestId) {
+            return PageLinks::default();
+        }
+        let neighbour = |cursor| {
+            FeedPage {
+                cursor,
+                link: self.link.clone(),
+                limit: self.limit,
+            }
+            .page_link()
+        };
+        PageLinks {
+            older: neighbour(OffsetCursor::Before(
+                last.date().clone(),
+                Some(last.db_id),
+            )),
+            newer: match self.is_paged() {
+                true => neighbour(OffsetCursor::After(
+                    first.date().clone(),
+                    Some(first.db_id),
+                )),
+                false => None,
+            },
+        }
+    }
+}
+
+/// Links to the pages next to a served page.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PageLinks {
+    /// Link to older entries.
+    pub older: Option<String>,
+    /// Link to newer entries.
+    pub newer: Option<String>,
+}
+
+/// Entries to collect from the database.
+pub struct EntryCollection {
+    /// Criteria entries must match.
+    pub criteria: Vec<DatabaseSearch>,
+    /// Where to start collecting.
+    pub cursor: OffsetCursor,
+    /// Number of entries to read from the database at a time.
+    pub window: usize,
+    /// Most entries to collect.
+    pub max: usize,
+    /// Whether to filter entries before or after limiting them.
+    pub order: CollectionOrder,
+    /// Whether entries with the same title, author, and source are merged
+    /// into one entry.
+    pub dedup: bool,
 }
 
 #[derive(Clone)]
 pub struct UpdaterHandle {
     /// Handle's sender.
     to_updater_sender: Sender<UpdaterRequest>,
+    /// Statistics about completed updates.
+    stats: Arc<std::sync::Mutex<UpdateStats>>,
 }
 
 impl UpdaterHandle {
+    /// Whether the updater has finished an update cycle.
+    /// This doesn't wait on the updater, so it's cheap to check.
+    pub fn ready(&self) -> bool {
+        self.stats.lock().unwrap().ready
+    }
+
+    /// Generation that changes whenever new entries are stored.
+    pub fn generation(&self) -> Arc<std::sync::atomic::AtomicU64> {
+        self.stats.lock().unwrap().generation.clone()
+    }
+
     async fn send(&self, message: UpdaterRequest) {
         let res = self.to_updater_sender.send(message).await;
         if let Err(e) = res {
@@ -347,12 +1046,14 @@ impl UpdaterHandle {
         &self,
         criteria: Vec<DatabaseSearch>,
         offset: OffsetCursor,
+        order: EntryOrder,
     ) -> DatabaseEntryList {
         let (tx, rx) = oneshot::channel::<DatabaseEntryList>();
         self.send(UpdaterRequest::EntriesSearch {
             tx,
             criteria,
             offset,
+            order,
         })
         .await;
         match rx.await {
@@ -364,18 +1065,30 @@ impl UpdaterHandle {
         }
     }
 
+    /// Get a single entry.
+    pub async fn entry(&self, id: EntryDbId) -> Option<DatabaseEntry> {
+        self.search(
+            vec![DatabaseSearch::Id(id)],
+            OffsetCursor::LatestId,
+            EntryOrder::Newest,
+        )
+        .await
+        .iter()
+        .next()
+        .cloned()
+    }
+
     /// Collect the /all feed.
     pub async fn collect_all(
         &self,
-        modified_since: Option<slipfeed::DateTime>,
+        cursor: OffsetCursor,
+        limit: Option<usize>,
     ) -> DatabaseEntryList {
         let (tx, rx) = oneshot::channel::<DatabaseEntryList>();
         self.send(UpdaterRequest::FeedFetch {
             tx,
-            options: FeedFetchOptions::All {
-                since: None,
-                modified_since,
-            },
+            options: FeedFetchOptions::All { cursor },
+            limit,
         })
         .await;
         match rx.await {
@@ -387,43 +1100,79 @@ impl UpdaterHandle {
         }
     }
 
-    /// Convert the /all feed into an atom feed.
+    /// Convert the /all feed into a syndicated feed.
     pub async fn syndicate_all(
         &self,
         config: Arc<Config>,
-        modified_since: Option<slipfeed::DateTime>,
-    ) -> String {
+        page: FeedPage,
+        format: SyndicationFormat,
+    ) -> Syndication {
         let (tx, rx) = oneshot::channel::<DatabaseEntryList>();
         self.send(UpdaterRequest::FeedFetch {
             tx,
             options: FeedFetchOptions::All {
-                since: None,
-                modified_since,
+                cursor: page.cursor.clone(),
             },
+            limit: page.limit,
         })
         .await;
         match rx.await {
-            Ok(data) => data.syndicate("All", &config),
+            Ok(data) => Syndication::new(&data, "All", &config, format, &page),
             Err(e) => {
                 tracing::error!("Failed to syndicate_all: {}", e);
-                String::new()
+                Syndication::default()
+            }
+        }
+    }
+
+    /// Collect the /saved feed.
+    pub async fn collect_saved(
+        &self,
+        cursor: OffsetCursor,
+        limit: Option<usize>,
+    ) -> DatabaseEntryList {
+        let (tx, rx) = oneshot::channel::<DatabaseEntryList>();
+        self.send(UpdaterRequest::FeedFetch {
+            tx,
+            options: FeedFetchOptions::Saved { cursor },
+            limit,
+        })
+        .await;
+        match rx.await {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::error!("Failed to collect_saved: {}", e);
+                DatabaseEntryList::new(0)
             }
         }
     }
 
+    /// Convert the /saved feed into a syndicated feed.
+    pub async fn syndicate_saved(
+        &self,
+        config: Arc<Config>,
+        page: FeedPage,
+        format: SyndicationFormat,
+    ) -> Syndication {
+        let data = self.collect_saved(page.cursor.clone(), page.limit).await;
+        Syndication::new(&data, "Saved", &config, format, &page)
+    }
+
     /// Collect the /feed feed.
     pub async fn collect_feed(
         &self,
         feed: impl Into<String>,
-        modified_since: Option<slipfeed::DateTime>,
+        cursor: OffsetCursor,
+        limit: Option<usize>,
     ) -> DatabaseEntryList {
         let (tx, rx) = oneshot::channel::<DatabaseEntryList>();
         self.send(UpdaterRequest::FeedFetch {
             tx,
             options: FeedFetchOptions::Feed {
                 feed: feed.into(),
-                modified_since,
+                cursor,
             },
+            limit,
         })
         .await;
         match rx.await {
@@ -435,45 +1184,51 @@ impl UpdaterHandle {
         }
     }
 
-    /// Convert the /feed feed into an atom feed.
+    /// Convert the /feed feed into a syndicated feed.
     pub async fn syndicate_feed(
         &self,
         feed: impl Into<String>,
         config: Arc<Config>,
-        modified_since: Option<slipfeed::DateTime>,
-    ) -> String {
+        page: FeedPage,
+        format: SyndicationFormat,
+    ) -> Syndication {
         let feed = feed.into();
         let (tx, rx) = oneshot::channel::<DatabaseEntryList>();
         self.send(UpdaterRequest::FeedFetch {
             tx,
             options: FeedFetchOptions::Feed {
                 feed: feed.clone(),
-                modified_since,
+                cursor: page.cursor.clone(),
             },
+            limit: page.limit,
         })
         .await;
         match rx.await {
-            Ok(data) => data.syndicate(&feed, &config),
+            Ok(data) => Syndication::new(&data, &feed, &config, format, &page),
             Err(e) => {
                 tracing::error!("Failed to syndicate_tag: {}", e);
-                String::new()
+                Syndication::default()
             }
         }
     }
 
-    /// Collect the /tag feed.
+    /// Collect the /tag feed, for one or more comma-separated tags.
     pub async fn collect_tag(
         &self,
         tag: impl Into<String>,
-        modified_since: Option<slipfeed::DateTime>,
+        matching: SearchMatch,
+        cursor: OffsetCursor,
+        limit: Option<usize>,
     ) -> DatabaseEntryList {
         let (tx, rx) = oneshot::channel::<DatabaseEntryList>();
         self.send(UpdaterRequest::FeedFetch {
             tx,
             options: FeedFetchOptions::Tag {
                 tag: tag.into(),
-                modified_since,
+                matching,
+                cursor,
             },
+            limit,
         })
         .await;
         match rx.await {
@@ -485,32 +1240,74 @@ impl UpdaterHandle {
         }
     }
 
-    /// Convert the /tag feed into an atom feed.
+    /// Convert the /tag feed into a syndicated feed.
     pub async fn syndicate_tag(
         &self,
         tag: impl Into<String>,
+        matching: SearchMatch,
         config: Arc<Config>,
-        modified_since: Option<slipfeed::DateTime>,
-    ) -> String {
+        page: FeedPage,
+        format: SyndicationFormat,
+    ) -> Syndication {
         let tag = tag.into();
         let (tx, rx) = oneshot::channel::<DatabaseEntryList>();
         self.send(UpdaterRequest::FeedFetch {
             tx,
             options: FeedFetchOptions::Tag {
                 tag: tag.clone(),
-                modified_since,
+                matching,
+                cursor: page.cursor.clone(),
             },
+            limit: page.limit,
         })
         .await;
         match rx.await {
-            Ok(data) => data.syndicate(&tag, &config),
+            Ok(data) => Syndication::new(&data, &tag, &config, format, &page),
             Err(e) => {
                 tracing::error!("Failed to syndicate_tag: {}", e);
-                String::new()
+                Syndication::default()
             }
         }
     }
 
+    /// Collect the /search feed.
+    pub async fn collect_search(
+        &self,
+        criteria: Vec<DatabaseSearch>,
+        cursor: OffsetCursor,
+        limit: Option<usize>,
+    ) -> DatabaseEntryList {
+        let (tx, rx) = oneshot::channel::<DatabaseEntryList>();
+        self.send(UpdaterRequest::FeedFetch {
+            tx,
+            options: FeedFetchOptions::Search { criteria, cursor },
+            limit,
+        })
+        .await;
+        match rx.await {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::error!("Failed to collect_search: {}", e);
+                DatabaseEntryList::new(0)
+            }
+        }
+    }
+
+    /// Convert the /search feed into a syndicated feed.
+    pub async fn syndicate_search(
+        &self,
+        name: impl AsRef<str>,
+        criteria: Vec<DatabaseSearch>,
+        config: Arc<Config>,
+        page: FeedPage,
+        format: SyndicationFormat,
+    ) -> Syndication {
+        let data = self
+            .collect_search(criteria, page.cursor.clone(), page.limit)
+            .await;
+        Syndication::new(&data, name, &config, format, &page)
+    }
+
     /// Get the feed name from id.
     #[allow(unused)]
     pub async fn feed_name(&self, id: slipfeed::FeedId) -> Option<String> {
@@ -525,6 +1322,76 @@ impl UpdaterHandle {
         }
     }
 
+    /// Reload feeds and filters from a new config.
+    pub async fn reload(&self, config: Arc<Config>) -> Result<()> {
+        let (tx, rx) = oneshot::channel::<Result<()>>();
+        self.send(UpdaterRequest::Reload { tx, config }).await;
+        match rx.await {
+            Ok(result) => result,
+            Err(e) => bail!("Failed to reload: {e}"),
+        }
+    }
+
+    /// Get every distinct tag in the database.
+    pub async fn all_tags(&self) -> Vec<String> {
+        let (tx, rx) = oneshot::channel::<Vec<String>>();
+        self.send(UpdaterRequest::AllTags { tx }).await;
+        match rx.await {
+            Ok(tags) => tags,
+            Err(e) => {
+                tracing::error!("Failed to get tags: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Count the unread entries of each feed and tag.
+    pub async fn unread_counts(&self) -> Vec<UnreadCount> {
+        let (tx, rx) = oneshot::channel::<Vec<UnreadCount>>();
+        self.send(UpdaterRequest::UnreadCounts { tx }).await;
+        match rx.await {
+            Ok(counts) => counts,
+            Err(e) => {
+                tracing::error!("Failed to count unread entries: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Get metrics about stored entries and updates.
+    pub async fn metrics(&self) -> UpdaterMetrics {
+        let (tx, rx) = oneshot::channel::<UpdaterMetrics>();
+        self.send(UpdaterRequest::Metrics { tx }).await;
+        match rx.await {
+            Ok(metrics) => metrics,
+            Err(e) => {
+                tracing::error!("Failed to get metrics: {}", e);
+                UpdaterMetrics::default()
+            }
+        }
+    }
+
+    /// Re-apply a feed's configured tags to its stored entries.
+    /// This returns the feed's tags, if it exists.
+    pub async fn retag(
+        &self,
+        feed: impl Into<String>,
+    ) -> Option<Vec<slipfeed::Tag>> {
+        let (tx, rx) = oneshot::channel::<Option<Vec<slipfeed::Tag>>>();
+        self.send(UpdaterRequest::Retag {
+            tx,
+            feed: feed.into(),
+        })
+        .await;
+        match rx.await {
+            Ok(tags) => tags,
+            Err(e) => {
+                tracing::error!("Failed to retag: {}", e);
+                None
+            }
+        }
+    }
+
     pub async fn update_tags(
         &self,
         entry_id: EntryDbId,
@@ -533,6 +1400,59 @@ impl UpdaterHandle {
         self.send(UpdaterRequest::EntryUpdate {
             entry_id,
             tags: Some(tags),
+            read: None,
+            important: None,
+            saved: None,
+        })
+        .await;
+    }
+
+    /// Set whether an entry has been read.
+    pub async fn toggle_read(&self, entry_id: EntryDbId, read: bool) {
+        self.send(UpdaterRequest::EntryUpdate {
+            entry_id,
+            tags: None,
+            read: Some(read),
+            important: None,
+            saved: None,
+        })
+        .await;
+    }
+
+    /// Set whether many entries have been read.
+    /// This is sent as one request, so large batches don't block the reader.
+    pub async fn toggle_read_many(
+        &self,
+        entry_ids: Vec<EntryDbId>,
+        read: bool,
+    ) {
+        if entry_ids.is_empty() {
+            return;
+        }
+        self.send(UpdaterRequest::EntriesRead { entry_ids, read })
+            .await;
+    }
+
+    /// Set whether an entry is important.
+    pub async fn toggle_important(&self, entry_id: EntryDbId, important: bool) {
+        self.send(UpdaterRequest::EntryUpdate {
+            entry_id,
+            tags: None,
+            read: None,
+            important: Some(important),
+            saved: None,
+        })
+        .await;
+    }
+
+    /// Set whether an entry is saved.
+    pub async fn toggle_saved(&self, entry_id: EntryDbId, saved: bool) {
+        self.send(UpdaterRequest::EntryUpdate {
+            entry_id,
+            tags: None,
+            read: None,
+            important: None,
+            saved: Some(saved),
         })
         .await;
     }
@@ -8,9 +8,86 @@ use std::sync::LazyLock;
 use std::sync::RwLock;
 use std::sync::atomic::{AtomicBool, Ordering};
 
-use tracing::{Level, level_filters::LevelFilter};
+use tracing::{Level, Subscriber, level_filters::LevelFilter};
 use tracing_subscriber::Layer;
+use tracing_subscriber::fmt::MakeWriter;
 use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Logging configuration.
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    /// Format of log lines (default compact).
+    #[serde(default)]
+    pub format: Option<LogFormat>,
+    /// Log file. This takes precedence over the top-level `log`.
+    pub file: Option<String>,
+    /// When the log file rolls over (default never).
+    #[serde(default)]
+    pub rotation: Option<LogRotation>,
+    /// Size in bytes at which the log file rolls over, for size rotation
+    /// (default 10MiB).
+    #[serde(default, alias = "max-size")]
+    pub max_size: Option<u64>,
+    /// Number of rolled over log files kept (default 7).
+    #[serde(default, alias = "max-files")]
+    pub max_files: Option<usize>,
+}
+
+impl LoggingConfig {
+    /// Get the log rotation.
+    pub fn rotation(&self) -> LogRotation {
+        self.rotation.unwrap_or_default()
+    }
+
+    /// Get the size at which the log file rolls over.
+    pub fn max_size(&self) -> u64 {
+        self.max_size.unwrap_or(10 * 1024 * 1024)
+    }
+
+    /// Get the number of rolled over log files kept.
+    pub fn max_files(&self) -> usize {
+        self.max_files.unwrap_or(7)
+    }
+}
+
+/// When the log file rolls over.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum LogRotation {
+    /// Always append to the same file.
+    #[default]
+    Never,
+    /// Start a new file every hour.
+    Hourly,
+    /// Start a new file every day.
+    Daily,
+    /// Start a new file when it reaches `max-size`.
+    Size,
+}
+
+/// Format of log lines.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    clap::ValueEnum,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum LogFormat {
+    /// Compact, human-readable lines.
+    #[default]
+    Compact,
+    /// One JSON object per line, with event fields as attributes.
+    Json,
+}
 
 static LOGGER: LazyLock<Logger> = LazyLock::new(|| Logger::new());
 
@@ -100,8 +177,165 @@ impl std::io::Write for Logger {
     }
 }
 
+/// Log file that rolls over when it reaches a maximum size.
+/// Rolled over files are named `<file>.1` (newest) to `<file>.<max_files>`.
+pub struct SizeRollingFile {
+    path: PathBuf,
+    file: std::fs::File,
+    size: u64,
+    max_size: u64,
+    max_files: usize,
+}
+
+impl SizeRollingFile {
+    /// Open a log file, appending to it.
+    pub fn new(
+        path: PathBuf,
+        max_size: u64,
+        max_files: usize,
+    ) -> std::io::Result<Self> {
+        let file = Self::open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file,
+            size,
+            max_size,
+            max_files,
+        })
+    }
+
+    /// Open the log file for appending.
+    fn open(path: &std::path::Path) -> std::io::Result<std::fs::File> {
+        std::fs::OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(path)
+    }
+
+    /// Get the path of a rolled over log file.
+    fn rolled_path(&self, index: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{index}"));
+        PathBuf::from(path)
+    }
+
+    /// Roll the log file over, removing the oldest rolled over file.
+    fn roll(&mut self) -> std::io::Result<()> {
+        if self.max_files == 0 {
+            std::fs::remove_file(&self.path)?;
+        } else {
+            std::fs::remove_file(self.rolled_path(self.max_files)).ok();
+            for index in (1..self.max_files).rev() {
+                std::fs::rename(
+                    self.rolled_path(index),
+                    self.rolled_path(index + 1),
+                )
+                .ok();
+            }
+            std::fs::rename(&self.path, self.rolled_path(1))?;
+        }
+        self.file = Self::open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl std::io::Write for SizeRollingFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_size {
+            self.roll()?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Open the log file, rolling it over as configured.
+pub fn open_log_file(
+    log_file: &str,
+    config: &LoggingConfig,
+) -> Result<Box<dyn std::io::Write + Send>> {
+    let path = match shellexpand::full(log_file) {
+        Ok(filename) => PathBuf::from(filename.into_owned()),
+        Err(e) => bail!("Unable to expand log file {log_file}: {e}"),
+    };
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    if let Err(e) = std::fs::create_dir_all(&directory) {
+        bail!("Unable to initialize path for {log_file}: {e}");
+    }
+    let rotation = match config.rotation() {
+        LogRotation::Never => {
+            return match SizeRollingFile::open(&path) {
+                Ok(file) => Ok(Box::new(file)),
+                Err(e) => bail!("Failed to create log file {log_file}: {e}"),
+            };
+        }
+        LogRotation::Size => {
+            return match SizeRollingFile::new(
+                path,
+                config.max_size(),
+                config.max_files(),
+            ) {
+                Ok(file) => Ok(Box::new(file)),
+                Err(e) => bail!("Failed to create log file {log_file}: {e}"),
+            };
+        }
+        LogRotation::Hourly => tracing_appender::rolling::Rotation::HOURLY,
+        LogRotation::Daily => tracing_appender::rolling::Rotation::DAILY,
+    };
+    let Some(prefix) = path.file_name() else {
+        bail!("Log file at invalid path {log_file}");
+    };
+    match tracing_appender::rolling::RollingFileAppender::builder()
+        .rotation(rotation)
+        .filename_prefix(prefix.to_string_lossy())
+        // The current file is counted too.
+        .max_log_files(config.max_files() + 1)
+        .build(directory)
+    {
+        Ok(appender) => Ok(Box::new(appender)),
+        Err(e) => bail!("Failed to create log file {log_file}: {e}"),
+    }
+}
+
+/// Make a formatting layer for the log format.
+pub fn format_layer<S, W>(
+    format: LogFormat,
+    writer: W,
+) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    match format {
+        LogFormat::Compact => tracing_subscriber::fmt::layer()
+            .compact()
+            .with_writer(writer)
+            .boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_writer(writer)
+            .boxed(),
+    }
+}
+
 /// Setup logging.
-pub fn setup_logging(cli: &Cli, config: &Config) -> Result<()> {
+/// Logs are only written to the log file while the returned guard is alive.
+pub fn setup_logging(
+    cli: &Cli,
+    config: &Config,
+) -> Result<Option<tracing_appender::non_blocking::WorkerGuard>> {
+    let format = cli.log_format.or(config.logging.format).unwrap_or_default();
     let level = match cli.verbose {
         true => Level::TRACE,
         false => match cli.debug {
@@ -119,51 +353,26 @@ pub fn setup_logging(cli: &Cli, config: &Config) -> Result<()> {
         CommandMode::Read { .. } => {
             if cli.debug || cli.verbose {
                 Some(
-                    tracing_subscriber::fmt::layer()
-                        .compact()
-                        // .event_format(CliFormatter)
-                        .with_writer(get_logger)
+                    format_layer(format, get_logger)
                         .with_filter(filter.clone()),
                 )
             } else {
                 None
             }
         }
-        _ => Some(
-            tracing_subscriber::fmt::layer()
-                .compact()
-                // .event_format(CliFormatter)
-                .with_writer(get_logger)
-                .with_filter(filter.clone()),
-        ),
+        _ => Some(format_layer(format, get_logger).with_filter(filter.clone())),
     };
 
-    // File layer.
-    let file_logger = match config.log.as_ref() {
+    // File layer. This is written to from a separate thread, until the
+    // returned guard is dropped.
+    let mut guard = None;
+    let file_logger = match config.logging.file.as_ref().or(config.log.as_ref())
+    {
         Some(log_file) => {
-            let filename = shellexpand::full(log_file)
-                .expect(&format!("Unable to expand log file {}", log_file))
-                .into_owned();
-            let path = std::path::PathBuf::from_str(&filename)
-                .expect(&format!("Log file at invalid path {}", filename));
-            if let Some(parent_dir) = path.parent() {
-                std::fs::create_dir_all(parent_dir).expect(&format!(
-                    "Unable to initialize path for {}",
-                    filename
-                ));
-            }
-            let file = std::fs::OpenOptions::new()
-                .append(true)
-                .create(true)
-                .open(&filename)
-                .expect(&format!("Failed to create log file {}", log_file));
-            Some(
-                tracing_subscriber::fmt::layer()
-                    .compact()
-                    .with_writer(file)
-                    // .event_format(FileFormatter)
-                    .with_filter(filter),
-            )
+            let file = open_log_file(log_file, &config.logging)?;
+            let (writer, file_guard) = tracing_appender::non_blocking(file);
+            guard = Some(file_guard);
+            Some(format_layer(format, writer).with_filter(filter))
         }
         None => None,
     };
@@ -178,5 +387,5 @@ pub fn setup_logging(cli: &Cli, config: &Config) -> Result<()> {
         bail!("Unable to initialize logging.");
     }
 
-    Ok(())
+    Ok(guard)
 }
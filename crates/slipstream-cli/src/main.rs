@@ -6,6 +6,7 @@ mod database;
 mod feeds;
 mod logging;
 mod modes;
+mod notify;
 pub mod prelude;
 
 #[cfg(test)]
@@ -25,7 +26,8 @@ const DEFAULT_CONFIG_DIR: LazyCell<String> = LazyCell::new(|| {
     }
 });
 const DEFAULT_PORT: u16 = 3000;
-const DEFAULT_ADDRESS: &str = "0.0.0.0";
+const DEFAULT_ADDRESS: std::net::IpAddr =
+    std::net::IpAddr::V4(std::net::Ipv4Addr::UNSPECIFIED);
 const DEFAULT_UPDATE_SEC: u16 = 120;
 
 /// Entry point for slipstream.
@@ -42,7 +44,7 @@ async fn main() -> Result<()> {
                 Ok(cp) => cp,
                 Err(e) => bail!("Failed to determine config path: {e}"),
             };
-            return config_cli(config_mode.clone(), config_path);
+            return config_cli(config_mode.clone(), config_path).await;
         }
         _ => {}
     };
@@ -51,21 +53,55 @@ async fn main() -> Result<()> {
         Ok(config) => config,
         Err(e) => bail!("Failed to parse config:\n{e}"),
     });
-    setup_logging(&cli, &config)?;
+    let _log_guard = setup_logging(&cli, &config)?;
+
+    // The self-test runs its own updater.
+    if let CommandMode::SelfTest = &cli.command {
+        return self_test_cli(config).await;
+    }
+
+    // Debugging a feed fetches it without storing its entries.
+    if let CommandMode::DebugFeed { feed, entries } = &cli.command {
+        return debug_feed_cli(config, feed.clone(), *entries).await;
+    }
+
+    // Listing feeds only reads the config and database.
+    if let CommandMode::Feeds { feeds_mode } = &cli.command {
+        return feeds_cli(feeds_mode.clone(), config).await;
+    }
 
     let cancel_token = CancellationToken::new();
     let mut tasks = JoinSet::new();
 
-    // Run feed updates:
+    // Run feed updates until the command is done, so requests still being
+    // answered can use the updater:
     let mut updater = config.updater().await?;
     let updater_handle = updater.handle()?;
-    tasks.spawn(update(updater, config.clone(), cancel_token.clone()));
+    let updater_token = CancellationToken::new();
+    let updater_task = {
+        let config = config.clone();
+        let updater_token = updater_token.clone();
+        let cancel_token = cancel_token.clone();
+        tokio::spawn(async move {
+            let res = update(updater, config, updater_token).await;
+            cancel_token.cancel();
+            res
+        })
+    };
+
+    // Reload the config on SIGHUP:
+    #[cfg(unix)]
+    tasks.spawn(reload_on_hangup(
+        cli.config_path()?,
+        updater_handle.clone(),
+        cancel_token.clone(),
+    ));
 
     // Run the command:
     match &cli.command {
-        CommandMode::Serve {port, address } => tasks.spawn(serve_cli(
-            port.clone(),
-            address.clone(),
+        CommandMode::Serve { port, address } => tasks.spawn(serve_cli(
+            *port,
+            *address,
             config.clone(),
             updater_handle,
             cancel_token.clone(),
@@ -76,6 +112,9 @@ async fn main() -> Result<()> {
             cancel_token.clone(),
         )),
         CommandMode::Config { .. } => unreachable!(),
+        CommandMode::SelfTest => unreachable!(),
+        CommandMode::Feeds { .. } => unreachable!(),
+        CommandMode::DebugFeed { .. } => unreachable!(),
     };
 
     // Wait for ctrl+c (top-level):
@@ -95,13 +134,23 @@ async fn main() -> Result<()> {
     // Wait for tasks to complete.
     while let Some(task_res) = tasks.join_next().await {
         // If the task failed, print the error.
-        if let Err(e) = task_res {
-            tracing::error!("{}", e);
+        match task_res {
+            Err(e) => tracing::error!("{}", e),
+            Ok(Err(e)) => tracing::error!("{}", e),
+            Ok(Ok(())) => {}
         }
 
         // Kill all other tasks.
         cancel_token.cancel();
     }
 
+    // Stop updating, once fetched entries are stored.
+    updater_token.cancel();
+    match updater_task.await {
+        Err(e) => tracing::error!("{}", e),
+        Ok(Err(e)) => tracing::error!("{}", e),
+        Ok(Ok(())) => {}
+    }
+
     Ok(())
 }
@@ -0,0 +1,103 @@
+//! Feed reachability checks.
+
+use super::*;
+
+/// Feeds checked at once.
+const CHECK_CONCURRENCY: usize = 8;
+
+/// Time allowed for each feed check.
+const CHECK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// Content types that can hold a feed.
+const FEED_CONTENT_TYPES: &[&str] = &["xml", "rss", "atom", "json"];
+
+/// Check that the feeds of a config are reachable and serve feeds.
+pub async fn check_feeds(config: &Config) -> Result<()> {
+    let client = match reqwest::Client::builder().timeout(CHECK_TIMEOUT).build()
+    {
+        Ok(client) => client,
+        Err(e) => bail!("Unable to build client: {e}."),
+    };
+    let permits = Arc::new(tokio::sync::Semaphore::new(CHECK_CONCURRENCY));
+
+    let mut checks = tokio::task::JoinSet::new();
+    for (feed_name, feed_def) in config.feeds.iter().flatten() {
+        let Some(url) = check_url(feed_def.feed()) else {
+            continue;
+        };
+        let client = client.clone();
+        let permits = permits.clone();
+        let feed_name = feed_name.clone();
+        checks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let result = check_feed(&client, &url).await;
+            (feed_name, url, result)
+        });
+    }
+    let mut results = checks.join_all().await;
+    results.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut failed = 0;
+    for (feed_name, url, result) in &results {
+        match result {
+            Ok(()) => println!("OK     {feed_name}"),
+            Err(e) => {
+                failed += 1;
+                println!("FAILED {feed_name} ({url}): {e}");
+            }
+        }
+    }
+    println!("{} feeds OK, {failed} failed.", results.len() - failed);
+
+    if failed > 0 {
+        bail!("{failed} feeds failed checks.");
+    }
+    Ok(())
+}
+
+/// Get the url checked for a feed, if it can be checked.
+fn check_url(feed: &RawFeed) -> Option<String> {
+    match feed {
+        RawFeed::Raw { url } | RawFeed::Json { url, .. } => Some(url.clone()),
+        RawFeed::MastodonStatuses { mastodon, .. }
+        | RawFeed::MastodonUserStatuses { mastodon, .. }
+        | RawFeed::MastodonHashtag { mastodon, .. }
+        | RawFeed::MastodonList { mastodon, .. } => {
+            let instance = match mastodon.contains("://") {
+                true => mastodon.clone(),
+                false => format!("https://{mastodon}"),
+            };
+            Some(format!(
+                "{}/api/v1/instance",
+                instance.trim_end_matches('/')
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Fetch a url, checking that it serves feed content.
+async fn check_feed(
+    client: &reqwest::Client,
+    url: &str,
+) -> std::result::Result<(), String> {
+    let response = match client.get(url).send().await {
+        Ok(response) => response,
+        Err(e) => return Err(format!("unreachable: {e}")),
+    };
+    if !response.status().is_success() {
+        return Err(format!("status {}", response.status()));
+    }
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_lowercase();
+    if !content_type.is_empty()
+        && !FEED_CONTENT_TYPES.iter().any(|t| content_type.contains(t))
+    {
+        return Err(format!("not a feed ({content_type})"));
+    }
+    Ok(())
+}
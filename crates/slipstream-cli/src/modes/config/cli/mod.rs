@@ -6,13 +6,20 @@ use super::*;
 #[derive(Clone, Subcommand)]
 pub enum ConfigMode {
     /// Verify configuration.
-    Verify,
+    Verify {
+        /// Check that each feed is reachable and serves a feed.
+        #[arg(long, action)]
+        check_feeds: bool,
+    },
     /// Export from current configuration.
     Export {
         /// Conversion destination.
         config_type: ConfigDestination,
         /// Conversion .
         out_file: std::path::PathBuf,
+        /// Don't group OPML feeds into categories by tag.
+        #[arg(long, action)]
+        flat: bool,
     },
     /// Import into current configuration.
     Import {
@@ -31,4 +38,8 @@ pub enum ConfigDestination {
     Slipstream,
     Opml,
     List,
+    /// Newsboat `urls` file (import only).
+    Newsboat,
+    /// Slipstream config as JSON.
+    Json,
 }
@@ -4,20 +4,25 @@ use std::io::Write;
 
 use super::*;
 
+mod check;
 mod cli;
 
+use check::*;
 pub use cli::*;
 
-pub fn config_cli(
+pub async fn config_cli(
     config_mode: ConfigMode,
     config_path: std::path::PathBuf,
 ) -> Result<()> {
     match config_mode {
-        ConfigMode::Verify => verify_config(config_path)?,
+        ConfigMode::Verify { check_feeds } => {
+            verify_config(config_path, check_feeds).await?
+        }
         ConfigMode::Export {
             config_type,
             out_file,
-        } => export_config(config_path, config_type, out_file)?,
+            flat,
+        } => export_config(config_path, config_type, out_file, flat)?,
         ConfigMode::Import {
             in_type,
             in_file,
@@ -27,9 +32,8 @@ pub fn config_cli(
     Ok(())
 }
 
-fn read_config(config_path: &std::path::PathBuf) -> Result<Config> {
-    // Read file.
-    let config_data = match std::fs::read_to_string(&config_path) {
+fn read_json_config(config_path: &std::path::PathBuf) -> Result<Config> {
+    let config_data = match std::fs::read_to_string(config_path) {
         Ok(data) => data,
         Err(e) => {
             bail!(
@@ -40,36 +44,39 @@ fn read_config(config_path: &std::path::PathBuf) -> Result<Config> {
         }
     };
 
-    // Parse.
-    match toml::from_str::<Config>(&config_data) {
+    match serde_json::from_str::<Config>(&config_data) {
         Ok(config) => {
-            return Ok(config);
-        }
-        Err(e) => {
-            bail!("Configuration file is not valid: {}.", e);
+            if let Err(e) = config.validate() {
+                bail!("Configuration file is not valid: {}", e);
+            }
+            Ok(config)
         }
-    };
+        Err(e) => bail!("Configuration file is not valid: {}.", e),
+    }
 }
 
-fn verify_config(config_path: std::path::PathBuf) -> Result<()> {
-    match read_config(&config_path) {
-        Ok(_) => {
-            println!(
-                "Successfully parsed config at {}.",
-                config_path.to_string_lossy()
-            );
-            return Ok(());
-        }
-        Err(e) => bail!(e),
+async fn verify_config(
+    config_path: std::path::PathBuf,
+    check: bool,
+) -> Result<()> {
+    let config = Config::from_path(&config_path)?;
+    println!(
+        "Successfully parsed config at {}.",
+        config_path.to_string_lossy()
+    );
+    if check {
+        check_feeds(&config).await?;
     }
+    Ok(())
 }
 
 fn export_config(
     config_path: PathBuf,
     config_destination: ConfigDestination,
     out: PathBuf,
+    flat: bool,
 ) -> Result<()> {
-    let config = read_config(&config_path)?;
+    let config = Config::from_path(&config_path)?;
     let out_data: String = match config_destination {
         ConfigDestination::Slipstream => {
             match toml::to_string_pretty(&Arc::new(config)) {
@@ -77,57 +84,18 @@ fn export_config(
                 Err(e) => bail!("Cannot write toml data: {e}"),
             }
         }
-        ConfigDestination::Opml => {
-            let mut opml_data = opml::OPML::default();
-            opml_data.version = "1.0".into();
-            match &config.feeds {
-                Some(feeds) => {
-                    for (feed_name, feed) in feeds.iter() {
-                        match feed.feed() {
-                            RawFeed::Raw { url } => {
-                                opml_data.add_feed(&feed_name, url);
-                                if let Some(added_feed) =
-                                    opml_data.body.outlines.last_mut()
-                                {
-                                    added_feed.r#type = Some("rss".into());
-                                }
-                            }
-                            RawFeed::Aggregate { .. } => {
-                                // Do nothing.
-                            }
-                            RawFeed::AggregateTag { .. } => {
-                                // Do nothing.
-                            }
-                            RawFeed::MastodonStatuses {
-                                mastodon,
-                                feed_type,
-                                ..
-                            } => {
-                                eprintln!(
-                                    "Unable to export mastodon feed: {} ({:?}).",
-                                    mastodon, feed_type
-                                );
-                            }
-                            RawFeed::MastodonUserStatuses {
-                                mastodon,
-                                user,
-                                ..
-                            } => {
-                                eprintln!(
-                                    "Unable to export mastodon feed: {} (User {}).",
-                                    mastodon, user
-                                );
-                            }
-                        }
-                    }
-                }
-                None => bail!("No feeds to export."),
+        ConfigDestination::Json => {
+            match serde_json::to_string_pretty(&config) {
+                Ok(data) => data,
+                Err(e) => bail!("Cannot write json data: {e}"),
             }
-
-            match opml_data.to_string() {
-                Ok(d) => d,
-                Err(e) => bail!("Unable to export OPML: {e}."),
+        }
+        ConfigDestination::Opml => {
+            let (opml, notes) = export_opml(&config, !flat)?;
+            for note in notes {
+                eprintln!("{note}");
             }
+            opml
         }
         ConfigDestination::List => {
             let mut converted_feeds: Vec<String> = vec![];
@@ -136,9 +104,19 @@ fn export_config(
                 Some(feeds) => {
                     for (_feed_name, feed) in feeds.iter() {
                         match feed.feed() {
-                            RawFeed::Raw { url } => {
+                            RawFeed::Raw { url }
+                            | RawFeed::Json { url, .. } => {
                                 converted_feeds.push(url.clone());
                             }
+                            RawFeed::Reddit { subreddit, sort } => {
+                                converted_feeds
+                                    .push(reddit_rss_url(subreddit, sort));
+                            }
+                            RawFeed::YouTube { channel } => {
+                                let channel = channel.replace("https://", "");
+                                converted_feeds
+                                    .push(format!("youtube://{channel}"));
+                            }
                             RawFeed::Aggregate { .. } => {
                                 // Do nothing.
                             }
@@ -173,6 +151,25 @@ fn export_config(
                                 converted_feeds
                                     .push(format!("mastodon://{base}/@{user}"));
                             }
+                            RawFeed::MastodonHashtag {
+                                mastodon,
+                                hashtag,
+                                ..
+                            } => {
+                                let base = mastodon.replace("https://", "");
+                                let hashtag = hashtag.trim_start_matches('#');
+                                converted_feeds.push(format!(
+                                    "mastodon://{base}/tag/{hashtag}"
+                                ));
+                            }
+                            RawFeed::MastodonList {
+                                mastodon, list, ..
+                            } => {
+                                let base = mastodon.replace("https://", "");
+                                converted_feeds.push(format!(
+                                    "mastodon://{base}/list/{list}"
+                                ));
+                            }
                         }
                     }
                 }
@@ -181,6 +178,9 @@ fn export_config(
 
             converted_feeds.join("\n")
         }
+        ConfigDestination::Newsboat => {
+            bail!("Exporting to newsboat is not supported.")
+        }
     };
 
     let mut out_file = std::fs::File::create(&out)?;
@@ -188,17 +188,138 @@ fn export_config(
     Ok(())
 }
 
+/// Get the rss url of a subreddit, for exporting to other readers.
+fn reddit_rss_url(
+    subreddit: &str,
+    sort: &Option<slipfeed::RedditSort>,
+) -> String {
+    let url =
+        slipfeed::RedditFeed::listing_url(subreddit, sort.unwrap_or_default());
+    match url.strip_suffix(".json") {
+        Some(url) => format!("{url}.rss"),
+        None => url,
+    }
+}
+
+/// Export the feeds of a config as OPML.
+///
+/// When grouped, tagged feeds are placed in a category outline for each of
+/// their tags (so feeds with several tags appear more than once) and untagged
+/// feeds stay at the top level. Feeds that can't be exported are left out,
+/// with a note for each.
+pub fn export_opml(
+    config: &Config,
+    grouped: bool,
+) -> Result<(String, Vec<String>)> {
+    let mut opml_data = opml::OPML {
+        version: "1.0".into(),
+        ..Default::default()
+    };
+    let feeds = match &config.feeds {
+        Some(feeds) => feeds,
+        None => bail!("No feeds to export."),
+    };
+
+    let mut notes = Vec::new();
+    let mut categories: BTreeMap<&str, Vec<opml::Outline>> = BTreeMap::new();
+    for (feed_name, feed) in feeds.iter() {
+        let url = match opml_feed_url(feed.feed()) {
+            Ok(Some(url)) => url,
+            Ok(None) => continue,
+            Err(note) => {
+                notes.push(note);
+                continue;
+            }
+        };
+        let outline = opml::Outline {
+            text: feed_name.clone(),
+            r#type: Some("rss".into()),
+            xml_url: Some(url),
+            ..Default::default()
+        };
+        match feed.tags() {
+            Some(tags) if grouped && !tags.is_empty() => {
+                for tag in tags {
+                    categories.entry(tag).or_default().push(outline.clone());
+                }
+            }
+            _ => opml_data.body.outlines.push(outline),
+        }
+    }
+    for (tag, outlines) in categories {
+        opml_data.body.outlines.push(opml::Outline {
+            text: tag.into(),
+            outlines,
+            ..Default::default()
+        });
+    }
+
+    match opml_data.to_string() {
+        Ok(d) => Ok((d, notes)),
+        Err(e) => bail!("Unable to export OPML: {e}."),
+    }
+}
+
+/// Get the rss url of a feed for OPML, if it has one. This fails with a note
+/// for feeds that can't be exported.
+fn opml_feed_url(
+    feed: &RawFeed,
+) -> std::result::Result<Option<String>, String> {
+    match feed {
+        RawFeed::Raw { url } | RawFeed::Json { url, .. } => {
+            Ok(Some(url.clone()))
+        }
+        RawFeed::Reddit { subreddit, sort } => {
+            Ok(Some(reddit_rss_url(subreddit, sort)))
+        }
+        RawFeed::YouTube { channel } => {
+            match slipfeed::YouTubeFeed::feed_url(channel) {
+                Some(url) => Ok(Some(url)),
+                None => {
+                    Err(format!("Unable to export youtube feed: {}.", channel))
+                }
+            }
+        }
+        RawFeed::Aggregate { .. } | RawFeed::AggregateTag { .. } => Ok(None),
+        RawFeed::MastodonStatuses {
+            mastodon,
+            feed_type,
+            ..
+        } => Err(format!(
+            "Unable to export mastodon feed: {} ({:?}).",
+            mastodon, feed_type
+        )),
+        RawFeed::MastodonUserStatuses { mastodon, user, .. } => Err(format!(
+            "Unable to export mastodon feed: {} (User {}).",
+            mastodon, user
+        )),
+        RawFeed::MastodonHashtag {
+            mastodon, hashtag, ..
+        } => Err(format!(
+            "Unable to export mastodon feed: {} (Hashtag {}).",
+            mastodon, hashtag
+        )),
+        RawFeed::MastodonList { mastodon, list, .. } => Err(format!(
+            "Unable to export mastodon feed: {} (List {}).",
+            mastodon, list
+        )),
+    }
+}
+
 fn import_config(
     config_path: PathBuf,
     in_type: ConfigDestination,
     in_file: PathBuf,
     out: PathBuf,
 ) -> Result<()> {
-    let mut config = read_config(&config_path)?;
+    let mut config = Config::from_path(&config_path)?;
 
     match in_type {
-        ConfigDestination::Slipstream => {
-            let other_config = read_config(&in_file)?;
+        ConfigDestination::Slipstream | ConfigDestination::Json => {
+            let other_config = match in_type {
+                ConfigDestination::Json => read_json_config(&in_file)?,
+                _ => Config::from_path(&in_file)?,
+            };
             if let Some(feeds) = &other_config.feeds {
                 for (feed_name, feed_def) in feeds.iter() {
                     config.add_feed(feed_name, feed_def.clone());
@@ -217,32 +338,7 @@ fn import_config(
                 }
             };
             let opml_data = opml::OPML::from_str(&in_data)?;
-
-            for outline in &opml_data.body.outlines {
-                if let Some(r#type) = &outline.r#type {
-                    if r#type != "rss" {
-                        eprintln!(
-                            "Unable to parse {:?} as valid feed.",
-                            outline
-                        );
-                        continue;
-                    }
-                    if let Some(url) = &outline.xml_url {
-                        config.add_feed(
-                            outline.text.clone(),
-                            FeedDefinition::from_feed(RawFeed::Raw {
-                                url: url.clone(),
-                            }),
-                        );
-                    } else {
-                        eprintln!(
-                            "Unable to parse {:?} as valid feed.",
-                            outline
-                        );
-                        continue;
-                    }
-                }
-            }
+            import_outlines(&mut config, &opml_data.body.outlines, &[]);
         }
         ConfigDestination::List => {
             let in_data = match std::fs::read_to_string(&in_file) {
@@ -278,6 +374,25 @@ fn import_config(
                     continue;
                 }
 
+                // Add youtube feeds.
+                if let Some(channel) = line.strip_prefix("youtube://") {
+                    let name = channel
+                        .trim_end_matches('/')
+                        .rsplit(['/', '@'])
+                        .next()
+                        .unwrap_or(channel);
+                    let name = format!("youtube-{name}");
+                    let channel = match channel.contains('/') {
+                        true => format!("https://{channel}"),
+                        false => channel.into(),
+                    };
+                    config.add_feed(
+                        name,
+                        FeedDefinition::from_feed(RawFeed::YouTube { channel }),
+                    );
+                    continue;
+                }
+
                 // Add mastodon feeds.
                 if line.starts_with("mastodon://") {
                     let schemeless = line.replace("mastodon://", "");
@@ -287,6 +402,34 @@ fn import_config(
                         .into();
                     let remaining: String = schemeless[base.len()..].into();
 
+                    // Hashtag timeline.
+                    if let Some(hashtag) = remaining.strip_prefix("/tag/") {
+                        config.add_feed(
+                            format!("{base}-tag-{hashtag}"),
+                            FeedDefinition::from_feed(
+                                RawFeed::MastodonHashtag {
+                                    mastodon: base,
+                                    hashtag: hashtag.into(),
+                                    token: None,
+                                },
+                            ),
+                        );
+                        continue;
+                    }
+
+                    // List timeline.
+                    if let Some(list) = remaining.strip_prefix("/list/") {
+                        config.add_feed(
+                            format!("{base}-list-{list}"),
+                            FeedDefinition::from_feed(RawFeed::MastodonList {
+                                mastodon: base,
+                                list: list.into(),
+                                token: None,
+                            }),
+                        );
+                        continue;
+                    }
+
                     // Public timeline.
                     if remaining.ends_with("/public/local") {
                         config.add_feed(
@@ -342,9 +485,151 @@ fn import_config(
                 }
             }
         }
+        ConfigDestination::Newsboat => {
+            let in_data = match std::fs::read_to_string(&in_file) {
+                Ok(data) => data,
+                Err(e) => {
+                    bail!(
+                        "Unable to read data from in file {:?}: {}.",
+                        in_file,
+                        e
+                    );
+                }
+            };
+            for line in in_data.lines() {
+                if let Some((name, feed_def)) = newsboat_feed(line) {
+                    config.add_feed(name, feed_def);
+                }
+            }
+        }
     }
 
     let mut out_file = std::fs::File::create(&out)?;
     out_file.write_all(toml::to_string(&config)?.as_bytes())?;
     Ok(())
 }
+
+/// Import OPML outlines, tagging feeds with their ancestor categories.
+fn import_outlines(
+    config: &mut Config,
+    outlines: &[opml::Outline],
+    categories: &[String],
+) {
+    for outline in outlines {
+        if outline.r#type.as_deref().is_some_and(|t| t != "rss") {
+            eprintln!("Unable to parse {:?} as valid feed.", outline);
+            continue;
+        }
+        match &outline.xml_url {
+            Some(url) => {
+                let mut feed_def = FeedDefinition::from_feed(RawFeed::Raw {
+                    url: url.clone(),
+                });
+                let mut tags = Vec::new();
+                // Feeds listed under several categories keep all of them.
+                if let Some(existing) = config.feed(&outline.text) {
+                    if let RawFeed::Raw { url: existing_url } = existing.feed()
+                    {
+                        if existing_url == url {
+                            tags.extend(
+                                existing.tags().iter().flatten().cloned(),
+                            );
+                        }
+                    }
+                }
+                for category in categories {
+                    if !tags.contains(category) {
+                        tags.push(category.clone());
+                    }
+                }
+                if !tags.is_empty() {
+                    feed_def = feed_def.with_tags(tags);
+                }
+                config.add_feed(outline.text.clone(), feed_def);
+            }
+            None if !outline.outlines.is_empty() => {
+                let mut categories = categories.to_vec();
+                categories.push(outline.text.clone());
+                import_outlines(config, &outline.outlines, &categories);
+            }
+            None => {
+                eprintln!("Unable to parse {:?} as valid feed.", outline);
+            }
+        }
+    }
+}
+
+/// Parse a line of a newsboat `urls` file into a named feed.
+fn newsboat_feed(line: &str) -> Option<(String, FeedDefinition)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let mut tokens = newsboat_tokens(line).into_iter();
+    let url = tokens.next()?;
+    if url.starts_with("query:") {
+        eprintln!("Unable to import newsboat query feed: {line}.");
+        return None;
+    }
+
+    let mut title = None;
+    let mut tags = Vec::new();
+    for token in tokens {
+        match token.strip_prefix('~') {
+            Some(name) => title = Some(name.to_string()),
+            None if token == "!" => {
+                // Hidden feeds are imported as usual.
+            }
+            None => tags.push(token),
+        }
+    }
+
+    let name = title.unwrap_or_else(|| {
+        url.trim_start_matches("https://")
+            .trim_start_matches("http://")
+            .into()
+    });
+    let mut feed_def = FeedDefinition::from_feed(RawFeed::Raw { url });
+    if !tags.is_empty() {
+        feed_def = feed_def.with_tags(tags);
+    }
+    Some((name, feed_def))
+}
+
+/// Split a newsboat line on whitespace, joining quoted sections.
+fn newsboat_tokens(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut in_token = false;
+    let mut quoted = false;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_token = true;
+            }
+            '\\' if quoted => {
+                if let Some(escaped) = chars.next() {
+                    token.push(escaped);
+                }
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_token {
+                    tokens.push(std::mem::take(&mut token));
+                    in_token = false;
+                }
+            }
+            '#' if !quoted && !in_token => break,
+            c => {
+                token.push(c);
+                in_token = true;
+            }
+        }
+    }
+    if in_token {
+        tokens.push(token);
+    }
+    tokens
+}
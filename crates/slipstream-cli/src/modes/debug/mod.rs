@@ -0,0 +1,230 @@
+//! Debug mode.
+
+use super::*;
+
+/// Time allowed for fetching the feed.
+const DEBUG_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// An entry parsed while debugging a feed.
+#[derive(Clone, Debug)]
+pub struct DebugEntry {
+    /// Entry title.
+    pub title: String,
+    /// Formatted entry date.
+    pub date: String,
+    /// Why the entry would be dropped, if it would be.
+    pub dropped: Option<&'static str>,
+}
+
+/// Result of fetching and parsing a feed once.
+#[derive(Clone, Debug, Default)]
+pub struct FeedDebugReport {
+    /// Feed name.
+    pub name: String,
+    /// Feed type.
+    pub kind: &'static str,
+    /// Url the feed is read from, if it has one.
+    pub url: Option<String>,
+    /// Response status of the url, if it was requested.
+    pub status: Option<String>,
+    /// Content type of the url, if it was requested.
+    pub content_type: Option<String>,
+    /// Why fetching or parsing the feed failed, if it did.
+    pub error: Option<String>,
+    /// Parsed entries, newest first.
+    pub entries: Vec<DebugEntry>,
+    /// Number of entries shown.
+    pub shown: usize,
+}
+
+impl std::fmt::Display for FeedDebugReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Feed:         {} ({})", self.name, self.kind)?;
+        if let Some(url) = &self.url {
+            writeln!(f, "Url:          {url}")?;
+        }
+        if let Some(status) = &self.status {
+            writeln!(f, "Status:       {status}")?;
+        }
+        if let Some(content_type) = &self.content_type {
+            writeln!(f, "Content type: {content_type}")?;
+        }
+        if let Some(error) = &self.error {
+            return writeln!(f, "Failed:       {error}");
+        }
+        let dropped: Vec<&DebugEntry> = self
+            .entries
+            .iter()
+            .filter(|e| e.dropped.is_some())
+            .collect();
+        writeln!(
+            f,
+            "Entries:      {} parsed, {} kept, {} dropped",
+            self.entries.len(),
+            self.entries.len() - dropped.len(),
+            dropped.len()
+        )?;
+
+        let shown = self.entries.len().min(self.shown);
+        if shown > 0 {
+            writeln!(f)?;
+            for entry in &self.entries[..shown] {
+                let status = match entry.dropped {
+                    Some(_) => "dropped",
+                    None => "kept",
+                };
+                writeln!(f, "[{status:<7}] {}  {}", entry.date, entry.title)?;
+            }
+            if self.entries.len() > shown {
+                writeln!(f, "... and {} more", self.entries.len() - shown)?;
+            }
+        }
+        if !dropped.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "Dropped:")?;
+            for entry in dropped {
+                let reason = entry.dropped.unwrap_or_default();
+                writeln!(f, "[{reason}] {}  {}", entry.date, entry.title)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Fetch and parse a feed once, and print what it would keep.
+pub async fn debug_feed_cli(
+    config: Arc<Config>,
+    feed: String,
+    shown: usize,
+) -> Result<()> {
+    let report = debug_feed(&config, &feed, shown).await?;
+    print!("{report}");
+    if let Some(error) = &report.error {
+        bail!("Failed to update {feed}: {error}");
+    }
+    Ok(())
+}
+
+/// Fetch and parse a feed, by name or url, without storing its entries.
+pub async fn debug_feed(
+    config: &Config,
+    feed: &str,
+    shown: usize,
+) -> Result<FeedDebugReport> {
+    let feed_def = match config.feed(feed) {
+        Some(feed_def) => feed_def.clone(),
+        None if feed.contains("://") || std::path::Path::new(feed).exists() => {
+            FeedDefinition::from_feed(RawFeed::Raw { url: feed.into() })
+        }
+        None => bail!("No feed named {feed}."),
+    };
+    if feed_def.is_aggregate() {
+        bail!("{feed} gathers entries from other feeds, so it isn't fetched.");
+    }
+    let mut report = FeedDebugReport {
+        name: feed.to_string(),
+        kind: feed_def.feed().kind(),
+        url: feed_def.feed().url(),
+        shown,
+        ..Default::default()
+    };
+
+    // Keep every parsed entry, so dropped entries can be shown.
+    let (mut debugged, mut attr) = config.build_feed(feed, &feed_def);
+    let feed_filters = std::mem::take(&mut attr.filters);
+    let oldest = attr.timeout.clone();
+    attr.timeout = slipfeed::Duration::from_days(365 * 1000);
+
+    let client = match reqwest::Client::builder().timeout(DEBUG_TIMEOUT).build()
+    {
+        Ok(client) => client,
+        Err(e) => bail!("Unable to build client: {e}."),
+    };
+    if let Some(url) = debug_url(feed_def.feed()) {
+        let mut request = client.get(&url);
+        for (header, value) in &attr.headers {
+            request = request.header(header, value);
+        }
+        match request.send().await {
+            Ok(response) => {
+                report.status = Some(response.status().to_string());
+                report.content_type = response
+                    .headers()
+                    .get(reqwest::header::CONTENT_TYPE)
+                    .and_then(|value| value.to_str().ok())
+                    .map(String::from);
+            }
+            Err(e) => report.status = Some(format!("request failed ({e})")),
+        }
+    }
+
+    let now = slipfeed::DateTime::now();
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let ctx = slipfeed::UpdaterContext {
+        feed_id: slipfeed::FeedId::new(0),
+        parse_time: now.clone(),
+        last_update: None,
+        retries: 0,
+        retry_delay: slipfeed::Duration::from_seconds(0),
+        client,
+        request_timeout: attr.request_timeout.clone(),
+        sender: tx,
+    };
+    if let Err(e) = debugged.update(&ctx, &attr).await {
+        report.error = Some(e.to_string());
+        return Ok(report);
+    }
+
+    let global_filters = config.global.filters.get_filters();
+    let noop = NoopFeed::default();
+    let mut entries = Vec::new();
+    while let Ok((mut entry, feed_ref)) = rx.try_recv() {
+        // Feed filters see entries before they are tagged by the updater.
+        let dropped = if *entry.date() < now.clone() - oldest.clone() {
+            Some("too old")
+        } else if !feed_filters.iter().all(|f| f(debugged.as_ref(), &entry)) {
+            Some("feed filters")
+        } else {
+            entry.add_feed(feed_ref.clone());
+            debugged.tag(&mut entry, feed_ref.id, &attr).await;
+            match global_filters.iter().all(|f| f(&noop, &entry)) {
+                true => None,
+                false => Some("global filters"),
+            }
+        };
+        entries.push((entry.date().clone(), entry.title().clone(), dropped));
+    }
+    entries.sort_by(|a, b| b.0.cmp(&a.0));
+    report.entries = entries
+        .into_iter()
+        .map(|(date, title, dropped)| DebugEntry {
+            title,
+            date: config.format_date(&date),
+            dropped,
+        })
+        .collect();
+    Ok(report)
+}
+
+/// Get the url requested to show a feed's status, if it has one.
+fn debug_url(feed: &RawFeed) -> Option<String> {
+    match feed {
+        RawFeed::Raw { url } | RawFeed::Json { url, .. } => {
+            match url.starts_with("http://") || url.starts_with("https://") {
+                true => Some(url.clone()),
+                false => None,
+            }
+        }
+        RawFeed::Reddit { subreddit, sort } => {
+            Some(slipfeed::RedditFeed::listing_url(
+                subreddit,
+                sort.unwrap_or_default(),
+            ))
+        }
+        RawFeed::YouTube { channel } => {
+            slipfeed::YouTubeFeed::feed_url(channel)
+                .filter(|url| url.starts_with("https://"))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,138 @@
+//! Feeds mode.
+
+use super::*;
+
+/// Slipstream feeds mode.
+#[derive(Clone, Subcommand)]
+pub enum FeedsMode {
+    /// List the configured feeds, with their stored entries.
+    List {
+        /// Print the feeds as JSON.
+        #[arg(long, action)]
+        json: bool,
+    },
+}
+
+/// A configured feed, as listed.
+#[derive(Clone, Debug, Serialize)]
+pub struct FeedListing {
+    /// Feed name.
+    pub name: String,
+    /// Feed type.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Url the feed is read from.
+    pub url: Option<String>,
+    /// Feed tags.
+    pub tags: Vec<String>,
+    /// Number of stored entries, if there is a database.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entries: Option<u64>,
+    /// Date of the newest stored entry.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub newest: Option<slipfeed::DateTime>,
+}
+
+/// Run a feeds command.
+pub async fn feeds_cli(
+    feeds_mode: FeedsMode,
+    config: Arc<Config>,
+) -> Result<()> {
+    match feeds_mode {
+        FeedsMode::List { json } => {
+            let listings = list_feeds(&config).await?;
+            match json {
+                true => match serde_json::to_string_pretty(&listings) {
+                    Ok(data) => println!("{data}"),
+                    Err(e) => bail!("Cannot write json data: {e}"),
+                },
+                false => print!("{}", feeds_table(&listings, &config)),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// List the configured feeds, in display order.
+/// Entry counts are only read from a database that already exists.
+pub async fn list_feeds(config: &Config) -> Result<Vec<FeedListing>> {
+    let summaries = match &config.database {
+        Some(path) if PathBuf::from(path).resolve().exists() => {
+            let database = Database::new(path).await?;
+            let summaries = database.feed_summaries().await;
+            database.close().await;
+            Some(summaries)
+        }
+        _ => None,
+    };
+
+    let mut listings = Vec::new();
+    for name in config.feed_names() {
+        let Some(feed_def) = config.feed(name) else {
+            continue;
+        };
+        let summary = summaries.as_ref().and_then(|s| s.get(name));
+        listings.push(FeedListing {
+            name: name.clone(),
+            kind: feed_def.feed().kind().to_string(),
+            url: feed_def.feed().url(),
+            tags: feed_def.tags().clone().unwrap_or_default(),
+            entries: summaries
+                .as_ref()
+                .map(|_| summary.map(|s| s.entries).unwrap_or(0)),
+            newest: summary.and_then(|s| s.newest.clone()),
+        });
+    }
+    Ok(listings)
+}
+
+/// Format feed listings as an aligned table.
+pub fn feeds_table(listings: &[FeedListing], config: &Config) -> String {
+    let with_entries = listings.iter().any(|l| l.entries.is_some());
+    let mut rows: Vec<Vec<String>> = vec![
+        ["NAME", "TYPE", "URL", "TAGS", "ENTRIES", "NEWEST"]
+            .iter()
+            .map(|header| header.to_string())
+            .collect(),
+    ];
+    for listing in listings {
+        rows.push(vec![
+            listing.name.clone(),
+            listing.kind.clone(),
+            listing.url.clone().unwrap_or_else(|| "-".into()),
+            match listing.tags.is_empty() {
+                true => "-".into(),
+                false => listing.tags.join(","),
+            },
+            listing.entries.unwrap_or(0).to_string(),
+            match &listing.newest {
+                Some(newest) => config.format_date(newest),
+                None => "-".into(),
+            },
+        ]);
+    }
+    let columns = match with_entries {
+        true => 6,
+        false => 4,
+    };
+    let widths: Vec<usize> = (0..columns)
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let mut table = String::new();
+    for row in &rows {
+        let line: Vec<String> = row[..columns]
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        table.push_str(line.join("  ").trim_end());
+        table.push('\n');
+    }
+    table
+}
@@ -3,9 +3,13 @@
 use super::*;
 
 mod config;
+mod debug;
+mod feeds;
 mod read;
 mod serve;
 
 pub use config::*;
+pub use debug::*;
+pub use feeds::*;
 pub use read::*;
 pub use serve::*;
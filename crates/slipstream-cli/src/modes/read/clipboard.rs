@@ -0,0 +1,89 @@
+//! System clipboard.
+
+use super::*;
+
+/// Part of an entry that can be copied.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum YankTarget {
+    /// The source link.
+    Link,
+    /// The comments link.
+    Comments,
+    /// The title.
+    Title,
+    /// A citation, in the configured format.
+    Citation,
+}
+
+impl YankTarget {
+    /// Get the text copied from an entry, if it has any.
+    pub fn text(
+        &self,
+        entry: &slipfeed::Entry,
+        config: &Config,
+    ) -> Option<String> {
+        let text = match self {
+            YankTarget::Link => &entry.source().url,
+            YankTarget::Comments => &entry.comments().url,
+            YankTarget::Title => entry.title(),
+            YankTarget::Citation => {
+                &config.read.citation.render(entry, &config.timezone)
+            }
+        };
+        match text.trim().is_empty() {
+            true => None,
+            false => Some(text.trim().to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for YankTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            YankTarget::Link => write!(f, "link"),
+            YankTarget::Comments => write!(f, "comments link"),
+            YankTarget::Title => write!(f, "title"),
+            YankTarget::Citation => write!(f, "citation"),
+        }
+    }
+}
+
+/// Copy text to the system clipboard.
+/// This pipes the text to the platform's clipboard tool.
+pub async fn copy_to_clipboard(text: &str) -> Result<()> {
+    use std::process::Stdio;
+    use tokio::io::AsyncWriteExt;
+
+    let (program, args) = clipboard_command()?;
+    let mut child = tokio::process::Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes()).await?;
+    }
+    let status = child.wait().await?;
+    if !status.success() {
+        bail!("{program} exited with {status}");
+    }
+    Ok(())
+}
+
+/// Get the clipboard tool for the current platform.
+fn clipboard_command() -> Result<(&'static str, &'static [&'static str])> {
+    if cfg!(target_os = "macos") {
+        return Ok(("pbcopy", &[]));
+    }
+    if cfg!(target_os = "windows") {
+        return Ok(("clip", &[]));
+    }
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        return Ok(("wl-copy", &[]));
+    }
+    if std::env::var_os("DISPLAY").is_some() {
+        return Ok(("xclip", &["-selection", "clipboard"]));
+    }
+    bail!("No clipboard is available");
+}
@@ -58,6 +58,46 @@ pub enum Command {
     /// Toggle a tag.
     #[command(alias = "toggle-tag")]
     TagToggle { tag: String },
+    /// Re-apply a feed's configured tags to its stored entries.
+    Retag { feed: String },
+    /// Toggle whether the entry has been read.
+    #[command(alias = "read")]
+    ToggleRead,
+    /// Mark all entries read.
+    #[command(alias = "read-all")]
+    ReadAll,
+    /// Mark entries above the selected entry read.
+    #[command(alias = "read-above")]
+    ReadAbove,
+    /// Mark entries below the selected entry read.
+    #[command(alias = "read-below")]
+    ReadBelow,
+    /// Copy the entry's link to the clipboard.
+    #[command(alias = "copy")]
+    Yank,
+    /// Copy the entry's comments link to the clipboard.
+    YankComments,
+    /// Copy the entry's title to the clipboard.
+    YankTitle,
+    /// Copy a citation of the entry to the clipboard.
+    #[command(alias = "cite")]
+    YankCitation,
+    /// Toggle only showing unread entries.
+    #[command(alias = "unread", alias = "unread-only")]
+    ToggleUnread,
+    /// Sort entries newest first, oldest first, or by source, or cycle
+    /// through the orders.
+    Sort {
+        #[arg(value_enum)]
+        order: Option<EntryOrder>,
+    },
+    /// Toggle whether the entry is important.
+    #[command(alias = "important", alias = "star")]
+    ToggleImportant,
+    /// Save the entry to read later.
+    Save,
+    /// Stop saving the entry.
+    Unsave,
     /// Run a user-defined command.
     #[command(alias = "run")]
     Command { command: String },
@@ -69,7 +109,7 @@ pub enum Command {
     PageBackwards,
 }
 
-#[derive(Parser, Clone)]
+#[derive(Parser, Clone, Default)]
 pub struct SearchContext {
     /// Filter by tag.
     #[arg(short, long, value_parser, num_args = 1.., value_delimiter = ' ')]
@@ -93,6 +133,52 @@ pub struct SearchContext {
     /// WARNING: This is purposefully not checked.
     #[arg(short, long, value_parser, num_args = 1..)]
     pub raw: Vec<String>,
+    /// Only show saved entries.
+    #[arg(long, action)]
+    pub saved: bool,
+    /// Match tags and feeds containing the searched text, rather than only
+    /// those named exactly.
+    #[arg(long, action)]
+    pub substring: bool,
     /// Search text.
     pub text: Option<String>,
 }
+
+impl SearchContext {
+    /// Build the database search for this context.
+    pub fn criteria(&self) -> Vec<DatabaseSearch> {
+        let matching = match self.substring {
+            true => SearchMatch::Substring,
+            false => SearchMatch::Exact,
+        };
+        let mut criteria: Vec<DatabaseSearch> = Vec::new();
+        for tag in &self.tag {
+            criteria.push(matching.tag(tag));
+        }
+        for not_tag in &self.not_tag {
+            criteria.push(DatabaseSearch::NotTag(not_tag.clone()));
+        }
+        for feed in &self.feed {
+            criteria.push(matching.feed(feed));
+        }
+        for not_feed in &self.not_feed {
+            criteria.push(DatabaseSearch::NotFeed(not_feed.clone()));
+        }
+        for cmd in &self.command {
+            criteria.push(DatabaseSearch::Command(cmd.clone()));
+        }
+        for not_cmd in &self.not_command {
+            criteria.push(DatabaseSearch::NotCommand(not_cmd.clone()));
+        }
+        for raw_clause in &self.raw {
+            criteria.push(DatabaseSearch::Raw(raw_clause.clone()));
+        }
+        if self.saved {
+            criteria.push(DatabaseSearch::Saved);
+        }
+        if let Some(text) = &self.text {
+            criteria.push(DatabaseSearch::Search(text.clone()));
+        }
+        criteria
+    }
+}
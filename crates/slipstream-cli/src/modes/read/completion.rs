@@ -0,0 +1,61 @@
+//! Command mode completion.
+
+/// Command mode commands that take a tag.
+const TAG_COMMANDS: &[&str] = &[
+    "tag",
+    "add-tag",
+    "tag-add",
+    "untag",
+    "remove-tag",
+    "tag-remove",
+    "toggle-tag",
+    "tag-toggle",
+];
+
+/// Get the partially typed tag of a tag command.
+fn partial_tag(command: &str) -> Option<&str> {
+    let (name, tag) = command.split_once(' ')?;
+    if !TAG_COMMANDS.contains(&name) || tag.contains(char::is_whitespace) {
+        return None;
+    }
+    Some(tag)
+}
+
+/// Get the tags that could complete a command, in order.
+pub fn tag_completions<'a>(command: &str, tags: &'a [String]) -> Vec<&'a str> {
+    let Some(partial) = partial_tag(command) else {
+        return Vec::new();
+    };
+    let partial = partial.to_lowercase();
+    tags.iter()
+        .map(|tag| tag.as_str())
+        .filter(|tag| tag.to_lowercase().starts_with(&partial))
+        .collect()
+}
+
+/// Complete the tag of a command as far as the matching tags agree.
+/// This returns `None` when there is nothing to complete.
+pub fn complete_tag(command: &str, tags: &[String]) -> Option<String> {
+    let partial = partial_tag(command)?;
+    let completions = tag_completions(command, tags);
+    let (first, rest) = completions.split_first()?;
+
+    // Keep the longest prefix shared by every match.
+    let mut common: &str = first;
+    for tag in rest {
+        let length = common
+            .char_indices()
+            .zip(tag.chars())
+            .take_while(|((_, a), b)| a.to_lowercase().eq(b.to_lowercase()))
+            .last()
+            .map(|((index, c), _)| index + c.len_utf8())
+            .unwrap_or(0);
+        common = &common[..length];
+    }
+    if common.len() <= partial.len() {
+        return None;
+    }
+
+    let name = &command[..command.len() - partial.len()];
+    Some(format!("{name}{common}"))
+}
@@ -0,0 +1,79 @@
+//! Citation options.
+
+use super::*;
+
+/// Markdown link citation.
+const MARKDOWN: &str = "[{title}]({url}) ({author}, {date})";
+
+/// BibTeX-style citation.
+const BIBTEX: &str = "@online{{key},
+  title = {{title}},
+  author = {{author}},
+  year = {{year}},
+  url = {{url}},
+  urldate = {{date}}
+}";
+
+/// Plain text citation.
+const PLAIN: &str = "{author}. \"{title}\". {date}. {url}";
+
+/// Template for citations copied with `yank-citation`.
+/// This is "markdown", "bibtex", "plain", or a custom template using
+/// `{title}`, `{author}`, `{date}`, `{year}`, `{url}`, `{feed}`, and `{key}`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CitationFormat(String);
+
+impl CitationFormat {
+    /// Get the template, expanding named formats.
+    pub fn template(&self) -> &str {
+        match self.0.to_lowercase().as_str() {
+            "markdown" | "md" => MARKDOWN,
+            "bibtex" | "bib" => BIBTEX,
+            "plain" | "text" => PLAIN,
+            _ => &self.0,
+        }
+    }
+
+    /// Render the citation of an entry.
+    /// Entries without an author are attributed to their feed.
+    pub fn render(
+        &self,
+        entry: &slipfeed::Entry,
+        timezone: &TimeZone,
+    ) -> String {
+        let feed = entry
+            .feeds()
+            .iter()
+            .map(|feed| feed.name.as_str())
+            .next()
+            .unwrap_or("");
+        let author = match entry.author().trim() {
+            "" => feed,
+            author => author,
+        };
+        let date = timezone.date(entry.date());
+        let key: String = entry
+            .title()
+            .split_whitespace()
+            .next()
+            .unwrap_or("entry")
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+        self.template()
+            .replace("{title}", entry.title().trim())
+            .replace("{author}", author)
+            .replace("{date}", &date.format("%Y-%m-%d").to_string())
+            .replace("{year}", &date.format("%Y").to_string())
+            .replace("{url}", &entry.source().url)
+            .replace("{feed}", feed)
+            .replace("{key}", &format!("{key}{}", date.format("%Y")))
+    }
+}
+
+impl Default for CitationFormat {
+    fn default() -> Self {
+        Self("markdown".into())
+    }
+}
@@ -71,7 +71,7 @@ impl From<ColorConfig> for Style {
 }
 
 /// A literal color value used for parsing.
-/// This only supports ANSI colors.
+/// This supports ANSI color names and `#rrggbb` hex colors.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum ColorLiteral {
     #[serde(alias = "black")]
@@ -148,6 +148,47 @@ pub enum ColorLiteral {
         alias = "light-white"
     )]
     BrightWhite,
+    #[serde(untagged)]
+    Hex(HexColor),
+}
+
+/// A `#rrggbb` hex color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HexColor(u8, u8, u8);
+
+impl Serialize for HexColor {
+    fn serialize<S>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&format!(
+            "#{:02x}{:02x}{:02x}",
+            self.0, self.1, self.2
+        ))
+    }
+}
+
+impl<'de> Deserialize<'de> for HexColor {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let text = String::deserialize(deserializer)?;
+        let hex = text.trim().trim_start_matches('#');
+        let channel = |i: usize| {
+            hex.get(i..i + 2)
+                .and_then(|channel| u8::from_str_radix(channel, 16).ok())
+        };
+        match (hex.len(), channel(0), channel(2), channel(4)) {
+            (6, Some(r), Some(g), Some(b)) => Ok(Self(r, g, b)),
+            _ => Err(<D::Error as serde::de::Error>::custom(format!(
+                "invalid color `{text}`"
+            ))),
+        }
+    }
 }
 
 impl From<&ColorLiteral> for Color {
@@ -169,6 +210,7 @@ impl From<&ColorLiteral> for Color {
             ColorLiteral::BrightMagenta => Color::LightMagenta,
             ColorLiteral::BrightCyan => Color::LightCyan,
             ColorLiteral::BrightWhite => Color::White,
+            ColorLiteral::Hex(HexColor(r, g, b)) => Color::Rgb(r, g, b),
         }
     }
 }
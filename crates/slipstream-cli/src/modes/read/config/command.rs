@@ -141,6 +141,12 @@ pub enum ReadCommandLiteral {
     /// Go far up in the current context.
     #[serde(alias = "page-up")]
     PageUp,
+    /// Go to the first entry.
+    #[serde(alias = "top")]
+    Top,
+    /// Go to the last entry.
+    #[serde(alias = "bottom")]
+    Bottom,
     /// Swap the current context.
     #[serde(alias = "swap")]
     Swap,
@@ -159,7 +165,78 @@ pub enum ReadCommandLiteral {
     /// Page backwards.
     #[serde(alias = "page-backwards", alias = "prev", alias = "previous")]
     PageBackwards,
+    /// Expand or collapse a group of entries with similar titles.
+    #[serde(alias = "toggle-group", alias = "expand")]
+    ToggleGroup,
+    /// Only show unread entries, or show all entries again.
+    #[serde(alias = "toggle-unread", alias = "unread")]
+    ToggleUnread,
+    /// Cycle the order of entries: newest first, oldest first, or by source.
+    #[serde(alias = "cycle-sort", alias = "sort")]
+    CycleSort,
+    /// Show the key bindings.
+    #[serde(alias = "help")]
+    Help,
+    /// Copy the entry's link to the clipboard.
+    #[serde(alias = "yank", alias = "copy")]
+    Yank,
+    /// Copy the entry's comments link to the clipboard.
+    #[serde(alias = "yank-comments")]
+    YankComments,
+    /// Copy the entry's title to the clipboard.
+    #[serde(alias = "yank-title")]
+    YankTitle,
+    /// Copy a citation of the entry to the clipboard.
+    #[serde(alias = "yank-citation", alias = "cite")]
+    YankCitation,
+    /// Select the next entry matching the last search.
+    #[serde(alias = "next-match")]
+    NextMatch,
+    /// Select the previous entry matching the last search.
+    #[serde(alias = "previous-match", alias = "prev-match")]
+    PreviousMatch,
+    /// Save the entry to read later, or stop saving it.
+    #[serde(alias = "toggle-saved", alias = "save")]
+    ToggleSaved,
+    /// Open the command palette.
+    #[serde(alias = "palette", alias = "command-palette")]
+    Palette,
     /// Run a specific command_mode command.
     #[serde(alias = "command")]
     Command(String),
 }
+
+impl ReadCommandLiteral {
+    /// Built-in commands that can be run from the command palette, by name.
+    pub fn builtins() -> Vec<(&'static str, ReadCommandLiteral)> {
+        vec![
+            ("quit", ReadCommandLiteral::Quit),
+            ("update", ReadCommandLiteral::Update),
+            ("down", ReadCommandLiteral::Down),
+            ("up", ReadCommandLiteral::Up),
+            ("left", ReadCommandLiteral::Left),
+            ("right", ReadCommandLiteral::Right),
+            ("page-down", ReadCommandLiteral::PageDown),
+            ("page-up", ReadCommandLiteral::PageUp),
+            ("top", ReadCommandLiteral::Top),
+            ("bottom", ReadCommandLiteral::Bottom),
+            ("swap", ReadCommandLiteral::Swap),
+            ("menu", ReadCommandLiteral::Menu),
+            ("command-mode", ReadCommandLiteral::CommandMode),
+            ("search-mode", ReadCommandLiteral::SearchMode),
+            ("page-forwards", ReadCommandLiteral::PageForwards),
+            ("page-backwards", ReadCommandLiteral::PageBackwards),
+            ("toggle-group", ReadCommandLiteral::ToggleGroup),
+            ("toggle-unread", ReadCommandLiteral::ToggleUnread),
+            ("cycle-sort", ReadCommandLiteral::CycleSort),
+            ("help", ReadCommandLiteral::Help),
+            ("yank", ReadCommandLiteral::Yank),
+            ("yank-comments", ReadCommandLiteral::YankComments),
+            ("yank-title", ReadCommandLiteral::YankTitle),
+            ("yank-citation", ReadCommandLiteral::YankCitation),
+            ("next-match", ReadCommandLiteral::NextMatch),
+            ("previous-match", ReadCommandLiteral::PreviousMatch),
+            ("toggle-saved", ReadCommandLiteral::ToggleSaved),
+        ]
+    }
+}
@@ -2,17 +2,23 @@
 
 use super::*;
 
+mod citation;
 mod color;
 mod command;
 mod flag;
 mod preview;
 mod tag;
+mod theme;
+mod time_format;
 
+pub use citation::*;
 pub use color::*;
 pub use command::*;
 pub use flag::*;
 pub use preview::*;
 pub use tag::*;
+pub use theme::*;
+pub use time_format::*;
 
 /// Read configuration.
 #[derive(Clone, Default, Debug, Serialize, Deserialize)]
@@ -44,6 +50,18 @@ pub struct ReadConfig {
     /// Per-entry preview format.
     #[serde(default, alias = "preview-format")]
     pub preview_format: PreviewFormat,
+    /// Title similarity (0 to 1) at which entries are collapsed together.
+    #[serde(default, alias = "collapse-similar")]
+    pub collapse_similar: Option<f64>,
+    /// Citation format for `yank-citation`.
+    #[serde(default)]
+    pub citation: CitationFormat,
+    /// How dates are shown in the entry view.
+    #[serde(default, alias = "time-format")]
+    pub time_format: TimeFormat,
+    /// Colors of the reader.
+    #[serde(default)]
+    pub theme: ThemeConfig,
 }
 
 impl ReadConfig {
@@ -80,33 +98,47 @@ impl ReadConfig {
             }
         }
 
-        if *key == UPDATE {
-            Commandish::Literal(ReadCommandLiteral::Update)
-        } else if *key == QUIT {
-            Commandish::Literal(ReadCommandLiteral::Quit)
-        } else if *key == DOWN {
-            Commandish::Literal(ReadCommandLiteral::Down)
-        } else if *key == UP {
-            Commandish::Literal(ReadCommandLiteral::Up)
-        } else if *key == LEFT {
-            Commandish::Literal(ReadCommandLiteral::Left)
-        } else if *key == RIGHT {
-            Commandish::Literal(ReadCommandLiteral::Right)
-        } else if *key == PAGE_DOWN {
-            Commandish::Literal(ReadCommandLiteral::PageDown)
-        } else if *key == PAGE_UP {
-            Commandish::Literal(ReadCommandLiteral::PageUp)
-        } else if *key == TAB {
-            Commandish::Literal(ReadCommandLiteral::Swap)
-        } else if *key == MENU {
-            Commandish::Literal(ReadCommandLiteral::Menu)
-        } else if *key == COMMAND_MODE {
-            Commandish::Literal(ReadCommandLiteral::CommandMode)
-        } else if *key == SEARCH_MODE {
-            Commandish::Literal(ReadCommandLiteral::SearchMode)
-        } else {
-            Commandish::Literal(ReadCommandLiteral::None)
+        for (_, default, literal) in default_bindings() {
+            if *key == default {
+                return Commandish::Literal(literal);
+            }
         }
+        Commandish::Literal(ReadCommandLiteral::None)
+    }
+
+    /// Get every active binding and its resolved command, for help.
+    /// Configured bindings come first, followed by the defaults they don't
+    /// override. Keys mapped to "none" are left out.
+    pub fn help_bindings(&self) -> Vec<(String, Commandish)> {
+        let configured = self.bindings.iter().map(|(binding, command)| {
+            let command = match command {
+                Commandish::CustomCommandRef(name) => {
+                    self.get_custom_command(name.as_str())
+                }
+                _ => command.clone(),
+            };
+            (binding.binding().to_string(), command)
+        });
+        let defaults = default_bindings()
+            .into_iter()
+            .filter(|(_, default, _)| {
+                !self
+                    .bindings
+                    .keys()
+                    .any(|binding| *default == binding.into())
+            })
+            .map(|(name, _, literal)| {
+                (name.to_string(), Commandish::Literal(literal))
+            });
+        configured
+            .chain(defaults)
+            .filter(|(_, command)| {
+                !matches!(
+                    command,
+                    Commandish::Literal(ReadCommandLiteral::None)
+                )
+            })
+            .collect()
     }
 
     /// Get custom command associated with a command name.
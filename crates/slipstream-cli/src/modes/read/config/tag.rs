@@ -15,11 +15,24 @@ pub struct TagConfig {
     pub colors: Vec<TagColor>,
 }
 
+/// Color and indicator for entries matching every condition given.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TagColor {
     /// Tag(s) that color should apply to.
     #[serde(flatten)]
-    tag: TagOrTags,
+    tag: Option<TagOrTags>,
+    /// Feed the entry must be from.
+    #[serde(default)]
+    feed: Option<String>,
+    /// Author the entry must have (case-insensitive).
+    #[serde(default)]
+    author: Option<String>,
+    /// Text the entry's title must contain (case-insensitive).
+    #[serde(default)]
+    title: Option<String>,
+    /// Regex the entry's title must match.
+    #[serde(default, alias = "title-regex")]
+    title_regex: Option<RegexPattern>,
     /// Optional color for the tag.
     #[serde(default)]
     color: ColorConfig,
@@ -36,7 +49,30 @@ impl TagColor {
             return false;
         }
 
-        self.tag.matches_all(entry)
+        // Rules without conditions don't match anything.
+        if self.tag.is_none()
+            && self.feed.is_none()
+            && self.author.is_none()
+            && self.title.is_none()
+            && self.title_regex.is_none()
+        {
+            return false;
+        }
+
+        self.tag.as_ref().is_none_or(|tag| tag.matches_all(entry))
+            && self.feed.as_ref().is_none_or(|feed| {
+                entry.feeds().iter().any(|f| f.name.as_str() == feed)
+            })
+            && self.author.as_ref().is_none_or(|author| {
+                entry.author().to_lowercase() == author.to_lowercase()
+            })
+            && self.title.as_ref().is_none_or(|title| {
+                entry.title().to_lowercase().contains(&title.to_lowercase())
+            })
+            && self
+                .title_regex
+                .as_ref()
+                .is_none_or(|regex| regex.is_match(entry.title()))
     }
 
     #[allow(unused)]
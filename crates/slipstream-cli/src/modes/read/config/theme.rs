@@ -0,0 +1,82 @@
+//! Theme options.
+
+use super::*;
+
+/// Colors of the reader.
+/// Unspecified colors keep their defaults.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ThemeConfig {
+    /// Header and selected tab background (default blue).
+    #[serde(default)]
+    header: Option<ColorLiteral>,
+    /// Selected entry and focused border (default green).
+    #[serde(default)]
+    selection: Option<ColorLiteral>,
+    /// Hovered entry (default gray).
+    #[serde(default)]
+    hover: Option<ColorLiteral>,
+    /// Feed names in the entry list (default light cyan).
+    #[serde(default, alias = "feed-tag")]
+    feed_tag: Option<ColorLiteral>,
+    /// Tags (default yellow).
+    #[serde(default)]
+    tag: Option<ColorLiteral>,
+    /// Dates in the entry list (default light yellow).
+    #[serde(default)]
+    date: Option<ColorLiteral>,
+    /// Entry authors (default light green).
+    #[serde(default)]
+    author: Option<ColorLiteral>,
+    /// Entry links (default red).
+    #[serde(default)]
+    link: Option<ColorLiteral>,
+    /// Saved entries in the entry list (default magenta).
+    #[serde(default)]
+    saved: Option<ColorLiteral>,
+}
+
+impl ThemeConfig {
+    pub fn header(&self) -> Color {
+        Self::color(&self.header, Color::Blue)
+    }
+
+    pub fn selection(&self) -> Color {
+        Self::color(&self.selection, Color::Green)
+    }
+
+    pub fn hover(&self) -> Color {
+        Self::color(&self.hover, Color::Gray)
+    }
+
+    pub fn feed_tag(&self) -> Color {
+        Self::color(&self.feed_tag, Color::LightCyan)
+    }
+
+    pub fn tag(&self) -> Color {
+        Self::color(&self.tag, Color::Yellow)
+    }
+
+    pub fn date(&self) -> Color {
+        Self::color(&self.date, Color::LightYellow)
+    }
+
+    pub fn author(&self) -> Color {
+        Self::color(&self.author, Color::LightGreen)
+    }
+
+    pub fn link(&self) -> Color {
+        Self::color(&self.link, Color::Red)
+    }
+
+    pub fn saved(&self) -> Color {
+        Self::color(&self.saved, Color::Magenta)
+    }
+
+    /// Get a configured color, or its default.
+    fn color(color: &Option<ColorLiteral>, default: Color) -> Color {
+        match color {
+            Some(color) => color.into(),
+            None => default,
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//! Time format options.
+
+use super::*;
+
+/// How entry dates are shown in the entry view.
+#[derive(
+    Clone, Copy, Default, Debug, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum TimeFormat {
+    /// The date and time, e.g. "2025-06-12 09:00".
+    #[default]
+    Absolute,
+    /// How long ago, e.g. "3h ago", for dates within a week.
+    Relative,
+    /// The date and time, followed by how long ago.
+    Both,
+}
+
+impl TimeFormat {
+    /// Format a date as configured, relative to now.
+    pub fn format(
+        &self,
+        dt: &slipfeed::DateTime,
+        config: &Config,
+        now: &slipfeed::DateTime,
+    ) -> String {
+        let absolute = config.format_date(dt);
+        match (self, dt.relative_to(now)) {
+            (TimeFormat::Absolute, _) | (_, None) => absolute,
+            (TimeFormat::Relative, Some(relative)) => relative,
+            (TimeFormat::Both, Some(relative)) => {
+                format!("{absolute} ({relative})")
+            }
+        }
+    }
+}
@@ -20,10 +20,20 @@ pub struct DatabaseEntry {
     /// Index for which result is being displayed.
     /// Index 0 represent the "info" metadata pannel.
     pub result_selection_index: usize,
+    /// Whether the entry has been read.
+    pub read: bool,
+    /// Whether the entry is marked important.
+    pub important: bool,
+    /// Whether the entry is saved to read later.
+    pub saved: bool,
     /// List of unique binding/command results.
     command_results: Vec<CommandResultContext>,
     /// List of commands that were ran.
     ran_commands: Vec<Arc<String>>,
+    /// Entries with similar titles collapsed into this entry.
+    pub similar: Vec<DatabaseEntry>,
+    /// The entry this was collapsed into, while its group is expanded.
+    pub group: Option<EntryDbId>,
 }
 
 impl DatabaseEntry {
@@ -33,8 +43,13 @@ impl DatabaseEntry {
             entry,
             db_id: id,
             result_selection_index: 0,
+            read: false,
+            important: false,
+            saved: false,
             command_results: Vec::new(),
             ran_commands: Vec::new(),
+            similar: Vec::new(),
+            group: None,
         }
     }
 
@@ -108,8 +123,26 @@ impl EntryExt for DatabaseEntry {
     fn to_atom(&self, config: &Config) -> atom_syndication::Entry {
         let mut atom_entry = self.entry.to_atom(config);
         atom_entry.id = format!("{}", self.db_id);
+        if config.serve.expose_entries() {
+            atom_entry.links.push(
+                atom::LinkBuilder::default()
+                    .href(format!("/entry/{}", self.db_id))
+                    .rel("alternate")
+                    .mime_type(Some("text/html".into()))
+                    .build(),
+            );
+        }
         return atom_entry;
     }
+
+    fn to_rss(&self, config: &Config) -> rss::Item {
+        let mut rss_item = self.entry.to_rss(config);
+        rss_item.guid = Some(rss::Guid {
+            value: format!("{}", self.db_id),
+            permalink: false,
+        });
+        rss_item
+    }
 }
 
 impl Deref for DatabaseEntry {
@@ -153,7 +186,7 @@ impl<'a> Widget for EntryViewWidget<'a> {
             .title(self.entry.title().as_str())
             .border_type(ratatui::widgets::BorderType::Rounded)
             .fg(match self.interaction_state.focus {
-                Focus::Entry => Color::Green,
+                Focus::Entry => self.config.read.theme.selection(),
                 _ => Color::White,
             });
         let inner_block = block.inner(area);
@@ -193,7 +226,10 @@ impl<'a> Widget for EntryViewWidget<'a> {
                 .divider(" ")
                 // .bg(Color::Green)
                 .select(self.entry.result_selection_index)
-                .highlight_style((Color::Black, Color::Blue));
+                .highlight_style((
+                    Color::Black,
+                    self.config.read.theme.header(),
+                ));
         tabs.render(tab_layouts[0], buf);
         match self.entry.get_result() {
             None => {
@@ -207,6 +243,36 @@ impl<'a> Widget for EntryViewWidget<'a> {
     }
 }
 
+/// Remove raw html, such as scripts, and terminal control characters from
+/// markdown, keeping the rest of the markdown as it is.
+pub fn sanitize_markdown(content: &str) -> String {
+    let html: Vec<std::ops::Range<usize>> = pulldown_cmark::Parser::new_ext(
+        content,
+        pulldown_cmark::Options::all(),
+    )
+    .into_offset_iter()
+    .filter(|(event, _)| {
+        matches!(
+            event,
+            pulldown_cmark::Event::Html(_)
+                | pulldown_cmark::Event::InlineHtml(_)
+        )
+    })
+    .map(|(_, range)| range)
+    .collect();
+    let mut sanitized = String::with_capacity(content.len());
+    let mut start = 0;
+    for range in html {
+        if range.start >= start {
+            sanitized.push_str(&content[start..range.start]);
+            start = range.end;
+        }
+    }
+    sanitized.push_str(&content[start..]);
+    sanitized.retain(|c| !c.is_control() || c == '\n' || c == '\t');
+    sanitized
+}
+
 /// Widget for displaying entry info.
 struct EntryInfoWidget<'a>(&'a slipfeed::Entry, &'a Config);
 
@@ -241,7 +307,7 @@ impl<'a> Widget for EntryInfoWidget<'a> {
                         false => "---",
                     }
                 ),
-                Style::default().fg(Color::LightGreen),
+                Style::default().fg(self.1.read.theme.author()),
             )
             .into(),
         );
@@ -257,7 +323,7 @@ impl<'a> Widget for EntryInfoWidget<'a> {
         tags.sort();
         top_lines.push(Line::styled(
             tags.join(", "),
-            Style::default().fg(Color::Yellow),
+            Style::default().fg(self.1.read.theme.tag()),
         ));
 
         // Add links:
@@ -267,7 +333,7 @@ impl<'a> Widget for EntryInfoWidget<'a> {
             top_lines.push(
                 Span::styled(
                     format!("[{}] {}", link_count, self.0.source().url),
-                    Style::default().fg(Color::Red),
+                    Style::default().fg(self.1.read.theme.link()),
                 )
                 .into(),
             );
@@ -277,7 +343,7 @@ impl<'a> Widget for EntryInfoWidget<'a> {
             top_lines.push(
                 Span::styled(
                     format!("[{}] {}", link_count, self.0.comments().url),
-                    Style::default().fg(Color::Red),
+                    Style::default().fg(self.1.read.theme.link()),
                 )
                 .into(),
             );
@@ -287,7 +353,7 @@ impl<'a> Widget for EntryInfoWidget<'a> {
             top_lines.push(
                 Span::styled(
                     format!("[{}] {}", link_count, self.0.other_links()[i].url),
-                    Style::default().fg(Color::Red),
+                    Style::default().fg(self.1.read.theme.link()),
                 )
                 .into(),
             );
@@ -298,7 +364,11 @@ impl<'a> Widget for EntryInfoWidget<'a> {
             .render(layouts[0], buf);
 
         if !self.0.content().is_empty() {
-            Paragraph::new(tui_markdown::from_str(self.0.content()).to_text())
+            let content = match self.1.serve.sanitize() {
+                true => sanitize_markdown(self.0.content()),
+                false => self.0.content().clone(),
+            };
+            Paragraph::new(tui_markdown::from_str(&content).to_text())
                 .left_aligned()
                 .wrap(Wrap { trim: false })
                 .render(layouts[1], buf);
@@ -313,7 +383,11 @@ impl<'a> Widget for EntryInfoWidget<'a> {
         // Add date:
         bottom_lines.push(
             Line::from(Span::styled(
-                self.1.timezone.format(self.0.date()),
+                self.1.read.time_format.format(
+                    self.0.date(),
+                    self.1,
+                    &slipfeed::DateTime::now(),
+                ),
                 Style::default(),
             ))
             .right_aligned(),
@@ -352,6 +426,153 @@ impl DatabaseEntryList {
         bail!("Entry list at max length ({}).", self.max_size);
     }
 
+    /// Add an entry to the list, merging it into an entry with the same
+    /// title, author, and source if one exists.
+    pub fn add_or_merge(&mut self, entry: DatabaseEntry) -> Result<()> {
+        if let Some(other) = self.entries.iter_mut().find(|other| {
+            other.title() == entry.title()
+                && other.author() == entry.author()
+                && other.source().url == entry.source().url
+        }) {
+            for feed in entry.feeds().iter() {
+                other.entry.add_feed(feed.clone());
+            }
+            for tag in entry.tags().iter() {
+                other.entry.add_tag(tag);
+            }
+            return Ok(());
+        }
+        self.add(entry)
+    }
+
+    /// Collapse entries with similar titles into the first entry of each
+    /// group.
+    pub fn collapse_similar(self, threshold: f64) -> Self {
+        let mut collapsed = DatabaseEntryList::new(self.max_size);
+        for entry in self.entries {
+            match collapsed.entries.iter_mut().find(|first| {
+                token_set_ratio(first.title(), entry.title()) >= threshold
+            }) {
+                Some(first) => first.similar.push(entry),
+                None => {
+                    collapsed.add(entry).ok();
+                }
+            }
+        }
+        collapsed
+    }
+
+    /// Expand or collapse the group of the entry at an index.
+    /// This returns the index of the first entry of the group.
+    pub fn toggle_group(&mut self, index: usize) -> usize {
+        let Some(entry) = self.entries.get(index) else {
+            return index;
+        };
+        let group_id = entry.group.unwrap_or(entry.db_id);
+        let Some(first) = self.lookup.get(&group_id).copied() else {
+            return index;
+        };
+        if self.entries[first].similar.is_empty() {
+            let mut end = first + 1;
+            while end < self.entries.len()
+                && self.entries[end].group == Some(group_id)
+            {
+                end += 1;
+            }
+            let similar = self
+                .entries
+                .drain(first + 1..end)
+                .map(|mut entry| {
+                    entry.group = None;
+                    entry
+                })
+                .collect();
+            self.entries[first].similar = similar;
+        } else {
+            let similar = std::mem::take(&mut self.entries[first].similar)
+                .into_iter()
+                .map(|mut entry| {
+                    entry.group = Some(group_id);
+                    entry
+                });
+            self.entries.splice(first + 1..first + 1, similar);
+        }
+        self.lookup = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| (entry.db_id, i))
+            .collect();
+        first
+    }
+
+    /// Mark the entries in a range of the list read, along with entries
+    /// collapsed into them.
+    /// This returns the ids of the entries that changed.
+    pub fn mark_read(
+        &mut self,
+        range: std::ops::Range<usize>,
+        read: bool,
+    ) -> Vec<EntryDbId> {
+        let end = range.end.min(self.entries.len());
+        let start = range.start.min(end);
+        let mut changed = Vec::new();
+        for entry in self.entries[start..end].iter_mut() {
+            if entry.read != read {
+                entry.read = read;
+                changed.push(entry.db_id);
+            }
+            for similar in entry.similar.iter_mut() {
+                if similar.read != read {
+                    similar.read = read;
+                    changed.push(similar.db_id);
+                }
+            }
+        }
+        changed
+    }
+
+    /// Find the next entry, after `from`, where every word of the text starts
+    /// a word of its title, content, or author, like database searches. This
+    /// wraps around the list, going backwards unless `forwards`.
+    pub fn find_match(
+        &self,
+        from: usize,
+        text: &str,
+        forwards: bool,
+    ) -> Option<usize> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        if words.is_empty() {
+            return None;
+        }
+        let len = self.entries.len();
+        let from = from.min(len.checked_sub(1)?);
+        (1..=len)
+            .map(|step| match forwards {
+                true => (from + step) % len,
+                false => (from + len - step) % len,
+            })
+            .find(|index| {
+                let entry = &self.entries[*index];
+                words.iter().all(|word| {
+                    [entry.title(), entry.content(), entry.author()]
+                        .into_iter()
+                        .any(|field| word_match_range(field, word).is_some())
+                })
+            })
+    }
+
+    /// Reverse the order of the list.
+    pub fn reverse(&mut self) {
+        self.entries.reverse();
+        self.lookup = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| (entry.db_id, i))
+            .collect();
+    }
+
     /// Get the length of the list.
     pub fn len(&self) -> usize {
         self.entries.len()
@@ -389,6 +610,11 @@ impl DatabaseEntryList {
         self.entries.iter()
     }
 
+    /// Iterate the list, mutably.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut DatabaseEntry> {
+        self.entries.iter_mut()
+    }
+
     /// Iterate the list's slipfeed entries.
     pub fn iter_entries(&self) -> impl Iterator<Item = &slipfeed::Entry> {
         self.entries.iter().map(|e| &e.entry)
@@ -396,13 +622,94 @@ impl DatabaseEntryList {
 
     /// Turn list into an atom syndication.
     pub fn syndicate(&self, name: impl AsRef<str>, config: &Config) -> String {
+        self.syndicate_atom(name, config, &PageLinks::default())
+    }
+
+    /// Turn list into an atom syndication, linking to the pages next to it.
+    pub fn syndicate_atom(
+        &self,
+        name: impl AsRef<str>,
+        config: &Config,
+        links: &PageLinks,
+    ) -> String {
         let mut syn = atom::FeedBuilder::default();
         syn.title(name.as_ref())
             .author(atom::PersonBuilder::default().name("slipstream").build());
+        for (rel, href) in [("next", &links.older), ("previous", &links.newer)]
+        {
+            if let Some(href) = href {
+                syn.link(
+                    atom::LinkBuilder::default()
+                        .href(href)
+                        .rel(rel)
+                        .mime_type(Some("application/atom+xml".into()))
+                        .build(),
+                );
+            }
+        }
         for entry in self.iter() {
             syn.entry(entry.to_atom(config));
         }
-        syn.build().to_string()
+        let syn = syn.build();
+        if config.serve.pretty_output {
+            let write_config = atom::WriteConfig {
+                write_document_declaration: true,
+                indent_size: Some(2),
+            };
+            match syn.write_with_config(Vec::new(), write_config) {
+                Ok(buf) => return String::from_utf8_lossy(&buf).into_owned(),
+                Err(e) => tracing::warn!("Unable to indent atom: {e}"),
+            }
+        }
+        syn.to_string()
+    }
+
+    /// Turn list into an rss syndication.
+    pub fn syndicate_rss(
+        &self,
+        name: impl AsRef<str>,
+        config: &Config,
+    ) -> String {
+        let mut syn = rss::ChannelBuilder::default();
+        syn.title(name.as_ref())
+            .description(format!("slipstream: {}", name.as_ref()))
+            .generator(Some("slipstream".into()));
+        for entry in self.iter() {
+            syn.item(entry.to_rss(config));
+        }
+        let syn = syn.build();
+        if config.serve.pretty_output {
+            match syn.pretty_write_to(Vec::new(), b' ', 2) {
+                Ok(buf) => return String::from_utf8_lossy(&buf).into_owned(),
+                Err(e) => tracing::warn!("Unable to indent rss: {e}"),
+            }
+        }
+        syn.to_string()
+    }
+
+    /// Turn list into a syndication of the specified format.
+    pub fn syndicate_as(
+        &self,
+        name: impl AsRef<str>,
+        config: &Config,
+        format: SyndicationFormat,
+    ) -> String {
+        self.syndicate_page(name, config, format, &PageLinks::default())
+    }
+
+    /// Turn list into a syndication of the specified format, linking to the
+    /// pages next to it where the format allows.
+    pub fn syndicate_page(
+        &self,
+        name: impl AsRef<str>,
+        config: &Config,
+        format: SyndicationFormat,
+        links: &PageLinks,
+    ) -> String {
+        match format {
+            SyndicationFormat::Atom => self.syndicate_atom(name, config, links),
+            SyndicationFormat::Rss => self.syndicate_rss(name, config),
+        }
     }
 }
 
@@ -419,3 +726,52 @@ impl std::ops::IndexMut<usize> for DatabaseEntryList {
         self.entries.index_mut(index)
     }
 }
+
+/// Find where text first appears in another, ignoring case.
+pub fn match_range(text: &str, query: &str) -> Option<std::ops::Range<usize>> {
+    find_range(text, query, false)
+}
+
+/// Find where text first appears at the start of a word in another, ignoring
+/// case.
+pub fn word_match_range(
+    text: &str,
+    query: &str,
+) -> Option<std::ops::Range<usize>> {
+    find_range(text, query, true)
+}
+
+/// Find where text first appears in another, ignoring case, optionally only
+/// at the start of words.
+fn find_range(
+    text: &str,
+    query: &str,
+    word_start: bool,
+) -> Option<std::ops::Range<usize>> {
+    if query.is_empty() {
+        return None;
+    }
+    for (start, _) in text.char_indices() {
+        if word_start
+            && text[..start]
+                .chars()
+                .next_back()
+                .is_some_and(char::is_alphanumeric)
+        {
+            continue;
+        }
+        let mut rest = text[start..].chars();
+        let mut end = start;
+        let matched = query.chars().all(|q| match rest.next() {
+            Some(c) if c.to_lowercase().eq(q.to_lowercase()) => {
+                end += c.len_utf8();
+                true
+            }
+            _ => false,
+        });
+        if matched {
+            return Some(start..end);
+        }
+    }
+    None
+}
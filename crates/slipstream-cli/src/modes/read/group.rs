@@ -0,0 +1,23 @@
+//! Grouping of entries with similar titles.
+
+use super::*;
+
+/// Get the normalized tokens of a title.
+pub fn title_tokens(title: &str) -> HashSet<String> {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(|token| token.to_lowercase())
+        .collect()
+}
+
+/// Get the similarity of two titles, from 0 to 1.
+/// This is a token-set ratio, the share of tokens the titles have in common.
+pub fn token_set_ratio(a: &str, b: &str) -> f64 {
+    let (a, b) = (title_tokens(a), title_tokens(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let shared = a.intersection(&b).count();
+    (2 * shared) as f64 / (a.len() + b.len()) as f64
+}
@@ -0,0 +1,84 @@
+//! Help overlay.
+
+use ratatui::widgets::{BorderType, Clear};
+
+use super::*;
+
+/// Maximum width of the help overlay.
+const HELP_WIDTH: u16 = 60;
+
+/// Widget to render the key bindings over the reader.
+pub(super) struct HelpWidget<'a> {
+    reader: &'a mut Reader,
+}
+
+impl<'a> HelpWidget<'a> {
+    pub(super) fn new(reader: &'a mut Reader) -> Self {
+        Self { reader }
+    }
+}
+
+impl<'a> Widget for HelpWidget<'a> {
+    fn render(self, area: Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let bindings = self.reader.config.read.help_bindings();
+
+        // Center the overlay.
+        let width = HELP_WIDTH.min(area.width.saturating_sub(4));
+        let height = (bindings.len() as u16 + 2)
+            .min(area.height.saturating_sub(4))
+            .max(3)
+            .min(area.height);
+        let help_area = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+        Clear.render(help_area, buf);
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title_top("Bindings")
+            .title_bottom("Press any key to close");
+        let inner = block.inner(help_area);
+        block.render(help_area, buf);
+
+        // Keep the scroll within the bindings.
+        let max_scroll = bindings.len().saturating_sub(inner.height as usize);
+        let scroll = match &mut self.reader.interaction_state.focus {
+            Focus::Help { scroll } => {
+                *scroll = (*scroll).min(max_scroll as u16);
+                *scroll
+            }
+            _ => 0,
+        };
+
+        // Show bindings.
+        let name_width = bindings
+            .iter()
+            .map(|(name, _)| name.chars().count())
+            .max()
+            .unwrap_or(0);
+        let lines: Vec<Line> = bindings
+            .iter()
+            .skip(scroll as usize)
+            .take(inner.height as usize)
+            .map(|(name, command)| {
+                Line::from(vec![
+                    Span::styled(
+                        format!(
+                            "{:<width$} ",
+                            format!("<{name}>"),
+                            width = name_width + 2
+                        ),
+                        Style::new().fg(Color::Blue),
+                    ),
+                    Span::raw(command.to_string()),
+                ])
+            })
+            .collect();
+        Paragraph::new(lines).render(inner, buf);
+    }
+}
@@ -19,12 +19,65 @@ pub const PAGE_DOWN: KeyEvent =
     KeyEvent::new(KeyCode::Char('j'), KeyModifiers::SHIFT);
 pub const PAGE_UP: KeyEvent =
     KeyEvent::new(KeyCode::Char('k'), KeyModifiers::SHIFT);
+pub const TOP: KeyEvent = KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE);
+pub const BOTTOM: KeyEvent =
+    KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT);
 pub const MENU: KeyEvent = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
 pub const TAB: KeyEvent = KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE);
 pub const COMMAND_MODE: KeyEvent =
     KeyEvent::new(KeyCode::Char(':'), KeyModifiers::NONE);
 pub const SEARCH_MODE: KeyEvent =
     KeyEvent::new(KeyCode::Char('/'), KeyModifiers::NONE);
+pub const TOGGLE_GROUP: KeyEvent =
+    KeyEvent::new(KeyCode::Char('z'), KeyModifiers::NONE);
+pub const TOGGLE_UNREAD: KeyEvent =
+    KeyEvent::new(KeyCode::Char('U'), KeyModifiers::SHIFT);
+pub const CYCLE_SORT: KeyEvent =
+    KeyEvent::new(KeyCode::Char('S'), KeyModifiers::SHIFT);
+pub const PALETTE: KeyEvent =
+    KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL);
+pub const HELP: KeyEvent =
+    KeyEvent::new(KeyCode::Char('?'), KeyModifiers::NONE);
+pub const YANK: KeyEvent =
+    KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE);
+pub const YANK_CITATION: KeyEvent =
+    KeyEvent::new(KeyCode::Char('Y'), KeyModifiers::SHIFT);
+pub const NEXT_MATCH: KeyEvent =
+    KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE);
+pub const PREVIOUS_MATCH: KeyEvent =
+    KeyEvent::new(KeyCode::Char('N'), KeyModifiers::SHIFT);
+pub const TOGGLE_SAVED: KeyEvent =
+    KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE);
+
+/// Default key bindings, with the name each is shown as.
+pub fn default_bindings() -> Vec<(&'static str, KeyEvent, ReadCommandLiteral)> {
+    vec![
+        ("u", UPDATE, ReadCommandLiteral::Update),
+        ("q", QUIT, ReadCommandLiteral::Quit),
+        ("j", DOWN, ReadCommandLiteral::Down),
+        ("k", UP, ReadCommandLiteral::Up),
+        ("h", LEFT, ReadCommandLiteral::Left),
+        ("l", RIGHT, ReadCommandLiteral::Right),
+        ("S-j", PAGE_DOWN, ReadCommandLiteral::PageDown),
+        ("S-k", PAGE_UP, ReadCommandLiteral::PageUp),
+        ("g", TOP, ReadCommandLiteral::Top),
+        ("S-g", BOTTOM, ReadCommandLiteral::Bottom),
+        ("tab", TAB, ReadCommandLiteral::Swap),
+        ("esc", MENU, ReadCommandLiteral::Menu),
+        (":", COMMAND_MODE, ReadCommandLiteral::CommandMode),
+        ("/", SEARCH_MODE, ReadCommandLiteral::SearchMode),
+        ("C-p", PALETTE, ReadCommandLiteral::Palette),
+        ("z", TOGGLE_GROUP, ReadCommandLiteral::ToggleGroup),
+        ("S-u", TOGGLE_UNREAD, ReadCommandLiteral::ToggleUnread),
+        ("S-s", CYCLE_SORT, ReadCommandLiteral::CycleSort),
+        ("?", HELP, ReadCommandLiteral::Help),
+        ("y", YANK, ReadCommandLiteral::Yank),
+        ("S-y", YANK_CITATION, ReadCommandLiteral::YankCitation),
+        ("n", NEXT_MATCH, ReadCommandLiteral::NextMatch),
+        ("S-n", PREVIOUS_MATCH, ReadCommandLiteral::PreviousMatch),
+        ("s", TOGGLE_SAVED, ReadCommandLiteral::ToggleSaved),
+    ]
+}
 
 /// Keyboard key.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Hash)]
@@ -26,16 +26,18 @@ impl<'a> Widget for MenuWidget<'a> {
             .constraints(vec![
                 Constraint::Min(1),
                 Constraint::Min(1),
-                Constraint::Percentage(30),
-                Constraint::Percentage(70),
+                Constraint::Percentage(40),
+                Constraint::Percentage(20),
+                Constraint::Percentage(40),
                 Constraint::Min(1),
             ])
             .split(area);
         let title_layout = layouts[0];
         let stats_layout = layouts[1];
-        let keyboard_layout = layouts[2];
-        let log_layout = layouts[3];
-        let help_layout = layouts[4];
+        let unread_layout = layouts[2];
+        let keyboard_layout = layouts[3];
+        let log_layout = layouts[4];
+        let help_layout = layouts[5];
 
         // Show slipstream menu header.
         Text::styled(
@@ -53,10 +55,50 @@ impl<'a> Widget for MenuWidget<'a> {
         .render(title_layout, buf);
 
         // Show status.
-        Line::from("Status")
-            .bg(Color::Green)
-            .fg(Color::Black)
-            .render(stats_layout, buf);
+        let feeds = self.reader.config.feed_names();
+        Line::from(format!(
+            "Status: {} feeds ({})",
+            feeds.len(),
+            feeds
+                .iter()
+                .map(|name| name.as_str())
+                .collect::<Vec<&str>>()
+                .join(", ")
+        ))
+        .bg(Color::Green)
+        .fg(Color::Black)
+        .render(stats_layout, buf);
+
+        // Show unread counts, keeping the selected count in view.
+        let counts = &self.reader.interaction_state.unread_counts;
+        let selected = match self.reader.interaction_state.focus {
+            Focus::Menu { scroll } => scroll as usize,
+            _ => 0,
+        };
+        let height = unread_layout.height.saturating_sub(2) as usize;
+        let theme = &self.reader.config.read.theme;
+        let lines: Vec<Line> = counts
+            .iter()
+            .enumerate()
+            .skip((selected + 1).saturating_sub(height))
+            .take(height)
+            .map(|(i, count)| {
+                let line =
+                    Line::from(format!("{} ({})", count.label(), count.unread));
+                match (i == selected, count.unread) {
+                    (true, _) => line.bg(theme.selection()).fg(Color::Black),
+                    (false, 0) => line.fg(Color::DarkGray),
+                    (false, _) => line,
+                }
+            })
+            .collect();
+        Paragraph::new(lines)
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .title_top("Unread"),
+            )
+            .render(unread_layout, buf);
 
         // Show keyboard layout.
         let keyboard_text: String = self
@@ -98,9 +140,12 @@ impl<'a> Widget for MenuWidget<'a> {
         .render(log_layout, buf);
 
         // Show help.
-        Line::from("Help: Press <q> to quit, <esc> to return to slipstream.")
-            .bg(Color::Red)
-            .fg(Color::Black)
-            .render(help_layout, buf);
+        Line::from(
+            "Help: Press <enter> to show a feed or tag, <q> to quit, <esc> to \
+            return to slipstream.",
+        )
+        .bg(Color::Red)
+        .fg(Color::Black)
+        .render(help_layout, buf);
     }
 }
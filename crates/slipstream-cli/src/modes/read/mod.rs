@@ -2,18 +2,28 @@
 
 use super::*;
 
+mod clipboard;
 mod command;
 mod command_mode;
+mod completion;
 mod config;
 mod entry;
+mod group;
+mod help;
 mod keyboard;
 mod menu;
+mod palette;
 mod state;
 
+pub use clipboard::*;
 pub use command::*;
+pub use command_mode::SearchContext;
+pub use completion::*;
 pub use config::*;
 pub use entry::*;
+pub use group::*;
 pub use keyboard::*;
+pub use palette::*;
 pub use state::*;
 
 use std::time::Duration;
@@ -140,6 +150,14 @@ impl Reader {
                     Focus::Menu { .. } => {
                         menu::MenuWidget::new(self).render(area, buf);
                     }
+                    Focus::Palette { .. } => {
+                        ReaderWidget::new(self).render(area, buf);
+                        palette::PaletteWidget::new(self).render(area, buf);
+                    }
+                    Focus::Help { .. } => {
+                        ReaderWidget::new(self).render(area, buf);
+                        help::HelpWidget::new(self).render(area, buf);
+                    }
                     _ => {
                         ReaderWidget::new(self).render(area, buf);
                     }
@@ -156,12 +174,19 @@ impl Reader {
             // Manage updater.
             self.check_for_updates().await;
 
-            // Sync current with db.
-            if self.interaction_state.selection < self.entries.len() {
-                // TODO: Read hook!
-                // let entry = &mut self.entries[self.interaction_state.selection];
-                // self.updater.toggle_read(entry.db_id, true).await;
-                // self.updater.update_view(entry).await;
+            // Mark the entry read when the selection moves onto it, leaving
+            // any state the user set afterwards alone.
+            let viewed = self.interaction_state.viewed;
+            if let Some(entry) = self.get_selected_entry_mut() {
+                if viewed != Some(entry.db_id) {
+                    let id = entry.db_id;
+                    let newly_read = !entry.read;
+                    entry.read = true;
+                    self.interaction_state.viewed = Some(id);
+                    if newly_read {
+                        self.updater.toggle_read(id, true).await;
+                    }
+                }
             }
         }
     }
@@ -222,6 +247,17 @@ impl Reader {
                         Focus::Command { .. } => {
                             self.handle_command_mode_input(&key).await?;
                         }
+                        Focus::Palette { .. } => {
+                            self.handle_palette_input(&key).await?;
+                        }
+                        Focus::Help { .. } => {
+                            self.handle_help_input(&key);
+                        }
+                        Focus::Menu { scroll }
+                            if key.code == KeyCode::Enter =>
+                        {
+                            self.search_unread_count(*scroll as usize).await;
+                        }
                         _ => {
                             let command =
                                 self.config.read.get_key_command(&key);
@@ -251,6 +287,9 @@ impl Reader {
                         entry.scroll(scroll);
                     }
                 }
+                Focus::Help { .. } => {
+                    self.interaction_state.scroll_help(scroll);
+                }
                 _ => {}
             }
         }
@@ -265,6 +304,9 @@ impl Reader {
                         entry.scroll(scroll);
                     }
                 }
+                Focus::Help { .. } => {
+                    self.interaction_state.scroll_help(scroll);
+                }
                 _ => {}
             }
         }
@@ -336,11 +378,16 @@ impl Reader {
                     }
                 }
                 Focus::Menu { scroll } => {
+                    let max = self.interaction_state.unread_counts.len();
                     self.interaction_state.focus = Focus::Menu {
-                        scroll: scroll.saturating_add(1),
+                        scroll: scroll
+                            .saturating_add(1)
+                            .min(max.saturating_sub(1) as u16),
                     };
                 }
                 Focus::Command { .. } => {}
+                Focus::Palette { .. } => {}
+                Focus::Help { .. } => {}
             },
             ReadCommandLiteral::Up => match self.interaction_state.focus {
                 Focus::List => {
@@ -360,6 +407,8 @@ impl Reader {
                     };
                 }
                 Focus::Command { .. } => {}
+                Focus::Palette { .. } => {}
+                Focus::Help { .. } => {}
             },
             ReadCommandLiteral::Left => {
                 if self.interaction_state.selection < self.entries.len() {
@@ -390,6 +439,8 @@ impl Reader {
                     }
                     Focus::Menu { .. } => {}
                     Focus::Command { .. } => {}
+                    Focus::Palette { .. } => {}
+                    Focus::Help { .. } => {}
                 }
             }
             ReadCommandLiteral::PageUp => match self.interaction_state.focus {
@@ -408,14 +459,33 @@ impl Reader {
                 }
                 Focus::Menu { .. } => {}
                 Focus::Command { .. } => {}
+                Focus::Palette { .. } => {}
+                Focus::Help { .. } => {}
             },
+            ReadCommandLiteral::Top => {
+                if let Focus::List = self.interaction_state.focus {
+                    self.interaction_state.select_top();
+                    self.terminal_state.window = 0;
+                }
+            }
+            ReadCommandLiteral::Bottom => {
+                // The window follows the selection when the list is drawn.
+                if let Focus::List = self.interaction_state.focus {
+                    self.interaction_state.select_bottom(&self.entries);
+                }
+            }
             ReadCommandLiteral::Swap => {
                 self.interaction_state.focus.swap();
             }
             ReadCommandLiteral::Menu => {
                 self.interaction_state.focus.toggle_menu();
+                if let Focus::Menu { .. } = self.interaction_state.focus {
+                    self.interaction_state.unread_counts =
+                        self.updater.unread_counts().await;
+                }
             }
             ReadCommandLiteral::CommandMode => {
+                self.interaction_state.tags = self.updater.all_tags().await;
                 self.interaction_state.focus = Focus::Command {
                     command: String::new(),
                     message: None,
@@ -427,31 +497,49 @@ impl Reader {
                     message: None,
                 };
             }
-            ReadCommandLiteral::PageForwards => {
-                let offset = if let Some(entry) = self.entries.last() {
-                    OffsetCursor::Before(entry.date().clone())
-                } else {
-                    OffsetCursor::LatestTimestamp
+            ReadCommandLiteral::Palette => {
+                self.interaction_state.focus = Focus::Palette {
+                    query: String::new(),
+                    selection: 0,
                 };
-                self.update_entries(
-                    self.interaction_state.previous_search.clone(),
-                    offset,
-                    false,
-                )
-                .await;
+            }
+            ReadCommandLiteral::Yank => self.yank(YankTarget::Link).await,
+            ReadCommandLiteral::YankComments => {
+                self.yank(YankTarget::Comments).await;
+            }
+            ReadCommandLiteral::YankTitle => self.yank(YankTarget::Title).await,
+            ReadCommandLiteral::YankCitation => {
+                self.yank(YankTarget::Citation).await;
+            }
+            ReadCommandLiteral::NextMatch => self.select_match(true),
+            ReadCommandLiteral::PreviousMatch => self.select_match(false),
+            ReadCommandLiteral::ToggleSaved => {
+                if let Some(entry) = self.get_selected_entry_mut() {
+                    let saved = !entry.saved;
+                    self.set_saved(saved).await;
+                }
+            }
+            ReadCommandLiteral::Help => {
+                self.interaction_state.focus = Focus::Help { scroll: 0 };
+            }
+            ReadCommandLiteral::ToggleGroup => {
+                if self.interaction_state.selection < self.entries.len() {
+                    self.interaction_state.selection = self
+                        .entries
+                        .toggle_group(self.interaction_state.selection);
+                }
+            }
+            ReadCommandLiteral::ToggleUnread => {
+                self.toggle_unread_only().await;
+            }
+            ReadCommandLiteral::CycleSort => {
+                self.set_sort(self.interaction_state.sort.next()).await;
+            }
+            ReadCommandLiteral::PageForwards => {
+                self.page(true).await;
             }
             ReadCommandLiteral::PageBackwards => {
-                let offset = if let Some(entry) = self.entries.first() {
-                    OffsetCursor::After(entry.date().clone())
-                } else {
-                    OffsetCursor::LatestTimestamp
-                };
-                self.update_entries(
-                    self.interaction_state.previous_search.clone(),
-                    offset,
-                    false,
-                )
-                .await;
+                self.page(false).await;
             }
             ReadCommandLiteral::Command(command) => {
                 if self.interaction_state.selection < self.entries.len() {
@@ -585,7 +673,12 @@ impl Reader {
                 // Update entries.
                 match entries_fut.await {
                     Ok(entries) => {
-                        self.entries = entries;
+                        self.entries = match self.config.read.collapse_similar {
+                            Some(threshold) => {
+                                entries.collapse_similar(threshold)
+                            }
+                            None => entries,
+                        };
                         if !self.interaction_state.repeat_previous
                             || self.interaction_state.selection
                                 >= self.entries.len()
@@ -643,13 +736,17 @@ impl Reader {
         self.refresh = Some({
             let delay = self.interaction_state.next_delay.take();
             let updater = self.updater.clone();
-            let criteria = criteria.clone();
+            let mut criteria = criteria.clone();
+            if self.interaction_state.unread_only {
+                criteria.push(DatabaseSearch::Unread);
+            }
             let offset = offset.clone();
+            let sort = self.interaction_state.sort;
             tokio::spawn(async move {
                 if let Some(delay) = delay {
                     tokio::time::sleep(delay).await;
                 }
-                updater.search(criteria, offset).await
+                updater.search(criteria, offset, sort).await
             })
         });
         self.interaction_state.repeat_previous = repeat;
@@ -657,6 +754,112 @@ impl Reader {
         self.interaction_state.previous_offset = offset;
     }
 
+    /// Select the next or previous loaded entry matching the last search.
+    fn select_match(&mut self, forwards: bool) {
+        let Some(text) = self.interaction_state.last_search.clone() else {
+            return;
+        };
+        if let Some(index) = self.entries.find_match(
+            self.interaction_state.selection,
+            &text,
+            forwards,
+        ) {
+            self.interaction_state.selection = index;
+        }
+    }
+
+    /// Copy part of the selected entry to the clipboard.
+    async fn yank(&mut self, target: YankTarget) {
+        let config = self.config.clone();
+        let Some(entry) = self.get_selected_entry_mut() else {
+            return;
+        };
+        let message = match target.text(&entry.entry, &config) {
+            Some(text) => match copy_to_clipboard(&text).await {
+                // Only show the first line of longer text.
+                Ok(_) => match text.split_once('\n') {
+                    Some((first, _)) => format!("Copied {target}: {first}…"),
+                    None => format!("Copied {target}: {text}"),
+                },
+                Err(e) => format!("Failed to copy {target}: {e}"),
+            },
+            None => format!("No {target} to copy"),
+        };
+        self.interaction_state.focus = Focus::Command {
+            command: String::new(),
+            message: Some(message),
+        };
+    }
+
+    /// Set whether the selected entry is saved.
+    async fn set_saved(&mut self, saved: bool) {
+        if let Some(entry) = self.get_selected_entry_mut() {
+            entry.saved = saved;
+            let id = entry.db_id;
+            self.updater.toggle_saved(id, saved).await;
+        }
+    }
+
+    /// Toggle only showing unread entries and rerun the previous search.
+    async fn toggle_unread_only(&mut self) {
+        self.interaction_state.unread_only =
+            !self.interaction_state.unread_only;
+        self.update_entries(
+            self.interaction_state.previous_search.clone(),
+            self.interaction_state.previous_offset.clone(),
+            self.interaction_state.repeat_previous,
+        )
+        .await;
+    }
+
+    /// Search the entries of the feed or tag selected in the menu.
+    async fn search_unread_count(&mut self, index: usize) {
+        let Some(count) = self.interaction_state.unread_counts.get(index)
+        else {
+            return;
+        };
+        let criteria = vec![count.search.clone()];
+        self.interaction_state.focus = Focus::List;
+        self.update_entries(criteria, OffsetCursor::LatestTimestamp, false)
+            .await;
+    }
+
+    /// Change the sort order and rerun the previous search from the start.
+    async fn set_sort(&mut self, order: EntryOrder) {
+        self.interaction_state.sort = order;
+        self.update_entries(
+            self.interaction_state.previous_search.clone(),
+            OffsetCursor::LatestTimestamp,
+            self.interaction_state.repeat_previous,
+        )
+        .await;
+    }
+
+    /// Show the page after the loaded entries, or before them unless
+    /// `forwards`.
+    async fn page(&mut self, forwards: bool) {
+        self.update_entries(
+            self.interaction_state.previous_search.clone(),
+            self.page_cursor(forwards),
+            false,
+        )
+        .await;
+    }
+
+    /// Cursor of the page after the loaded entries, or before them unless
+    /// `forwards`. Oldest-first pages forwards to newer entries.
+    fn page_cursor(&self, forwards: bool) -> OffsetCursor {
+        let keys =
+            || self.entries.iter().map(|entry| (entry.date(), entry.db_id));
+        let (Some(oldest), Some(newest)) = (keys().min(), keys().max()) else {
+            return OffsetCursor::LatestTimestamp;
+        };
+        match forwards == (self.interaction_state.sort == EntryOrder::Oldest) {
+            true => OffsetCursor::After(newest.0.clone(), Some(newest.1)),
+            false => OffsetCursor::Before(oldest.0.clone(), Some(oldest.1)),
+        }
+    }
+
     async fn handle_command_mode_input(
         &mut self,
         key: &KeyEvent,
@@ -676,10 +879,14 @@ impl Reader {
         }
 
         // If an error, clear and let the user continue typing.
+        // Messages without a command return to the list instead.
         if is_error {
-            self.interaction_state.focus = Focus::Command {
-                command,
-                message: None,
+            self.interaction_state.focus = match command.is_empty() {
+                true => Focus::List,
+                false => Focus::Command {
+                    command,
+                    message: None,
+                },
             };
             return Ok(());
         }
@@ -688,6 +895,13 @@ impl Reader {
             KeyCode::Char(c) => {
                 command.push(c);
             }
+            KeyCode::Tab => {
+                if let Some(completed) =
+                    complete_tag(&command, &self.interaction_state.tags)
+                {
+                    command = completed;
+                }
+            }
             KeyCode::Backspace => {
                 if command.len() > 0 {
                     command.pop();
@@ -698,6 +912,15 @@ impl Reader {
             }
             KeyCode::Enter => {
                 match self.handle_command_mode_command(&command).await {
+                    // Keep messages shown by the command.
+                    Ok(_)
+                        if matches!(
+                            self.interaction_state.focus,
+                            Focus::Command {
+                                message: Some(_),
+                                ..
+                            }
+                        ) => {}
                     Ok(_) => {
                         self.interaction_state.focus = Focus::List;
                     }
@@ -721,6 +944,75 @@ impl Reader {
         Ok(())
     }
 
+    async fn handle_palette_input(&mut self, key: &KeyEvent) -> Result<()> {
+        let (mut query, mut selection) = match &self.interaction_state.focus {
+            Focus::Palette { query, selection } => (query.clone(), *selection),
+            _ => (String::new(), 0),
+        };
+
+        // Go back to list if menu pressed.
+        if *key == MENU {
+            self.interaction_state.focus = Focus::List;
+            return Ok(());
+        }
+
+        match key.code {
+            KeyCode::Char(c) => {
+                query.push(c);
+                selection = 0;
+            }
+            KeyCode::Backspace => {
+                if query.pop().is_none() {
+                    self.interaction_state.focus = Focus::List;
+                    return Ok(());
+                }
+                selection = 0;
+            }
+            KeyCode::Up => {
+                selection = selection.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Tab => {
+                let matches = palette_matches(&self.config.read, &query);
+                if selection + 1 < matches.len() {
+                    selection += 1;
+                }
+            }
+            KeyCode::Enter => {
+                let matches = palette_matches(&self.config.read, &query);
+                self.interaction_state.focus = Focus::List;
+                if let Some(item) = matches.into_iter().nth(selection) {
+                    self.run_command(item.command).await?;
+                }
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        self.interaction_state.focus = Focus::Palette { query, selection };
+
+        Ok(())
+    }
+
+    /// Scroll help with the movement keys, and close it on any other key.
+    fn handle_help_input(&mut self, key: &KeyEvent) {
+        let paging_lines = self.terminal_state.get_paging_lines(&self.config);
+        match self.config.read.get_key_command(key) {
+            Commandish::Literal(ReadCommandLiteral::Down) => {
+                self.interaction_state.scroll_help(1);
+            }
+            Commandish::Literal(ReadCommandLiteral::Up) => {
+                self.interaction_state.scroll_help(-1);
+            }
+            Commandish::Literal(ReadCommandLiteral::PageDown) => {
+                self.interaction_state.scroll_help(paging_lines);
+            }
+            Commandish::Literal(ReadCommandLiteral::PageUp) => {
+                self.interaction_state.scroll_help(-paging_lines);
+            }
+            _ => self.interaction_state.focus = Focus::List,
+        }
+    }
+
     async fn handle_command_mode_command(
         &mut self,
         command: &str,
@@ -741,31 +1033,9 @@ impl Reader {
                 .await
             }
             command_mode::Command::SearchAny(search) => {
-                let mut criteria: Vec<DatabaseSearch> = Vec::new();
-                for tag in &search.tag {
-                    criteria.push(DatabaseSearch::Tag(tag.clone()));
-                }
-                for not_tag in &search.not_tag {
-                    criteria.push(DatabaseSearch::NotTag(not_tag.clone()));
-                }
-                for feed in &search.feed {
-                    criteria.push(DatabaseSearch::Feed(feed.clone()));
-                }
-                for not_feed in &search.not_feed {
-                    criteria.push(DatabaseSearch::NotFeed(not_feed.clone()));
-                }
-                for cmd in &search.command {
-                    criteria.push(DatabaseSearch::Command(cmd.clone()));
-                }
-                for not_cmd in &search.not_command {
-                    criteria.push(DatabaseSearch::NotCommand(not_cmd.clone()));
-                }
-                for raw_clause in &search.raw {
-                    criteria.push(DatabaseSearch::Raw(raw_clause.clone()));
-                }
-                if let Some(text) = &search.text {
-                    criteria.push(DatabaseSearch::Search(text.clone()));
-                }
+                let criteria = search.criteria();
+                self.interaction_state.last_search =
+                    search.text.clone().filter(|text| !text.trim().is_empty());
                 tracing::info!("searchany: {:?}", criteria);
                 self.update_entries(
                     criteria,
@@ -811,6 +1081,77 @@ impl Reader {
                     entry.entry.tags().iter().map(|t| t.clone()).collect();
                 self.updater.update_tags(entry.db_id, tags).await;
             }
+            command_mode::Command::Retag { feed } => {
+                match self.updater.retag(&feed).await {
+                    Some(tags) => {
+                        // Keep the loaded entries in sync.
+                        for entry in self.entries.iter_mut() {
+                            if entry
+                                .entry
+                                .feeds()
+                                .iter()
+                                .any(|feed_ref| *feed_ref.name == feed)
+                            {
+                                for tag in &tags {
+                                    entry.entry.add_tag(tag);
+                                }
+                            }
+                        }
+                    }
+                    None => {
+                        tracing::warn!("Unable to retag unknown feed {feed}.")
+                    }
+                }
+            }
+            command_mode::Command::ToggleRead => {
+                if let Some(entry) = self.get_selected_entry_mut() {
+                    entry.read = !entry.read;
+                    let (id, read) = (entry.db_id, entry.read);
+                    self.updater.toggle_read(id, read).await;
+                }
+            }
+            command_mode::Command::ReadAll => {
+                let ids = self.entries.mark_read(0..self.entries.len(), true);
+                self.updater.toggle_read_many(ids, true).await;
+            }
+            command_mode::Command::ReadAbove => {
+                let selection = self.interaction_state.selection;
+                let ids = self.entries.mark_read(0..selection, true);
+                self.updater.toggle_read_many(ids, true).await;
+            }
+            command_mode::Command::ReadBelow => {
+                let ids = self.entries.mark_read(
+                    self.interaction_state.selection + 1..self.entries.len(),
+                    true,
+                );
+                self.updater.toggle_read_many(ids, true).await;
+            }
+            command_mode::Command::Yank => self.yank(YankTarget::Link).await,
+            command_mode::Command::YankComments => {
+                self.yank(YankTarget::Comments).await;
+            }
+            command_mode::Command::YankTitle => {
+                self.yank(YankTarget::Title).await
+            }
+            command_mode::Command::YankCitation => {
+                self.yank(YankTarget::Citation).await;
+            }
+            command_mode::Command::ToggleUnread => {
+                self.toggle_unread_only().await;
+            }
+            command_mode::Command::Sort { order } => {
+                let order = order.unwrap_or(self.interaction_state.sort.next());
+                self.set_sort(order).await;
+            }
+            command_mode::Command::ToggleImportant => {
+                if let Some(entry) = self.get_selected_entry_mut() {
+                    entry.important = !entry.important;
+                    let (id, important) = (entry.db_id, entry.important);
+                    self.updater.toggle_important(id, important).await;
+                }
+            }
+            command_mode::Command::Save => self.set_saved(true).await,
+            command_mode::Command::Unsave => self.set_saved(false).await,
             command_mode::Command::Command { command } => {
                 let command = self.config.read.get_custom_command(&command);
                 match command {
@@ -838,30 +1179,10 @@ impl Reader {
                 }
             }
             command_mode::Command::PageForwards => {
-                let offset = if let Some(entry) = self.entries.last() {
-                    OffsetCursor::Before(entry.date().clone())
-                } else {
-                    OffsetCursor::LatestTimestamp
-                };
-                self.update_entries(
-                    self.interaction_state.previous_search.clone(),
-                    offset,
-                    false,
-                )
-                .await;
+                self.page(true).await;
             }
             command_mode::Command::PageBackwards => {
-                let offset = if let Some(entry) = self.entries.first() {
-                    OffsetCursor::After(entry.date().clone())
-                } else {
-                    OffsetCursor::LatestTimestamp
-                };
-                self.update_entries(
-                    self.interaction_state.previous_search.clone(),
-                    offset,
-                    false,
-                )
-                .await;
+                self.page(false).await;
             }
         };
 
@@ -943,20 +1264,26 @@ impl<'a> Widget for ReaderWidget<'a> {
                     .max(0) as usize;
         }
 
-        // Update focus based on mouse.
-        if self
-            .reader
-            .terminal_state
-            .last_frame_inputs
-            .hovered(list_layout)
+        // Update focus based on mouse, unless an overlay is open.
+        let overlay_open = matches!(
+            self.reader.interaction_state.focus,
+            Focus::Palette { .. } | Focus::Help { .. }
+        );
+        if !overlay_open
+            && self
+                .reader
+                .terminal_state
+                .last_frame_inputs
+                .hovered(list_layout)
         {
             self.reader.interaction_state.focus = Focus::List;
         }
-        if self
-            .reader
-            .terminal_state
-            .last_frame_inputs
-            .hovered(entry_layout)
+        if !overlay_open
+            && self
+                .reader
+                .terminal_state
+                .last_frame_inputs
+                .hovered(entry_layout)
         {
             self.reader.interaction_state.focus = Focus::Entry;
         }
@@ -973,10 +1300,18 @@ impl<'a> Widget for ReaderWidget<'a> {
                     .render(title_layout, buf);
                 }
                 None => {
+                    let completions = tag_completions(
+                        command,
+                        &self.reader.interaction_state.tags,
+                    );
                     Line::from(vec![
                         Span::styled(":", Style::new()),
                         Span::styled(command, Style::new().fg(Color::Blue)),
                         Span::styled("█", Style::new()),
+                        Span::styled(
+                            format!(" {}", completions.join(" ")),
+                            Style::new().fg(Color::DarkGray),
+                        ),
                     ])
                     .bg(Color::Black)
                     .render(title_layout, buf);
@@ -987,9 +1322,18 @@ impl<'a> Widget for ReaderWidget<'a> {
                     format!(
                         "{:<width$}",
                         format!(
-                            "slipstream {}/{}",
+                            "slipstream {}/{}{}{}",
                             self.reader.interaction_state.selection + 1,
-                            self.reader.entries.len()
+                            self.reader.entries.len(),
+                            match self.reader.interaction_state.unread_only {
+                                true => " (unread)",
+                                false => "",
+                            },
+                            match self.reader.interaction_state.sort {
+                                EntryOrder::Newest => "",
+                                EntryOrder::Oldest => " (oldest first)",
+                                EntryOrder::Source => " (by source)",
+                            }
                         ),
                         width = &(title_layout.width as usize),
                     ),
@@ -997,7 +1341,7 @@ impl<'a> Widget for ReaderWidget<'a> {
                         .bg(
                             match self.reader.interaction_state.repeat_previous
                             {
-                                false => Color::Blue,
+                                false => self.reader.config.read.theme.header(),
                                 true => Color::LightYellow,
                             },
                         )
@@ -1046,6 +1390,8 @@ impl<'a> Widget for ReaderWidget<'a> {
 
                 let mut indicators = Vec::new();
 
+                let theme = &self.reader.config.read.theme;
+
                 // Find style by iterating through color rules.
                 let mut line_style = Style::new();
                 let mut entry_style = Style::new();
@@ -1060,27 +1406,35 @@ impl<'a> Widget for ReaderWidget<'a> {
                         }
                         if let Some(mut indicator) = color_rule.indicator() {
                             if hovering {
-                                indicator =
-                                    indicator.bg(Color::Gray).fg(Color::Black);
+                                indicator = indicator
+                                    .bg(theme.hover())
+                                    .fg(Color::Black);
                             }
                             if selected {
-                                indicator =
-                                    indicator.bg(Color::Green).fg(Color::Black);
+                                indicator = indicator
+                                    .bg(theme.selection())
+                                    .fg(Color::Black);
                             }
                             indicators.push(indicator);
                         }
                     }
                 }
 
+                if entry.saved {
+                    entry_style = entry_style.fg(theme.saved());
+                }
+
                 // Selected, hovering, and focused state affects style.
                 if hovering {
                     if self.reader.terminal_state.has_focus {
                         entry_style = match self.reader.interaction_state.focus
                         {
                             Focus::Entry => {
-                                Style::new().bg(Color::Black).fg(Color::Gray)
+                                Style::new().bg(Color::Black).fg(theme.hover())
+                            }
+                            _ => {
+                                Style::new().bg(theme.hover()).fg(Color::Black)
                             }
-                            _ => Style::new().bg(Color::Gray).fg(Color::Black),
                         };
                         line_style = entry_style;
                     }
@@ -1088,10 +1442,12 @@ impl<'a> Widget for ReaderWidget<'a> {
                 if selected {
                     entry_style = if self.reader.terminal_state.has_focus {
                         match self.reader.interaction_state.focus {
-                            Focus::Entry => {
-                                Style::new().bg(Color::Black).fg(Color::Green)
-                            }
-                            _ => Style::new().bg(Color::Green).fg(Color::Black),
+                            Focus::Entry => Style::new()
+                                .bg(Color::Black)
+                                .fg(theme.selection()),
+                            _ => Style::new()
+                                .bg(theme.selection())
+                                .fg(Color::Black),
                         }
                     } else {
                         Style::new().bg(Color::White).fg(Color::Black)
@@ -1131,8 +1487,45 @@ impl<'a> Widget for ReaderWidget<'a> {
                                     .split(split_line_layout[i])
                             };
 
-                            Span::styled(entry.title(), entry_style)
-                                .render(summary_layout[0], buf);
+                            let title = match entry.group {
+                                Some(_) => format!("└ {}", entry.title()),
+                                None if entry.similar.is_empty() => {
+                                    entry.title().to_string()
+                                }
+                                None => format!(
+                                    "{} (+{})",
+                                    entry.title(),
+                                    entry.similar.len()
+                                ),
+                            };
+                            // Highlight the last search.
+                            let highlight = self
+                                .reader
+                                .interaction_state
+                                .last_search
+                                .as_ref()
+                                .and_then(|text| match_range(&title, text));
+                            match highlight {
+                                Some(range) => Line::from(vec![
+                                    Span::styled(
+                                        &title[..range.start],
+                                        entry_style,
+                                    ),
+                                    Span::styled(
+                                        &title[range.clone()],
+                                        entry_style
+                                            .bg(Color::Yellow)
+                                            .fg(Color::Black),
+                                    ),
+                                    Span::styled(
+                                        &title[range.end..],
+                                        entry_style,
+                                    ),
+                                ])
+                                .render(summary_layout[0], buf),
+                                None => Span::styled(title, entry_style)
+                                    .render(summary_layout[0], buf),
+                            }
                         }
                         PreviewToken::Flags => {
                             let mut offset: u16 = 0;
@@ -1154,11 +1547,16 @@ impl<'a> Widget for ReaderWidget<'a> {
                             }
                         }
                         PreviewToken::Feed => {
-                            let feed: String = 'feed: {
-                                for feed_ref in entry.feeds().iter() {
-                                    break 'feed (*feed_ref.name).clone();
-                                }
-                                "???".to_owned()
+                            let feed: String = match entry
+                                .feeds()
+                                .iter()
+                                .min_by_key(|feed_ref| {
+                                    self.reader
+                                        .config
+                                        .feed_rank(feed_ref.name.as_str())
+                                }) {
+                                Some(feed_ref) => (*feed_ref.name).clone(),
+                                None => "???".to_owned(),
                             };
 
                             let feed_layout = if !last_token {
@@ -1185,7 +1583,7 @@ impl<'a> Widget for ReaderWidget<'a> {
                                     width = feed_layout[0].width as usize - 2
                                 ),
                                 if !selected && !hovering {
-                                    Style::new().fg(Color::LightCyan)
+                                    Style::new().fg(theme.feed_tag())
                                 } else {
                                     entry_style
                                 },
@@ -1214,7 +1612,7 @@ impl<'a> Widget for ReaderWidget<'a> {
                             Span::styled(
                                 &date,
                                 if !selected && !hovering {
-                                    Style::new().fg(Color::LightYellow)
+                                    Style::new().fg(theme.date())
                                 } else {
                                     entry_style
                                 },
@@ -1260,7 +1658,7 @@ impl<'a> Widget for ReaderWidget<'a> {
                                 if selected || hovering {
                                     entry_style
                                 } else {
-                                    Style::new().fg(Color::Yellow)
+                                    Style::new().fg(theme.tag())
                                 },
                             )
                             .render(primary_tag_layout[0], buf);
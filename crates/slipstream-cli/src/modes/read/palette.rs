@@ -0,0 +1,156 @@
+//! Command palette.
+
+use ratatui::widgets::{BorderType, Clear};
+
+use super::*;
+
+/// Maximum number of matches shown in the palette.
+const PALETTE_HEIGHT: usize = 12;
+/// Maximum width of the palette.
+const PALETTE_WIDTH: u16 = 60;
+
+/// A command that can be run from the palette.
+#[derive(Clone, Debug)]
+pub struct PaletteItem {
+    /// The name matched against.
+    pub name: String,
+    /// The command to run.
+    pub command: Commandish,
+}
+
+impl std::fmt::Display for PaletteItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.command {
+            Commandish::Literal(_) => write!(f, ":{}", self.name),
+            _ => write!(f, "!{}", self.name),
+        }
+    }
+}
+
+/// Get all commands that can be run from the palette.
+pub fn palette_items(config: &ReadConfig) -> Vec<PaletteItem> {
+    let builtins =
+        ReadCommandLiteral::builtins()
+            .into_iter()
+            .map(|(name, literal)| PaletteItem {
+                name: name.into(),
+                command: Commandish::Literal(literal),
+            });
+    let custom = config.commands.iter().map(|command| PaletteItem {
+        name: (*command.name).clone(),
+        command: command.into(),
+    });
+    builtins.chain(custom).collect()
+}
+
+/// Get the palette commands matching a query, best match first.
+pub fn palette_matches(config: &ReadConfig, query: &str) -> Vec<PaletteItem> {
+    let mut scored: Vec<(i64, PaletteItem)> = palette_items(config)
+        .into_iter()
+        .filter_map(|item| Some((fuzzy_score(query, &item.name)?, item)))
+        .collect();
+    // The sort is stable, so equal scores keep their listed order.
+    scored.sort_by_key(|(score, _)| -score);
+    scored.into_iter().map(|(_, item)| item).collect()
+}
+
+/// Score how well a query fuzzy-matches a candidate.
+/// Every query character must appear in order. Consecutive characters and
+/// characters at the start of words score higher, while skipped characters
+/// score lower. This returns `None` if the query does not match.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score: i64 = 0;
+    let mut position: usize = 0;
+    let mut previous: Option<usize> = None;
+    for c in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = position
+            + candidate[position..].iter().position(|other| *other == c)?;
+        score += 1;
+        if previous.is_some_and(|previous| previous + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || matches!(candidate[found - 1], '-' | '_' | ' ') {
+            score += 8;
+        }
+        score -= (found - position) as i64;
+        previous = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
+
+/// Widget to render the command palette over the reader.
+pub(super) struct PaletteWidget<'a> {
+    reader: &'a Reader,
+}
+
+impl<'a> PaletteWidget<'a> {
+    pub(super) fn new(reader: &'a Reader) -> Self {
+        Self { reader }
+    }
+}
+
+impl<'a> Widget for PaletteWidget<'a> {
+    fn render(self, area: Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let Focus::Palette { query, selection } =
+            &self.reader.interaction_state.focus
+        else {
+            return;
+        };
+        let matches = palette_matches(&self.reader.config.read, query);
+
+        // Center the palette.
+        let width = PALETTE_WIDTH.min(area.width.saturating_sub(4));
+        let height = (PALETTE_HEIGHT as u16 + 3).min(area.height);
+        let palette_area = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+        Clear.render(palette_area, buf);
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title_top("Commands");
+        let inner = block.inner(palette_area);
+        block.render(palette_area, buf);
+
+        // Show query.
+        let layouts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Percentage(100)])
+            .split(inner);
+        Line::from(vec![
+            Span::styled("> ", Style::new().bold()),
+            Span::styled(query, Style::new().fg(Color::Blue)),
+            Span::styled("█", Style::new()),
+        ])
+        .render(layouts[0], buf);
+
+        // Show matches, keeping the selection visible.
+        let visible = layouts[1].height as usize;
+        let skip = (selection + 1).saturating_sub(visible);
+        let lines: Vec<Line> = matches
+            .iter()
+            .enumerate()
+            .skip(skip)
+            .take(visible)
+            .map(|(i, item)| {
+                let line = Line::from(format!(
+                    "{:<width$}",
+                    item.to_string(),
+                    width = layouts[1].width as usize
+                ));
+                match i == *selection {
+                    true => line.bg(Color::Blue).fg(Color::Black),
+                    false => line,
+                }
+            })
+            .collect();
+        Paragraph::new(lines).render(layouts[1], buf);
+    }
+}
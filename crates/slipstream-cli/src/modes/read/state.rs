@@ -45,12 +45,24 @@ pub struct InteractionState {
     pub focus: Focus,
     /// Current selected entry index.
     pub selection: usize,
+    /// Entry the selection last moved onto, which was marked read.
+    pub viewed: Option<EntryDbId>,
     /// Previous search.
     pub previous_search: Vec<DatabaseSearch>,
     /// Previous offset.
     pub previous_offset: OffsetCursor,
     /// Whether or not to repeat previous search.
     pub repeat_previous: bool,
+    /// Whether or not to only show unread entries.
+    pub unread_only: bool,
+    /// Order of the entries.
+    pub sort: EntryOrder,
+    /// Known tags, for completion.
+    pub tags: Vec<String>,
+    /// Unread counts of feeds and tags, refreshed when the menu is opened.
+    pub unread_counts: Vec<UnreadCount>,
+    /// Text of the last search, for stepping through matches.
+    pub last_search: Option<String>,
     /// Next delay for search.
     pub next_delay: Option<tokio::time::Duration>,
 }
@@ -68,6 +80,25 @@ impl InteractionState {
                 self.selection.saturating_sub(amount.abs() as usize);
         }
     }
+
+    /// Select the first entry.
+    pub fn select_top(&mut self) {
+        self.selection = 0;
+    }
+
+    /// Select the last entry, if there is one.
+    pub fn select_bottom(&mut self, entries: &DatabaseEntryList) {
+        if entries.len() > 0 {
+            self.selection = entries.len() - 1;
+        }
+    }
+
+    /// Scroll the help overlay, if it is open.
+    pub fn scroll_help(&mut self, amount: i16) {
+        if let Focus::Help { scroll } = &mut self.focus {
+            *scroll = scroll.saturating_add_signed(amount);
+        }
+    }
 }
 
 impl Default for InteractionState {
@@ -75,9 +106,15 @@ impl Default for InteractionState {
         Self {
             focus: Focus::List,
             selection: 0,
+            viewed: None,
             previous_search: Vec::new(),
             previous_offset: OffsetCursor::LatestTimestamp,
             repeat_previous: false,
+            unread_only: false,
+            sort: EntryOrder::default(),
+            tags: Vec::new(),
+            unread_counts: Vec::new(),
+            last_search: None,
             next_delay: None,
         }
     }
@@ -94,6 +131,13 @@ pub enum Focus {
         command: String,
         message: Option<String>,
     },
+    Palette {
+        query: String,
+        selection: usize,
+    },
+    Help {
+        scroll: u16,
+    },
 }
 
 impl Focus {
@@ -106,6 +150,8 @@ impl Focus {
                 command: _,
                 message: _,
             } => Focus::List,
+            Focus::Palette { .. } => Focus::List,
+            Focus::Help { .. } => Focus::List,
         };
     }
 
@@ -0,0 +1,60 @@
+//! Token authentication.
+
+use super::*;
+
+use axum::http::header;
+use subtle::ConstantTimeEq;
+
+/// Paths served without a token.
+const PUBLIC_PATHS: &[&str] = &["/robots.txt", "/favicon.ico", "/healthz"];
+
+/// Query parameters that may carry the token.
+#[derive(Debug, Default, Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
+
+/// Require the configured token, via `Authorization: Bearer <token>` or
+/// `?token=<token>`, for everything except public paths.
+/// CORS preflight requests can't carry the token, so they're let through.
+pub async fn require_token(
+    State(token): State<Arc<String>>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    if PUBLIC_PATHS.contains(&request.uri().path())
+        || request.method() == axum::http::Method::OPTIONS
+    {
+        return next.run(request).await;
+    }
+
+    let bearer = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|value| value.trim().to_string());
+    let query = axum::extract::Query::<TokenQuery>::try_from_uri(request.uri())
+        .map(|query| query.0.token)
+        .unwrap_or_default();
+    let authorized = [bearer, query]
+        .iter()
+        .flatten()
+        .any(|presented| tokens_match(presented, &token));
+    if !authorized {
+        tracing::debug!("Unauthorized request for {}", request.uri().path());
+        return (
+            axum::http::StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+        )
+            .into_response();
+    }
+
+    next.run(request).await
+}
+
+/// Compare tokens in constant time, so response times don't reveal how much
+/// of a guess was right.
+fn tokens_match(presented: &str, expected: &str) -> bool {
+    presented.as_bytes().ct_eq(expected.as_bytes()).into()
+}
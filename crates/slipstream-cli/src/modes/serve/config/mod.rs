@@ -7,27 +7,220 @@ use super::*;
 pub struct ServeConfig {
     /// Port.
     pub port: Option<u16>,
-    /// Address.
+    /// Address to listen on (default 0.0.0.0).
+    #[serde(default, alias = "bind")]
     pub address: Option<String>,
     /// All configuration.
     pub all: Option<GlobalConfig>,
     /// Cache duration.
     #[serde(default, with = "humantime_serde::option")]
     pub cache: Option<std::time::Duration>,
+    /// Cache durations of routes, overriding `cache` for paths under a
+    /// prefix (default none).
+    #[serde(default, alias = "cache-routes")]
+    pub cache_routes: Option<Vec<RouteCacheConfig>>,
+    /// Oldest entry served. Older entries are kept, but left out of served
+    /// pages and feeds (default none).
+    #[serde(default, alias = "max-age", with = "humantime_serde::option")]
+    pub max_age: Option<std::time::Duration>,
     /// Put source into served title.
     #[serde(default = "ServeConfig::default_show_source_in_title")]
     pub show_source_in_title: bool,
     /// Export content format.
     #[serde(default = "ExportFormat::default")]
     pub export_format: ExportFormat,
+    /// How much of each entry's content is syndicated.
+    #[serde(default, alias = "content-in-feed")]
+    pub content_in_feed: ContentInFeed,
+    /// Indent syndicated output, instead of serving it compact.
+    #[serde(default, alias = "pretty-output")]
+    pub pretty_output: bool,
+    /// Whether the config is served at /config and /opml (default true).
+    #[serde(default, alias = "expose-config")]
+    pub expose_config: Option<bool>,
+    /// Whether single entries are served at /entry/<id>, and linked from
+    /// feeds (default true).
+    #[serde(default, alias = "expose-entries")]
+    pub expose_entries: Option<bool>,
+    /// Most entries a request can ask for with `?limit=` (default 1000).
+    #[serde(default, alias = "max-limit")]
+    pub max_limit: Option<usize>,
+    /// Maximum requests handled at once. Requests over the limit get a 503.
+    #[serde(default, alias = "max-concurrent-requests")]
+    pub max_concurrent_requests: Option<usize>,
+    /// Requests allowed from each client. Requests over the limit get a 429
+    /// (default none).
+    #[serde(default, alias = "rate-limit")]
+    pub rate_limit: Option<RateLimitConfig>,
+    /// Whether responses are compressed for clients that accept it (default
+    /// true).
+    #[serde(default)]
+    pub compression: Option<bool>,
+    /// Token required to access served feeds, as a bearer token or
+    /// `?token=` (default none).
+    #[serde(default, alias = "auth-token")]
+    pub auth_token: Option<String>,
+    /// Whether Prometheus metrics are served at /metrics (default false).
+    #[serde(default)]
+    pub metrics: Option<bool>,
+    /// Origins allowed to fetch feeds from browsers, or `*` for any (default
+    /// none).
+    #[serde(default, alias = "cors-origins")]
+    pub cors_origins: Option<Vec<String>>,
+    /// Whether entry content in html pages is sanitized, keeping links and
+    /// formatting but dropping scripts and event handlers (default true).
+    #[serde(default)]
+    pub sanitize: Option<bool>,
+    /// Handlebars template for html pages (default embedded).
+    #[serde(default, alias = "template-path")]
+    pub template_path: Option<String>,
+    /// Stylesheet served at /styles.css (default embedded).
+    #[serde(default, alias = "styles-path")]
+    pub styles_path: Option<String>,
+    /// File served at /robots.txt (default embedded).
+    #[serde(default, alias = "robots-path")]
+    pub robots_path: Option<String>,
 }
 
 impl ServeConfig {
+    /// Get the cache duration (default 2 minutes).
+    pub fn cache_duration(&self) -> slipfeed::Duration {
+        slipfeed::Duration::from_seconds(match self.cache {
+            Some(cache) => cache.as_secs(),
+            None => 120,
+        })
+    }
+
+    /// Get the cache durations of every route.
+    pub fn cache_durations(&self) -> CacheDurations {
+        let mut durations = CacheDurations::new(self.cache_duration());
+        for route in self.cache_routes.iter().flatten() {
+            durations = durations.route(
+                route.prefix.trim(),
+                slipfeed::Duration::from_seconds(route.cache.as_secs()),
+            );
+        }
+        durations
+    }
+
+    /// Check if an entry is too old to be served.
+    pub fn too_old(&self, dt: &slipfeed::DateTime) -> bool {
+        match self.max_age {
+            Some(max_age) => {
+                slipfeed::DateTime::now()
+                    > dt.clone()
+                        + slipfeed::Duration::from_seconds(max_age.as_secs())
+            }
+            None => false,
+        }
+    }
+
+    /// Get the address to listen on.
+    pub fn bind_address(&self) -> Result<std::net::IpAddr> {
+        match &self.address {
+            Some(address) => match address.trim().parse() {
+                Ok(address) => Ok(address),
+                Err(e) => bail!("Invalid serve address `{address}`: {e}."),
+            },
+            None => Ok(DEFAULT_ADDRESS),
+        }
+    }
+
+    /// Whether the config is served.
+    pub fn expose_config(&self) -> bool {
+        self.expose_config.unwrap_or(true)
+    }
+
+    /// Whether single entries are served.
+    pub fn expose_entries(&self) -> bool {
+        self.expose_entries.unwrap_or(true)
+    }
+
+    /// Whether entry content in html pages is sanitized.
+    pub fn sanitize(&self) -> bool {
+        self.sanitize.unwrap_or(true)
+    }
+
+    /// Get the most entries a request can ask for.
+    pub fn max_limit(&self) -> usize {
+        self.max_limit.unwrap_or(1000)
+    }
+
+    /// Get the token required to access served feeds, if any.
+    pub fn auth_token(&self) -> Option<&str> {
+        self.auth_token
+            .as_deref()
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+    }
+
+    /// Get the origins allowed by CORS, if any are configured.
+    pub fn cors_origins(
+        &self,
+    ) -> Result<Option<tower_http::cors::AllowOrigin>> {
+        let Some(origins) = &self.cors_origins else {
+            return Ok(None);
+        };
+        if origins.iter().any(|origin| origin.trim() == "*") {
+            return Ok(Some(tower_http::cors::AllowOrigin::any()));
+        }
+        let mut allowed = Vec::new();
+        for origin in origins {
+            match axum::http::HeaderValue::from_str(origin.trim()) {
+                Ok(origin) => allowed.push(origin),
+                Err(e) => bail!("Invalid CORS origin `{origin}`: {e}."),
+            }
+        }
+        Ok(Some(tower_http::cors::AllowOrigin::list(allowed)))
+    }
+
+    /// Whether metrics are served.
+    pub fn metrics(&self) -> bool {
+        self.metrics.unwrap_or(false)
+    }
+
+    /// Whether responses are compressed.
+    pub fn compression(&self) -> bool {
+        self.compression.unwrap_or(true)
+    }
+
     fn default_show_source_in_title() -> bool {
         false
     }
 }
 
+/// Cache duration of a route.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RouteCacheConfig {
+    /// Path prefix of the route, like `/all` or `/tag/rust`.
+    pub prefix: String,
+    /// How long pages and feeds under the prefix are cached.
+    #[serde(with = "humantime_serde")]
+    pub cache: std::time::Duration,
+}
+
+/// Rate limit configuration.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    /// Requests allowed from a client in each window.
+    pub requests: u32,
+    /// Window the requests are allowed in (default 1 minute).
+    #[serde(default, with = "humantime_serde::option")]
+    pub window: Option<std::time::Duration>,
+    /// Whether clients are identified by the last address in
+    /// `X-Forwarded-For`, as added by a proxy in front of slipstream
+    /// (default false).
+    #[serde(default, alias = "trust-forwarded-for")]
+    pub trust_forwarded_for: bool,
+}
+
+impl RateLimitConfig {
+    /// Get the window the requests are allowed in.
+    pub fn window(&self) -> std::time::Duration {
+        self.window.unwrap_or(std::time::Duration::from_secs(60))
+    }
+}
+
 /// The export format for serving content.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum ExportFormat {
@@ -37,6 +230,21 @@ pub enum ExportFormat {
     Markdown,
 }
 
+/// How much content is included in syndicated entries.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentInFeed {
+    /// The full content.
+    #[default]
+    Full,
+    /// A short plain-text summary of the content.
+    Summary,
+    /// No content, only metadata and links.
+    None,
+}
+
 impl Default for ExportFormat {
     fn default() -> Self {
         Self::HTML
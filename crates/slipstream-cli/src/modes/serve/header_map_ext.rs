@@ -9,9 +9,18 @@ pub trait HeaderMapExt {
     /// Create a HeaderMap with appropriate Atom headers.
     fn atom_headers() -> HeaderMap;
 
+    /// Create a HeaderMap with appropriate RSS headers.
+    fn rss_headers() -> HeaderMap;
+
+    /// Create a HeaderMap with headers for a syndication format.
+    fn syndication_headers(format: SyndicationFormat) -> HeaderMap;
+
     /// Create a HeaderMap with appropriate TOML headers.
     fn toml_headers() -> HeaderMap;
 
+    /// Create a HeaderMap with appropriate OPML headers.
+    fn opml_headers() -> HeaderMap;
+
     /// Create a HeaderMap with appropriate CSS headers.
     fn css_headers() -> HeaderMap;
 
@@ -21,6 +30,24 @@ pub trait HeaderMapExt {
     /// Create a HeaderMap with appropriate favicon headers.
     fn favicon_headers() -> HeaderMap;
 
+    /// Create a HeaderMap with Prometheus metrics headers.
+    fn metrics_headers() -> HeaderMap;
+
+    /// Allow caches to store the response for the cache duration.
+    /// Private responses, such as those behind a token, are only stored by
+    /// the client.
+    fn cacheable(
+        self,
+        max_age: &slipfeed::Duration,
+        private: bool,
+    ) -> HeaderMap;
+
+    /// Prevent any cache from storing the response.
+    fn uncacheable(self) -> HeaderMap;
+
+    /// Set the Last-Modified header, if there is a date.
+    fn last_modified(self, date: Option<&slipfeed::DateTime>) -> HeaderMap;
+
     /// Grab the If-Modified-Since header as a datetime, if present.
     fn if_modified_since(&self) -> Option<slipfeed::DateTime>;
 
@@ -47,6 +74,22 @@ impl HeaderMapExt for HeaderMap {
         headers
     }
 
+    fn rss_headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            axum::http::header::CONTENT_TYPE,
+            axum::http::HeaderValue::from_static("application/rss+xml"),
+        );
+        headers
+    }
+
+    fn syndication_headers(format: SyndicationFormat) -> HeaderMap {
+        match format {
+            SyndicationFormat::Atom => HeaderMap::atom_headers(),
+            SyndicationFormat::Rss => HeaderMap::rss_headers(),
+        }
+    }
+
     fn toml_headers() -> HeaderMap {
         let mut headers = HeaderMap::new();
         headers.insert(
@@ -55,6 +98,16 @@ impl HeaderMapExt for HeaderMap {
         );
         headers
     }
+
+    fn opml_headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            axum::http::header::CONTENT_TYPE,
+            axum::http::HeaderValue::from_static("text/x-opml"),
+        );
+        headers
+    }
+
     fn css_headers() -> HeaderMap {
         let mut headers = HeaderMap::new();
         headers.insert(
@@ -82,6 +135,57 @@ impl HeaderMapExt for HeaderMap {
         headers
     }
 
+    fn metrics_headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            axum::http::header::CONTENT_TYPE,
+            axum::http::HeaderValue::from_static(
+                "text/plain; version=0.0.4; charset=utf-8",
+            ),
+        );
+        headers
+    }
+
+    fn cacheable(
+        mut self,
+        max_age: &slipfeed::Duration,
+        private: bool,
+    ) -> HeaderMap {
+        let (scope, vary) = match private {
+            true => ("private", "Accept, Accept-Encoding, Authorization"),
+            false => ("public", "Accept, Accept-Encoding"),
+        };
+        let cache_control =
+            format!("{scope}, max-age={}", max_age.to_std().as_secs());
+        if let Ok(value) = axum::http::HeaderValue::from_str(&cache_control) {
+            self.insert(axum::http::header::CACHE_CONTROL, value);
+        }
+        self.insert(
+            axum::http::header::VARY,
+            axum::http::HeaderValue::from_static(vary),
+        );
+        self
+    }
+
+    fn uncacheable(mut self) -> HeaderMap {
+        self.insert(
+            axum::http::header::CACHE_CONTROL,
+            axum::http::HeaderValue::from_static("no-store"),
+        );
+        self
+    }
+
+    fn last_modified(mut self, date: Option<&slipfeed::DateTime>) -> HeaderMap {
+        if let Some(date) = date {
+            if let Ok(value) =
+                axum::http::HeaderValue::from_str(&date.to_if_modified_since())
+            {
+                self.insert(axum::http::header::LAST_MODIFIED, value);
+            }
+        }
+        self
+    }
+
     fn if_modified_since(&self) -> Option<slipfeed::DateTime> {
         if let Some(header) = self.get(axum::http::header::IF_MODIFIED_SINCE) {
             if let Ok(since) = header.to_str() {
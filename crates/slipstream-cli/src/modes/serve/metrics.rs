@@ -0,0 +1,136 @@
+//! Prometheus metrics.
+
+use super::*;
+
+use std::fmt::Write;
+
+/// Route label for requests that didn't match a route.
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Counts of handled requests.
+#[derive(Debug, Default)]
+pub struct HttpMetrics {
+    /// Requests handled, by route and status.
+    requests: std::sync::Mutex<BTreeMap<(String, u16), u64>>,
+}
+
+impl HttpMetrics {
+    /// Count a handled request.
+    fn count(&self, route: &str, status: axum::http::StatusCode) {
+        *self
+            .requests
+            .lock()
+            .unwrap()
+            .entry((route.to_string(), status.as_u16()))
+            .or_default() += 1;
+    }
+}
+
+/// Count requests by the route they matched.
+pub async fn count_requests(
+    State(metrics): State<Arc<HttpMetrics>>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    let route = request
+        .extensions()
+        .get::<axum::extract::MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+    let response = next.run(request).await;
+    metrics.count(&route, response.status());
+    response
+}
+
+/// Render metrics in the Prometheus text format.
+pub fn render_metrics(updater: &UpdaterMetrics, http: &HttpMetrics) -> String {
+    let mut out = String::new();
+
+    describe(&mut out, "slipstream_entries", "gauge", "Entries stored.");
+    writeln!(out, "slipstream_entries {}", updater.entries).ok();
+
+    describe(
+        &mut out,
+        "slipstream_feed_entries",
+        "gauge",
+        "Entries stored from each feed.",
+    );
+    for (feed, count) in &updater.feed_entries {
+        writeln!(
+            out,
+            "slipstream_feed_entries{{feed=\"{}\"}} {count}",
+            escape_label(feed)
+        )
+        .ok();
+    }
+
+    describe(
+        &mut out,
+        "slipstream_feed_last_update_seconds",
+        "gauge",
+        "Unix time of each feed's last successful update.",
+    );
+    for (feed, date) in &updater.feed_last_update {
+        writeln!(
+            out,
+            "slipstream_feed_last_update_seconds{{feed=\"{}\"}} {}",
+            escape_label(feed),
+            date.to_chrono().timestamp()
+        )
+        .ok();
+    }
+
+    describe(
+        &mut out,
+        "slipstream_updates_total",
+        "counter",
+        "Feed updates run.",
+    );
+    writeln!(out, "slipstream_updates_total {}", updater.updates).ok();
+
+    describe(
+        &mut out,
+        "slipstream_update_duration_seconds",
+        "gauge",
+        "Duration of the latest feed update.",
+    );
+    if let Some(duration) = &updater.last_update_duration {
+        writeln!(
+            out,
+            "slipstream_update_duration_seconds {}",
+            duration.to_std().as_secs_f64()
+        )
+        .ok();
+    }
+
+    describe(
+        &mut out,
+        "slipstream_http_requests_total",
+        "counter",
+        "HTTP requests handled, by route and status.",
+    );
+    for ((route, status), count) in http.requests.lock().unwrap().iter() {
+        writeln!(
+            out,
+            "slipstream_http_requests_total{{route=\"{}\",status=\"{status}\"}} {count}",
+            escape_label(route)
+        )
+        .ok();
+    }
+
+    out
+}
+
+/// Write the help and type of a metric.
+fn describe(out: &mut String, name: &str, kind: &str, help: &str) {
+    writeln!(out, "# HELP {name} {help}").ok();
+    writeln!(out, "# TYPE {name} {kind}").ok();
+}
+
+/// Escape a label value.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
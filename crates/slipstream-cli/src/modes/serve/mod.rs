@@ -4,68 +4,218 @@ use super::*;
 
 use axum::extract::State;
 use axum::http::HeaderMap;
+use axum::response::IntoResponse;
 
+mod auth;
 mod config;
 mod header_map_ext;
+mod metrics;
+mod rate_limit;
+mod self_test;
 mod web;
 
 pub use config::*;
 use header_map_ext::HeaderMapExt;
+pub use self_test::*;
 use web::*;
 
+/// Longest wait for requests in progress to finish when quitting.
+const SHUTDOWN_TIMEOUT: std::time::Duration =
+    std::time::Duration::from_secs(10);
+
 /// Serve slipstream over http.
 pub async fn serve_cli(
     port: Option<u16>,
-    address: Option<String>,
+    address: Option<std::net::IpAddr>,
     config: Arc<Config>,
     updater: UpdaterHandle,
     cancel_token: CancellationToken,
 ) -> Result<()> {
-    // Create caches.
-    let duration = slipfeed::Duration::from_seconds(match config.serve.cache {
-        Some(freq) => freq.as_secs(),
-        None => 120,
-    });
-    let cache = Arc::new(Mutex::new(Cache::new(duration.clone())));
-    let html = Arc::new(Mutex::new(HtmlServer::new(duration)?));
+    let app = router(&config, updater)?;
+    let port = port.unwrap_or(config.serve.port.unwrap_or(DEFAULT_PORT));
+    let address = match address {
+        Some(address) => address,
+        None => config.serve.bind_address()?,
+    };
+    let address = std::net::SocketAddr::new(address, port);
+    let listener = match tokio::net::TcpListener::bind(address).await {
+        Ok(listener) => listener,
+        Err(e) => bail!("Unable to bind to {address}: {e}"),
+    };
+    serve_router(listener, app, cancel_token).await
+}
+
+/// Serve slipstream over http on a bound listener.
+pub async fn serve_listener(
+    listener: tokio::net::TcpListener,
+    config: Arc<Config>,
+    updater: UpdaterHandle,
+    cancel_token: CancellationToken,
+) -> Result<()> {
+    let app = router(&config, updater)?;
+    serve_router(listener, app, cancel_token).await
+}
+
+/// Create the server's routes and layers.
+fn router(
+    config: &Arc<Config>,
+    updater: UpdaterHandle,
+) -> Result<axum::Router> {
+    // Create caches, which expire when new entries are stored.
+    let durations = config.serve.cache_durations();
+    let generation = updater.generation();
+    let cache = Arc::new(
+        Cache::new(durations.clone()).invalidated_by(generation.clone()),
+    );
+    let html = Arc::new(
+        HtmlServer::new(durations.clone(), &config.serve)?
+            .invalidated_by(generation),
+    );
+    let metrics = match config.serve.metrics() {
+        true => Some(Arc::new(metrics::HttpMetrics::default())),
+        false => None,
+    };
+
+    // Feeds and config, which browsers may fetch from other origins.
+    let feeds = axum::Router::new()
+        .route("/config", axum::routing::get(get_config))
+        .route("/opml", axum::routing::get(get_opml))
+        .route("/entry/{id}", axum::routing::get(get_entry))
+        .route("/search/feed", axum::routing::get(get_search_syndication))
+        .route(
+            "/search/feed.atom",
+            axum::routing::get(get_search_syndication),
+        )
+        .route(
+            "/search/feed.xml",
+            axum::routing::get(get_search_syndication),
+        )
+        .route("/all/feed", axum::routing::get(get_all_syndication))
+        .route("/all/feed.atom", axum::routing::get(get_all_syndication))
+        .route("/all/feed.xml", axum::routing::get(get_all_syndication))
+        .route("/saved/feed", axum::routing::get(get_saved_syndication))
+        .route(
+            "/saved/feed.atom",
+            axum::routing::get(get_saved_syndication),
+        )
+        .route("/saved/feed.xml", axum::routing::get(get_saved_syndication))
+        .route(
+            "/feed/{feed}/feed",
+            axum::routing::get(get_feed_syndication),
+        )
+        .route(
+            "/feed/{feed}/feed.atom",
+            axum::routing::get(get_feed_syndication),
+        )
+        .route(
+            "/feed/{feed}/feed.xml",
+            axum::routing::get(get_feed_syndication),
+        )
+        .route("/tag/{tag}/feed", axum::routing::get(get_tag_syndication))
+        .route(
+            "/tag/{tag}/feed.atom",
+            axum::routing::get(get_tag_syndication),
+        )
+        .route(
+            "/tag/{tag}/feed.xml",
+            axum::routing::get(get_tag_syndication),
+        );
+    let feeds = match config.serve.cors_origins()? {
+        Some(origins) => feeds.layer(
+            tower_http::cors::CorsLayer::new()
+                .allow_origin(origins)
+                .allow_methods([
+                    axum::http::Method::GET,
+                    axum::http::Method::HEAD,
+                ])
+                .allow_headers([axum::http::header::AUTHORIZATION]),
+        ),
+        None => feeds,
+    };
 
     // Create server.
     let app = axum::Router::new()
         .route("/", axum::routing::get(get_all_web))
-        .route("/config", axum::routing::get(get_config))
         .route("/all", axum::routing::get(get_all_web))
-        .route("/all/feed", axum::routing::get(get_all_atom))
-        .route("/all/feed.atom", axum::routing::get(get_all_atom))
-        .route("/all/feed.xml", axum::routing::get(get_all_atom))
+        .route("/saved", axum::routing::get(get_saved_web))
         .route("/feed/{feed}", axum::routing::get(get_feed_web))
-        .route("/feed/{feed}/feed", axum::routing::get(get_feed_atom))
-        .route("/feed/{feed}/feed.atom", axum::routing::get(get_feed_atom))
-        .route("/feed/{feed}/feed.xml", axum::routing::get(get_feed_atom))
         .route("/tag/{tag}", axum::routing::get(get_tag_web))
-        .route("/tag/{tag}/feed", axum::routing::get(get_tag_atom))
-        .route("/tag/{tag}/feed.atom", axum::routing::get(get_tag_atom))
-        .route("/tag/{tag}/feed.xml", axum::routing::get(get_tag_atom))
+        .route("/search", axum::routing::get(get_search_web))
         .route("/styles.css", axum::routing::get(get_styles))
         .route("/robots.txt", axum::routing::get(get_robots_txt))
         .route("/favicon.ico", axum::routing::get(get_favicon))
-        .with_state(Arc::new(SFState {
-            updater: Arc::new(updater),
-            config: config.clone(),
-            cache,
-            html,
-        }));
-    let port = port.unwrap_or(config.serve.port.unwrap_or(DEFAULT_PORT));
-    let address = address.unwrap_or(config.serve.address.clone().unwrap_or(DEFAULT_ADDRESS.into()));
-    let listener = tokio::net::TcpListener::bind(format!("{address}:{port}"))
-        .await
-        .expect(&format!("Unable to bind to port {}", port));
+        .route("/healthz", axum::routing::get(get_healthz))
+        .merge(feeds);
+    let app = match &metrics {
+        Some(metrics) => app
+            .route("/metrics", axum::routing::get(get_metrics))
+            .layer(axum::middleware::from_fn_with_state(
+                metrics.clone(),
+                metrics::count_requests,
+            )),
+        None => app,
+    };
+    let app = app.with_state(Arc::new(SFState {
+        updater: Arc::new(updater),
+        config: config.clone(),
+        cache,
+        cache_durations: durations,
+        html,
+        metrics,
+    }));
+    let app = match config.serve.max_concurrent_requests {
+        Some(max) => app.layer(
+            tower::ServiceBuilder::new()
+                .layer(axum::error_handling::HandleErrorLayer::new(overloaded))
+                .load_shed()
+                .layer(tower::limit::GlobalConcurrencyLimitLayer::new(max)),
+        ),
+        None => app,
+    };
+    let app = match config.serve.auth_token() {
+        Some(token) => app.layer(axum::middleware::from_fn_with_state(
+            Arc::new(token.to_string()),
+            auth::require_token,
+        )),
+        None => app,
+    };
+    let app = match config.serve.compression() {
+        true => app.layer(tower_http::compression::CompressionLayer::new()),
+        false => app,
+    };
+    let app = match &config.serve.rate_limit {
+        Some(rate_limit) => app.layer(axum::middleware::from_fn_with_state(
+            Arc::new(rate_limit::RateLimiter::new(rate_limit)),
+            rate_limit::limit_requests,
+        )),
+        None => app,
+    };
+    Ok(app)
+}
 
-    // Serve.
+/// Serve routes until cancelled.
+async fn serve_router(
+    listener: tokio::net::TcpListener,
+    app: axum::Router,
+    cancel_token: CancellationToken,
+) -> Result<()> {
     tracing::info!("slipstream serve");
-    tracing::info!("Serving feeds @ {address}:{port}");
+    match listener.local_addr() {
+        Ok(address) => tracing::info!("Serving feeds @ {address}"),
+        Err(e) => bail!("Unable to serve: {e}"),
+    }
 
-    let served = axum::serve(listener, app);
-    let cancelled = cancel_token.cancelled();
+    // Once cancelled, stop accepting connections and let requests in
+    // progress finish, for a while.
+    let served = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .with_graceful_shutdown(cancel_token.clone().cancelled_owned());
+    let drained = async {
+        cancel_token.cancelled().await;
+        tokio::time::sleep(SHUTDOWN_TIMEOUT).await;
+    };
     tokio::select! {
         served_res = served => {
             if let Err(e) = served_res {
@@ -73,39 +223,215 @@ pub async fn serve_cli(
                 cancel_token.cancel();
             }
         },
-        _ = cancelled => {
-            // Quit.
+        _ = drained => {
+            tracing::warn!(
+                "Closing requests still open after {SHUTDOWN_TIMEOUT:?}."
+            );
         },
     };
 
     Ok(())
 }
 
+/// Respond to requests over the concurrency limit.
+async fn overloaded(_err: tower::BoxError) -> axum::http::StatusCode {
+    axum::http::StatusCode::SERVICE_UNAVAILABLE
+}
+
 /// State shared by the axum web server.
 #[derive(Clone)]
 struct SFState {
     updater: Arc<UpdaterHandle>,
     config: Arc<Config>,
-    cache: Arc<Mutex<Cache>>,
-    html: Arc<Mutex<HtmlServer>>,
+    cache: Arc<Cache<Syndication>>,
+    /// Cache durations of routes, for cache headers.
+    cache_durations: CacheDurations,
+    html: Arc<HtmlServer>,
+    metrics: Option<Arc<metrics::HttpMetrics>>,
+}
+
+impl SFState {
+    /// Add cache headers for a path. Responses behind a token are private,
+    /// so shared caches can't serve them to anyone else.
+    fn cacheable(&self, headers: HeaderMap, path: &str) -> HeaderMap {
+        headers.cacheable(
+            self.cache_durations.get(path),
+            self.config.serve.auth_token().is_some(),
+        )
+    }
 }
 
 /// The wrapped state type.
 type StateType = axum::extract::State<Arc<SFState>>;
 
+/// Query parameters for syndicated feeds.
+#[derive(Debug, Default, Deserialize)]
+struct SyndicationQuery {
+    /// The syndication format (default atom).
+    #[serde(default)]
+    format: SyndicationFormat,
+}
+
+/// The wrapped syndication query type.
+type SyndicationQueryType = axum::extract::Query<SyndicationQuery>;
+
+/// Query parameters for pages of feeds.
+#[derive(Debug, Default, Deserialize)]
+struct PageQuery {
+    /// Only entries before a date.
+    before: Option<slipfeed::DateTime>,
+    /// Only entries before this entry at the `before` date.
+    before_id: Option<EntryDbId>,
+    /// Only entries after a date, unless paging before one.
+    after: Option<slipfeed::DateTime>,
+    /// Only entries after this entry at the `after` date.
+    after_id: Option<EntryDbId>,
+    /// Most entries on the page, up to the served maximum.
+    limit: Option<usize>,
+}
+
+impl PageQuery {
+    /// Get the requested page of the feed at a path. The latest page only
+    /// collects entries modified since a date.
+    fn page(
+        &self,
+        path: &str,
+        uri: &axum::http::Uri,
+        modified_since: Option<slipfeed::DateTime>,
+        config: &ServeConfig,
+    ) -> FeedPage {
+        // Keep the rest of the query, such as the format, in page links.
+        // The token is left out, so it isn't shown in links or cache keys.
+        let query: Vec<&str> = uri
+            .query()
+            .unwrap_or_default()
+            .split('&')
+            .filter(|param| {
+                !param.is_empty()
+                    && !param.starts_with("before=")
+                    && !param.starts_with("before_id=")
+                    && !param.starts_with("after=")
+                    && !param.starts_with("after_id=")
+                    && !param.starts_with("token=")
+            })
+            .collect();
+        let link = match query.is_empty() {
+            true => path.to_string(),
+            false => format!("{path}?{}", query.join("&")),
+        };
+        FeedPage {
+            cursor: match (&self.before, &self.after) {
+                (Some(before), _) => {
+                    OffsetCursor::Before(before.clone(), self.before_id)
+                }
+                (None, Some(after)) => {
+                    OffsetCursor::After(after.clone(), self.after_id)
+                }
+                (None, None) => OffsetCursor::modified_since(modified_since),
+            },
+            link: Some(link),
+            limit: self.limit.map(|limit| limit.min(config.max_limit())),
+        }
+    }
+}
+
+/// The wrapped page query type.
+type PageQueryType = axum::extract::Query<PageQuery>;
+
+/// Query parameters for tag feeds.
+#[derive(Debug, Default, Deserialize)]
+struct TagQuery {
+    /// How tags are matched (default exact).
+    #[serde(default, rename = "match")]
+    matching: SearchMatch,
+}
+
+/// The wrapped tag query type.
+type TagQueryType = axum::extract::Query<TagQuery>;
+
+/// Query parameters for searches.
+#[derive(Debug, Default, Deserialize)]
+struct SearchQuery {
+    /// Search text.
+    q: Option<String>,
+    /// Only entries with a tag.
+    tag: Option<String>,
+    /// Only entries without a tag.
+    #[serde(alias = "not-tag")]
+    not_tag: Option<String>,
+    /// Only entries from a feed.
+    feed: Option<String>,
+    /// Only entries not from a feed.
+    #[serde(alias = "not-feed")]
+    not_feed: Option<String>,
+    /// How tags and feeds are matched (default exact).
+    #[serde(default, rename = "match")]
+    matching: SearchMatch,
+    /// The syndication format (default atom).
+    #[serde(default)]
+    format: SyndicationFormat,
+}
+
+impl SearchQuery {
+    /// Build the database search, ignoring empty parameters.
+    /// Commands and raw sql are only searchable from the reader.
+    fn criteria(&self) -> Vec<DatabaseSearch> {
+        let param = |value: &Option<String>| -> Vec<String> {
+            value
+                .iter()
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+                .map(String::from)
+                .collect()
+        };
+        let search = SearchContext {
+            tag: param(&self.tag),
+            not_tag: param(&self.not_tag),
+            feed: param(&self.feed),
+            not_feed: param(&self.not_feed),
+            text: param(&self.q).pop(),
+            substring: self.matching == SearchMatch::Substring,
+            ..SearchContext::default()
+        };
+        let mut criteria = vec![DatabaseSearch::Latest];
+        criteria.extend(search.criteria());
+        criteria
+    }
+
+    /// Get the name of the searched feed.
+    fn name(&self) -> String {
+        match self.q.as_deref().map(str::trim) {
+            Some(q) if !q.is_empty() => format!("search: {q}"),
+            _ => "search".into(),
+        }
+    }
+}
+
+/// The wrapped search query type.
+type SearchQueryType = axum::extract::Query<SearchQuery>;
+
 /// Get the web view for the /all feed.
 async fn get_all_web(
     State(state): StateType,
     headers: HeaderMap,
+    uri: axum::http::Uri,
+    axum::extract::Query(page): PageQueryType,
 ) -> impl axum::response::IntoResponse {
     tracing::debug!("/all");
-    let mut html = state.html.lock().await;
+    let html = &state.html;
     let updater = state.updater.clone();
+    let page = page.page(
+        "/all",
+        &uri,
+        headers.if_modified_since(),
+        &state.config.serve,
+    );
+    let (cursor, limit) = (page.cursor.clone(), page.limit);
     return (
-        HeaderMap::html_headers(),
+        state.cacheable(HeaderMap::html_headers(), uri.path()),
         html.get(
-            "/all",
-            async move { updater.collect_all(headers.if_modified_since()).await },
+            &page,
+            async move { updater.collect_all(cursor, limit).await },
             state.updater.clone(),
             state.config.clone(),
         )
@@ -113,51 +439,154 @@ async fn get_all_web(
     );
 }
 
-/// Get the atom feed for the /all feed.
-async fn get_all_atom(
+/// Get the syndicated feed for the /all feed.
+async fn get_all_syndication(
     State(state): StateType,
     headers: HeaderMap,
-) -> impl axum::response::IntoResponse {
+    uri: axum::http::Uri,
+    axum::extract::Query(query): SyndicationQueryType,
+    axum::extract::Query(page): PageQueryType,
+) -> axum::response::Response {
     tracing::debug!("/all/feed");
     let config = state.config.clone();
     let updater = state.updater.clone();
-    let mut cache = state.cache.lock().await;
+    let cache = &state.cache;
     let cache_behavior = headers.cache_behavior();
-    return (
-        HeaderMap::atom_headers(),
+    let response_headers = state
+        .cacheable(HeaderMap::syndication_headers(query.format), uri.path());
+    let if_modified_since = headers.if_modified_since();
+    let page = page.page(
+        uri.path(),
+        &uri,
+        headers.if_modified_since(),
+        &state.config.serve,
+    );
+    let syndication =
         cache
             .get(
-                "/all",
+                &page.page_link().unwrap_or_default(),
                 async move {
-                    updater
-                        .syndicate_all(config, headers.if_modified_since())
-                        .await
+                    updater.syndicate_all(config, page, query.format).await
                 },
                 cache_behavior,
             )
-            .await,
+            .await;
+    syndication_response(response_headers, if_modified_since, syndication)
+}
+
+/// Get the web view for the /saved feed.
+async fn get_saved_web(
+    State(state): StateType,
+    headers: HeaderMap,
+    uri: axum::http::Uri,
+    axum::extract::Query(page): PageQueryType,
+) -> impl axum::response::IntoResponse {
+    tracing::debug!("/saved");
+    let html = &state.html;
+    let updater = state.updater.clone();
+    let page = page.page(
+        "/saved",
+        &uri,
+        headers.if_modified_since(),
+        &state.config.serve,
+    );
+    let (cursor, limit) = (page.cursor.clone(), page.limit);
+    return (
+        state.cacheable(HeaderMap::html_headers(), uri.path()),
+        html.get(
+            &page,
+            async move { updater.collect_saved(cursor, limit).await },
+            state.updater.clone(),
+            state.config.clone(),
+        )
+        .await,
     );
 }
 
+/// Get the syndicated feed for the /saved feed.
+async fn get_saved_syndication(
+    State(state): StateType,
+    headers: HeaderMap,
+    uri: axum::http::Uri,
+    axum::extract::Query(query): SyndicationQueryType,
+    axum::extract::Query(page): PageQueryType,
+) -> axum::response::Response {
+    tracing::debug!("/saved/feed");
+    let config = state.config.clone();
+    let updater = state.updater.clone();
+    let cache = &state.cache;
+    let cache_behavior = headers.cache_behavior();
+    let response_headers = state
+        .cacheable(HeaderMap::syndication_headers(query.format), uri.path());
+    let if_modified_since = headers.if_modified_since();
+    let page = page.page(
+        uri.path(),
+        &uri,
+        headers.if_modified_since(),
+        &state.config.serve,
+    );
+    let syndication =
+        cache
+            .get(
+                &page.page_link().unwrap_or_default(),
+                async move {
+                    updater.syndicate_saved(config, page, query.format).await
+                },
+                cache_behavior,
+            )
+            .await;
+    syndication_response(response_headers, if_modified_since, syndication)
+}
+
+/// Respond with a syndicated feed, or with 304 Not Modified if the client
+/// already has its newest entry.
+fn syndication_response(
+    headers: HeaderMap,
+    if_modified_since: Option<slipfeed::DateTime>,
+    syndication: Syndication,
+) -> axum::response::Response {
+    let headers = headers.last_modified(syndication.last_modified.as_ref());
+    if let Some(since) = if_modified_since {
+        // Only entries modified since are collected, so an empty feed means
+        // nothing changed.
+        let unmodified = match &syndication.last_modified {
+            Some(last_modified) => {
+                last_modified.to_chrono().timestamp()
+                    <= since.to_chrono().timestamp()
+            }
+            None => true,
+        };
+        if unmodified {
+            return (axum::http::StatusCode::NOT_MODIFIED, headers)
+                .into_response();
+        }
+    }
+    (headers, syndication.body).into_response()
+}
+
 /// Get the web view for a feed.
 async fn get_feed_web(
     State(state): StateType,
     headers: HeaderMap,
     uri: axum::http::Uri,
+    axum::extract::Query(page): PageQueryType,
 ) -> impl axum::response::IntoResponse {
     tracing::debug!("{}", uri.path());
     let feed = &uri.path()["/feed/".len()..];
     let updater = state.updater.clone();
-    let mut html = state.html.lock().await;
+    let html = &state.html;
+    let page = page.page(
+        uri.path(),
+        &uri,
+        headers.if_modified_since(),
+        &state.config.serve,
+    );
+    let (cursor, limit) = (page.cursor.clone(), page.limit);
     return (
-        HeaderMap::html_headers(),
+        state.cacheable(HeaderMap::html_headers(), uri.path()),
         html.get(
-            uri.path(),
-            async move {
-                updater
-                    .collect_feed(feed, headers.if_modified_since())
-                    .await
-            },
+            &page,
+            async move { updater.collect_feed(feed, cursor, limit).await },
             state.updater.clone(),
             state.config.clone(),
         )
@@ -165,36 +594,41 @@ async fn get_feed_web(
     );
 }
 
-/// Get the atom feed for a feed.
-async fn get_feed_atom(
+/// Get the syndicated feed for a feed.
+async fn get_feed_syndication(
     State(state): StateType,
     headers: HeaderMap,
     uri: axum::http::Uri,
     axum::extract::Path(feed): axum::extract::Path<String>,
-) -> impl axum::response::IntoResponse {
+    axum::extract::Query(query): SyndicationQueryType,
+    axum::extract::Query(page): PageQueryType,
+) -> axum::response::Response {
     tracing::debug!("{}", uri.path());
     let config = state.config.clone();
     let updater = state.updater.clone();
-    let mut cache = state.cache.lock().await;
+    let cache = &state.cache;
     let cache_behavior = headers.cache_behavior();
-    return (
-        HeaderMap::atom_headers(),
-        cache
-            .get(
-                uri.path(),
-                async move {
-                    updater
-                        .syndicate_feed(
-                            &feed,
-                            config,
-                            headers.if_modified_since(),
-                        )
-                        .await
-                },
-                cache_behavior,
-            )
-            .await,
+    let response_headers = state
+        .cacheable(HeaderMap::syndication_headers(query.format), uri.path());
+    let if_modified_since = headers.if_modified_since();
+    let page = page.page(
+        uri.path(),
+        &uri,
+        headers.if_modified_since(),
+        &state.config.serve,
     );
+    let syndication = cache
+        .get(
+            &page.page_link().unwrap_or_default(),
+            async move {
+                updater
+                    .syndicate_feed(&feed, config, page, query.format)
+                    .await
+            },
+            cache_behavior,
+        )
+        .await;
+    syndication_response(response_headers, if_modified_since, syndication)
 }
 
 /// Get the web view for a tag.
@@ -202,74 +636,319 @@ async fn get_tag_web(
     State(state): StateType,
     headers: HeaderMap,
     uri: axum::http::Uri,
-) -> impl axum::response::IntoResponse {
+    axum::extract::Path(tag): axum::extract::Path<String>,
+    axum::extract::Query(tag_query): TagQueryType,
+    axum::extract::Query(page): PageQueryType,
+) -> axum::response::Response {
     tracing::debug!("{}", uri.path());
-    let tag = &uri.path()["/tag/".len()..];
+    let matching = tag_query.matching;
+    // Blank tags would match every entry.
+    if matching.tags(&tag).is_empty() {
+        return axum::http::StatusCode::NOT_FOUND.into_response();
+    }
     let updater = state.updater.clone();
-    let mut html = state.html.lock().await;
+    let html = &state.html;
+    let page = page.page(
+        uri.path(),
+        &uri,
+        headers.if_modified_since(),
+        &state.config.serve,
+    );
+    let (cursor, limit) = (page.cursor.clone(), page.limit);
     return (
-        HeaderMap::html_headers(),
+        state.cacheable(HeaderMap::html_headers(), uri.path()),
         html.get(
-            uri.path(),
+            &page,
             async move {
-                updater.collect_tag(tag, headers.if_modified_since()).await
+                updater.collect_tag(tag, matching, cursor, limit).await
             },
             state.updater.clone(),
             state.config.clone(),
         )
         .await,
-    );
+    )
+        .into_response();
 }
 
-/// Get the atom feed for a tag.
-async fn get_tag_atom(
+/// Get the syndicated feed for a tag.
+async fn get_tag_syndication(
     State(state): StateType,
     headers: HeaderMap,
     uri: axum::http::Uri,
     axum::extract::Path(tag): axum::extract::Path<String>,
-) -> impl axum::response::IntoResponse {
+    axum::extract::Query(tag_query): TagQueryType,
+    axum::extract::Query(query): SyndicationQueryType,
+    axum::extract::Query(page): PageQueryType,
+) -> axum::response::Response {
     tracing::debug!("{}", uri.path());
+    // Blank tags would match every entry.
+    if tag_query.matching.tags(&tag).is_empty() {
+        return axum::http::StatusCode::NOT_FOUND.into_response();
+    }
     let config = state.config.clone();
     let updater = state.updater.clone();
-    let mut cache = state.cache.lock().await;
+    let cache = &state.cache;
     let cache_behavior = headers.cache_behavior();
+    let response_headers = state
+        .cacheable(HeaderMap::syndication_headers(query.format), uri.path());
+    let if_modified_since = headers.if_modified_since();
+    let page = page.page(
+        uri.path(),
+        &uri,
+        headers.if_modified_since(),
+        &state.config.serve,
+    );
+    let syndication = cache
+        .get(
+            &page.page_link().unwrap_or_default(),
+            async move {
+                updater
+                    .syndicate_tag(
+                        &tag,
+                        tag_query.matching,
+                        config,
+                        page,
+                        query.format,
+                    )
+                    .await
+            },
+            cache_behavior,
+        )
+        .await;
+    syndication_response(response_headers, if_modified_since, syndication)
+}
+
+/// Get the web view for a search.
+async fn get_search_web(
+    State(state): StateType,
+    headers: HeaderMap,
+    uri: axum::http::Uri,
+    axum::extract::Query(query): SearchQueryType,
+    axum::extract::Query(page): PageQueryType,
+) -> impl axum::response::IntoResponse {
+    tracing::debug!("{uri}");
+    let updater = state.updater.clone();
+    let html = &state.html;
+    let page = page.page(
+        uri.path(),
+        &uri,
+        headers.if_modified_since(),
+        &state.config.serve,
+    );
+    let (cursor, limit) = (page.cursor.clone(), page.limit);
     return (
-        HeaderMap::atom_headers(),
-        cache
-            .get(
-                uri.path(),
-                async move {
-                    updater
-                        .syndicate_tag(
-                            &tag,
-                            config,
-                            headers.if_modified_since(),
-                        )
-                        .await
-                },
-                cache_behavior,
-            )
-            .await,
+        state.cacheable(HeaderMap::html_headers(), uri.path()),
+        html.get(
+            &page,
+            async move {
+                updater
+                    .collect_search(query.criteria(), cursor, limit)
+                    .await
+            },
+            state.updater.clone(),
+            state.config.clone(),
+        )
+        .await,
     );
 }
 
+/// Get the syndicated feed for a search.
+async fn get_search_syndication(
+    State(state): StateType,
+    headers: HeaderMap,
+    uri: axum::http::Uri,
+    axum::extract::Query(query): SearchQueryType,
+    axum::extract::Query(page): PageQueryType,
+) -> axum::response::Response {
+    tracing::debug!("{uri}");
+    let config = state.config.clone();
+    let updater = state.updater.clone();
+    let cache = &state.cache;
+    let cache_behavior = headers.cache_behavior();
+    let response_headers = state
+        .cacheable(HeaderMap::syndication_headers(query.format), uri.path());
+    let if_modified_since = headers.if_modified_since();
+    let page = page.page(
+        uri.path(),
+        &uri,
+        headers.if_modified_since(),
+        &state.config.serve,
+    );
+    let syndication = cache
+        .get(
+            &page.page_link().unwrap_or_default(),
+            async move {
+                updater
+                    .syndicate_search(
+                        query.name(),
+                        query.criteria(),
+                        config,
+                        page,
+                        query.format,
+                    )
+                    .await
+            },
+            cache_behavior,
+        )
+        .await;
+    syndication_response(response_headers, if_modified_since, syndication)
+}
+
+/// A single entry, as served at /entry/<id>.json.
+#[derive(Debug, Serialize)]
+struct EntryJson {
+    id: EntryDbId,
+    title: String,
+    date: String,
+    author: String,
+    content: String,
+    source: slipfeed::Link,
+    comments: slipfeed::Link,
+    links: Vec<slipfeed::Link>,
+    feeds: Vec<String>,
+    tags: Vec<String>,
+    language: Option<String>,
+}
+
+impl From<&DatabaseEntry> for EntryJson {
+    fn from(entry: &DatabaseEntry) -> Self {
+        Self {
+            id: entry.db_id,
+            title: entry.title().clone(),
+            date: entry.date().to_chrono().to_rfc3339(),
+            author: entry.author().clone(),
+            content: entry.content().clone(),
+            source: entry.source().clone(),
+            comments: entry.comments().clone(),
+            links: entry.other_links().clone(),
+            feeds: entry
+                .feeds()
+                .iter()
+                .map(|feed| (*feed.name).clone())
+                .collect(),
+            tags: entry.tags().iter().map(|tag| tag.to_string()).collect(),
+            language: entry.language().map(String::from),
+        }
+    }
+}
+
+/// Get a single entry as html, or as json or atom with a `.json` or `.atom`
+/// extension.
+async fn get_entry(
+    State(state): StateType,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> axum::response::Response {
+    tracing::debug!("/entry/{id}");
+    if !state.config.serve.expose_entries() {
+        return axum::http::StatusCode::NOT_FOUND.into_response();
+    }
+    let (id, extension) = match id.split_once('.') {
+        Some((id, extension)) => (id, Some(extension)),
+        None => (id.as_str(), None),
+    };
+    let Ok(id) = id.parse::<EntryDbId>() else {
+        return axum::http::StatusCode::NOT_FOUND.into_response();
+    };
+    let entry = state.updater.entry(id).await;
+    let Some(entry) = entry.filter(|e| !state.config.serve.too_old(e.date()))
+    else {
+        return axum::http::StatusCode::NOT_FOUND.into_response();
+    };
+    let path = format!("/entry/{id}");
+    match extension {
+        None => {
+            let mut entries = DatabaseEntryList::new(1);
+            entries.add(entry).ok();
+            let page = FeedPage {
+                cursor: OffsetCursor::LatestId,
+                link: Some(format!("/entry/{id}")),
+                limit: None,
+            };
+            let page = state
+                .html
+                .get(
+                    &page,
+                    async move { entries },
+                    state.updater.clone(),
+                    state.config.clone(),
+                )
+                .await;
+            (state.cacheable(HeaderMap::html_headers(), &path), page)
+                .into_response()
+        }
+        Some("json") => (
+            state.cacheable(HeaderMap::new(), &path),
+            axum::Json(EntryJson::from(&entry)),
+        )
+            .into_response(),
+        Some("atom") => {
+            let title = entry.title().clone();
+            let mut entries = DatabaseEntryList::new(1);
+            entries.add(entry).ok();
+            (
+                state.cacheable(HeaderMap::atom_headers(), &path),
+                entries.syndicate(title, &state.config),
+            )
+                .into_response()
+        }
+        Some(_) => axum::http::StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
 /// Get the server config toml.
 /// This is for convenience for anyone who may want to copy the feeds of a public
 /// server.
 /// This serves the parsed and re-exported config.toml. Any comments or extraneous
 /// metadata is stripped.
-async fn get_config(
-    State(state): StateType,
-) -> impl axum::response::IntoResponse {
+async fn get_config(State(state): StateType) -> axum::response::Response {
     tracing::debug!("/config");
-    let serialized: String = match toml::to_string_pretty(&state.config) {
+    if !state.config.serve.expose_config() {
+        return axum::http::StatusCode::NOT_FOUND.into_response();
+    }
+    // Don't leak secrets to anyone reading the config.
+    let serialized = match state.config.export_public() {
         Ok(config) => config,
         Err(e) => {
             tracing::error!("Failed to serialize config: {e}");
             String::new()
         }
     };
-    return (HeaderMap::toml_headers(), serialized);
+    return (HeaderMap::toml_headers().uncacheable(), serialized)
+        .into_response();
+}
+
+/// Get the configured feeds as OPML.
+async fn get_opml(State(state): StateType) -> axum::response::Response {
+    tracing::debug!("/opml");
+    if !state.config.serve.expose_config() {
+        return axum::http::StatusCode::NOT_FOUND.into_response();
+    }
+    match export_opml(&state.config, true) {
+        Ok((opml, notes)) => {
+            for note in notes {
+                tracing::debug!("{note}");
+            }
+            (HeaderMap::opml_headers().uncacheable(), opml).into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to export OPML: {e}");
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// Get Prometheus metrics.
+async fn get_metrics(State(state): StateType) -> axum::response::Response {
+    tracing::debug!("/metrics");
+    let Some(http) = &state.metrics else {
+        return axum::http::StatusCode::NOT_FOUND.into_response();
+    };
+    let updater = state.updater.metrics().await;
+    (
+        HeaderMap::metrics_headers().uncacheable(),
+        metrics::render_metrics(&updater, http),
+    )
+        .into_response()
 }
 
 /// Get the styles for the web view.
@@ -277,7 +956,7 @@ async fn get_styles(
     State(state): StateType,
 ) -> impl axum::response::IntoResponse {
     tracing::debug!("/styles.css");
-    let html = state.html.lock().await;
+    let html = &state.html;
     return (HeaderMap::css_headers(), (*html.styles).clone());
 }
 
@@ -286,15 +965,36 @@ async fn get_robots_txt(
     State(state): StateType,
 ) -> impl axum::response::IntoResponse {
     tracing::debug!("/robots.txt");
-    let html = state.html.lock().await;
+    let html = &state.html;
     return (HeaderMap::plaintext_headers(), (*html.robots_txt).clone());
 }
 
+/// Health of the server, as served at /healthz.
+#[derive(Debug, Serialize)]
+struct HealthJson {
+    status: &'static str,
+}
+
+/// Report whether the updater has run yet, for health checks.
+async fn get_healthz(State(state): StateType) -> axum::response::Response {
+    tracing::debug!("/healthz");
+    let (status, health) = match state.updater.ready() {
+        true => (axum::http::StatusCode::OK, "ready"),
+        false => (axum::http::StatusCode::SERVICE_UNAVAILABLE, "starting"),
+    };
+    (
+        status,
+        HeaderMap::new().uncacheable(),
+        axum::Json(HealthJson { status: health }),
+    )
+        .into_response()
+}
+
 /// Get the slipstream favicon.
 async fn get_favicon(
     State(state): StateType,
 ) -> impl axum::response::IntoResponse {
     tracing::debug!("/favicon.ico");
-    let html = state.html.lock().await;
+    let html = &state.html;
     return (HeaderMap::favicon_headers(), (*html.favicon).clone());
 }
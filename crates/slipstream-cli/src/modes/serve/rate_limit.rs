@@ -0,0 +1,154 @@
+//! Per-client rate limiting.
+
+use super::*;
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+use std::time::{Duration, Instant};
+
+use axum::http::header;
+
+/// Paths served without counting against the limit.
+const UNLIMITED_PATHS: &[&str] = &["/robots.txt", "/healthz"];
+
+/// Token buckets of recent clients, refilled evenly over the window.
+pub struct RateLimiter {
+    /// Requests allowed in a window, and the most a bucket holds.
+    requests: u32,
+    /// Window the requests are allowed in.
+    window: Duration,
+    /// Whether to identify clients by `X-Forwarded-For`.
+    trust_forwarded_for: bool,
+    /// Buckets by client.
+    buckets: std::sync::Mutex<Buckets>,
+}
+
+/// Buckets of clients seen within the last window or so.
+struct Buckets {
+    /// Buckets by client.
+    clients: HashMap<IpAddr, Bucket>,
+    /// When full buckets were last forgotten.
+    pruned: Instant,
+}
+
+/// Requests left for a client.
+struct Bucket {
+    /// Requests left, including partially refilled ones.
+    tokens: f64,
+    /// When the tokens were last counted.
+    updated: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(config: &RateLimitConfig) -> Self {
+        Self {
+            requests: config.requests,
+            window: config.window(),
+            trust_forwarded_for: config.trust_forwarded_for,
+            buckets: std::sync::Mutex::new(Buckets {
+                clients: HashMap::new(),
+                pruned: Instant::now(),
+            }),
+        }
+    }
+
+    /// Get the key of a client's bucket.
+    /// IPv6 clients are usually given a whole /64, so they share a bucket.
+    fn key(client: IpAddr) -> IpAddr {
+        match client.to_canonical() {
+            IpAddr::V6(address) => IpAddr::V6(Ipv6Addr::from_bits(
+                address.to_bits() & !u128::from(u64::MAX),
+            )),
+            address => address,
+        }
+    }
+
+    /// Take a request from a client's bucket, or get how long until one
+    /// refills.
+    pub(crate) fn take(
+        &self,
+        client: IpAddr,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        let capacity = self.requests as f64;
+        let refill = capacity / self.window.as_secs_f64().max(f64::EPSILON);
+        let mut buckets = self.buckets.lock().unwrap();
+        // Buckets untouched for a window have refilled, so they're forgotten
+        // once per window.
+        if now.saturating_duration_since(buckets.pruned) >= self.window {
+            let window = self.window;
+            buckets.clients.retain(|_, bucket| {
+                now.saturating_duration_since(bucket.updated) < window
+            });
+            buckets.pruned = now;
+        }
+        let bucket =
+            buckets.clients.entry(Self::key(client)).or_insert(Bucket {
+                tokens: capacity,
+                updated: now,
+            });
+        let elapsed =
+            now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * refill).min(capacity);
+        bucket.updated = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+        match refill > 0.0 {
+            true => {
+                Err(Duration::from_secs_f64((1.0 - bucket.tokens) / refill))
+            }
+            false => Err(self.window),
+        }
+    }
+
+    /// Get the address of the client making a request, if it is known.
+    fn client(&self, request: &axum::extract::Request) -> Option<IpAddr> {
+        if self.trust_forwarded_for {
+            let forwarded = request
+                .headers()
+                .get_all("x-forwarded-for")
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .flat_map(|value| value.split(','))
+                .filter_map(|address| address.trim().parse().ok())
+                .next_back();
+            if forwarded.is_some() {
+                return forwarded;
+            }
+        }
+        request
+            .extensions()
+            .get::<axum::extract::ConnectInfo<SocketAddr>>()
+            .map(|info| info.0.ip())
+    }
+}
+
+/// Respond with 429 Too Many Requests to clients over the rate limit.
+pub async fn limit_requests(
+    State(limiter): State<Arc<RateLimiter>>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    if UNLIMITED_PATHS.contains(&request.uri().path()) {
+        return next.run(request).await;
+    }
+    let Some(client) = limiter.client(&request) else {
+        return next.run(request).await;
+    };
+    if let Err(wait) = limiter.take(client, Instant::now()) {
+        tracing::debug!(
+            client = %client,
+            path = %request.uri().path(),
+            "Rate limited request"
+        );
+        let retry_after = wait.as_secs_f64().ceil().max(1.0) as u64;
+        return (
+            axum::http::StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, retry_after.to_string())],
+        )
+            .into_response();
+    }
+    next.run(request).await
+}
@@ -0,0 +1,195 @@
+//! Self-test mode.
+
+use std::str::FromStr;
+
+use super::*;
+
+/// Feed fetched by the self-test, with `{date}` replaced by the current time.
+const FIXTURE: &str = include_str!("fixture.atom");
+
+/// Titles of the fixture's entries.
+const FIXTURE_TITLES: &[&str] = &["Fixture entry one", "Fixture entry two"];
+
+/// How long to wait for the fixture's entries to be stored.
+const STORE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Result of a self-test.
+#[derive(Clone, Debug, Default)]
+pub struct SelfTestReport {
+    /// Checks run, with an error for each failure.
+    pub checks: Vec<(&'static str, Option<String>)>,
+}
+
+impl SelfTestReport {
+    /// Whether every check passed.
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|(_, error)| error.is_none())
+    }
+
+    /// Record a check, returning whether it passed.
+    fn check(&mut self, name: &'static str, result: Result<()>) -> bool {
+        let error = result.err().map(|e| e.to_string());
+        let passed = error.is_none();
+        self.checks.push((name, error));
+        passed
+    }
+}
+
+impl std::fmt::Display for SelfTestReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, error) in &self.checks {
+            match error {
+                None => writeln!(f, "[pass] {name}")?,
+                Some(e) => writeln!(f, "[FAIL] {name}: {e}")?,
+            }
+        }
+        let passed = self.checks.iter().filter(|(_, e)| e.is_none()).count();
+        writeln!(f, "{passed}/{} checks passed.", self.checks.len())
+    }
+}
+
+/// Run the self-test and print a summary.
+pub async fn self_test_cli(config: Arc<Config>) -> Result<()> {
+    let report = self_test(&config).await?;
+    print!("{report}");
+    if !report.passed() {
+        bail!("Self-test failed.");
+    }
+    Ok(())
+}
+
+/// Fetch a bundled feed into an in-memory database and render it the way
+/// `slipstream serve` would.
+/// Only the serve settings of the config are used, so configured feeds,
+/// filters, and notifications are left alone.
+pub async fn self_test(config: &Config) -> Result<SelfTestReport> {
+    let mut report = SelfTestReport::default();
+
+    // Write the fixture somewhere the feed can read it.
+    let path = std::env::temp_dir()
+        .join(format!("slipstream-self-test-{}.atom", std::process::id()));
+    let fixture = FIXTURE.replace("{date}", &chrono::Utc::now().to_rfc3339());
+    if let Err(e) = std::fs::write(&path, fixture) {
+        bail!("Unable to write fixture to {path:?}: {e}");
+    }
+
+    let config = Arc::new(Config {
+        timezone: config.timezone.clone(),
+        date_format: config.date_format.clone(),
+        feeds: Some(BTreeMap::from([(
+            "fixture".to_string(),
+            FeedDefinition::from_feed(RawFeed::Raw {
+                url: path.to_string_lossy().into_owned(),
+            }),
+        )])),
+        serve: config.serve.clone(),
+        ..Config::default()
+    });
+    let cancel_token = CancellationToken::new();
+    let mut updater = config.updater().await?;
+    let handle = Arc::new(updater.handle()?);
+    let updater_task =
+        tokio::spawn(update(updater, config.clone(), cancel_token.clone()));
+
+    // Wait for the fixture to be parsed and stored.
+    let stored = async {
+        let deadline = tokio::time::Instant::now() + STORE_TIMEOUT;
+        loop {
+            let entries =
+                handle.collect_all(OffsetCursor::default(), None).await;
+            let titles: Vec<&str> =
+                entries.iter_entries().map(|e| e.title().as_str()).collect();
+            let missing: Vec<&&str> = FIXTURE_TITLES
+                .iter()
+                .filter(|title| !titles.contains(title))
+                .collect();
+            if missing.is_empty() {
+                return Ok(());
+            }
+            if tokio::time::Instant::now() > deadline {
+                bail!("Entries were never stored: {missing:?}");
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+    };
+    if report.check("parse and store fixture feed", stored.await) {
+        let atom = handle
+            .syndicate_all(
+                config.clone(),
+                FeedPage::default(),
+                SyndicationFormat::Atom,
+            )
+            .await;
+        report.check("render /all/feed as atom", check_atom(&atom.body));
+
+        let rss = handle
+            .syndicate_all(
+                config.clone(),
+                FeedPage::default(),
+                SyndicationFormat::Rss,
+            )
+            .await;
+        report.check("render /all/feed as rss", check_rss(&rss.body));
+
+        let html =
+            HtmlServer::new(config.serve.cache_duration(), &config.serve)?;
+        let page = FeedPage {
+            link: Some("/all".into()),
+            ..FeedPage::default()
+        };
+        let html = html
+            .get(
+                &page,
+                handle.collect_all(page.cursor.clone(), page.limit),
+                handle.clone(),
+                config.clone(),
+            )
+            .await;
+        report.check("render /all as html", check_html(&html));
+    }
+
+    cancel_token.cancel();
+    updater_task.await.ok();
+    std::fs::remove_file(&path).ok();
+
+    Ok(report)
+}
+
+/// Check that a rendered atom feed is valid and has the fixture's entries.
+fn check_atom(atom: &str) -> Result<()> {
+    match atom_syndication::Feed::from_str(atom) {
+        Ok(feed) => contains_fixture(
+            feed.entries().iter().map(|entry| entry.title().as_str()),
+        ),
+        Err(e) => bail!("Invalid atom: {e}"),
+    }
+}
+
+/// Check that a rendered rss feed is valid and has the fixture's entries.
+fn check_rss(rss: &str) -> Result<()> {
+    match rss::Channel::from_str(rss) {
+        Ok(channel) => contains_fixture(
+            channel.items().iter().filter_map(|item| item.title()),
+        ),
+        Err(e) => bail!("Invalid rss: {e}"),
+    }
+}
+
+/// Check that a rendered page is html with the fixture's entries.
+fn check_html(html: &str) -> Result<()> {
+    if !html.contains("<html") {
+        bail!("Invalid html: {html}");
+    }
+    contains_fixture([html].into_iter())
+}
+
+/// Check that every fixture entry appears in some rendered text.
+fn contains_fixture<'a>(texts: impl Iterator<Item = &'a str>) -> Result<()> {
+    let texts: Vec<&str> = texts.collect();
+    for title in FIXTURE_TITLES {
+        if !texts.iter().any(|text| text.contains(title)) {
+            bail!("Missing entry {title:?}");
+        }
+    }
+    Ok(())
+}
@@ -17,29 +17,50 @@ pub struct HtmlServer {
     pub robots_txt: Arc<String>,
     pub styles: Arc<String>,
     templater: Arc<handlebars::Handlebars<'static>>,
-    cache: HashMap<String, CacheEntry>,
-    duration: slipfeed::Duration,
+    cache: Cache,
     error_pages: ErrorPages,
 }
 
 impl HtmlServer {
-    pub fn new(duration: slipfeed::Duration) -> Result<Self> {
-        let mut handlebars = Handlebars::new();
-        handlebars.register_template_string(
-            "feed",
-            (*HtmlServer::read_file("template.html")?).clone(),
+    pub fn new(
+        durations: impl Into<CacheDurations>,
+        config: &ServeConfig,
+    ) -> Result<Self> {
+        let template = HtmlServer::read_file_or(
+            config.template_path.as_ref(),
+            "template.html",
         )?;
+        let mut handlebars = Handlebars::new();
+        if let Err(e) =
+            handlebars.register_template_string("feed", (*template).clone())
+        {
+            bail!("Invalid html template: {e}");
+        }
         Ok(Self {
             favicon: HtmlServer::read_file_bytes("favicon.ico")?,
-            styles: HtmlServer::read_file("pico.blue.min.css")?,
-            robots_txt: HtmlServer::read_file("robots.txt")?,
-            cache: HashMap::new(),
+            styles: HtmlServer::read_file_or(
+                config.styles_path.as_ref(),
+                "pico.blue.min.css",
+            )?,
+            robots_txt: HtmlServer::read_file_or(
+                config.robots_path.as_ref(),
+                "robots.txt",
+            )?,
+            cache: Cache::new(durations),
             templater: Arc::new(handlebars),
-            duration,
             error_pages: ErrorPages::new(),
         })
     }
 
+    /// Expire cached pages whenever the generation changes.
+    pub fn invalidated_by(
+        mut self,
+        generation: Arc<std::sync::atomic::AtomicU64>,
+    ) -> Self {
+        self.cache = self.cache.invalidated_by(generation);
+        self
+    }
+
     fn read_file(name: impl AsRef<str>) -> Result<Arc<String>> {
         match Content::get(name.as_ref()) {
             Some(f) => match str::from_utf8(&f.data) {
@@ -50,6 +71,22 @@ impl HtmlServer {
         }
     }
 
+    /// Read a file from disk if a path is set, otherwise the embedded file.
+    fn read_file_or(
+        path: Option<impl AsRef<str>>,
+        name: impl AsRef<str>,
+    ) -> Result<Arc<String>> {
+        let Some(path) = path else {
+            return HtmlServer::read_file(name);
+        };
+        let path = PathBuf::from(path.as_ref()).resolve().into_owned();
+        tracing::debug!("Using {} for {}.", path.display(), name.as_ref());
+        match std::fs::read_to_string(&path) {
+            Ok(s) => Ok(Arc::new(s)),
+            Err(e) => bail!("Unable to read {}: {e}.", path.display()),
+        }
+    }
+
     fn read_file_bytes(name: impl AsRef<str>) -> Result<Arc<Vec<u8>>> {
         match Content::get(name.as_ref()) {
             Some(f) => Ok(Arc::new(Vec::from(f.data.into_owned()))),
@@ -58,67 +95,76 @@ impl HtmlServer {
     }
 
     pub async fn get(
-        &mut self,
-        uri: impl AsRef<str>,
+        &self,
+        page: &FeedPage,
         entries: impl Future<Output = DatabaseEntryList>,
         _updater: Arc<UpdaterHandle>,
         config: Arc<Config>,
     ) -> String {
-        let now = slipfeed::DateTime::now();
-
-        // Check and use cache.
-        if let Some(entry) = self.cache.get(uri.as_ref()) {
-            if entry.creation.clone() + self.duration.clone() > now {
-                tracing::debug!("Using entry from cache.");
-                return entry.entry.clone();
-            }
-        }
-
-        // Create entry.
-        tracing::debug!("Creating new entry for cache.");
-        let entries = entries.await;
-        let params;
-        {
-            params = TemplateParams {
-                feed: String::from(uri.as_ref()),
-                entries: entries
-                    .iter_entries()
-                    .map(|e| {
-                        let mut sources = Vec::<String>::new();
-                        let mut min = MinEntry::from_entry(e, config.as_ref());
-                        for source in e.feeds() {
-                            sources.push((*source.name).clone());
+        let uri = page.page_link().unwrap_or_default();
+        self.cache
+            .try_get(
+                &uri,
+                async {
+                    let entries = entries.await;
+                    let links = page.links(&entries);
+                    let params = TemplateParams {
+                        feed: uri.clone(),
+                        syndication: syndication_link(&uri),
+                        older: links.older.unwrap_or_default(),
+                        newer: links.newer.unwrap_or_default(),
+                        entries: entries
+                            .iter()
+                            .map(|e| {
+                                let mut sources = Vec::<String>::new();
+                                let mut min =
+                                    MinEntry::from_entry(e, config.as_ref());
+                                if config.serve.expose_entries() {
+                                    min.link = format!("/entry/{}", e.db_id);
+                                }
+                                for source in e.feeds() {
+                                    sources.push((*source.name).clone());
+                                }
+                                sources.sort_by_key(|source| {
+                                    config.feed_rank(source)
+                                });
+                                if !sources.is_empty() {
+                                    min.sources = sources.join(", ");
+                                } else {
+                                    min.sources = "<Unknown Source>".into();
+                                }
+                                min
+                            })
+                            .collect(),
+                    };
+                    match self.templater.render("feed", &params) {
+                        Ok(page) => Ok(page),
+                        Err(e) => {
+                            tracing::error!("Unable to render page {}.", e);
+                            Err(self.error_pages.error_500.clone())
                         }
-                        if sources.len() > 0 {
-                            min.sources = sources.join(", ");
-                        } else {
-                            min.sources = "<Unknown Source>".into();
-                        }
-                        min
-                    })
-                    .collect(),
-            };
-        }
-        let page: String = match self.templater.render("feed", &params) {
-            Ok(page) => page,
-            Err(e) => {
-                tracing::error!("Unable to render page {}.", e);
-                return self.error_pages.error_500.clone();
-            }
-        };
-        let entry = CacheEntry {
-            creation: now,
-            entry: page,
-        };
-        self.cache.insert(uri.as_ref().to_string(), entry.clone());
-        entry.entry
+                    }
+                },
+                CacheBehavior::UseOrWrite,
+            )
+            .await
     }
 }
 
-#[derive(Clone, Debug)]
-struct CacheEntry {
-    creation: slipfeed::DateTime,
-    entry: String,
+/// Get the syndicated feed for a page, keeping its query.
+fn syndication_link(uri: &str) -> String {
+    let (path, query) = match uri.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (uri, None),
+    };
+    let link = match path.starts_with("/entry/") {
+        true => format!("{path}.atom"),
+        false => format!("{path}/feed.xml"),
+    };
+    match query {
+        Some(query) => format!("{link}?{query}"),
+        None => link,
+    }
 }
 
 struct ErrorPages {
@@ -136,6 +182,11 @@ impl ErrorPages {
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 struct TemplateParams {
     feed: String,
+    syndication: String,
+    /// Link to older entries, if there may be any.
+    older: String,
+    /// Link to newer entries, if this isn't the latest page.
+    newer: String,
     entries: Vec<MinEntry>,
 }
 
@@ -152,6 +203,8 @@ struct MinEntry {
     links: Vec<slipfeed::Link>,
     icon: String,
     tags: Vec<String>,
+    /// Link to the entry's own page, if entries are exposed.
+    link: String,
 }
 
 impl MinEntry {
@@ -162,20 +215,42 @@ impl MinEntry {
         );
         let mut content = String::new();
         pulldown_cmark::html::push_html(&mut content, md_parser);
+        if config.serve.sanitize() {
+            content = ammonia::clean(&content);
+        }
+        let link = |link: &slipfeed::Link| slipfeed::Link {
+            url: safe_url(&link.url),
+            ..link.clone()
+        };
         Self {
             title: value.title().clone(),
-            date: config.timezone.format(value.date()),
+            date: config.format_date(value.date()),
             author: value.author().clone(),
             sources: String::default(),
-            source: value.source().clone(),
+            source: link(value.source()),
             content,
-            comments: value.comments().clone(),
-            links: value.other_links().clone(),
+            comments: link(value.comments()),
+            links: value.other_links().iter().map(link).collect(),
             icon: match value.icon() {
-                Some(icon) => icon.url.clone(),
+                Some(icon) => safe_url(&icon.url),
                 None => String::default(),
             },
             tags: value.tags().iter().map(|t| t.to_string()).collect(),
+            link: String::default(),
+        }
+    }
+}
+
+/// Blank urls that aren't http(s) or relative, such as `javascript:` links,
+/// since escaping doesn't stop them from running in `href` and `src`.
+fn safe_url(url: &str) -> String {
+    match ammonia::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {
+            url.to_string()
+        }
+        Err(ammonia::url::ParseError::RelativeUrlWithoutBase) => {
+            url.to_string()
         }
+        _ => String::default(),
     }
 }
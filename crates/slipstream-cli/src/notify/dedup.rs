@@ -0,0 +1,54 @@
+//! Notification deduplication.
+
+use super::*;
+
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// Tracks which entries have notified recently.
+/// Entries are keyed by their identity, so an entry that is re-processed
+/// (e.g. after being modified) does not notify again within the window.
+pub struct DedupWindow {
+    window: slipfeed::Duration,
+    /// When each entry last notified.
+    seen: HashMap<u64, slipfeed::DateTime>,
+}
+
+impl DedupWindow {
+    /// Create a new dedup window.
+    pub fn new(window: std::time::Duration) -> Self {
+        Self {
+            window: slipfeed::Duration::from_std(window),
+            seen: HashMap::new(),
+        }
+    }
+
+    /// Check if an entry should notify at a time, recording it if so.
+    pub fn check(
+        &mut self,
+        entry: &slipfeed::Entry,
+        now: &slipfeed::DateTime,
+    ) -> bool {
+        let mut hasher = DefaultHasher::new();
+        entry.hash(&mut hasher);
+        let key = hasher.finish();
+        if let Some(seen) = self.seen.get(&key) {
+            if seen.clone() + self.window.clone() > *now {
+                tracing::trace!(
+                    "Skipping duplicate notification: {}",
+                    entry.title()
+                );
+                return false;
+            }
+        }
+        self.seen.insert(key, now.clone());
+        true
+    }
+
+    /// Forget entries outside of the window.
+    pub fn prune(&mut self, now: &slipfeed::DateTime) {
+        let window = self.window.clone();
+        self.seen
+            .retain(|_, seen| seen.clone() + window.clone() > *now);
+    }
+}
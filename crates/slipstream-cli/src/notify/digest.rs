@@ -0,0 +1,170 @@
+//! Digest webhooks.
+
+use super::*;
+
+use handlebars::Handlebars;
+
+/// Default digest body.
+const DEFAULT_TEMPLATE: &str =
+    "{{count}} new entries in the last {{interval}}, top {{top_count}}:
+{{#each top}}
+- {{title}}{{#if url}} ({{url}}){{/if}}
+{{/each}}";
+
+/// Digest configuration.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DigestConfig {
+    /// The webhook url to post digests to.
+    pub url: String,
+    /// How often to send a digest.
+    #[serde(with = "humantime_serde")]
+    pub interval: std::time::Duration,
+    /// Handlebars template for the posted body.
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Number of entries listed in the digest (default 3).
+    #[serde(default)]
+    pub top: Option<usize>,
+    /// Content type of the posted body (default text/plain).
+    #[serde(default, alias = "content-type")]
+    pub content_type: Option<String>,
+}
+
+/// Template parameters for a digest.
+#[derive(Serialize)]
+struct DigestParams {
+    count: usize,
+    interval: String,
+    top_count: usize,
+    top: Vec<DigestEntry>,
+}
+
+/// Template parameters for an entry in a digest.
+#[derive(Serialize)]
+struct DigestEntry {
+    title: String,
+    author: String,
+    url: String,
+    date: String,
+    feed: String,
+}
+
+/// Batches new entries and posts them as a single webhook.
+pub struct Digest {
+    config: DigestConfig,
+    client: reqwest::Client,
+    templater: Handlebars<'static>,
+    /// Entries since the last digest.
+    pending: Vec<slipfeed::Entry>,
+    /// When the last digest was sent, or when digests were first flushed.
+    last_sent: Option<slipfeed::DateTime>,
+}
+
+impl Digest {
+    /// Create a new digest.
+    pub fn new(config: DigestConfig) -> Result<Self> {
+        let mut templater = Handlebars::new();
+        templater.register_escape_fn(handlebars::no_escape);
+        templater.register_template_string(
+            "digest",
+            config.template.as_deref().unwrap_or(DEFAULT_TEMPLATE),
+        )?;
+        Ok(Self {
+            config,
+            client: reqwest::Client::new(),
+            templater,
+            pending: Vec::new(),
+            last_sent: None,
+        })
+    }
+
+    /// Add a new entry to the next digest.
+    pub fn push(&mut self, entry: &slipfeed::Entry) {
+        self.pending.push(entry.clone());
+    }
+
+    /// Drop the entries of the next digest.
+    pub fn discard(&mut self) {
+        self.pending.clear();
+    }
+
+    /// Whether or not the interval has passed since the last digest.
+    pub fn is_due(&self, now: &slipfeed::DateTime) -> bool {
+        let interval = slipfeed::Duration::from_std(self.config.interval);
+        self.config.interval.is_zero()
+            || self
+                .last_sent
+                .as_ref()
+                .is_some_and(|last_sent| last_sent.clone() + interval <= *now)
+    }
+
+    /// Render the digest body, if there are any new entries.
+    pub fn render(&self) -> Result<Option<String>> {
+        if self.pending.is_empty() {
+            return Ok(None);
+        }
+
+        let mut entries: Vec<&slipfeed::Entry> = self.pending.iter().collect();
+        entries.sort_by(|a, b| b.date().cmp(a.date()));
+        let top: Vec<DigestEntry> = entries
+            .iter()
+            .take(self.config.top.unwrap_or(3))
+            .map(|entry| DigestEntry {
+                title: entry.title().clone(),
+                author: entry.author().clone(),
+                url: entry.source().url.clone(),
+                date: entry.date().to_iso8601(),
+                feed: (*entry.primary_feed().name).clone(),
+            })
+            .collect();
+        let params = DigestParams {
+            count: self.pending.len(),
+            interval: humantime_serde::re::humantime::format_duration(
+                self.config.interval,
+            )
+            .to_string(),
+            top_count: top.len(),
+            top,
+        };
+        Ok(Some(self.templater.render("digest", &params)?))
+    }
+
+    /// Send the digest if it is due at a time.
+    /// Empty digests are skipped, and the first interval starts at the first
+    /// flush.
+    pub async fn flush(&mut self, now: &slipfeed::DateTime) -> Result<()> {
+        if self.last_sent.is_none() {
+            self.last_sent = Some(now.clone());
+        }
+        if !self.is_due(now) {
+            return Ok(());
+        }
+        let body = match self.render()? {
+            Some(body) => body,
+            None => return Ok(()),
+        };
+
+        tracing::debug!(
+            "Sending digest of {} entries to {}",
+            self.pending.len(),
+            &self.config.url
+        );
+        let response = self
+            .client
+            .post(&self.config.url)
+            .header(
+                reqwest::header::CONTENT_TYPE,
+                self.config.content_type.as_deref().unwrap_or("text/plain"),
+            )
+            .body(body)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            bail!("Digest webhook responded with {}", response.status());
+        }
+
+        self.pending.clear();
+        self.last_sent = Some(now.clone());
+        Ok(())
+    }
+}
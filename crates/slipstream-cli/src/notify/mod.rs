@@ -0,0 +1,86 @@
+//! Notifications.
+
+use super::*;
+
+mod dedup;
+mod digest;
+
+pub use dedup::*;
+pub use digest::*;
+
+/// Notification configuration.
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct NotifyConfig {
+    /// Periodic digest of new entries.
+    #[serde(default)]
+    pub digest: Option<DigestConfig>,
+    /// Window in which an entry only notifies once (default 1h).
+    #[serde(default, with = "humantime_serde", alias = "dedup-window")]
+    pub dedup_window: Option<std::time::Duration>,
+}
+
+impl NotifyConfig {
+    /// Get the dedup window.
+    pub fn dedup_window(&self) -> std::time::Duration {
+        self.dedup_window
+            .unwrap_or(std::time::Duration::from_secs(60 * 60))
+    }
+}
+
+/// Sends notifications for new entries.
+pub struct Notifier {
+    dedup: DedupWindow,
+    digest: Option<Digest>,
+}
+
+impl Notifier {
+    /// Create a notifier from the configuration.
+    pub fn new(config: &NotifyConfig) -> Result<Self> {
+        Ok(Self {
+            dedup: DedupWindow::new(config.dedup_window()),
+            digest: match &config.digest {
+                Some(digest_config) => {
+                    Some(Digest::new(digest_config.clone())?)
+                }
+                None => None,
+            },
+        })
+    }
+
+    /// Whether or not any notifications are configured.
+    pub fn is_enabled(&self) -> bool {
+        self.digest.is_some()
+    }
+
+    /// Notify for an entry at a time, unless it already notified within the
+    /// dedup window. This returns whether or not the entry was notified.
+    pub fn notify(
+        &mut self,
+        entry: &slipfeed::Entry,
+        now: &slipfeed::DateTime,
+    ) -> bool {
+        if !self.is_enabled() || !self.dedup.check(entry, now) {
+            return false;
+        }
+        if let Some(digest) = &mut self.digest {
+            digest.push(entry);
+        }
+        true
+    }
+
+    /// Drop any pending notifications.
+    pub fn discard(&mut self) {
+        if let Some(digest) = &mut self.digest {
+            digest.discard();
+        }
+    }
+
+    /// Send any pending notifications that are due at a time.
+    pub async fn flush(&mut self, now: &slipfeed::DateTime) -> Result<()> {
+        self.dedup.prune(now);
+        if let Some(digest) = &mut self.digest {
+            digest.flush(now).await?;
+        }
+        Ok(())
+    }
+}
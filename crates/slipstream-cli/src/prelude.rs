@@ -6,6 +6,7 @@ pub use database::*;
 pub use feeds::*;
 pub use logging::*;
 pub use modes::*;
+pub use notify::*;
 
 pub(crate) mod internal {
     pub use std::cell::LazyCell;
@@ -19,6 +19,28 @@ async fn config_serialization() {
     let fd_toml = toml::to_string_pretty(&fd).unwrap();
     let _fd: ReadConfig = toml::from_str(&fd_toml).unwrap();
 
+    let fd = FeedDefinition::from_feed(RawFeed::Json {
+        url: "https://example.com/feed.json".into(),
+        feed_type: JsonFeedType::Json,
+    });
+    let fd_toml = toml::to_string_pretty(&fd).unwrap();
+    let fd: FeedDefinition = toml::from_str(&fd_toml).unwrap();
+    assert!(matches!(fd.feed(), RawFeed::Json { .. }));
+
+    let fd = FeedDefinition::from_feed(RawFeed::Reddit {
+        subreddit: "rust".into(),
+        sort: Some(slipfeed::RedditSort::Top),
+    });
+    let fd_toml = toml::to_string_pretty(&fd).unwrap();
+    let fd: FeedDefinition = toml::from_str(&fd_toml).unwrap();
+    assert!(matches!(
+        fd.feed(),
+        RawFeed::Reddit {
+            sort: Some(slipfeed::RedditSort::Top),
+            ..
+        }
+    ));
+
     let fd = FeedDefinition::from_feed(RawFeed::Aggregate {
         feeds: vec!["foo".into()],
     });
@@ -61,3 +83,5252 @@ async fn config_serialization() {
     };
     assert!(e.is_ok());
 }
+
+#[tokio::test]
+async fn digest_webhook() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let (url, bodies) = webhook_server().await;
+    let db = Database::new(":memory:").await.unwrap();
+    let mut digest = Digest::new(DigestConfig {
+        url,
+        interval: std::time::Duration::from_millis(200),
+        template: None,
+        top: None,
+        content_type: None,
+    })
+    .unwrap();
+    use slipfeed::Clock;
+    let clock = slipfeed::ManualClock::new(
+        slipfeed::DateTime::try_from("2025-06-12T00:00:00Z").unwrap(),
+    );
+    let later = || {
+        clock.advance(slipfeed::Duration::from_std(
+            std::time::Duration::from_millis(250),
+        ));
+        clock.now()
+    };
+
+    // Only newly inserted entries are added to the digest.
+    let first = slipfeed::EntryBuilder::new()
+        .title("First")
+        .source_id("first")
+        .source("https://example.com/first")
+        .build();
+    let second = slipfeed::EntryBuilder::new()
+        .title("Second")
+        .source_id("second")
+        .source("https://example.com/second")
+        .build();
+    for entry in [&first, &second, &first] {
+        if db.insert_slipfeed_entry(entry).await.is_new() {
+            digest.push(entry);
+        }
+    }
+
+    // Nothing is sent before the interval has passed.
+    digest.flush(&clock.now()).await.unwrap();
+    assert!(bodies.lock().unwrap().is_empty());
+
+    digest.flush(&later()).await.unwrap();
+    {
+        let bodies = bodies.lock().unwrap();
+        assert_eq!(bodies.len(), 1);
+        assert!(bodies[0].starts_with("2 new entries"));
+        assert!(bodies[0].contains("First"));
+        assert!(bodies[0].contains("Second"));
+    }
+
+    // Empty digests are skipped.
+    digest.flush(&later()).await.unwrap();
+    assert_eq!(bodies.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn notification_dedup_window() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let (url, bodies) = webhook_server().await;
+    let mut notifier = Notifier::new(&NotifyConfig {
+        digest: Some(DigestConfig {
+            url,
+            interval: std::time::Duration::ZERO,
+            template: Some("{{count}}".into()),
+            top: None,
+            content_type: None,
+        }),
+        dedup_window: Some(std::time::Duration::from_millis(200)),
+    })
+    .unwrap();
+    let entry = slipfeed::EntryBuilder::new()
+        .title("Repeated")
+        .source_id("repeated")
+        .build();
+
+    use slipfeed::Clock;
+    let clock = slipfeed::ManualClock::new(
+        slipfeed::DateTime::try_from("2025-06-12T00:00:00Z").unwrap(),
+    );
+
+    // Processing the same entry within the window notifies once.
+    assert!(notifier.notify(&entry, &clock.now()));
+    assert!(!notifier.notify(&entry, &clock.now()));
+    notifier.flush(&clock.now()).await.unwrap();
+    assert_eq!(*bodies.lock().unwrap(), vec!["1".to_string()]);
+    clock.advance(slipfeed::Duration::from_std(
+        std::time::Duration::from_millis(150),
+    ));
+    assert!(!notifier.notify(&entry, &clock.now()));
+
+    // Processing it again outside of the window notifies again.
+    clock.advance(slipfeed::Duration::from_std(
+        std::time::Duration::from_millis(100),
+    ));
+    assert!(notifier.notify(&entry, &clock.now()));
+    notifier.flush(&clock.now()).await.unwrap();
+    assert_eq!(*bodies.lock().unwrap(), vec!["1".to_string(), "1".into()]);
+}
+
+#[tokio::test]
+async fn rss_syndication() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let config = Config::default();
+    let date = slipfeed::DateTime::try_from("2024-01-02T03:04:05Z").unwrap();
+    let mut entries = DatabaseEntryList::new(10);
+    entries
+        .add(DatabaseEntry::new(
+            slipfeed::EntryBuilder::new()
+                .title("First")
+                .source("https://example.com/first")
+                .content("Hello")
+                .source_id("guid-1")
+                .date(date.clone())
+                .build(),
+            7,
+        ))
+        .unwrap();
+
+    let rss = entries.syndicate_rss("All", &config);
+    let channel = rss.parse::<rss::Channel>().unwrap();
+    assert_eq!(channel.title(), "All");
+    assert_eq!(channel.items().len(), 1);
+    let item = &channel.items()[0];
+    assert_eq!(item.title(), Some("First"));
+    assert_eq!(item.link(), Some("https://example.com/first"));
+    assert!(item.description().unwrap().contains("Hello"));
+    // The guid is the database id, like the atom id.
+    assert_eq!(item.guid().unwrap().value(), "7");
+    assert_eq!(
+        slipfeed::DateTime::try_from(item.pub_date().unwrap()).unwrap(),
+        date
+    );
+
+    let atom = entries.syndicate_as("All", &config, SyndicationFormat::Atom);
+    let feed = atom.parse::<atom::Feed>().unwrap();
+    assert_eq!(feed.entries()[0].id(), "7");
+}
+
+#[tokio::test]
+async fn content_in_feed() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let content = "word ".repeat(200);
+    let mut entries = DatabaseEntryList::new(10);
+    entries
+        .add(DatabaseEntry::new(
+            slipfeed::EntryBuilder::new()
+                .title("Long")
+                .source("https://example.com/long")
+                .content(content.clone())
+                .build(),
+            1,
+        ))
+        .unwrap();
+    let syndicate = |content_in_feed: ContentInFeed| {
+        let mut config = Config::default();
+        config.serve.content_in_feed = content_in_feed;
+        let atom = entries
+            .syndicate_as("All", &config, SyndicationFormat::Atom)
+            .parse::<atom::Feed>()
+            .unwrap();
+        let rss = entries
+            .syndicate_as("All", &config, SyndicationFormat::Rss)
+            .parse::<rss::Channel>()
+            .unwrap();
+        (atom.entries()[0].clone(), rss.items()[0].clone())
+    };
+
+    let (atom_entry, rss_item) = syndicate(ContentInFeed::Full);
+    assert!(atom_entry.content().unwrap().value().unwrap().len() >= 999);
+    assert!(atom_entry.summary().unwrap().value.len() >= 999);
+    assert!(rss_item.description().unwrap().len() >= 999);
+
+    let (atom_entry, rss_item) = syndicate(ContentInFeed::Summary);
+    assert!(atom_entry.content().is_none());
+    let summary = &atom_entry.summary().unwrap().value;
+    assert!(summary.chars().count() <= 281);
+    assert!(summary.starts_with("word word"));
+    assert!(summary.ends_with("word…"));
+    assert_eq!(rss_item.description(), Some(summary.as_str()));
+
+    // Summaries of html are plain text.
+    let mut html_entries = DatabaseEntryList::new(10);
+    html_entries
+        .add(DatabaseEntry::new(
+            slipfeed::EntryBuilder::new()
+                .title("Html")
+                .source("https://example.com/html")
+                .content(format!(
+                    "<style>p {{ color: red; }}</style>\
+                    <p>Fish &amp; chips &lt;3 <a href=\"/x\">café</a></p>{}",
+                    "<p>wörd</p> ".repeat(100)
+                ))
+                .build(),
+            1,
+        ))
+        .unwrap();
+    let mut config = Config::default();
+    config.serve.content_in_feed = ContentInFeed::Summary;
+    let atom = html_entries
+        .syndicate_as("All", &config, SyndicationFormat::Atom)
+        .parse::<atom::Feed>()
+        .unwrap();
+    let summary = &atom.entries()[0].summary().unwrap().value;
+    assert!(
+        summary.starts_with("Fish & chips <3 café wörd wörd"),
+        "{summary}"
+    );
+    assert!(summary.ends_with("wörd…"));
+    assert!(!summary.contains("<p") && !summary.contains("color"));
+
+    // Only metadata and links remain.
+    let (atom_entry, rss_item) = syndicate(ContentInFeed::None);
+    assert!(atom_entry.content().is_none());
+    assert!(atom_entry.summary().is_none());
+    assert_eq!(atom_entry.links()[0].href(), "https://example.com/long");
+    assert!(rss_item.description().is_none());
+    assert_eq!(rss_item.link(), Some("https://example.com/long"));
+}
+
+#[tokio::test]
+async fn persisted_flags() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let path = std::env::temp_dir()
+        .join(format!("slipstream-flags-{}.db", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    let path = path.to_string_lossy().into_owned();
+
+    let entry = slipfeed::EntryBuilder::new()
+        .title("Flagged")
+        .source_id("flagged")
+        .build();
+    let id = {
+        let db = Database::new(&path).await.unwrap();
+        let id = match db.insert_slipfeed_entry(&entry).await {
+            EntryInsertion::New(id) => id,
+            other => panic!("Unexpected insertion: {other:?}"),
+        };
+
+        // Flags default to unset.
+        let entries = db
+            .get_entries(
+                vec![DatabaseSearch::Latest],
+                10,
+                OffsetCursor::LatestTimestamp,
+                EntryOrder::Newest,
+            )
+            .await;
+        assert!(!entries[0].read);
+        assert!(!entries[0].important);
+
+        db.toggle_read(id, true).await;
+        db.toggle_important(id, true).await;
+        db.toggle_important(id, false).await;
+        id
+    };
+
+    // Flags survive reopening the database.
+    let db = Database::new(&path).await.unwrap();
+    let entries = db
+        .get_entries(
+            vec![DatabaseSearch::Latest],
+            10,
+            OffsetCursor::LatestTimestamp,
+            EntryOrder::Newest,
+        )
+        .await;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].db_id, id);
+    assert!(entries[0].read);
+    assert!(!entries[0].important);
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[tokio::test]
+async fn mark_many_read() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let db = Database::new(":memory:").await.unwrap();
+    for i in 0..5 {
+        let entry = slipfeed::EntryBuilder::new()
+            .title(format!("Entry {i}"))
+            .source_id(format!("entry-{i}"))
+            .source(format!("https://example.com/{i}"))
+            .date(slipfeed::DateTime::from_unix_timestamp_s(1_000 * i))
+            .build();
+        assert!(db.insert_slipfeed_entry(&entry).await.is_new());
+    }
+    let latest = || {
+        db.get_entries(
+            vec![DatabaseSearch::Latest],
+            10,
+            OffsetCursor::LatestTimestamp,
+            EntryOrder::Newest,
+        )
+    };
+
+    // Mark entries above the third entry.
+    let mut entries = latest().await;
+    let ids = entries.mark_read(0..2, true);
+    assert_eq!(ids.len(), 2);
+    assert!(entries.iter().take(2).all(|entry| entry.read));
+    assert!(!entries[2].read);
+    db.toggle_read_many(&ids, true).await;
+    let read: Vec<bool> = latest().await.iter().map(|e| e.read).collect();
+    assert_eq!(read, vec![true, true, false, false, false]);
+
+    // Already read entries aren't updated again.
+    let ids = entries.mark_read(0..entries.len(), true);
+    assert_eq!(ids.len(), 3);
+    db.toggle_read_many(&ids, true).await;
+    assert!(latest().await.iter().all(|entry| entry.read));
+}
+
+#[tokio::test]
+async fn unread_only() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let db = Database::new(":memory:").await.unwrap();
+    let mut ids = Vec::new();
+    for i in 0..3 {
+        let entry = slipfeed::EntryBuilder::new()
+            .title(format!("Entry {i}"))
+            .source_id(format!("entry-{i}"))
+            .source(format!("https://example.com/{i}"))
+            .date(slipfeed::DateTime::from_unix_timestamp_s(1_000 * i))
+            .build();
+        match db.insert_slipfeed_entry(&entry).await {
+            EntryInsertion::New(id) => ids.push(id),
+            insertion => panic!("Unexpected insertion: {insertion:?}"),
+        }
+    }
+    db.toggle_read(ids[1], true).await;
+
+    let titles = |criteria: Vec<DatabaseSearch>| async {
+        db.get_entries(
+            criteria,
+            10,
+            OffsetCursor::LatestTimestamp,
+            EntryOrder::Newest,
+        )
+        .await
+        .iter()
+        .map(|entry| entry.entry.title().clone())
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        titles(vec![DatabaseSearch::Latest, DatabaseSearch::Unread]).await,
+        vec!["Entry 2", "Entry 0"]
+    );
+    assert_eq!(
+        titles(vec![
+            DatabaseSearch::Search("entry 0".into()),
+            DatabaseSearch::Unread,
+        ])
+        .await,
+        vec!["Entry 0"]
+    );
+
+    // Unread entries return once marked unread.
+    db.toggle_read(ids[1], false).await;
+    assert_eq!(
+        titles(vec![DatabaseSearch::Latest, DatabaseSearch::Unread])
+            .await
+            .len(),
+        3
+    );
+}
+
+#[tokio::test]
+async fn tag_completion() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let db = Database::new(":memory:").await.unwrap();
+    for (i, tags) in [vec!["rust", "news"], vec!["rust", "rustlang"]]
+        .into_iter()
+        .enumerate()
+    {
+        let mut entry = slipfeed::EntryBuilder::new()
+            .title(format!("Entry {i}"))
+            .source_id(format!("entry-{i}"))
+            .source(format!("https://example.com/{i}"))
+            .build();
+        for tag in tags {
+            entry.add_tag(&slipfeed::Tag::new(tag));
+        }
+        assert!(db.insert_slipfeed_entry(&entry).await.is_new());
+    }
+    let tags = db.all_tags().await;
+    assert_eq!(tags, vec!["news", "rust", "rustlang"]);
+
+    // Suggest tags matching the typed prefix.
+    assert_eq!(tag_completions("tag ru", &tags), vec!["rust", "rustlang"]);
+    assert_eq!(tag_completions("untag N", &tags), vec!["news"]);
+    assert!(tag_completions("search ru", &tags).is_empty());
+
+    // Tab completes as far as the matches agree.
+    assert_eq!(complete_tag("tag r", &tags).as_deref(), Some("tag rust"));
+    assert_eq!(complete_tag("tag rust", &tags), None);
+    assert_eq!(
+        complete_tag("toggle-tag rustl", &tags).as_deref(),
+        Some("toggle-tag rustlang")
+    );
+    assert_eq!(complete_tag("tag x", &tags), None);
+}
+
+#[tokio::test]
+async fn full_text_search() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let db = Database::new(":memory:").await.unwrap();
+    for (title, content) in [
+        ("Rust release", "It's 100% faster"),
+        ("Zig release", "A new allocator"),
+    ] {
+        let entry = slipfeed::EntryBuilder::new()
+            .title(title)
+            .content(content)
+            .source_id(title)
+            .source(format!("https://example.com/{title}"))
+            .build();
+        assert!(db.insert_slipfeed_entry(&entry).await.is_new());
+    }
+
+    let search = async |text: &str| -> Vec<String> {
+        db.get_entries(
+            vec![DatabaseSearch::Search(text.into())],
+            10,
+            OffsetCursor::LatestTimestamp,
+            EntryOrder::Newest,
+        )
+        .await
+        .iter()
+        .map(|entry| entry.entry.title().clone())
+        .collect()
+    };
+
+    // Titles and content are matched by word prefix.
+    assert_eq!(search("rust").await, vec!["Rust release"]);
+    assert_eq!(search("alloc").await, vec!["Zig release"]);
+    assert_eq!(search("release").await.len(), 2);
+    assert_eq!(search("new alloc").await, vec!["Zig release"]);
+    assert_eq!(search("rel zig").await, vec!["Zig release"]);
+    // Punctuation is matched literally.
+    assert_eq!(search("it's 100%").await, vec!["Rust release"]);
+    assert_eq!(search("\"zig").await, vec!["Zig release"]);
+    assert_eq!(search("").await.len(), 2);
+}
+
+#[tokio::test]
+async fn cache_single_flight() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let cache = Arc::new(Cache::new(slipfeed::Duration::from_std(
+        std::time::Duration::from_millis(500),
+    )));
+    let rebuilds = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let rebuild = |rebuilds: Arc<std::sync::atomic::AtomicUsize>| async move {
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        rebuilds.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        "page".to_string()
+    };
+
+    cache
+        .get("/all", rebuild(rebuilds.clone()), CacheBehavior::UseOrWrite)
+        .await;
+    tokio::time::sleep(std::time::Duration::from_millis(600)).await;
+
+    // Concurrent requests for the expired key share a single rebuild.
+    let mut requests = JoinSet::new();
+    for _ in 0..8 {
+        let cache = cache.clone();
+        let rebuild = rebuild(rebuilds.clone());
+        requests.spawn(async move {
+            cache.get("/all", rebuild, CacheBehavior::UseOrWrite).await
+        });
+    }
+    for page in requests.join_all().await {
+        assert_eq!(page, "page");
+    }
+    assert_eq!(rebuilds.load(std::sync::atomic::Ordering::SeqCst), 2);
+    // Finished keys aren't kept around.
+    assert_eq!(cache.in_flight(), 0);
+}
+
+#[tokio::test]
+async fn filter_then_limit() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    // The newest page of entries is all filtered out.
+    let db = Database::new(":memory:").await.unwrap();
+    for (i, title) in ["Keep", "Keep", "Skip", "Skip"].iter().enumerate() {
+        let entry = slipfeed::EntryBuilder::new()
+            .title(*title)
+            .source_id(format!("{i}"))
+            .source(format!("https://example.com/{i}"))
+            .date(slipfeed::DateTime::from_unix_timestamp_s(i as u64))
+            .build();
+        assert!(db.insert_slipfeed_entry(&entry).await.is_new());
+    }
+
+    let collect = async |order: CollectionOrder| {
+        Updater::collect_entries(
+            &db,
+            EntryCollection {
+                criteria: vec![DatabaseSearch::Latest],
+                cursor: OffsetCursor::LatestTimestamp,
+                window: 2,
+                max: 2,
+                order,
+                dedup: false,
+            },
+            |entry| entry.title() != "Skip",
+        )
+        .await
+    };
+
+    assert_eq!(collect(CollectionOrder::CollectThenFilter).await.len(), 0);
+    let entries = collect(CollectionOrder::FilterThenLimit).await;
+    assert_eq!(entries.len(), 2);
+    assert!(entries.iter().all(|entry| entry.title() == "Keep"));
+}
+
+#[tokio::test]
+async fn regex_filters() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let config: Config = toml::from_str(
+        r#"
+        [feeds.alpha]
+        url = "https://example.com/alpha"
+        regex = [
+            { regex = "(?i)\\b(rust|zig)\\b" },
+            { regex = "^bot-", field = "author", exclude = true },
+        ]
+        "#,
+    )
+    .unwrap();
+    let filters = config.feed("alpha").unwrap().filters().get_filters();
+    let feed = slipfeed::StandardSyndication::new("https://example.com/alpha");
+    let passes = |title: &str, author: &str| {
+        let entry = slipfeed::EntryBuilder::new()
+            .title(title)
+            .author(author)
+            .build();
+        filters.iter().all(|filter| filter(feed.as_ref(), &entry))
+    };
+
+    // Included titles must match on word boundaries.
+    assert!(passes("Rust 2.0 released", "ferris"));
+    assert!(passes("Notes on zig", ""));
+    assert!(!passes("Trusty tools", "ferris"));
+    // Excluded authors are dropped even when the title matches.
+    assert!(!passes("Rust 2.0 released", "bot-news"));
+
+    // Invalid patterns are config errors.
+    let error = toml::from_str::<Config>(
+        r#"
+        [feeds.alpha]
+        url = "https://example.com/alpha"
+        regex = [{ regex = "(unclosed" }]
+        "#,
+    )
+    .unwrap_err();
+    assert!(error.to_string().contains("regex"), "{error}");
+}
+
+#[tokio::test]
+async fn language_filters() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let english = slipfeed::EntryBuilder::new()
+        .title("The release of the new compiler is out")
+        .build();
+    let spanish = slipfeed::EntryBuilder::new()
+        .title("La nueva versión del compilador ya está disponible para todos")
+        .build();
+    let japanese = slipfeed::EntryBuilder::new()
+        .title("新しいコンパイラがリリースされました")
+        .build();
+    let unknown = slipfeed::EntryBuilder::new().title("Rust 2.0").build();
+    assert_eq!(english.language(), Some("en"));
+    assert_eq!(spanish.language(), Some("es"));
+    assert_eq!(japanese.language(), Some("ja"));
+    assert_eq!(unknown.language(), None);
+
+    let kept = |filters: &str| {
+        let filters: Filters = toml::from_str(filters).unwrap();
+        let filters = filters.get_filters();
+        let feed = slipfeed::StandardSyndication::new("https://example.com");
+        [&english, &spanish, &japanese, &unknown]
+            .into_iter()
+            .filter(|entry| {
+                filters.iter().all(|filter| filter(feed.as_ref(), entry))
+            })
+            .filter_map(|entry| entry.language())
+            .collect::<Vec<&str>>()
+    };
+
+    // No filtering by default.
+    assert_eq!(kept(""), vec!["en", "es", "ja"]);
+    // Tags like `en-US` match their language.
+    assert_eq!(kept(r#"lang = ["en-US"]"#), vec!["en"]);
+    assert_eq!(kept(r#"lang = ["es", "ja"]"#), vec!["es", "ja"]);
+    assert_eq!(kept(r#"exclude-lang = ["ja"]"#), vec!["en", "es"]);
+
+    // Entries of unknown language are kept.
+    let filters: Filters = toml::from_str(r#"lang = ["en"]"#).unwrap();
+    let feed = slipfeed::StandardSyndication::new("https://example.com");
+    assert!(
+        filters
+            .get_filters()
+            .iter()
+            .all(|filter| filter(feed.as_ref(), &unknown))
+    );
+
+    // Languages are exposed in atom exports.
+    let config = Config::default();
+    let atom = spanish.to_atom(&config);
+    assert_eq!(atom.content().unwrap().lang.as_deref(), Some("es"));
+}
+
+#[tokio::test]
+async fn filter_combinators() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let passes = |filters: &str, title: &str, author: &str| {
+        let filters: Filters = toml::from_str(filters).unwrap();
+        let filters = filters.get_filters();
+        let feed = slipfeed::StandardSyndication::new("https://example.com");
+        let entry = slipfeed::EntryBuilder::new()
+            .title(title)
+            .author(author)
+            .build();
+        filters.iter().all(|filter| filter(feed.as_ref(), &entry))
+    };
+
+    // Flat filters are all required, as before.
+    let flat = r#"
+        must-include-substrings = ["rust"]
+        exclude-title-words = ["llm"]
+    "#;
+    assert!(passes(flat, "Rust news", ""));
+    assert!(!passes(flat, "Rust llm news", ""));
+
+    let any = r#"
+        any = [
+            { regex = [{ regex = "(?i)rust" }] },
+            { regex = [{ regex = "^ferris$", field = "author" }] },
+        ]
+    "#;
+    assert!(passes(any, "Rust news", ""));
+    assert!(passes(any, "Crab news", "ferris"));
+    assert!(!passes(any, "Crab news", "someone"));
+
+    let all = r#"
+        all = [
+            { must-include-substrings = ["rust"] },
+            { must-include-substrings = ["release"] },
+        ]
+    "#;
+    assert!(passes(all, "Rust release", ""));
+    assert!(!passes(all, "Rust news", ""));
+
+    // Entries passing any of the groups are dropped.
+    let not = r#"
+        not = [
+            { must-include-substrings = ["rust"] },
+            { must-include-substrings = ["llm"] },
+        ]
+    "#;
+    assert!(passes(not, "Crab news", ""));
+    assert!(!passes(not, "Rust news", ""));
+    assert!(!passes(not, "Crab llm news", ""));
+    assert!(!passes(not, "Rust llm news", ""));
+    assert!(passes("not = [{}]", "Crab news", ""));
+
+    // Keep rust titles or ferris posts, but not if they mention llms.
+    let nested = r#"
+        any = [
+            { must-include-substrings = ["rust"] },
+            { all = [
+                { regex = [{ regex = "^ferris$", field = "author" }] },
+                { not = [{ must-include-substrings = ["llm"] }] },
+            ] },
+        ]
+        not = [{ must-include-substrings = ["llm"] }]
+    "#;
+    assert!(passes(nested, "Rust news", ""));
+    assert!(passes(nested, "Crab news", "ferris"));
+    assert!(!passes(nested, "Crab llm news", "ferris"));
+    assert!(!passes(nested, "Rust llm news", ""));
+    assert!(!passes(nested, "Crab news", "someone"));
+
+    // Combinators also apply to feeds.
+    let config: Config = toml::from_str(
+        r#"
+        [feeds.alpha]
+        url = "https://example.com/alpha"
+        not = [{ exclude-title-words = ["rust"] }]
+        "#,
+    )
+    .unwrap();
+    let filters = config.feed("alpha").unwrap().filters();
+    assert_eq!(filters.not.as_ref().map(|not| not.len()), Some(1));
+}
+
+#[tokio::test]
+async fn aggregate_dedup() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let config: Config = toml::from_str(
+        r#"
+        [feeds.alpha]
+        url = "https://example.com/alpha"
+
+        [feeds.merged]
+        feeds = ["alpha"]
+
+        [feeds.duplicated]
+        feeds = ["alpha"]
+        dedup = false
+        "#,
+    )
+    .unwrap();
+    assert!(config.merges_duplicates(config.feed("merged").unwrap()));
+    assert!(!config.merges_duplicates(config.feed("duplicated").unwrap()));
+    assert!(!config.merges_duplicates(config.feed("alpha").unwrap()));
+
+    // The same post arrives from three feeds of the aggregate.
+    let db = Database::new(":memory:").await.unwrap();
+    for (feed, title) in [
+        ("alpha", "Shared"),
+        ("beta", "Shared"),
+        ("gamma", "Shared"),
+        ("alpha", "Unique"),
+    ] {
+        let mut entry = slipfeed::EntryBuilder::new()
+            .title(title)
+            .source(format!("https://example.com/{title}"))
+            .build();
+        for name in [feed, "merged"] {
+            entry.add_feed(slipfeed::FeedRef {
+                id: slipfeed::FeedId::new(0),
+                name: Arc::new(name.into()),
+            });
+        }
+        assert!(db.insert_slipfeed_entry(&entry).await.is_new());
+    }
+
+    let collect = async |dedup: bool| {
+        Updater::collect_entries(
+            &db,
+            EntryCollection {
+                criteria: vec![DatabaseSearch::Feed("merged".into())],
+                cursor: OffsetCursor::LatestTimestamp,
+                window: 10,
+                max: 10,
+                order: CollectionOrder::CollectThenFilter,
+                dedup,
+            },
+            |_entry| true,
+        )
+        .await
+    };
+
+    assert_eq!(collect(false).await.len(), 4);
+    let entries = collect(true).await;
+    assert_eq!(entries.len(), 2);
+    let shared = entries
+        .iter()
+        .find(|entry| entry.title() == "Shared")
+        .unwrap();
+    let feeds: Vec<&str> = shared
+        .feeds()
+        .iter()
+        .map(|feed| feed.name.as_str())
+        .collect();
+    for feed in ["alpha", "beta", "gamma", "merged"] {
+        assert!(feeds.contains(&feed), "{feeds:?}");
+    }
+}
+
+#[tokio::test]
+async fn dedup_by_link() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let entry = |feed: &str, title: &str, link: &str| {
+        let mut entry = slipfeed::EntryBuilder::new()
+            .title(title)
+            .author(feed)
+            .source(link)
+            .build();
+        entry.add_feed(slipfeed::FeedRef {
+            id: slipfeed::FeedId::new(0),
+            name: Arc::new(feed.into()),
+        });
+        entry
+    };
+    let first = entry(
+        "wire",
+        "Story",
+        "https://www.example.com/story/?utm_source=wire&b=2&a=1",
+    );
+    let copy = entry(
+        "outlet",
+        "Story (syndicated)",
+        "http://example.com/story?a=1&b=2#comments",
+    );
+
+    // Without dedup, both copies are stored.
+    let db = Database::new(":memory:").await.unwrap();
+    assert!(db.insert_slipfeed_entry(&first).await.is_new());
+    assert!(db.insert_slipfeed_entry(&copy).await.is_new());
+
+    let mut db = Database::new(":memory:").await.unwrap();
+    db.set_dedup_by_link(true);
+    let EntryInsertion::New(id) = db.insert_slipfeed_entry(&first).await else {
+        panic!("Expected a new entry");
+    };
+    assert_eq!(
+        db.insert_slipfeed_entry(&copy).await,
+        EntryInsertion::Existing(id)
+    );
+    let entries = db
+        .get_entries(
+            vec![DatabaseSearch::Latest],
+            10,
+            OffsetCursor::LatestId,
+            EntryOrder::Newest,
+        )
+        .await;
+    assert_eq!(entries.len(), 1);
+    let mut feeds: Vec<&str> = entries[0]
+        .entry
+        .feeds()
+        .iter()
+        .map(|feed| feed.name.as_str())
+        .collect();
+    feeds.sort();
+    assert_eq!(feeds, vec!["outlet", "wire"]);
+
+    // Other paths are different stories.
+    let other = entry("outlet", "Other", "https://example.com/other");
+    assert!(db.insert_slipfeed_entry(&other).await.is_new());
+
+    // So are other queries on the same path.
+    let video = |id: &str| {
+        entry(
+            "videos",
+            &format!("Video {id}"),
+            &format!("https://www.youtube.com/watch?v={id}"),
+        )
+    };
+    assert!(db.insert_slipfeed_entry(&video("one")).await.is_new());
+    assert!(db.insert_slipfeed_entry(&video("two")).await.is_new());
+}
+
+#[tokio::test]
+async fn special_character_search() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let db = Database::new(":memory:").await.unwrap();
+    for (title, tag) in [
+        ("O'Brien wins", "o'brien"),
+        ("50% off sale", "50%"),
+        ("500 offers", "500"),
+    ] {
+        let mut entry = slipfeed::EntryBuilder::new()
+            .title(title)
+            .source_id(title)
+            .source(format!("https://example.com/{title}"))
+            .build();
+        entry.add_tag(&slipfeed::Tag::new(tag));
+        assert!(db.insert_slipfeed_entry(&entry).await.is_new());
+    }
+
+    let search = async |criteria: DatabaseSearch| -> Vec<String> {
+        db.get_entries(
+            vec![criteria],
+            10,
+            OffsetCursor::LatestTimestamp,
+            EntryOrder::Newest,
+        )
+        .await
+        .iter()
+        .map(|entry| entry.entry.title().clone())
+        .collect()
+    };
+
+    assert_eq!(
+        search(DatabaseSearch::Search("O'Brien".into())).await,
+        vec!["O'Brien wins"]
+    );
+    assert_eq!(
+        search(DatabaseSearch::Search("50% off".into())).await,
+        vec!["50% off sale"]
+    );
+    assert_eq!(search(DatabaseSearch::Search("50".into())).await.len(), 2);
+    // Wildcards are matched literally in tags.
+    assert_eq!(
+        search(DatabaseSearch::Tag("o'brien".into())).await,
+        vec!["O'Brien wins"]
+    );
+    assert_eq!(
+        search(DatabaseSearch::Tag("50%".into())).await,
+        vec!["50% off sale"]
+    );
+    assert_eq!(search(DatabaseSearch::NotTag("5_".into())).await.len(), 3);
+}
+
+#[tokio::test]
+async fn feed_priority() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let config: Config = toml::from_str(
+        r#"
+        [feeds.alpha]
+        url = "https://example.com/alpha"
+
+        [feeds.beta]
+        url = "https://example.com/beta"
+        priority = -1
+
+        [feeds.gamma]
+        url = "https://example.com/gamma"
+        order = 1
+
+        [feeds.delta]
+        url = "https://example.com/delta"
+        "#,
+    )
+    .unwrap();
+
+    // Lower priorities are first, then feeds are ordered by name.
+    assert_eq!(config.feed_names(), vec!["beta", "alpha", "delta", "gamma"]);
+    assert!(config.feed_rank("gamma") > config.feed_rank("alpha"));
+    assert!(config.feed_rank("unknown") > config.feed_rank("gamma"));
+}
+
+#[tokio::test]
+async fn feed_headers() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let config: Config = toml::from_str(
+        r#"
+        [feeds.default]
+        url = "https://example.com/default"
+
+        [feeds.header]
+        url = "https://example.com/header"
+        headers = { User-Agent = "miniflux", x-api-key = "key" }
+
+        [feeds.agent]
+        url = "https://example.com/agent"
+        user-agent = "newsboat"
+        headers = { user-agent = "miniflux" }
+
+        [feeds.reddit]
+        subreddit = "rust"
+        "#,
+    )
+    .unwrap();
+    assert!(config.validate().is_ok());
+    let headers = |feed: &str| config.feed_headers(config.feed(feed).unwrap());
+
+    // Without a user agent, the default is used.
+    assert_eq!(
+        headers("default").get("user-agent"),
+        Some(&format!("slipstream/{}", env!("CARGO_PKG_VERSION")))
+    );
+    // The user agent header is kept as-is.
+    let header = headers("header");
+    assert_eq!(header.get("User-Agent"), Some(&"miniflux".to_string()));
+    assert_eq!(header.get("x-api-key"), Some(&"key".to_string()));
+    assert_eq!(header.len(), 2);
+    // The user agent option overrides the header.
+    assert_eq!(
+        headers("agent").get("user-agent"),
+        Some(&"newsboat".to_string())
+    );
+    // Reddit feeds use slipfeed's reddit user agent.
+    assert_eq!(headers("reddit").get("user-agent"), None);
+
+    // Invalid headers are rejected.
+    let config: Config = toml::from_str(
+        r#"
+        [feeds.bad]
+        url = "https://example.com/bad"
+        headers = { "bad header" = "value" }
+        "#,
+    )
+    .unwrap();
+    let e = config.validate().unwrap_err().to_string();
+    assert!(e.contains("bad header"), "{e}");
+    let config: Config = toml::from_str(
+        r#"
+        [feeds.bad]
+        url = "https://example.com/bad"
+        user-agent = "bad\nagent"
+        "#,
+    )
+    .unwrap();
+    assert!(config.validate().is_err());
+}
+
+#[tokio::test]
+async fn youtube_list_round_trip() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let dir = std::env::temp_dir()
+        .join(format!("slipstream-youtube-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let config_path = dir.join("slipstream.toml");
+    let list_path = dir.join("feeds.txt");
+    let imported_path = dir.join("imported.toml");
+    std::fs::write(
+        &config_path,
+        r#"
+        [feeds.handle]
+        channel = "@slipstream"
+
+        [feeds.id]
+        youtube = "https://www.youtube.com/channel/UCabcdefghijklmnopqrstuv"
+        "#,
+    )
+    .unwrap();
+
+    config_cli(
+        ConfigMode::Export {
+            config_type: ConfigDestination::List,
+            out_file: list_path.clone(),
+            flat: false,
+        },
+        config_path,
+    )
+    .await
+    .unwrap();
+    let list = std::fs::read_to_string(&list_path).unwrap();
+    assert_eq!(
+        list,
+        "youtube://@slipstream\n\
+        youtube://www.youtube.com/channel/UCabcdefghijklmnopqrstuv"
+    );
+
+    std::fs::write(dir.join("empty.toml"), "").unwrap();
+    config_cli(
+        ConfigMode::Import {
+            in_type: ConfigDestination::List,
+            in_file: list_path,
+            out_file: imported_path.clone(),
+        },
+        dir.join("empty.toml"),
+    )
+    .await
+    .unwrap();
+    let imported: Config =
+        toml::from_str(&std::fs::read_to_string(&imported_path).unwrap())
+            .unwrap();
+    let channel = |name: &str| match imported.feed(name).map(|f| f.feed()) {
+        Some(RawFeed::YouTube { channel }) => channel.clone(),
+        other => panic!("Unexpected feed {other:?}"),
+    };
+    assert_eq!(channel("youtube-slipstream"), "@slipstream");
+    assert_eq!(
+        channel("youtube-UCabcdefghijklmnopqrstuv"),
+        "https://www.youtube.com/channel/UCabcdefghijklmnopqrstuv"
+    );
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[tokio::test]
+async fn mastodon_list_round_trip() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let dir = std::env::temp_dir()
+        .join(format!("slipstream-mastodon-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let config_path = dir.join("slipstream.toml");
+    let list_path = dir.join("feeds.txt");
+    let imported_path = dir.join("imported.toml");
+    std::fs::write(
+        &config_path,
+        r##"
+        [feeds.a-rust]
+        mastodon = "https://mastodon.social"
+        hashtag = "#rust"
+
+        [feeds.b-friends]
+        mastodon = "https://mastodon.social"
+        list-id = "123"
+        token = "secret"
+        "##,
+    )
+    .unwrap();
+
+    config_cli(
+        ConfigMode::Export {
+            config_type: ConfigDestination::List,
+            out_file: list_path.clone(),
+            flat: false,
+        },
+        config_path,
+    )
+    .await
+    .unwrap();
+    let list = std::fs::read_to_string(&list_path).unwrap();
+    assert_eq!(
+        list,
+        "mastodon://mastodon.social/tag/rust\n\
+        mastodon://mastodon.social/list/123"
+    );
+
+    std::fs::write(dir.join("empty.toml"), "").unwrap();
+    config_cli(
+        ConfigMode::Import {
+            in_type: ConfigDestination::List,
+            in_file: list_path,
+            out_file: imported_path.clone(),
+        },
+        dir.join("empty.toml"),
+    )
+    .await
+    .unwrap();
+    let imported: Config =
+        toml::from_str(&std::fs::read_to_string(&imported_path).unwrap())
+            .unwrap();
+    match imported.feed("mastodon.social-tag-rust").map(|f| f.feed()) {
+        Some(RawFeed::MastodonHashtag {
+            mastodon, hashtag, ..
+        }) => {
+            assert_eq!(mastodon, "mastodon.social");
+            assert_eq!(hashtag, "rust");
+        }
+        other => panic!("Unexpected feed {other:?}"),
+    }
+    match imported.feed("mastodon.social-list-123").map(|f| f.feed()) {
+        Some(RawFeed::MastodonList { mastodon, list, .. }) => {
+            assert_eq!(mastodon, "mastodon.social");
+            assert_eq!(list, "123");
+        }
+        other => panic!("Unexpected feed {other:?}"),
+    }
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[tokio::test]
+async fn newsboat_import() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let dir = std::env::temp_dir()
+        .join(format!("slipstream-newsboat-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let urls_path = dir.join("urls");
+    let imported_path = dir.join("imported.toml");
+    std::fs::write(
+        &urls_path,
+        r#"
+        # Comment.
+        https://example.com/plain.xml
+        https://example.com/tagged.xml "rust" "news lang" # Trailing.
+        https://example.com/titled.xml "~Custom Title" tech
+        https://example.com/other.xml ~"Other \"Quoted\" Title" "!"
+        "query:Unread:unread = \"yes\""
+        "#,
+    )
+    .unwrap();
+    std::fs::write(dir.join("empty.toml"), "").unwrap();
+
+    config_cli(
+        ConfigMode::Import {
+            in_type: ConfigDestination::Newsboat,
+            in_file: urls_path,
+            out_file: imported_path.clone(),
+        },
+        dir.join("empty.toml"),
+    )
+    .await
+    .unwrap();
+    let imported: Config =
+        toml::from_str(&std::fs::read_to_string(&imported_path).unwrap())
+            .unwrap();
+    assert_eq!(imported.feeds.as_ref().unwrap().len(), 4);
+    let feed = |name: &str| {
+        let feed_def = imported.feed(name).unwrap();
+        match feed_def.feed() {
+            RawFeed::Raw { url } => (url.clone(), feed_def.tags().clone()),
+            other => panic!("Unexpected feed {other:?}"),
+        }
+    };
+    assert_eq!(
+        feed("example.com/plain.xml"),
+        ("https://example.com/plain.xml".into(), None)
+    );
+    assert_eq!(
+        feed("example.com/tagged.xml"),
+        (
+            "https://example.com/tagged.xml".into(),
+            Some(vec!["rust".into(), "news lang".into()])
+        )
+    );
+    assert_eq!(
+        feed("Custom Title"),
+        (
+            "https://example.com/titled.xml".into(),
+            Some(vec!["tech".into()])
+        )
+    );
+    assert_eq!(
+        feed("Other \"Quoted\" Title"),
+        ("https://example.com/other.xml".into(), None)
+    );
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[tokio::test]
+async fn opml_import_categories() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let dir = std::env::temp_dir()
+        .join(format!("slipstream-opml-import-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let opml_path = dir.join("feeds.opml");
+    let imported_path = dir.join("imported.toml");
+    std::fs::write(
+        &opml_path,
+        r#"<?xml version="1.0"?>
+        <opml version="2.0">
+          <head><title>Feeds</title></head>
+          <body>
+            <outline text="top" type="rss" xmlUrl="https://example.com/top" />
+            <outline text="Tech">
+              <outline text="tech" type="rss" xmlUrl="https://example.com/t" />
+              <outline text="Rust">
+                <outline text="rust" xmlUrl="https://example.com/rust" />
+              </outline>
+            </outline>
+          </body>
+        </opml>"#,
+    )
+    .unwrap();
+    std::fs::write(dir.join("empty.toml"), "").unwrap();
+
+    config_cli(
+        ConfigMode::Import {
+            in_type: ConfigDestination::Opml,
+            in_file: opml_path,
+            out_file: imported_path.clone(),
+        },
+        dir.join("empty.toml"),
+    )
+    .await
+    .unwrap();
+    let imported: Config =
+        toml::from_str(&std::fs::read_to_string(&imported_path).unwrap())
+            .unwrap();
+    assert_eq!(imported.feeds.as_ref().unwrap().len(), 3);
+    let tags = |name: &str| imported.feed(name).unwrap().tags().clone();
+    assert_eq!(tags("top"), None);
+    assert_eq!(tags("tech"), Some(vec!["Tech".into()]));
+    assert_eq!(tags("rust"), Some(vec!["Tech".into(), "Rust".into()]));
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[tokio::test]
+async fn opml_export_categories() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let dir = std::env::temp_dir()
+        .join(format!("slipstream-opml-export-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let config_path = dir.join("slipstream.toml");
+    let opml_path = dir.join("feeds.opml");
+    let imported_path = dir.join("imported.toml");
+    std::fs::write(
+        &config_path,
+        r#"
+        [feeds.a-untagged]
+        url = "https://example.com/a"
+
+        [feeds.b-rust]
+        url = "https://example.com/b"
+        tags = ["rust"]
+
+        [feeds.c-both]
+        url = "https://example.com/c"
+        tags = ["tech", "rust"]
+        "#,
+    )
+    .unwrap();
+
+    let outlines = async |grouped: bool| {
+        config_cli(
+            ConfigMode::Export {
+                config_type: ConfigDestination::Opml,
+                out_file: opml_path.clone(),
+                flat: !grouped,
+            },
+            config_path.clone(),
+        )
+        .await
+        .unwrap();
+        let opml =
+            opml::OPML::from_str(&std::fs::read_to_string(&opml_path).unwrap())
+                .unwrap();
+        opml.body
+            .outlines
+            .iter()
+            .map(|outline| {
+                let children: Vec<String> = outline
+                    .outlines
+                    .iter()
+                    .map(|child| child.text.clone())
+                    .collect();
+                (outline.text.clone(), children)
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        outlines(true).await,
+        vec![
+            ("a-untagged".to_string(), vec![]),
+            ("rust".to_string(), vec!["b-rust".into(), "c-both".into()]),
+            ("tech".to_string(), vec!["c-both".into()]),
+        ]
+    );
+    assert_eq!(
+        outlines(false).await,
+        vec![
+            ("a-untagged".to_string(), vec![]),
+            ("b-rust".to_string(), vec![]),
+            ("c-both".to_string(), vec![]),
+        ]
+    );
+
+    // Grouped feeds are imported with all of their tags.
+    outlines(true).await;
+    std::fs::write(dir.join("empty.toml"), "").unwrap();
+    config_cli(
+        ConfigMode::Import {
+            in_type: ConfigDestination::Opml,
+            in_file: opml_path,
+            out_file: imported_path.clone(),
+        },
+        dir.join("empty.toml"),
+    )
+    .await
+    .unwrap();
+    let imported: Config =
+        toml::from_str(&std::fs::read_to_string(&imported_path).unwrap())
+            .unwrap();
+    let tags = |name: &str| imported.feed(name).unwrap().tags().clone();
+    assert_eq!(tags("a-untagged"), None);
+    assert_eq!(tags("b-rust"), Some(vec!["rust".into()]));
+    assert_eq!(tags("c-both"), Some(vec!["rust".into(), "tech".into()]));
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[tokio::test]
+async fn json_config_round_trip() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let dir = std::env::temp_dir()
+        .join(format!("slipstream-json-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let json_path = dir.join("slipstream.json");
+    let imported_path = dir.join("imported.toml");
+
+    for config_path in [
+        "../../examples/config/slipreader.toml",
+        "../../examples/config/slipstream.toml",
+    ] {
+        config_cli(
+            ConfigMode::Export {
+                config_type: ConfigDestination::Json,
+                out_file: json_path.clone(),
+                flat: false,
+            },
+            config_path.into(),
+        )
+        .await
+        .unwrap();
+
+        // The json config matches the toml config.
+        let toml_config = toml::from_str::<Config>(
+            &std::fs::read_to_string(config_path).unwrap(),
+        )
+        .unwrap();
+        let json_config = serde_json::from_str::<Config>(
+            &std::fs::read_to_string(&json_path).unwrap(),
+        )
+        .unwrap();
+        // Sets are serialized in any order, so arrays are compared sorted.
+        fn sorted(value: serde_json::Value) -> serde_json::Value {
+            match value {
+                serde_json::Value::Array(values) => {
+                    let mut values: Vec<serde_json::Value> =
+                        values.into_iter().map(sorted).collect();
+                    values.sort_by_key(|value| value.to_string());
+                    serde_json::Value::Array(values)
+                }
+                serde_json::Value::Object(map) => serde_json::Value::Object(
+                    map.into_iter().map(|(k, v)| (k, sorted(v))).collect(),
+                ),
+                value => value,
+            }
+        }
+        assert_eq!(
+            sorted(serde_json::to_value(&json_config).unwrap()),
+            sorted(serde_json::to_value(&toml_config).unwrap())
+        );
+
+        // Feeds are merged on import.
+        std::fs::write(
+            dir.join("base.toml"),
+            "[feeds.existing]\nurl = \"https://example.com/existing\"\n",
+        )
+        .unwrap();
+        config_cli(
+            ConfigMode::Import {
+                in_type: ConfigDestination::Json,
+                in_file: json_path.clone(),
+                out_file: imported_path.clone(),
+            },
+            dir.join("base.toml"),
+        )
+        .await
+        .unwrap();
+        let imported: Config =
+            toml::from_str(&std::fs::read_to_string(&imported_path).unwrap())
+                .unwrap();
+        assert!(imported.feed("existing").is_some());
+        for (name, feed_def) in toml_config.feeds.iter().flatten() {
+            assert_eq!(
+                serde_json::to_value(imported.feed(name)).unwrap(),
+                serde_json::to_value(Some(feed_def)).unwrap()
+            );
+        }
+    }
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[tokio::test]
+async fn verify_check_feeds() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    tracing_subscriber::fmt::try_init().ok();
+
+    // Serve a feed, an html page, and a mastodon instance.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut buf = [0u8; 1024];
+            let n = stream.read(&mut buf).await.unwrap_or(0);
+            let request = String::from_utf8_lossy(&buf[..n]).into_owned();
+            let path = request.split(' ').nth(1).unwrap_or_default();
+            let response = match path {
+                "/feed" => "200 OK\r\nContent-Type: application/rss+xml",
+                "/page" => "200 OK\r\nContent-Type: text/html",
+                "/api/v1/instance" => {
+                    "200 OK\r\nContent-Type: application/json"
+                }
+                _ => "404 Not Found",
+            };
+            stream
+                .write_all(
+                    format!("HTTP/1.1 {response}\r\nContent-Length: 0\r\n\r\n")
+                        .as_bytes(),
+                )
+                .await
+                .ok();
+            stream.shutdown().await.ok();
+        }
+    });
+    let closed = {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        format!("http://{}", listener.local_addr().unwrap())
+    };
+
+    let dir = std::env::temp_dir()
+        .join(format!("slipstream-check-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let verify = async |feeds: &str| {
+        let config_path = dir.join("slipstream.toml");
+        std::fs::write(&config_path, feeds).unwrap();
+        config_cli(ConfigMode::Verify { check_feeds: true }, config_path).await
+    };
+
+    let reachable = format!(
+        r#"
+        [feeds.feed]
+        url = "{base}/feed"
+
+        [feeds.mastodon]
+        mastodon = "{base}"
+        type = "PublicTimeline"
+        "#
+    );
+    assert!(verify(&reachable).await.is_ok());
+    for unreachable in [
+        format!("[feeds.page]\nurl = \"{base}/page\""),
+        format!("[feeds.missing]\nurl = \"{base}/missing\""),
+        format!("[feeds.closed]\nurl = \"{closed}/feed\""),
+    ] {
+        assert!(
+            verify(&format!("{reachable}\n{unreachable}"))
+                .await
+                .is_err()
+        );
+    }
+
+    // Feeds are only checked when asked.
+    let config_path = dir.join("slipstream.toml");
+    config_cli(ConfigMode::Verify { check_feeds: false }, config_path)
+        .await
+        .unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[tokio::test]
+async fn retag_feed_entries() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let db = Database::new(":memory:").await.unwrap();
+    for feed in ["alpha", "beta"] {
+        let mut entry = slipfeed::EntryBuilder::new()
+            .title(feed)
+            .source_id(feed)
+            .source(format!("https://example.com/{feed}"))
+            .build();
+        entry.add_feed(slipfeed::FeedRef {
+            id: slipfeed::FeedId::new(0),
+            name: Arc::new(feed.into()),
+        });
+        entry.add_tag(&slipfeed::Tag::new("user"));
+        assert!(db.insert_slipfeed_entry(&entry).await.is_new());
+    }
+
+    // A tag is added to alpha in the config.
+    let config: Config = toml::from_str(
+        r#"
+        [feeds.alpha]
+        url = "https://example.com/alpha"
+        tags = ["old", "new"]
+
+        [feeds.beta]
+        url = "https://example.com/beta"
+        tags = ["old"]
+        "#,
+    )
+    .unwrap();
+    let tags = config.feed_tags("alpha").unwrap();
+    assert_eq!(db.add_source_tags("alpha", &tags).await, 2);
+    assert_eq!(db.add_source_tags("alpha", &tags).await, 0);
+    assert!(config.feed_tags("unknown").is_none());
+
+    let entries = db
+        .get_entries(
+            vec![DatabaseSearch::Latest],
+            10,
+            OffsetCursor::LatestTimestamp,
+            EntryOrder::Newest,
+        )
+        .await;
+    for entry in entries.iter() {
+        // User tags are kept.
+        assert!(entry.has_tag("user"));
+        assert_eq!(entry.has_tag("new"), entry.title() == "alpha");
+    }
+}
+
+#[tokio::test]
+async fn important_rules() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let config: Config = toml::from_str(
+        r#"
+        [global.important]
+        authors = ["Ferris"]
+
+        [feeds.alpha]
+        url = "https://example.com/alpha"
+        important = { substrings = ["security"], title-words = ["rust"] }
+
+        [feeds.beta]
+        url = "https://example.com/beta"
+        "#,
+    )
+    .unwrap();
+
+    let db = Database::new(":memory:").await.unwrap();
+    for (feed, title, author) in [
+        ("alpha", "Security advisory", ""),
+        ("alpha", "Trust issues", ""),
+        ("alpha", "Rust release", ""),
+        ("beta", "Security advisory", ""),
+        ("beta", "Crab news", "ferris"),
+    ] {
+        let mut entry = slipfeed::EntryBuilder::new()
+            .title(title)
+            .author(author)
+            .source_id(format!("{feed}-{title}"))
+            .source(format!("https://example.com/{feed}/{title}"))
+            .build();
+        entry.add_feed(slipfeed::FeedRef {
+            id: slipfeed::FeedId::new(0),
+            name: Arc::new(feed.into()),
+        });
+        assert!(store_entry(&db, &config, &entry).await.is_new());
+    }
+
+    let entries = db
+        .get_entries(
+            vec![DatabaseSearch::Latest],
+            10,
+            OffsetCursor::LatestTimestamp,
+            EntryOrder::Newest,
+        )
+        .await;
+    let mut important: Vec<String> = entries
+        .iter()
+        .filter(|entry| entry.important)
+        .map(|entry| {
+            format!(
+                "{}: {}",
+                entry.feeds().first().unwrap().name,
+                entry.title()
+            )
+        })
+        .collect();
+    important.sort();
+    // Feed rules only apply to their own feed, and words match whole words.
+    assert_eq!(
+        important,
+        vec![
+            "alpha: Rust release",
+            "alpha: Security advisory",
+            "beta: Crab news",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn pretty_syndication() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let mut entries = DatabaseEntryList::new(10);
+    entries
+        .add(DatabaseEntry::new(
+            slipfeed::EntryBuilder::new()
+                .title("First")
+                .source("https://example.com/first")
+                .build(),
+            1,
+        ))
+        .unwrap();
+
+    let mut config = Config::default();
+    for format in [SyndicationFormat::Atom, SyndicationFormat::Rss] {
+        config.serve.pretty_output = false;
+        let compact = entries.syndicate_as("All", &config, format);
+        assert!(!compact.contains("\n  <"));
+
+        config.serve.pretty_output = true;
+        let pretty = entries.syndicate_as("All", &config, format);
+        assert!(pretty.contains("\n  <"));
+        assert!(pretty.contains("First"));
+    }
+}
+
+/// Slipstream served on a local port, for tests.
+struct TestServer {
+    /// Address of the server, e.g. `http://127.0.0.1:1234`.
+    url: String,
+    config: Arc<Config>,
+    /// Handle to the served updater.
+    handle: UpdaterHandle,
+    client: reqwest::Client,
+    cancel_token: CancellationToken,
+    tasks: JoinSet<Result<()>>,
+}
+
+impl TestServer {
+    /// Serve a config, updating its feeds.
+    async fn start(config: Config) -> Self {
+        let (mut server, updater) = TestServer::without_updates(config).await;
+        server.tasks.spawn(update(
+            updater,
+            server.config.clone(),
+            server.cancel_token.clone(),
+        ));
+        server
+    }
+
+    /// Serve a config, returning its updater without running it.
+    async fn without_updates(config: Config) -> (Self, Updater) {
+        let listener =
+            tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let config = Arc::new(config);
+        let cancel_token = CancellationToken::new();
+        let mut updater = config.updater().await.unwrap();
+        let handle = updater.handle().unwrap();
+        let mut tasks = JoinSet::new();
+        tasks.spawn(serve_listener(
+            listener,
+            config.clone(),
+            handle.clone(),
+            cancel_token.clone(),
+        ));
+        let server = Self {
+            url,
+            config,
+            handle,
+            client: reqwest::Client::new(),
+            cancel_token,
+            tasks,
+        };
+        (server, updater)
+    }
+
+    /// Start a request for a path.
+    fn request(&self, path: &str) -> reqwest::RequestBuilder {
+        self.client.get(format!("{}{path}", self.url))
+    }
+
+    /// Get a path.
+    async fn get(&self, path: &str) -> reqwest::Response {
+        self.request(path).send().await.unwrap()
+    }
+
+    /// Get the body of a path.
+    async fn text(&self, path: &str) -> String {
+        self.get(path).await.text().await.unwrap()
+    }
+
+    /// Stop serving and updating.
+    async fn stop(mut self) {
+        self.cancel_token.cancel();
+        while self.tasks.join_next().await.is_some() {}
+    }
+}
+
+#[tokio::test]
+async fn serve_cache_control() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let config: Config = toml::from_str(
+        r#"
+        [serve]
+        cache = "5m"
+        "#,
+    )
+    .unwrap();
+    let server = TestServer::start(config).await;
+    let header = |response: &reqwest::Response, name| {
+        response
+            .headers()
+            .get(name)
+            .map(|v| v.to_str().unwrap().to_string())
+    };
+
+    for path in ["/", "/all/feed", "/all/feed?format=rss", "/tag/rust"] {
+        let response = server.get(path).await;
+        assert_eq!(
+            header(&response, reqwest::header::CACHE_CONTROL).as_deref(),
+            Some("public, max-age=300"),
+            "{path}"
+        );
+        assert_eq!(
+            header(&response, reqwest::header::VARY).as_deref(),
+            Some("Accept, Accept-Encoding"),
+            "{path}"
+        );
+    }
+    let response = server.get("/config").await;
+    assert_eq!(
+        header(&response, reqwest::header::CACHE_CONTROL).as_deref(),
+        Some("no-store")
+    );
+
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn serve_bind_address() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let config = Config::default();
+    assert_eq!(
+        config.serve.bind_address().unwrap(),
+        std::net::IpAddr::from([0, 0, 0, 0])
+    );
+
+    for (bind, expected) in [
+        ("127.0.0.1", Some(std::net::IpAddr::from([127, 0, 0, 1]))),
+        (
+            "::1",
+            Some(std::net::IpAddr::from(std::net::Ipv6Addr::LOCALHOST)),
+        ),
+        ("localhost", None),
+        ("127.0.0.1:3000", None),
+    ] {
+        let config: Config = toml::from_str(&format!(
+            r#"
+            [serve]
+            bind = "{bind}"
+            "#
+        ))
+        .unwrap();
+        match expected {
+            Some(expected) => {
+                assert_eq!(config.serve.bind_address().unwrap(), expected);
+                config.validate().unwrap();
+            }
+            None => {
+                let error = config.validate().unwrap_err().to_string();
+                assert!(error.contains(bind), "{error}");
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn serve_auth_token() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let path = std::env::temp_dir()
+        .join(format!("slipstream-auth-token-{}.db", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    let path = path.to_string_lossy().into_owned();
+    {
+        let db = Database::new(&path).await.unwrap();
+        for i in 1..=2 {
+            let entry = slipfeed::EntryBuilder::new()
+                .title(format!("Entry {i}"))
+                .source_id(format!("entry-{i}"))
+                .source(format!("https://example.com/{i}"))
+                .date(
+                    slipfeed::DateTime::now()
+                        - slipfeed::Duration::from_hours(i),
+                )
+                .build();
+            assert!(db.insert_slipfeed_entry(&entry).await.is_new());
+        }
+    }
+
+    for token in [Some("s3cret"), None] {
+        let mut config: Config = match token {
+            Some(token) => toml::from_str(&format!(
+                r#"
+                [global.limits]
+                max = 1
+                [serve]
+                auth-token = "{token}"
+                "#
+            ))
+            .unwrap(),
+            None => Config::default(),
+        };
+        config.database = Some(path.clone());
+        let server = TestServer::start(config).await;
+        let get = async |path: &str, bearer: Option<&str>| {
+            let mut request = server.request(path);
+            if let Some(bearer) = bearer {
+                request = request.bearer_auth(bearer);
+            }
+            request.send().await.unwrap().status()
+        };
+
+        // Public paths never need the token.
+        assert_eq!(get("/robots.txt", None).await, reqwest::StatusCode::OK);
+        assert_eq!(get("/favicon.ico", None).await, reqwest::StatusCode::OK);
+
+        let unauthorized = match token {
+            Some(_) => reqwest::StatusCode::UNAUTHORIZED,
+            None => reqwest::StatusCode::OK,
+        };
+        assert_eq!(get("/all/feed", None).await, unauthorized);
+        assert_eq!(get("/all/feed", Some("wrong")).await, unauthorized);
+        assert_eq!(get("/all/feed?token=s3cre", None).await, unauthorized);
+        if let Some(token) = token {
+            assert_eq!(
+                get("/all/feed", Some(token)).await,
+                reqwest::StatusCode::OK
+            );
+            assert_eq!(
+                get(&format!("/all/feed?format=rss&token={token}"), None).await,
+                reqwest::StatusCode::OK
+            );
+
+            // Responses behind the token are private to the client, and the
+            // token isn't kept in page links.
+            let response =
+                server.get(&format!("/all/feed?token={token}")).await;
+            let header =
+                |name| response.headers().get(name).unwrap().to_str().unwrap();
+            assert!(
+                header(reqwest::header::CACHE_CONTROL).starts_with("private")
+            );
+            assert_eq!(
+                header(reqwest::header::VARY),
+                "Accept, Accept-Encoding, Authorization"
+            );
+            let feed = response.text().await.unwrap();
+            assert!(feed.contains("/all/feed?before="), "{feed}");
+            assert!(!feed.contains(token), "{feed}");
+
+            // The token isn't shown in the served config.
+            let config = server
+                .request("/config")
+                .bearer_auth(token)
+                .send()
+                .await
+                .unwrap()
+                .text()
+                .await
+                .unwrap();
+            assert!(!config.contains(token), "{config}");
+        }
+
+        server.stop().await;
+    }
+    std::fs::remove_file(&path).ok();
+}
+
+#[tokio::test]
+async fn serve_cors() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let origins = [
+        Some(r#"["https://reader.example.com"]"#),
+        Some(r#"["*"]"#),
+        None,
+    ];
+    for origins in origins {
+        let config: Config = match origins {
+            Some(origins) => toml::from_str(&format!(
+                r#"
+                [serve]
+                cors-origins = {origins}
+                "#
+            ))
+            .unwrap(),
+            None => Config::default(),
+        };
+        let server = TestServer::start(config).await;
+        let request = async |method: reqwest::Method, path: &str| {
+            server
+                .client
+                .request(method, format!("{}{path}", server.url))
+                .header(reqwest::header::ORIGIN, "https://reader.example.com")
+                .header(reqwest::header::ACCESS_CONTROL_REQUEST_METHOD, "GET")
+                .send()
+                .await
+                .unwrap()
+        };
+        let allowed = |response: &reqwest::Response| {
+            response
+                .headers()
+                .get(reqwest::header::ACCESS_CONTROL_ALLOW_ORIGIN)
+                .map(|v| v.to_str().unwrap().to_string())
+        };
+
+        let expected = match origins {
+            Some(origins) if origins.contains('*') => Some("*"),
+            Some(_) => Some("https://reader.example.com"),
+            None => None,
+        };
+        let response = request(reqwest::Method::GET, "/all/feed").await;
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert_eq!(allowed(&response).as_deref(), expected);
+
+        // Preflight requests are answered for feeds.
+        let response =
+            request(reqwest::Method::OPTIONS, "/tag/rust/feed").await;
+        assert_eq!(allowed(&response).as_deref(), expected);
+        if expected.is_some() {
+            assert!(response.status().is_success());
+        }
+
+        // Web pages don't allow other origins.
+        let response = request(reqwest::Method::GET, "/all").await;
+        assert_eq!(allowed(&response), None);
+
+        server.stop().await;
+    }
+
+    // Invalid origins are config errors.
+    let config: Config = toml::from_str(
+        r#"
+        [serve]
+        cors-origins = ["https://bad\norigin"]
+        "#,
+    )
+    .unwrap();
+    assert!(config.validate().is_err());
+}
+
+#[tokio::test]
+async fn serve_entry() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let path = std::env::temp_dir()
+        .join(format!("slipstream-entry-{}.db", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    let path = path.to_string_lossy().into_owned();
+
+    let entry = slipfeed::EntryBuilder::new()
+        .title("Linked entry")
+        .author("Author")
+        .source("https://example.com/linked")
+        .source_id("linked")
+        .build();
+    let id = {
+        let db = Database::new(&path).await.unwrap();
+        match db.insert_slipfeed_entry(&entry).await {
+            EntryInsertion::New(id) => id,
+            other => panic!("Unexpected insertion: {other:?}"),
+        }
+    };
+
+    let mut config = Config::default();
+    config.database = Some(path.clone());
+    let server = TestServer::start(config).await;
+
+    let get = async |path: &str| server.get(path).await;
+
+    let response = get(&format!("/entry/{id}")).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let html = response.text().await.unwrap();
+    assert!(html.contains("Linked entry"));
+    assert!(html.contains(&format!("href=\"/entry/{id}\"")));
+
+    let response = get(&format!("/entry/{id}.json")).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let json = response.text().await.unwrap();
+    assert!(json.contains(&format!("\"id\":{id}")));
+    assert!(json.contains("\"title\":\"Linked entry\""));
+
+    let response = get(&format!("/entry/{id}.atom")).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let atom =
+        atom_syndication::Feed::from_str(&response.text().await.unwrap())
+            .unwrap();
+    assert_eq!(atom.entries().len(), 1);
+    assert_eq!(atom.entries()[0].title().as_str(), "Linked entry");
+
+    // Syndicated entries link back to their page.
+    let atom = atom_syndication::Feed::from_str(
+        &get("/all/feed").await.text().await.unwrap(),
+    )
+    .unwrap();
+    assert!(atom.entries()[0].links().iter().any(|link| {
+        link.rel() == "alternate" && link.href() == format!("/entry/{id}")
+    }));
+
+    for missing in [
+        format!("/entry/{}", id + 1),
+        format!("/entry/{}.json", id + 1),
+        format!("/entry/{id}.txt"),
+        "/entry/nope".to_string(),
+    ] {
+        assert_eq!(
+            get(&missing).await.status(),
+            reqwest::StatusCode::NOT_FOUND,
+            "{missing}"
+        );
+    }
+
+    server.stop().await;
+    std::fs::remove_file(&path).ok();
+}
+
+#[tokio::test]
+async fn serve_search() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let path = std::env::temp_dir()
+        .join(format!("slipstream-search-{}.db", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    let path = path.to_string_lossy().into_owned();
+    {
+        let db = Database::new(&path).await.unwrap();
+        for (i, (title, tags)) in [
+            ("Rust release", vec!["rust"]),
+            ("Zig release", vec!["zig"]),
+            ("Rust jobs", vec!["rust", "jobs"]),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let mut entry = slipfeed::EntryBuilder::new()
+                .title(title)
+                .source_id(format!("entry-{i}"))
+                .source(format!("https://example.com/{i}"))
+                .build();
+            for tag in tags {
+                entry.add_tag(&slipfeed::Tag::new(tag));
+            }
+            assert!(db.insert_slipfeed_entry(&entry).await.is_new());
+        }
+    }
+
+    let mut config: Config = toml::from_str(
+        r#"
+        [global.limits]
+        max = 2
+        "#,
+    )
+    .unwrap();
+    config.database = Some(path.clone());
+    let server = TestServer::start(config).await;
+
+    let get = async |path: &str| server.text(path).await;
+    let titles = async |path: &str| -> Vec<String> {
+        let feed = atom_syndication::Feed::from_str(&get(path).await).unwrap();
+        feed.entries()
+            .iter()
+            .map(|entry| entry.title().to_string())
+            .collect()
+    };
+
+    assert_eq!(
+        titles("/search/feed?q=release").await,
+        vec!["Zig release", "Rust release"]
+    );
+    assert_eq!(
+        titles("/search/feed?q=release&tag=rust").await,
+        vec!["Rust release"]
+    );
+    assert_eq!(
+        titles("/search/feed.xml?tag=rust&not-tag=jobs").await,
+        vec!["Rust release"]
+    );
+    assert_eq!(
+        titles("/search/feed?feed=&q=zig").await,
+        vec!["Zig release"]
+    );
+
+    // Empty parameters are ignored, and the global max still applies.
+    assert_eq!(titles("/search/feed?q=&tag=").await.len(), 2);
+
+    let rss = get("/search/feed?q=zig&format=rss").await;
+    let channel = rss::Channel::from_str(&rss).unwrap();
+    assert_eq!(channel.items().len(), 1);
+
+    let html = get("/search?q=zig").await;
+    assert!(html.contains("Zig release"));
+    assert!(!html.contains("Rust release"));
+    assert!(html.contains("href=\"/search/feed.xml?q"));
+
+    // Tags are decoded, and blank tags don't match everything.
+    assert_eq!(titles("/tag/%20rust/feed").await.len(), 2);
+    assert!(get("/tag/%20rust").await.contains("Rust jobs"));
+    for blank in ["/tag/%20", "/tag/%20/feed", "/tag/,/feed?match=substring"] {
+        let response = server.get(blank).await;
+        assert_eq!(
+            response.status(),
+            reqwest::StatusCode::NOT_FOUND,
+            "{blank}"
+        );
+    }
+
+    server.stop().await;
+    std::fs::remove_file(&path).ok();
+}
+
+#[tokio::test]
+async fn serve_max_age() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let path = std::env::temp_dir()
+        .join(format!("slipstream-max-age-{}.db", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    let path = path.to_string_lossy().into_owned();
+    let old_id = {
+        let db = Database::new(&path).await.unwrap();
+        let mut old_id = None;
+        for (i, (title, age)) in
+            [("Recent entry", 3_600), ("Old entry", 3 * 86_400)]
+                .into_iter()
+                .enumerate()
+        {
+            let entry = slipfeed::EntryBuilder::new()
+                .title(title)
+                .source_id(format!("entry-{i}"))
+                .source(format!("https://example.com/{i}"))
+                .date(
+                    slipfeed::DateTime::now()
+                        - slipfeed::Duration::from_seconds(age),
+                )
+                .build();
+            match db.insert_slipfeed_entry(&entry).await {
+                EntryInsertion::New(id) => old_id = Some(id),
+                other => panic!("Unexpected insertion: {other:?}"),
+            }
+        }
+        old_id.unwrap()
+    };
+
+    let mut config: Config = toml::from_str(
+        r#"
+        [serve]
+        max-age = "1day"
+        "#,
+    )
+    .unwrap();
+    config.database = Some(path.clone());
+    let server = TestServer::start(config).await;
+    let get = async |path: &str| server.get(path).await;
+
+    for path in ["/all/feed", "/all", "/search/feed?q=entry", "/search"] {
+        let body = get(path).await.text().await.unwrap();
+        assert!(body.contains("Recent entry"), "{path}");
+        assert!(!body.contains("Old entry"), "{path}");
+    }
+    assert_eq!(
+        get(&format!("/entry/{old_id}.json")).await.status(),
+        reqwest::StatusCode::NOT_FOUND
+    );
+
+    // Old entries are still stored for the reader.
+    let entries = server
+        .handle
+        .search(
+            vec![DatabaseSearch::Latest],
+            OffsetCursor::LatestTimestamp,
+            EntryOrder::Newest,
+        )
+        .await;
+    let titles: Vec<&str> =
+        entries.iter_entries().map(|e| e.title().as_str()).collect();
+    assert_eq!(titles, vec!["Recent entry", "Old entry"]);
+
+    server.stop().await;
+    std::fs::remove_file(&path).ok();
+}
+
+#[tokio::test]
+async fn serve_last_modified() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let path = std::env::temp_dir().join(format!(
+        "slipstream-last-modified-{}.db",
+        std::process::id()
+    ));
+    std::fs::remove_file(&path).ok();
+    let path = path.to_string_lossy().into_owned();
+    let newest =
+        slipfeed::DateTime::now() - slipfeed::Duration::from_seconds(3_600);
+    {
+        let db = Database::new(&path).await.unwrap();
+        for (i, date) in [
+            newest.clone() - slipfeed::Duration::from_seconds(3_600),
+            newest.clone(),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let entry = slipfeed::EntryBuilder::new()
+                .title(format!("Entry {i}"))
+                .source_id(format!("entry-{i}"))
+                .source(format!("https://example.com/{i}"))
+                .date(date)
+                .build();
+            assert!(db.insert_slipfeed_entry(&entry).await.is_new());
+        }
+    }
+
+    let mut config = Config::default();
+    config.database = Some(path.clone());
+    let server = TestServer::start(config).await;
+    let get = async |path: &str, since: Option<&slipfeed::DateTime>| {
+        let mut request = server.request(path);
+        if let Some(since) = since {
+            request = request.header(
+                reqwest::header::IF_MODIFIED_SINCE,
+                since.to_if_modified_since(),
+            );
+        }
+        request.send().await.unwrap()
+    };
+    let last_modified = |response: &reqwest::Response| {
+        response
+            .headers()
+            .get(reqwest::header::LAST_MODIFIED)
+            .map(|v| v.to_str().unwrap().to_string())
+    };
+
+    for path in ["/all/feed", "/all/feed?format=rss", "/search/feed?q=entry"] {
+        // The newest entry's date is the feed's modification date.
+        let response = get(path, None).await;
+        assert_eq!(response.status(), reqwest::StatusCode::OK, "{path}");
+        assert_eq!(
+            last_modified(&response),
+            Some(newest.to_if_modified_since()),
+            "{path}"
+        );
+
+        // Clients with the newest entry are told nothing changed.
+        let response = get(path, Some(&newest)).await;
+        assert_eq!(
+            response.status(),
+            reqwest::StatusCode::NOT_MODIFIED,
+            "{path}"
+        );
+        let later = newest.clone() + slipfeed::Duration::from_seconds(60);
+        let response = get(path, Some(&later)).await;
+        assert_eq!(
+            response.status(),
+            reqwest::StatusCode::NOT_MODIFIED,
+            "{path}"
+        );
+
+        // Older copies get the feed.
+        let earlier = newest.clone() - slipfeed::Duration::from_seconds(60);
+        let response = get(path, Some(&earlier)).await;
+        assert_eq!(response.status(), reqwest::StatusCode::OK, "{path}");
+        assert!(response.text().await.unwrap().contains("Entry 1"));
+    }
+
+    // Empty feeds don't claim a modification date.
+    let response = get("/tag/missing/feed", None).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(last_modified(&response), None);
+
+    server.stop().await;
+    std::fs::remove_file(&path).ok();
+}
+
+#[tokio::test]
+async fn serve_metrics() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let path = std::env::temp_dir()
+        .join(format!("slipstream-metrics-{}.atom", std::process::id()));
+    std::fs::write(
+        &path,
+        include_str!("modes/serve/self_test/fixture.atom")
+            .replace("{date}", &chrono::Utc::now().to_rfc3339()),
+    )
+    .unwrap();
+
+    for enabled in [true, false] {
+        let config: Config = toml::from_str(&format!(
+            r#"
+            [serve]
+            metrics = {enabled}
+
+            [feeds.local]
+            url = "{}"
+            "#,
+            path.display()
+        ))
+        .unwrap();
+        let server = TestServer::start(config).await;
+
+        let get = async |path: &str| server.get(path).await;
+
+        let response = get("/all/feed").await;
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        if !enabled {
+            let response = get("/metrics").await;
+            assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+        } else {
+            // Wait for the feed to be updated.
+            let mut metrics = String::new();
+            for _ in 0..100 {
+                let response = get("/metrics").await;
+                assert_eq!(response.status(), reqwest::StatusCode::OK);
+                metrics = response.text().await.unwrap();
+                // Counts are read separately, so wait for all of them.
+                if [
+                    "slipstream_entries 2\n",
+                    "slipstream_feed_entries{feed=\"local\"} 2\n",
+                    "slipstream_updates_total 1\n",
+                ]
+                .iter()
+                .all(|line| metrics.contains(line))
+                {
+                    break;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            }
+            assert!(metrics.contains("slipstream_entries 2\n"), "{metrics}");
+            assert!(metrics.contains(
+                "# TYPE slipstream_feed_last_update_seconds gauge\n"
+            ));
+            let last_update: i64 = metrics
+                .lines()
+                .find_map(|line| {
+                    line.strip_prefix(
+                        "slipstream_feed_last_update_seconds{feed=\"local\"} ",
+                    )
+                })
+                .unwrap()
+                .parse()
+                .unwrap();
+            assert!((chrono::Utc::now().timestamp() - last_update).abs() < 60);
+            assert!(metrics.contains("slipstream_updates_total 1\n"));
+            assert!(metrics.contains("slipstream_update_duration_seconds "));
+            assert!(metrics.contains(concat!(
+                "slipstream_http_requests_total",
+                "{route=\"/all/feed\",status=\"200\"} 1\n"
+            )));
+        }
+
+        server.stop().await;
+    }
+    std::fs::remove_file(&path).ok();
+}
+
+#[tokio::test]
+async fn serve_opml() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    for expose in [true, false] {
+        let config: Config = toml::from_str(&format!(
+            r#"
+            [serve]
+            expose-config = {expose}
+
+            [feeds.blog]
+            url = "https://example.com/blog.xml"
+
+            [feeds.reddit-rust]
+            subreddit = "rust"
+            "#
+        ))
+        .unwrap();
+        let (server, _updater) = TestServer::without_updates(config).await;
+        let response = server.get("/opml").await;
+
+        if expose {
+            assert_eq!(response.status(), reqwest::StatusCode::OK);
+            assert_eq!(
+                response.headers()[reqwest::header::CONTENT_TYPE],
+                "text/x-opml"
+            );
+            let opml =
+                opml::OPML::from_str(&response.text().await.unwrap()).unwrap();
+            let feeds: Vec<(String, Option<String>)> = opml
+                .body
+                .outlines
+                .iter()
+                .map(|outline| (outline.text.clone(), outline.xml_url.clone()))
+                .collect();
+            assert_eq!(
+                feeds,
+                vec![
+                    (
+                        "blog".to_string(),
+                        Some("https://example.com/blog.xml".to_string())
+                    ),
+                    (
+                        "reddit-rust".to_string(),
+                        Some("https://www.reddit.com/r/rust/hot.rss".into())
+                    ),
+                ]
+            );
+        } else {
+            assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+        }
+
+        server.stop().await;
+    }
+}
+
+#[tokio::test]
+async fn serve_concurrency_limit() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let config: Config = toml::from_str(
+        r#"
+        [serve]
+        max-concurrent-requests = 2
+        "#,
+    )
+    .unwrap();
+    // The updater never runs, so feed requests wait forever.
+    let (server, updater) = TestServer::without_updates(config).await;
+    let get = async |path: &str| server.get(path).await;
+    assert_eq!(get("/robots.txt").await.status(), reqwest::StatusCode::OK);
+
+    // Saturate the server with requests that do not finish.
+    let mut stuck = JoinSet::new();
+    for _ in 0..2 {
+        stuck.spawn(server.request("/all/feed").send());
+    }
+
+    // Requests over the limit are rejected rather than queued.
+    let mut rejected = false;
+    for _ in 0..50 {
+        if get("/robots.txt").await.status()
+            == reqwest::StatusCode::SERVICE_UNAVAILABLE
+        {
+            rejected = true;
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert!(rejected);
+
+    stuck.abort_all();
+    server.stop().await;
+    drop(updater);
+}
+
+#[tokio::test]
+async fn serve_compression() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    for enabled in [true, false] {
+        let config: Config = toml::from_str(&format!(
+            r#"
+            [serve]
+            compression = {enabled}
+            "#
+        ))
+        .unwrap();
+        let server = TestServer::start(config).await;
+        let get = async |path: &str, encoding: &str| {
+            server
+                .request(path)
+                .header(reqwest::header::ACCEPT_ENCODING, encoding)
+                .send()
+                .await
+                .unwrap()
+        };
+        let header = |response: &reqwest::Response, name| {
+            response
+                .headers()
+                .get(name)
+                .map(|v| v.to_str().unwrap().to_string())
+        };
+
+        // Feeds are compressed, keeping their content type.
+        let response = get("/all/feed", "br;q=1.0, gzip;q=0.8").await;
+        assert_eq!(
+            header(&response, reqwest::header::CONTENT_TYPE).as_deref(),
+            Some("application/atom+xml")
+        );
+        let body = response.bytes().await.unwrap();
+        if enabled {
+            let mut feed = String::new();
+            std::io::Read::read_to_string(
+                &mut flate2::read::GzDecoder::new(&body[..]),
+                &mut feed,
+            )
+            .unwrap();
+            assert!(feed.contains("<feed"));
+        } else {
+            assert!(String::from_utf8_lossy(&body).contains("<feed"));
+        }
+
+        let response = get("/styles.css", "deflate").await;
+        let encoding = header(&response, reqwest::header::CONTENT_ENCODING);
+        match enabled {
+            true => assert_eq!(encoding.as_deref(), Some("deflate")),
+            false => assert_eq!(encoding, None),
+        }
+
+        // Weights pick the preferred encoding.
+        let response = get("/styles.css", "gzip;q=0.5, deflate;q=0.9").await;
+        let encoding = header(&response, reqwest::header::CONTENT_ENCODING);
+        match enabled {
+            true => assert_eq!(encoding.as_deref(), Some("deflate")),
+            false => assert_eq!(encoding, None),
+        }
+
+        // Clients that don't accept compression get the plain response.
+        let response = get("/styles.css", "gzip;q=0, identity").await;
+        assert_eq!(header(&response, reqwest::header::CONTENT_ENCODING), None);
+
+        // Images aren't compressed again.
+        let response = get("/favicon.ico", "gzip").await;
+        assert_eq!(header(&response, reqwest::header::CONTENT_ENCODING), None);
+
+        server.stop().await;
+    }
+}
+
+#[tokio::test]
+async fn self_test_passes() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    // Configured feeds and filters don't affect the self-test.
+    let config: Config = toml::from_str(
+        r#"
+        [global.filters]
+        exclude-title-words = ["fixture"]
+
+        [serve]
+        show_source_in_title = true
+
+        [feeds.unreachable]
+        url = "http://127.0.0.1:9/feed.xml"
+        "#,
+    )
+    .unwrap();
+    let report = self_test(&config).await.unwrap();
+    assert!(report.passed(), "{report}");
+    assert_eq!(report.checks.len(), 4);
+    assert!(report.to_string().ends_with("4/4 checks passed.\n"));
+}
+
+#[tokio::test]
+async fn command_palette() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let config: ReadConfig = toml::from_str(
+        r#"
+        [[commands]]
+        name = "open-browser"
+        command = ["xdg-open", "{link.url}"]
+
+        [[commands]]
+        name = "download"
+        command = ["wget", "{link.url}"]
+        "#,
+    )
+    .unwrap();
+    let first = |query: &str| {
+        palette_matches(&config, query)
+            .first()
+            .map(|item| item.to_string())
+    };
+
+    // Partial names select the expected command.
+    assert_eq!(first("pgd").as_deref(), Some(":page-down"));
+    assert_eq!(first("pd").as_deref(), Some(":page-down"));
+    assert_eq!(first("upd").as_deref(), Some(":update"));
+    assert_eq!(first("Page B").as_deref(), Some(":page-backwards"));
+    assert_eq!(first("brow").as_deref(), Some("!open-browser"));
+    assert_eq!(first("dl").as_deref(), Some("!download"));
+    assert!(matches!(
+        palette_matches(&config, "pgd")[0].command,
+        Commandish::Literal(ReadCommandLiteral::PageDown)
+    ));
+    assert!(matches!(
+        palette_matches(&config, "brow")[0].command,
+        Commandish::CustomCommandFull(_)
+    ));
+
+    // Queries must match in order.
+    assert!(palette_matches(&config, "zzz").is_empty());
+    assert_eq!(fuzzy_score("nwod", "down"), None);
+    // Everything matches an empty query, in listed order.
+    let all = palette_matches(&config, "");
+    assert_eq!(all.len(), palette_items(&config).len());
+    assert_eq!(all[0].to_string(), ":quit");
+}
+
+/// Serve a local webhook that records posted bodies.
+#[tokio::test]
+async fn collapse_similar_titles() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let mut entries = DatabaseEntryList::new(10);
+    for (id, title) in [
+        "Rust 2.0 released today",
+        "Zig adds a new allocator",
+        "Rust 2.0 is released today!",
+    ]
+    .iter()
+    .enumerate()
+    {
+        let entry = slipfeed::EntryBuilder::new().title(*title).build();
+        entries
+            .add(DatabaseEntry::new(entry, id as EntryDbId))
+            .unwrap();
+    }
+    assert!(token_set_ratio(entries[0].title(), entries[2].title()) > 0.8);
+    assert!(token_set_ratio(entries[0].title(), entries[1].title()) < 0.2);
+
+    // Near-identical titles collapse into the first, others stay separate.
+    let mut entries = entries.collapse_similar(0.8);
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].title(), "Rust 2.0 released today");
+    assert_eq!(entries[0].similar.len(), 1);
+    assert!(entries[1].similar.is_empty());
+
+    // Groups expand to show every entry, and collapse from any of them.
+    assert_eq!(entries.toggle_group(0), 0);
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[1].title(), "Rust 2.0 is released today!");
+    assert_eq!(entries[1].group, Some(0));
+    assert_eq!(entries.toggle_group(1), 0);
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1].title(), "Zig adds a new allocator");
+}
+
+#[tokio::test]
+async fn quiet_hours() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let (url, requests) = webhook_server().await;
+    // Quiet from 13:00 to 22:00 UTC.
+    let config: Config = toml::from_str(&format!(
+        r#"
+        timezone = "utc"
+
+        [global]
+        fetch-retries = 0
+
+        [global.quiet-hours]
+        start = "22:00"
+        end = "07:00"
+        timezone = "Asia/Tokyo"
+
+        [feeds.quiet]
+        url = "{url}"
+        "#
+    ))
+    .unwrap();
+    let mut updater = config.updater().await.unwrap();
+    let clock = slipfeed::ManualClock::new(
+        slipfeed::DateTime::try_from("2025-06-12T14:00:00Z").unwrap(),
+    );
+    updater.set_clock(Arc::new(clock.clone())).await;
+    let mut notifier = Notifier::new(&config.notify).unwrap();
+
+    // Nothing is fetched during quiet hours.
+    let remaining = update_step(
+        &updater.updater,
+        updater.entry_db.as_deref(),
+        &config,
+        &mut notifier,
+        &clock,
+    )
+    .await;
+    assert_eq!(remaining, Some(std::time::Duration::from_secs(8 * 60 * 60)));
+    assert_eq!(requests.lock().unwrap().len(), 0);
+
+    // Fetching resumes once they end.
+    clock.advance(slipfeed::Duration::from_hours(8));
+    let remaining = update_step(
+        &updater.updater,
+        updater.entry_db.as_deref(),
+        &config,
+        &mut notifier,
+        &clock,
+    )
+    .await;
+    assert_eq!(remaining, None);
+    assert_eq!(requests.lock().unwrap().len(), 1);
+}
+
+/// Clock that returns each time once, then stays at the last.
+#[derive(Debug)]
+struct SteppingClock(std::sync::Mutex<Vec<slipfeed::DateTime>>);
+
+impl slipfeed::Clock for SteppingClock {
+    fn now(&self) -> slipfeed::DateTime {
+        let mut times = self.0.lock().unwrap();
+        match times.len() {
+            1 => times[0].clone(),
+            _ => times.remove(0),
+        }
+    }
+}
+
+#[tokio::test]
+async fn quiet_hours_begin_while_fetching() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let (url, requests) = webhook_server().await;
+    let dir = std::env::temp_dir()
+        .join(format!("slipstream-quiet-fetch-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let feed = dir.join("feed.atom");
+    std::fs::write(
+        &feed,
+        include_str!("modes/serve/self_test/fixture.atom")
+            .replace("{date}", &chrono::Utc::now().to_rfc3339()),
+    )
+    .unwrap();
+    let config: Config = toml::from_str(&format!(
+        r#"
+        timezone = "utc"
+        freq = "0s"
+
+        [global.quiet-hours]
+        start = "22:00"
+        end = "07:00"
+
+        [notify.digest]
+        url = "{url}"
+        interval = "0s"
+
+        [feeds.local]
+        url = "{}"
+        "#,
+        feed.display()
+    ))
+    .unwrap();
+    let updater = config.updater().await.unwrap();
+    let mut notifier = Notifier::new(&config.notify).unwrap();
+    let date = |date: &str| slipfeed::DateTime::try_from(date).unwrap();
+
+    // Quiet hours begin once the fetch is done, so notifications are held.
+    let clock = SteppingClock(std::sync::Mutex::new(vec![
+        date("2025-06-12T21:59:59Z"),
+        date("2025-06-12T22:00:01Z"),
+    ]));
+    let remaining = update_step(
+        &updater.updater,
+        updater.entry_db.as_deref(),
+        &config,
+        &mut notifier,
+        &clock,
+    )
+    .await;
+    assert_eq!(remaining, None);
+    assert!(requests.lock().unwrap().is_empty());
+
+    // Held notifications are sent once quiet hours end.
+    let clock = SteppingClock(std::sync::Mutex::new(vec![date(
+        "2025-06-13T07:00:01Z",
+    )]));
+    update_step(
+        &updater.updater,
+        updater.entry_db.as_deref(),
+        &config,
+        &mut notifier,
+        &clock,
+    )
+    .await;
+    assert_eq!(requests.lock().unwrap().len(), 1);
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+async fn webhook_server() -> (String, Arc<std::sync::Mutex<Vec<String>>>) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let bodies = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let received = bodies.clone();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            let body = loop {
+                match stream.read(&mut buf).await {
+                    Ok(0) | Err(_) => break None,
+                    Ok(n) => request.extend_from_slice(&buf[..n]),
+                }
+                let text = String::from_utf8_lossy(&request).into_owned();
+                let Some((head, body)) = text.split_once("\r\n\r\n") else {
+                    continue;
+                };
+                let length = head
+                    .lines()
+                    .filter_map(|line| line.split_once(':'))
+                    .find(|(k, _)| k.eq_ignore_ascii_case("content-length"))
+                    .and_then(|(_, v)| v.trim().parse::<usize>().ok())
+                    .unwrap_or(0);
+                if body.len() >= length {
+                    break Some(body.to_string());
+                }
+            };
+            if let Some(body) = body {
+                received.lock().unwrap().push(body);
+            }
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                .await
+                .ok();
+            stream.shutdown().await.ok();
+        }
+    });
+    (url, bodies)
+}
+
+#[tokio::test]
+async fn help_bindings() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let config: ReadConfig = toml::from_str(
+        r#"
+        [bindings]
+        u = "page-down"
+        z = "none"
+        b = "!open-browser"
+
+        [[commands]]
+        name = "open-browser"
+        command = ["xdg-open", "{link.url}"]
+        "#,
+    )
+    .unwrap();
+    let bindings: Vec<(String, String)> = config
+        .help_bindings()
+        .into_iter()
+        .map(|(name, command)| (name, command.to_string()))
+        .collect();
+    let command = |name: &str| {
+        bindings
+            .iter()
+            .filter(|(binding, _)| binding == name)
+            .map(|(_, command)| command.as_str())
+            .collect::<Vec<_>>()
+    };
+
+    // Configured bindings override the defaults.
+    assert_eq!(command("u"), vec![":PageDown"]);
+    assert_eq!(command("b"), vec!["!open-browser"]);
+    // Unbound keys aren't shown.
+    assert!(command("z").is_empty());
+    // Other defaults are still listed.
+    assert_eq!(command("q"), vec![":Quit"]);
+    assert_eq!(command("?"), vec![":Help"]);
+    // One default is overridden, one is unbound, and one binding is added.
+    assert_eq!(bindings.len(), default_bindings().len());
+}
+
+#[tokio::test]
+async fn yank_targets() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let entry = slipfeed::EntryBuilder::new()
+        .title("Headline")
+        .source("https://example.com/headline")
+        .build();
+    let config = Config::default();
+    assert_eq!(
+        YankTarget::Link.text(&entry, &config).as_deref(),
+        Some("https://example.com/headline")
+    );
+    assert_eq!(
+        YankTarget::Title.text(&entry, &config).as_deref(),
+        Some("Headline")
+    );
+    // Missing links aren't copied.
+    assert_eq!(YankTarget::Comments.text(&entry, &config), None);
+
+    // Yanking the link is bound by default.
+    let config = ReadConfig::default();
+    assert!(matches!(
+        config.get_key_command(&YANK),
+        Commandish::Literal(ReadCommandLiteral::Yank)
+    ));
+}
+
+#[tokio::test]
+async fn yank_citations() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let mut entry = slipfeed::EntryBuilder::new()
+        .title("Rust 2.0 Released")
+        .author("Ferris")
+        .date(slipfeed::DateTime::from_unix_timestamp_s(1_700_000_000))
+        .source("https://example.com/rust-2")
+        .build();
+    entry.add_feed(slipfeed::FeedRef {
+        id: slipfeed::FeedId::new(0),
+        name: Arc::new("blog".into()),
+    });
+
+    let citation = |format: &str| {
+        let config: Config = toml::from_str(&format!(
+            r#"
+            [read]
+            citation = """{format}"""
+            "#
+        ))
+        .unwrap();
+        YankTarget::Citation.text(&entry, &config).unwrap()
+    };
+
+    // Markdown links are the default.
+    assert_eq!(
+        YankTarget::Citation
+            .text(&entry, &Config::default())
+            .as_deref(),
+        Some(
+            "[Rust 2.0 Released](https://example.com/rust-2) (Ferris, 2023-11-14)"
+        )
+    );
+    assert_eq!(
+        citation("plain"),
+        "Ferris. \"Rust 2.0 Released\". 2023-11-14. https://example.com/rust-2"
+    );
+    assert_eq!(
+        citation("bibtex"),
+        "@online{rust2023,
+  title = {Rust 2.0 Released},
+  author = {Ferris},
+  year = {2023},
+  url = {https://example.com/rust-2},
+  urldate = {2023-11-14}
+}"
+    );
+    assert_eq!(
+        citation("{title} via {feed} ({year})"),
+        "Rust 2.0 Released via blog (2023)"
+    );
+
+    // Citing is bound by default.
+    assert!(matches!(
+        ReadConfig::default().get_key_command(&YANK_CITATION),
+        Commandish::Literal(ReadCommandLiteral::YankCitation)
+    ));
+}
+
+#[tokio::test]
+async fn search_matches() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let db = Database::new(":memory:").await.unwrap();
+    for (i, (title, content)) in [
+        ("Rust 2.0", ""),
+        ("Zig news", ""),
+        ("Weekly", "All about rust."),
+        ("Go news", ""),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let entry = slipfeed::EntryBuilder::new()
+            .title(title)
+            .content(content)
+            .source(format!("https://example.com/{i}"))
+            .date(slipfeed::DateTime::from_unix_timestamp_s(1_000 * i as u64))
+            .build();
+        assert!(db.insert_slipfeed_entry(&entry).await.is_new());
+    }
+    // Newest first: Go news, Weekly, Zig news, Rust 2.0.
+    let entries = db
+        .get_entries(
+            vec![DatabaseSearch::Latest],
+            10,
+            OffsetCursor::LatestTimestamp,
+            EntryOrder::Newest,
+        )
+        .await;
+
+    // Titles and content match, ignoring case, wrapping around.
+    assert_eq!(entries.find_match(0, "RUST", true), Some(1));
+    assert_eq!(entries.find_match(1, "rust", true), Some(3));
+    assert_eq!(entries.find_match(3, "rust", true), Some(1));
+    assert_eq!(entries.find_match(1, "rust", false), Some(3));
+    assert_eq!(entries.find_match(0, "news", false), Some(2));
+    assert_eq!(entries.find_match(0, "python", true), None);
+    assert_eq!(DatabaseEntryList::new(0).find_match(0, "rust", true), None);
+    // Each word matches the start of a word, in any order, like searches.
+    assert_eq!(entries.find_match(0, "ru", true), Some(1));
+    assert_eq!(entries.find_match(0, "ust", true), None);
+    assert_eq!(entries.find_match(0, "rust all", true), Some(1));
+    assert_eq!(entries.find_match(0, "new zig", true), Some(2));
+    assert_eq!(entries.find_match(0, "rust go", true), None);
+    assert_eq!(entries.find_match(0, " ", true), None);
+
+    assert_eq!(match_range("Rust 2.0", "st 2"), Some(2..6));
+    assert_eq!(match_range("Über", "über"), Some(0..5));
+    assert_eq!(match_range("Rust", ""), None);
+    assert_eq!(word_match_range("Trust rust", "rust"), Some(6..10));
+    assert_eq!(word_match_range("Trust", "rust"), None);
+}
+
+#[tokio::test]
+async fn serve_template() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let dir = std::env::temp_dir()
+        .join(format!("slipstream-template-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let template = dir.join("template.html");
+    let styles = dir.join("styles.css");
+    std::fs::write(
+        &template,
+        "<p>{{feed}}: {{#each entries}}{{title}} {{/each}}</p>",
+    )
+    .unwrap();
+    std::fs::write(&styles, "body { color: red; }").unwrap();
+
+    let config: Config = toml::from_str(&format!(
+        r#"
+        [serve]
+        template-path = "{}"
+        styles-path = "{}"
+        "#,
+        template.display(),
+        styles.display()
+    ))
+    .unwrap();
+    let server = TestServer::start(config).await;
+    let config = server.config.clone();
+    let get = async |path: &str| server.text(path).await;
+    assert_eq!(get("/all").await, "<p>/all: </p>");
+    assert_eq!(get("/styles.css").await, "body { color: red; }");
+    assert!(get("/robots.txt").await.contains("User-agent"));
+    server.stop().await;
+
+    // Invalid templates fail at startup.
+    std::fs::write(&template, "<p>{{#each entries}}</p>").unwrap();
+    let mut updater = config.updater().await.unwrap();
+    let handle = updater.handle().unwrap();
+    let result =
+        serve_cli(None, None, config, handle, CancellationToken::new()).await;
+    assert!(result.is_err());
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[tokio::test]
+async fn serve_pages() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let path = std::env::temp_dir()
+        .join(format!("slipstream-pages-{}.db", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    let path = path.to_string_lossy().into_owned();
+    {
+        let db = Database::new(&path).await.unwrap();
+        for i in 1..=5 {
+            let entry = slipfeed::EntryBuilder::new()
+                .title(format!("Entry {i}"))
+                .source_id(format!("entry-{i}"))
+                .source(format!("https://example.com/{i}"))
+                .date(
+                    slipfeed::DateTime::now()
+                        - slipfeed::Duration::from_hours(i),
+                )
+                .build();
+            assert!(db.insert_slipfeed_entry(&entry).await.is_new());
+        }
+    }
+
+    let mut config: Config = toml::from_str(
+        r#"
+        [global.limits]
+        max = 2
+        "#,
+    )
+    .unwrap();
+    config.database = Some(path.clone());
+    let server = TestServer::start(config).await;
+
+    let get = async |path: &str| server.get(path).await;
+    let get_feed = async |path: &str| {
+        let response = get(path).await;
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let feed =
+            atom::Feed::from_str(&response.text().await.unwrap()).unwrap();
+        let titles: Vec<String> = feed
+            .entries()
+            .iter()
+            .map(|entry| entry.title().to_string())
+            .collect();
+        let link = |rel: &str| {
+            feed.links()
+                .iter()
+                .find(|link| link.rel() == rel)
+                .map(|link| link.href().to_string())
+        };
+        (titles, link("next"), link("previous"))
+    };
+
+    // Page backwards through the feed.
+    let (titles, next, previous) = get_feed("/all/feed").await;
+    assert_eq!(titles, ["Entry 1", "Entry 2"]);
+    assert!(previous.is_none());
+    let next = next.unwrap();
+    assert!(next.starts_with("/all/feed?before="), "{next}");
+    let (titles, next, previous) = get_feed(&next).await;
+    assert_eq!(titles, ["Entry 3", "Entry 4"]);
+    let (titles, _, _) = get_feed(&previous.unwrap()).await;
+    assert_eq!(titles, ["Entry 1", "Entry 2"]);
+    let (titles, next, _) = get_feed(&next.unwrap()).await;
+    assert_eq!(titles, ["Entry 5"]);
+    let (titles, next, previous) = get_feed(&next.unwrap()).await;
+    assert!(titles.is_empty());
+    assert!(next.is_none() && previous.is_none());
+
+    // Other parameters are kept in page links.
+    let (titles, next, _) = get_feed("/search/feed?q=entry").await;
+    assert_eq!(titles, ["Entry 1", "Entry 2"]);
+    let next = next.unwrap();
+    assert!(next.starts_with("/search/feed?q=entry&before="), "{next}");
+    let (titles, _, _) = get_feed(&next).await;
+    assert_eq!(titles, ["Entry 3", "Entry 4"]);
+
+    // Out of range and invalid cursors.
+    for page in ["before=1970-01-01T00:00:00Z", "after=2999-01-01T00:00:00Z"] {
+        let (titles, _, _) = get_feed(&format!("/all/feed?{page}")).await;
+        assert!(titles.is_empty());
+    }
+    let response = get("/all/feed?before=yesterday").await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    // The web view links to older and newer pages.
+    let html = get("/all").await.text().await.unwrap();
+    assert!(html.contains(">older</a>"));
+    assert!(!html.contains(">newer</a>"));
+    let older = html
+        .split("href=\"")
+        .map(|link| &link[..link.find('"').unwrap()])
+        .find(|link| link.starts_with("/all?before"))
+        .unwrap()
+        .replace("&#x3D;", "=");
+    let html = get(&older).await.text().await.unwrap();
+    assert!(html.contains("Entry 3"));
+    assert!(html.contains(">newer</a>"));
+
+    server.stop().await;
+    std::fs::remove_file(&path).ok();
+}
+
+#[tokio::test]
+async fn feed_state() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let dir = std::env::temp_dir()
+        .join(format!("slipstream-feed-state-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let feed = dir.join("feed.atom");
+    std::fs::write(
+        &feed,
+        include_str!("modes/serve/self_test/fixture.atom")
+            .replace("{date}", &chrono::Utc::now().to_rfc3339()),
+    )
+    .unwrap();
+    let db_path = dir.join("slipstream.db").to_string_lossy().into_owned();
+
+    // State is stored by url, and missing state is ignored.
+    {
+        let db = Database::new(&db_path).await.unwrap();
+        assert_eq!(db.feed_state("https://example.com/feed").await, None);
+        let state = slipfeed::FeedState {
+            last_update: Some(slipfeed::DateTime::from_unix_timestamp_s(
+                1_700_000_000,
+            )),
+            etag: Some("\"v1\"".into()),
+        };
+        db.store_feed_state("https://example.com/feed", &state)
+            .await;
+        assert_eq!(
+            db.feed_state("https://example.com/feed").await,
+            Some(state)
+        );
+        db.store_feed_state(
+            "https://example.com/feed",
+            &slipfeed::FeedState::default(),
+        )
+        .await;
+        assert_eq!(
+            db.feed_state("https://example.com/feed").await,
+            Some(slipfeed::FeedState::default())
+        );
+    }
+
+    let config: Config = toml::from_str(&format!(
+        r#"
+        database = "{db_path}"
+
+        [feeds.local]
+        url = "{}"
+        "#,
+        feed.display()
+    ))
+    .unwrap();
+
+    // Successful updates are stored.
+    let updater = config.updater().await.unwrap();
+    let mut notifier = Notifier::new(&config.notify).unwrap();
+    update_step(
+        &updater.updater,
+        updater.entry_db.as_deref(),
+        &config,
+        &mut notifier,
+        &slipfeed::SystemClock,
+    )
+    .await;
+    let url = feed.to_string_lossy().into_owned();
+    let state = updater
+        .entry_db
+        .as_ref()
+        .unwrap()
+        .feed_state(&url)
+        .await
+        .unwrap();
+    assert!(state.last_update.is_some());
+    drop(updater);
+
+    // And restored by the next updater.
+    let updater = config.updater().await.unwrap();
+    let id = updater.feeds["local"];
+    let restored = updater.updater.read().await.feed_state(id).await;
+    assert_eq!(restored, Some(state));
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[tokio::test]
+async fn reload_config_file() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let dir = std::env::temp_dir()
+        .join(format!("slipstream-reload-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let feed = dir.join("feed.atom");
+    std::fs::write(
+        &feed,
+        include_str!("modes/serve/self_test/fixture.atom")
+            .replace("{date}", &chrono::Utc::now().to_rfc3339()),
+    )
+    .unwrap();
+    let config_path = dir.join("slipstream.toml");
+    let write_config = |feeds: &[&str]| {
+        let mut config = format!(
+            "freq = \"1s\"\ndatabase = \"{}\"\n",
+            dir.join("slipstream.db").display()
+        );
+        for name in feeds {
+            config.push_str(&format!(
+                "[feeds.{name}]\nurl = \"{}\"\n",
+                feed.display()
+            ));
+        }
+        std::fs::write(&config_path, config).unwrap();
+    };
+    let last_updates = async |handle: &UpdaterHandle, feeds: &[&str]| {
+        for _ in 0..200 {
+            let metrics = handle.metrics().await;
+            if feeds
+                .iter()
+                .all(|feed| metrics.feed_last_update.contains_key(*feed))
+            {
+                return metrics.feed_last_update;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        panic!("Feeds {feeds:?} were never updated");
+    };
+
+    write_config(&["kept", "removed"]);
+    let config = Arc::new(Config::from_path(&config_path).unwrap());
+    let mut updater = config.updater().await.unwrap();
+    let handle = updater.handle().unwrap();
+    let cancel_token = CancellationToken::new();
+    let task = tokio::spawn(update(updater, config, cancel_token.clone()));
+    let updated = last_updates(&handle, &["kept", "removed"]).await;
+
+    // Invalid configs are rejected.
+    std::fs::write(&config_path, "feeds = 1").unwrap();
+    assert!(reload_config(&config_path, &handle).await.is_err());
+
+    // Added feeds are updated, and unchanged feeds keep their state.
+    write_config(&["kept", "added"]);
+    reload_config(&config_path, &handle).await.unwrap();
+    let reloaded = last_updates(&handle, &["kept", "added"]).await;
+    assert_eq!(reloaded["kept"], updated["kept"]);
+    assert!(!reloaded.contains_key("removed"));
+
+    cancel_token.cancel();
+    task.await.unwrap().unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[tokio::test]
+async fn config_env_substitution() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let var = |name: &str| match name {
+        "TOKEN" => Some("secret".to_string()),
+        "EMPTY" => Some(String::new()),
+        "QUOTED" => Some("a \"b\"\\".to_string()),
+        _ => None,
+    };
+    assert_eq!(
+        substitute_env("token = \"${TOKEN}\"", var).unwrap(),
+        "token = \"secret\""
+    );
+    assert_eq!(substitute_env("a = 'a${EMPTY}b'", var).unwrap(), "a = 'ab'");
+    assert_eq!(
+        substitute_env("a = \"$$ $${TOKEN} cost$ ^a$\"", var).unwrap(),
+        "a = \"$ ${TOKEN} cost$ ^a$\""
+    );
+
+    // Values are escaped in basic strings, and kept in literal strings.
+    let data = substitute_env(
+        "basic = \"${QUOTED}\"\nliteral = '${TOKEN}'\n\
+        multi = \"\"\"${QUOTED}\"\"\"",
+        var,
+    )
+    .unwrap();
+    let table: toml::Table = toml::from_str(&data).unwrap();
+    assert_eq!(table["basic"].as_str(), Some("a \"b\"\\"));
+    assert_eq!(table["literal"].as_str(), Some("secret"));
+    assert_eq!(table["multi"].as_str(), Some("a \"b\"\\"));
+
+    // Comments and bare keys are left as they are.
+    let data = "# ${MISSING}\nkey = \"\\\"${TOKEN}\" # ${MISSING}";
+    assert_eq!(
+        substitute_env(data, var).unwrap(),
+        "# ${MISSING}\nkey = \"\\\"secret\" # ${MISSING}"
+    );
+
+    assert_eq!(
+        substitute_env("a = \"${MISSING}\"", var).unwrap_err(),
+        "Environment variable MISSING is not set"
+    );
+    assert!(substitute_env("a = \"${TOKEN\"", var).is_err());
+
+    // Unset variables name the config file.
+    let dir = std::env::temp_dir()
+        .join(format!("slipstream-env-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let config_path = dir.join("slipstream.toml");
+    std::fs::write(
+        &config_path,
+        r#"
+        [feeds.social]
+        mastodon = "https://mastodon.social"
+        type = "HomeTimeline"
+        token = "${SLIPSTREAM_TEST_UNSET_TOKEN}"
+        "#,
+    )
+    .unwrap();
+    let error = Config::from_path(&config_path).unwrap_err().to_string();
+    assert!(error.contains("SLIPSTREAM_TEST_UNSET_TOKEN"), "{error}");
+    assert!(error.contains("slipstream.toml"), "{error}");
+    std::fs::remove_dir_all(&dir).ok();
+
+    // Exported configs show variables instead of their values, and drop
+    // secrets.
+    let mut config: Config = toml::from_str(
+        r#"
+        [feeds.social]
+        mastodon = "https://mastodon.social"
+        type = "HomeTimeline"
+        token = "mastodon-secret"
+
+        [feeds.private]
+        url = "https://example.com/feed?key=hunter2"
+
+        [feeds.english]
+        url = "https://example.com/en/feed"
+
+        [serve]
+        auth-token = "serve-secret"
+        "#,
+    )
+    .unwrap();
+    let var = |name: &str| match name {
+        "FEED_KEY" => Some("hunter2".to_string()),
+        "LANG" => Some("en".to_string()),
+        _ => None,
+    };
+    let (_, substituted) = substitute_env_strings(
+        "url = \"https://example.com/feed?key=${FEED_KEY}\"\n\
+        lang = '${LANG}'\nplain = \"en\"",
+        var,
+    )
+    .unwrap();
+    assert_eq!(
+        substituted,
+        vec![
+            (
+                "https://example.com/feed?key=${FEED_KEY}".to_string(),
+                "https://example.com/feed?key=hunter2".to_string()
+            ),
+            ("${LANG}".into(), "en".into()),
+        ]
+    );
+    config.substituted = substituted;
+    let exported = config.export_public().unwrap();
+    for secret in ["mastodon-secret", "hunter2", "serve-secret"] {
+        assert!(!exported.contains(secret), "{exported}");
+    }
+    assert!(exported.contains("?key=${FEED_KEY}"), "{exported}");
+    assert!(exported.contains("https://mastodon.social"), "{exported}");
+    // Short values only replace whole strings.
+    assert!(
+        exported.contains("https://example.com/en/feed"),
+        "{exported}"
+    );
+}
+
+#[tokio::test]
+async fn feed_freq() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let dir = std::env::temp_dir()
+        .join(format!("slipstream-feed-freq-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let feed = dir.join("feed.atom");
+    std::fs::write(
+        &feed,
+        include_str!("modes/serve/self_test/fixture.atom")
+            .replace("{date}", &chrono::Utc::now().to_rfc3339()),
+    )
+    .unwrap();
+    let config: Config = toml::from_str(&format!(
+        r#"
+        freq = "0s"
+        database = "{}"
+
+        [feeds.hourly]
+        url = "{}"
+        freq = "1h"
+
+        [feeds.always]
+        url = "{}"
+        freq = "0s"
+        "#,
+        dir.join("slipstream.db").display(),
+        feed.display(),
+        feed.display()
+    ))
+    .unwrap();
+    let updater = config.updater().await.unwrap();
+    let mut notifier = Notifier::new(&config.notify).unwrap();
+    let last_update = async |name: &str| {
+        let id = updater.feeds[name];
+        let inner = updater.updater.read().await;
+        inner.feed_state(id).await.unwrap().last_update.unwrap()
+    };
+
+    // A feed with an hourly frequency isn't fetched by an immediate update.
+    for _ in 0..2 {
+        update_step(
+            &updater.updater,
+            updater.entry_db.as_deref(),
+            &config,
+            &mut notifier,
+            &slipfeed::SystemClock,
+        )
+        .await;
+    }
+    let hourly = last_update("hourly").await;
+    let always = last_update("always").await;
+    assert!(hourly < always, "{hourly} {always}");
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[tokio::test]
+async fn dedup_by_guid() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let dir = std::env::temp_dir()
+        .join(format!("slipstream-guid-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let feed = dir.join("feed.rss");
+    let write_feed = |title: &str, link: &str| {
+        std::fs::write(
+            &feed,
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+                <rss version="2.0"><channel>
+                <title>Reposts</title>
+                <link>https://example.com</link>
+                <description>Reposts</description>
+                <item>
+                <title>{title}</title>
+                <link>{link}</link>
+                <guid isPermaLink="false">post-1</guid>
+                <pubDate>{}</pubDate>
+                </item>
+                </channel></rss>"#,
+                chrono::Utc::now().to_rfc2822()
+            ),
+        )
+        .unwrap();
+    };
+    let config: Config = toml::from_str(&format!(
+        r#"
+        freq = "0s"
+        database = "{}"
+
+        [feeds.reposts]
+        url = "{}"
+        freq = "0s"
+        "#,
+        dir.join("slipstream.db").display(),
+        feed.display()
+    ))
+    .unwrap();
+    let updater = config.updater().await.unwrap();
+    let mut notifier = Notifier::new(&config.notify).unwrap();
+
+    // The same guid with a changed title and link is the same entry.
+    for (title, link) in [
+        ("Post", "https://example.com/post"),
+        ("Post (updated)", "https://example.com/post?ref=rss"),
+    ] {
+        write_feed(title, link);
+        update_step(
+            &updater.updater,
+            updater.entry_db.as_deref(),
+            &config,
+            &mut notifier,
+            &slipfeed::SystemClock,
+        )
+        .await;
+    }
+    let entries = updater
+        .entry_db
+        .as_ref()
+        .unwrap()
+        .get_entries(
+            vec![DatabaseSearch::Latest],
+            10,
+            OffsetCursor::LatestId,
+            EntryOrder::Newest,
+        )
+        .await;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].entry.source_id(), Some("post-1"));
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[tokio::test]
+async fn read_time_format() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let config: Config = toml::from_str(
+        r#"
+        timezone = "utc"
+
+        [read]
+        time-format = "both"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.read.time_format, TimeFormat::Both);
+    assert_eq!(
+        toml::from_str::<Config>("").unwrap().read.time_format,
+        TimeFormat::Absolute
+    );
+
+    let now = slipfeed::DateTime::try_from("2025-06-12T12:00:00Z").unwrap();
+    let recent = slipfeed::DateTime::try_from("2025-06-12T09:00:00Z").unwrap();
+    let old = slipfeed::DateTime::try_from("2025-05-01T09:00:00Z").unwrap();
+    let format = |time_format: TimeFormat, dt: &slipfeed::DateTime| {
+        time_format.format(dt, &config, &now)
+    };
+    let absolute = config.timezone.format(&recent);
+    assert_eq!(format(TimeFormat::Absolute, &recent), absolute);
+    assert_eq!(format(TimeFormat::Relative, &recent), "3h ago");
+    assert_eq!(
+        format(TimeFormat::Both, &recent),
+        format!("{absolute} (3h ago)")
+    );
+
+    // Dates beyond a week are absolute.
+    let absolute = config.timezone.format(&old);
+    assert_eq!(format(TimeFormat::Relative, &old), absolute);
+    assert_eq!(format(TimeFormat::Both, &old), absolute);
+}
+
+#[tokio::test]
+async fn date_format() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let dt = slipfeed::DateTime::try_from("2025-06-12T12:00:00Z").unwrap();
+    let config: Config = toml::from_str(
+        r#"
+        timezone = "America/New_York"
+        date-format = "%a %d %b %H:%M %Z"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.format_date(&dt), "Thu 12 Jun 08:00 EDT");
+
+    // Without a format, dates use the default format in the timezone.
+    let config: Config =
+        toml::from_str(r#"timezone = "America/New_York""#).unwrap();
+    assert_eq!(config.format_date(&dt), "2025-06-12 08:00 EDT");
+
+    // Invalid formats and timezones are rejected.
+    assert!(toml::from_str::<Config>(r#"date-format = "%Q""#).is_err());
+    assert!(toml::from_str::<Config>(r#"timezone = "Mars/Olympus""#).is_err());
+}
+
+#[tokio::test]
+async fn read_theme() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    use ratatui::style::Color;
+
+    // Unspecified roles keep their defaults.
+    let config: Config = toml::from_str("").unwrap();
+    assert_eq!(config.read.theme.header(), Color::Blue);
+    assert_eq!(config.read.theme.selection(), Color::Green);
+    assert_eq!(config.read.theme.link(), Color::Red);
+
+    let config: Config = toml::from_str(
+        r##"
+        [read.theme]
+        header = "bright-blue"
+        feed-tag = "#1e66f5"
+        link = "#D20F39"
+        "##,
+    )
+    .unwrap();
+    let theme = &config.read.theme;
+    assert_eq!(theme.header(), Color::LightBlue);
+    assert_eq!(theme.feed_tag(), Color::Rgb(0x1e, 0x66, 0xf5));
+    assert_eq!(theme.link(), Color::Rgb(0xd2, 0x0f, 0x39));
+    assert_eq!(theme.selection(), Color::Green);
+    assert_eq!(theme.tag(), Color::Yellow);
+
+    // Hex colors work in tag colors too, and invalid colors are rejected.
+    let config: Config = toml::from_str(
+        r##"
+        [[read.tags.colors]]
+        tag = "rust"
+        color.fg = "#ce422b"
+        "##,
+    )
+    .unwrap();
+    assert_eq!(
+        config.read.tags.colors[0].style().fg,
+        Some(Color::Rgb(0xce, 0x42, 0x2b))
+    );
+    for color in ["#12345", "#gggggg", "blurple"] {
+        let theme = format!("[read.theme]\nlink = \"{color}\"");
+        assert!(toml::from_str::<Config>(&theme).is_err(), "{color}");
+    }
+}
+
+#[tokio::test]
+async fn color_rule_matching() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    use ratatui::style::{Color, Style};
+
+    let config: Config = toml::from_str(
+        r##"
+        [[read.tags.colors]]
+        tag = "rust"
+        color.fg = "red"
+
+        [[read.tags.colors]]
+        feed = "lobsters"
+        color.bg = "black"
+
+        [[read.tags.colors]]
+        author = "Ferris"
+        color.fg = "green"
+
+        [[read.tags.colors]]
+        title = "release"
+        color.fg = "blue"
+
+        [[read.tags.colors]]
+        title-regex = "^Ask HN:"
+        color.fg = "magenta"
+
+        [[read.tags.colors]]
+        tag = "news"
+        author = "Wire"
+        color.fg = "cyan"
+
+        [[read.tags.colors]]
+        color.fg = "white"
+        "##,
+    )
+    .unwrap();
+    let entry = |feed: &str, title: &str, author: &str, tags: &[&str]| {
+        let mut entry = slipfeed::EntryBuilder::new()
+            .title(title)
+            .author(author)
+            .build();
+        entry.add_feed(slipfeed::FeedRef {
+            id: slipfeed::FeedId::new(0),
+            name: Arc::new(feed.into()),
+        });
+        for tag in tags {
+            entry.add_tag(&slipfeed::Tag::new(*tag));
+        }
+        entry
+    };
+    // Matching rules are applied in order, as in the reader.
+    let style = |entry: &slipfeed::Entry| {
+        let mut style = Style::new();
+        for rule in &config.read.tags.colors {
+            if rule.matches(entry) {
+                rule.apply_style(&mut style);
+            }
+        }
+        (style.fg, style.bg)
+    };
+
+    assert_eq!(
+        style(&entry("hn", "Post", "", &["rust"])).0,
+        Some(Color::Red)
+    );
+    assert_eq!(
+        style(&entry("lobsters", "Post", "", &[])).1,
+        Some(Color::Black)
+    );
+    assert_eq!(
+        style(&entry("hn", "Post", "ferris", &[])).0,
+        Some(Color::Green)
+    );
+    assert_eq!(
+        style(&entry("hn", "New Release", "", &[])).0,
+        Some(Color::Blue)
+    );
+    assert_eq!(
+        style(&entry("hn", "Ask HN: Rust?", "", &[])).0,
+        Some(Color::Magenta)
+    );
+    assert_eq!(
+        style(&entry("hn", "Tell HN: Ask HN:", "", &[])),
+        (None, None)
+    );
+
+    // Every condition of a rule must match.
+    assert_eq!(style(&entry("hn", "Post", "Wire", &[])), (None, None));
+    assert_eq!(
+        style(&entry("hn", "Post", "Wire", &["news"])).0,
+        Some(Color::Cyan)
+    );
+
+    // Later rules override earlier ones, and distinct colors compose.
+    assert_eq!(
+        style(&entry("lobsters", "Release", "Ferris", &["rust"])),
+        (Some(Color::Blue), Some(Color::Black))
+    );
+}
+
+#[tokio::test]
+async fn entry_order() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let db = Database::new(":memory:").await.unwrap();
+    for (i, feed) in ["beta", "Alpha", "beta", "alpha", "Gamma"]
+        .iter()
+        .enumerate()
+    {
+        let mut entry = slipfeed::EntryBuilder::new()
+            .title(format!("Entry {i}"))
+            .source_id(format!("entry-{i}"))
+            .source(format!("https://example.com/{i}"))
+            .date(slipfeed::DateTime::from_unix_timestamp_s(1_000 * i as u64))
+            .build();
+        entry.add_feed(slipfeed::FeedRef {
+            id: slipfeed::FeedId::new(0),
+            name: Arc::new(feed.to_string()),
+        });
+        assert!(db.insert_slipfeed_entry(&entry).await.is_new());
+    }
+    let titles = |cursor: OffsetCursor, order: EntryOrder| {
+        let db = &db;
+        async move {
+            db.get_entries(vec![DatabaseSearch::Latest], 2, cursor, order)
+                .await
+                .iter()
+                .map(|entry| entry.entry.title().clone())
+                .collect::<Vec<_>>()
+        }
+    };
+    let date = |i: u64| slipfeed::DateTime::from_unix_timestamp_s(1_000 * i);
+
+    assert_eq!(
+        titles(OffsetCursor::LatestTimestamp, EntryOrder::Newest).await,
+        vec!["Entry 4", "Entry 3"]
+    );
+    assert_eq!(
+        titles(OffsetCursor::LatestTimestamp, EntryOrder::Oldest).await,
+        vec!["Entry 0", "Entry 1"]
+    );
+
+    // Pages next to a date keep the order.
+    assert_eq!(
+        titles(OffsetCursor::After(date(1), None), EntryOrder::Oldest).await,
+        vec!["Entry 2", "Entry 3"]
+    );
+    assert_eq!(
+        titles(OffsetCursor::Before(date(3), None), EntryOrder::Oldest).await,
+        vec!["Entry 1", "Entry 2"]
+    );
+    assert_eq!(
+        titles(OffsetCursor::After(date(1), None), EntryOrder::Newest).await,
+        vec!["Entry 3", "Entry 2"]
+    );
+
+    // Pages by source are the newest entries, sorted by source.
+    assert_eq!(
+        titles(OffsetCursor::LatestTimestamp, EntryOrder::Source).await,
+        vec!["Entry 3", "Entry 4"]
+    );
+    let sorted = db
+        .get_entries(
+            vec![DatabaseSearch::Latest],
+            10,
+            OffsetCursor::LatestTimestamp,
+            EntryOrder::Source,
+        )
+        .await;
+    let sorted: Vec<&str> =
+        sorted.iter_entries().map(|e| e.title().as_str()).collect();
+    assert_eq!(
+        sorted,
+        vec!["Entry 3", "Entry 1", "Entry 2", "Entry 0", "Entry 4"]
+    );
+
+    // Entries sharing the date at the edge of a page aren't skipped.
+    for i in 5..=6 {
+        let entry = slipfeed::EntryBuilder::new()
+            .title(format!("Entry {i}"))
+            .source_id(format!("entry-{i}"))
+            .source(format!("https://example.com/{i}"))
+            .date(date(5))
+            .build();
+        assert!(db.insert_slipfeed_entry(&entry).await.is_new());
+    }
+    let page = db
+        .get_entries(
+            vec![DatabaseSearch::Latest],
+            1,
+            OffsetCursor::LatestTimestamp,
+            EntryOrder::Newest,
+        )
+        .await;
+    let last = page.last().unwrap();
+    assert_eq!(last.entry.title(), "Entry 6");
+    let cursor = OffsetCursor::Before(date(5), Some(last.db_id));
+    assert_eq!(
+        titles(cursor.clone(), EntryOrder::Newest).await,
+        vec!["Entry 5", "Entry 4"]
+    );
+    let after = OffsetCursor::After(date(4), None);
+    assert_eq!(
+        titles(after, EntryOrder::Newest).await,
+        vec!["Entry 6", "Entry 5"]
+    );
+    let id = db
+        .get_entries(
+            vec![DatabaseSearch::Latest],
+            2,
+            cursor,
+            EntryOrder::Newest,
+        )
+        .await
+        .first()
+        .unwrap()
+        .db_id;
+    assert_eq!(
+        titles(OffsetCursor::After(date(5), Some(id)), EntryOrder::Oldest)
+            .await,
+        vec!["Entry 6"]
+    );
+}
+
+#[tokio::test]
+async fn unread_counts() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let db = Database::new(":memory:").await.unwrap();
+    let mut ids = Vec::new();
+    for (i, (feed, tag)) in [("news", "rust"), ("news", "go"), ("blog", "rust")]
+        .iter()
+        .enumerate()
+    {
+        let mut entry = slipfeed::EntryBuilder::new()
+            .title(format!("Entry {i}"))
+            .source_id(format!("entry-{i}"))
+            .source(format!("https://example.com/{i}"))
+            .build();
+        entry.add_feed(slipfeed::FeedRef {
+            id: slipfeed::FeedId::new(0),
+            name: Arc::new(feed.to_string()),
+        });
+        entry.add_tag(&slipfeed::Tag::new(*tag));
+        match db.insert_slipfeed_entry(&entry).await {
+            EntryInsertion::New(id) => ids.push(id),
+            insertion => panic!("Unexpected insertion: {insertion:?}"),
+        }
+    }
+    db.toggle_read(ids[2], true).await;
+
+    let counts: Vec<(String, u64)> = db
+        .unread_counts()
+        .await
+        .iter()
+        .map(|count| (count.label(), count.unread))
+        .collect();
+    assert_eq!(
+        counts,
+        vec![
+            ("blog".to_string(), 0),
+            ("news".to_string(), 2),
+            ("#go".to_string(), 1),
+            ("#rust".to_string(), 1),
+        ]
+    );
+
+    // Each count searches for its entries.
+    let count = &db.unread_counts().await[0];
+    let entries = db
+        .get_entries(
+            vec![count.search.clone()],
+            10,
+            OffsetCursor::LatestTimestamp,
+            EntryOrder::Newest,
+        )
+        .await;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].db_id, ids[2]);
+}
+
+#[tokio::test]
+async fn serve_limit() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let path = std::env::temp_dir()
+        .join(format!("slipstream-limit-{}.db", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    let path = path.to_string_lossy().into_owned();
+    {
+        let db = Database::new(&path).await.unwrap();
+        for i in 0..5 {
+            let entry = slipfeed::EntryBuilder::new()
+                .title(format!("Entry {i}"))
+                .source_id(format!("entry-{i}"))
+                .source(format!("https://example.com/{i}"))
+                .date(slipfeed::DateTime::now())
+                .build();
+            assert!(db.insert_slipfeed_entry(&entry).await.is_new());
+        }
+    }
+
+    let mut config: Config = toml::from_str(
+        r#"
+        [global.limits]
+        max = 2
+
+        [serve]
+        max-limit = 3
+        "#,
+    )
+    .unwrap();
+    config.database = Some(path.clone());
+    let server = TestServer::start(config).await;
+
+    let get = async |path: &str| server.get(path).await;
+    let entries = async |path: &str| {
+        let response = get(path).await;
+        assert_eq!(response.status(), reqwest::StatusCode::OK, "{path}");
+        atom_syndication::Feed::from_str(&response.text().await.unwrap())
+            .unwrap()
+            .entries()
+            .len()
+    };
+
+    // The limit overrides the configured default, up to the maximum.
+    assert_eq!(entries("/all/feed").await, 2);
+    assert_eq!(entries("/all/feed?limit=1").await, 1);
+    assert_eq!(entries("/all/feed?limit=0").await, 0);
+    assert_eq!(entries("/all/feed?limit=100").await, 3);
+    assert_eq!(entries("/search/feed?q=Entry&limit=3").await, 3);
+
+    for invalid in ["/all/feed?limit=-1", "/all/feed?limit=ten", "/all?limit="]
+    {
+        assert_eq!(
+            get(invalid).await.status(),
+            reqwest::StatusCode::BAD_REQUEST,
+            "{invalid}"
+        );
+    }
+
+    server.stop().await;
+    std::fs::remove_file(&path).ok();
+}
+
+#[tokio::test]
+async fn serve_healthz() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    // Starting during quiet hours isn't ready, since nothing was fetched.
+    let config: Config = toml::from_str(
+        r#"
+        timezone = "utc"
+
+        [global.quiet-hours]
+        start = "22:00"
+        end = "07:00"
+        "#,
+    )
+    .unwrap();
+    let (mut server, mut updater) = TestServer::without_updates(config).await;
+    let clock = slipfeed::ManualClock::new(
+        slipfeed::DateTime::try_from("2025-06-12T23:00:00Z").unwrap(),
+    );
+    updater.set_clock(Arc::new(clock)).await;
+    server.tasks.spawn(update(
+        updater,
+        server.config.clone(),
+        server.cancel_token.clone(),
+    ));
+    for _ in 0..10 {
+        let response = server.request("/healthz").send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    server.stop().await;
+
+    let config: Config = toml::from_str(
+        r#"
+        [serve]
+        auth-token = "secret"
+        "#,
+    )
+    .unwrap();
+    let (mut server, updater) = TestServer::without_updates(config).await;
+    let request = server.request("/healthz");
+    let get = async || request.try_clone().unwrap().send().await.unwrap();
+
+    // Not ready until the updater has run, and never behind the token.
+    let response = get().await;
+    assert_eq!(response.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(
+        response.headers()["cache-control"].to_str().unwrap(),
+        "no-store"
+    );
+    assert_eq!(response.text().await.unwrap(), r#"{"status":"starting"}"#);
+
+    server.tasks.spawn(update(
+        updater,
+        server.config.clone(),
+        server.cancel_token.clone(),
+    ));
+    let mut status = reqwest::StatusCode::SERVICE_UNAVAILABLE;
+    for _ in 0..100 {
+        status = get().await.status();
+        if status == reqwest::StatusCode::OK {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert_eq!(status, reqwest::StatusCode::OK);
+    assert_eq!(get().await.text().await.unwrap(), r#"{"status":"ready"}"#);
+
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn quit_promptly() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    // A feed that never responds.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let hanging = format!("http://{}/feed", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let mut streams = Vec::new();
+        while let Ok((stream, _)) = listener.accept().await {
+            streams.push(stream);
+        }
+    });
+
+    let config: Config = toml::from_str(&format!(
+        r#"
+        [feeds.hanging]
+        url = "{hanging}"
+        "#
+    ))
+    .unwrap();
+    let (mut server, updater) = TestServer::without_updates(config).await;
+    let updater_token = CancellationToken::new();
+    let updating = tokio::spawn(update(
+        updater,
+        server.config.clone(),
+        updater_token.clone(),
+    ));
+
+    // Leave a kept-alive connection open.
+    assert!(server.get("/all/feed").await.status().is_success());
+
+    // Idle connections and fetches in progress don't hold up quitting.
+    let timeout = std::time::Duration::from_secs(5);
+    server.cancel_token.cancel();
+    tokio::time::timeout(timeout, server.tasks.join_next())
+        .await
+        .expect("Serving never stopped")
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    updater_token.cancel();
+    tokio::time::timeout(timeout, updating)
+        .await
+        .expect("Updating never stopped")
+        .unwrap()
+        .unwrap();
+}
+
+#[test]
+fn json_logging() {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    tracing_subscriber::fmt::try_init().ok();
+
+    let cli =
+        Cli::try_parse_from(["slipstream", "--log-format", "json", "serve"])
+            .unwrap();
+    assert_eq!(cli.log_format, Some(LogFormat::Json));
+    let config: Config = toml::from_str(
+        r#"
+        [logging]
+        format = "json"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.logging.format, Some(LogFormat::Json));
+    let config: Config = toml::from_str("").unwrap();
+    assert_eq!(config.logging.format, None);
+
+    // Events are written as one object per line, with their fields.
+    let path = std::env::temp_dir()
+        .join(format!("slipstream-json-log-{}.log", std::process::id()));
+    let file = std::fs::File::create(&path).unwrap();
+    let subscriber = tracing_subscriber::Registry::default()
+        .with(format_layer(LogFormat::Json, file));
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::warn!(
+            feed = "example",
+            url = "https://example.com/feed",
+            error = "timed out",
+            "Failed to update"
+        );
+        tracing::info!("Updated");
+    });
+    let logs = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+    let lines: Vec<serde_json::Value> = logs
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["level"], "WARN");
+    assert_eq!(lines[0]["message"], "Failed to update");
+    assert_eq!(lines[0]["feed"], "example");
+    assert_eq!(lines[0]["url"], "https://example.com/feed");
+    assert_eq!(lines[0]["error"], "timed out");
+    assert_eq!(lines[1]["message"], "Updated");
+    assert!(lines[1].get("feed").is_none());
+}
+
+#[test]
+fn log_rotation() {
+    use std::io::Write;
+
+    tracing_subscriber::fmt::try_init().ok();
+
+    let config: Config = toml::from_str(
+        r#"
+        [logging]
+        file = "slipstream.log"
+        rotation = "size"
+        max-size = 1024
+        max-files = 2
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.logging.file.as_deref(), Some("slipstream.log"));
+    assert_eq!(config.logging.rotation(), LogRotation::Size);
+    assert_eq!(config.logging.max_size(), 1024);
+    assert_eq!(config.logging.max_files(), 2);
+    let config: Config = toml::from_str("").unwrap();
+    assert_eq!(config.logging.rotation(), LogRotation::Never);
+
+    // Full files are rolled over, keeping the newest.
+    let dir = std::env::temp_dir()
+        .join(format!("slipstream-log-rotation-{}", std::process::id()));
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("slipstream.log");
+    let mut file = SizeRollingFile::new(path.clone(), 8, 2).unwrap();
+    for line in ["one\n", "two\n", "three\n", "four\n", "five\n"] {
+        file.write_all(line.as_bytes()).unwrap();
+    }
+    file.flush().unwrap();
+    let read = |name: &str| std::fs::read_to_string(dir.join(name)).unwrap();
+    assert_eq!(read("slipstream.log"), "five\n");
+    assert_eq!(read("slipstream.log.1"), "four\n");
+    assert_eq!(read("slipstream.log.2"), "three\n");
+    assert!(!dir.join("slipstream.log.3").exists());
+
+    // Time-based rotation names files by date.
+    let config: LoggingConfig =
+        toml::from_str(r#"rotation = "daily""#).unwrap();
+    let log_file = dir.join("daily.log");
+    let mut file = open_log_file(&log_file.to_string_lossy(), &config).unwrap();
+    file.write_all(b"daily\n").unwrap();
+    file.flush().unwrap();
+    let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    assert_eq!(read(&format!("daily.log.{today}")), "daily\n");
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[tokio::test]
+async fn feeds_list() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let path = std::env::temp_dir()
+        .join(format!("slipstream-feeds-list-{}.db", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    let config_data = r#"
+        [feeds.news]
+        url = "https://example.com/news.xml"
+        tags = ["daily", "world"]
+        priority = -1
+
+        [feeds.blog]
+        url = "https://example.com/blog.xml"
+
+        [feeds.everything]
+        feeds = ["news", "blog"]
+        "#;
+
+    // Without a database, only the config is listed.
+    let config: Config = toml::from_str(&format!(
+        "database = {:?}\n{config_data}",
+        path.to_string_lossy()
+    ))
+    .unwrap();
+    let listings = list_feeds(&config).await.unwrap();
+    assert!(!path.exists());
+    let names: Vec<&str> = listings.iter().map(|l| l.name.as_str()).collect();
+    assert_eq!(names, vec!["news", "blog", "everything"]);
+    assert_eq!(listings[0].kind, "feed");
+    assert_eq!(
+        listings[0].url.as_deref(),
+        Some("https://example.com/news.xml")
+    );
+    assert_eq!(listings[0].tags, vec!["daily", "world"]);
+    assert_eq!(listings[0].entries, None);
+    assert_eq!(listings[2].kind, "aggregate");
+    assert_eq!(listings[2].url, None);
+    assert_eq!(
+        feeds_table(&listings, &config),
+        "NAME        TYPE       URL                           TAGS\n\
+        news        feed       https://example.com/news.xml  daily,world\n\
+        blog        feed       https://example.com/blog.xml  -\n\
+        everything  aggregate  -                             -\n"
+    );
+
+    // Stored entries are counted per feed.
+    let db = Database::new(path.to_string_lossy()).await.unwrap();
+    for i in 1..=2 {
+        let mut entry = slipfeed::EntryBuilder::new()
+            .title(format!("Entry {i}"))
+            .source_id(format!("entry-{i}"))
+            .source(format!("https://example.com/{i}"))
+            .date(slipfeed::DateTime::from_unix_timestamp_s(i * 86400))
+            .build();
+        entry.add_feed(slipfeed::FeedRef {
+            id: slipfeed::FeedId::new(0),
+            name: Arc::new("news".to_string()),
+        });
+        match db.insert_slipfeed_entry(&entry).await {
+            EntryInsertion::New(_) => {}
+            insertion => panic!("Unexpected insertion: {insertion:?}"),
+        }
+    }
+    db.close().await;
+    let listings = list_feeds(&config).await.unwrap();
+    assert_eq!(listings[0].entries, Some(2));
+    assert_eq!(
+        listings[0].newest,
+        Some(slipfeed::DateTime::from_unix_timestamp_s(2 * 86400))
+    );
+    assert_eq!(listings[1].entries, Some(0));
+    assert_eq!(listings[1].newest, None);
+    let table = feeds_table(&listings, &config);
+    assert!(table.starts_with("NAME"));
+    let row = table.lines().nth(1).unwrap();
+    assert!(row.ends_with("2        1970-01-03 00:00"), "{row}");
+    let json = serde_json::to_value(&listings).unwrap();
+    assert_eq!(json[0]["type"], "feed");
+    assert_eq!(json[0]["entries"], 2);
+    assert!(json[1].get("newest").is_none());
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn opml_export_notes() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let config: Config = toml::from_str(
+        r#"
+        [feeds.blog]
+        url = "https://example.com/blog.xml"
+        tags = ["tech"]
+
+        [feeds.toots]
+        mastodon = "https://mastodon.social"
+        hashtag = "rust"
+
+        [feeds.everything]
+        feeds = ["blog", "toots"]
+        "#,
+    )
+    .unwrap();
+    let (opml, notes) = export_opml(&config, true).unwrap();
+    let opml = opml::OPML::from_str(&opml).unwrap();
+    let outlines: Vec<&str> = opml
+        .body
+        .outlines
+        .iter()
+        .map(|outline| outline.text.as_str())
+        .collect();
+    assert_eq!(outlines, vec!["tech"]);
+    assert_eq!(opml.body.outlines[0].outlines[0].text, "blog");
+    assert_eq!(notes.len(), 1);
+    assert!(notes[0].contains("mastodon.social"), "{notes:?}");
+}
+
+#[tokio::test]
+async fn debug_feed_filters() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    tracing_subscriber::fmt::try_init().ok();
+
+    // Serve a feed with an old entry and entries for each filter.
+    let now = chrono::Utc::now();
+    let item = |title: &str, date: chrono::DateTime<chrono::Utc>| {
+        format!(
+            "<item><title>{title}</title>\
+            <link>https://example.com/{}</link>\
+            <pubDate>{}</pubDate></item>",
+            title.replace(' ', "-"),
+            date.to_rfc2822()
+        )
+    };
+    let body = format!(
+        r#"<?xml version="1.0"?><rss version="2.0"><channel>
+        <title>Debug</title><link>https://example.com</link>
+        <description>Debug</description>{}{}{}{}</channel></rss>"#,
+        item("Kept entry", now - chrono::Duration::hours(1)),
+        item("Sponsored entry", now - chrono::Duration::hours(2)),
+        item("Ads entry", now - chrono::Duration::hours(3)),
+        item("Old entry", now - chrono::Duration::days(60)),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut buf = [0u8; 1024];
+            if stream.read(&mut buf).await.unwrap_or(0) == 0 {
+                continue;
+            }
+            stream
+                .write_all(
+                    format!(
+                        "HTTP/1.1 200 OK\r\n\
+                        Content-Type: application/rss+xml\r\n\
+                        Content-Length: {}\r\n\r\n{body}",
+                        body.len()
+                    )
+                    .as_bytes(),
+                )
+                .await
+                .ok();
+            stream.shutdown().await.ok();
+        }
+    });
+
+    let config: Config = toml::from_str(&format!(
+        r#"
+        [global.filters]
+        exclude-title-words = ["ads"]
+
+        [feeds.debugged]
+        url = "{base}/feed"
+        oldest = "30d"
+        exclude-title-words = ["sponsored"]
+
+        [feeds.everything]
+        feeds = ["debugged"]
+        "#
+    ))
+    .unwrap();
+    let report = debug_feed(&config, "debugged", 2).await.unwrap();
+    assert_eq!(report.kind, "feed");
+    assert_eq!(report.status.as_deref(), Some("200 OK"));
+    assert_eq!(report.content_type.as_deref(), Some("application/rss+xml"));
+    assert_eq!(report.error, None);
+    let entries: Vec<(&str, Option<&str>)> = report
+        .entries
+        .iter()
+        .map(|entry| (entry.title.as_str(), entry.dropped))
+        .collect();
+    assert_eq!(
+        entries,
+        vec![
+            ("Kept entry", None),
+            ("Sponsored entry", Some("feed filters")),
+            ("Ads entry", Some("global filters")),
+            ("Old entry", Some("too old")),
+        ]
+    );
+    let printed = report.to_string();
+    assert!(printed.contains("4 parsed, 1 kept, 3 dropped"), "{printed}");
+    assert!(printed.contains("... and 2 more"), "{printed}");
+    assert!(printed.contains("[too old]"), "{printed}");
+
+    // Urls are fetched without configuring them, but aggregates can't be.
+    let report = debug_feed(&config, &format!("{base}/feed"), 5)
+        .await
+        .unwrap();
+    assert_eq!(report.entries.len(), 4);
+    assert_eq!(
+        report
+            .entries
+            .iter()
+            .filter(|e| e.dropped.is_some())
+            .count(),
+        2
+    );
+    assert!(debug_feed(&config, "everything", 5).await.is_err());
+    assert!(debug_feed(&config, "missing", 5).await.is_err());
+}
+
+#[tokio::test]
+async fn serve_sanitized_content() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let path = std::env::temp_dir()
+        .join(format!("slipstream-sanitize-{}.db", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    let path = path.to_string_lossy().into_owned();
+    {
+        let db = Database::new(&path).await.unwrap();
+        let entry = slipfeed::EntryBuilder::new()
+            .title("Payloads")
+            .source("javascript:alert(5)")
+            .comments("https://example.com/comments")
+            .other_link(slipfeed::Link::new("JAVASCRIPT:alert(6)", "Link"))
+            .icon("data:text/html,<script>alert(7)</script>")
+            .content(
+                [
+                    "**Bold** and [a link](https://example.com/safe).",
+                    "<script>alert(1)</script>",
+                    r#"<img src="/x.png" onerror="alert(2)">"#,
+                    "[click](javascript:alert(3))",
+                    r#"<a href="/a" onclick="alert(4)">a</a>"#,
+                    "<div><em>unclosed",
+                ]
+                .join("\n\n"),
+            )
+            .build();
+        assert!(db.insert_slipfeed_entry(&entry).await.is_new());
+    }
+
+    let mut config = Config::default();
+    config.database = Some(path.clone());
+    let server = TestServer::start(config).await;
+
+    let html = server.text("/all").await;
+    let content = &html[html.find("<strong>Bold</strong>").unwrap()..];
+    let content = &content[..content.find("</summary>").unwrap()];
+
+    // Links and formatting are kept, scripts and handlers are not.
+    assert!(content.contains("href=\"https://example.com/safe\""));
+    assert!(content.contains("href=\"/a\""));
+    assert!(content.contains("src=\"/x.png\""));
+    assert!(content.contains("<em>unclosed</em>"));
+    assert!(!content.contains("<script"));
+    assert!(!content.contains("alert(1)"));
+    assert!(!content.contains("onerror"));
+    assert!(!content.contains("onclick"));
+    assert!(!content.contains("javascript:"));
+
+    // Links outside of the content are dropped unless they're http(s) or
+    // relative.
+    assert!(!html.to_lowercase().contains("javascript:"), "{html}");
+    assert!(!html.contains("alert(7)"), "{html}");
+    assert!(html.contains("href=\"https://example.com/comments\""));
+    server.stop().await;
+    std::fs::remove_file(&path).ok();
+
+    // Sanitizing can be turned off.
+    let config: Config = toml::from_str(
+        r#"
+        [serve]
+        sanitize = false
+        "#,
+    )
+    .unwrap();
+    assert!(!config.serve.sanitize());
+    assert!(Config::default().serve.sanitize());
+
+    // The reader drops raw html and terminal escapes from markdown.
+    let markdown = sanitize_markdown(
+        "**Bold** <b onclick=\"x\">tag</b>\n\n<script>alert(1)</script>\n\n\
+        [link](https://example.com) \u{1b}[31mred",
+    );
+    assert!(markdown.starts_with("**Bold** tag\n"), "{markdown}");
+    assert!(markdown.ends_with("\n[link](https://example.com) [31mred"));
+    assert!(!markdown.contains("alert") && !markdown.contains('\u{1b}'));
+}
+
+#[tokio::test]
+async fn saved_entries() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let path = std::env::temp_dir()
+        .join(format!("slipstream-saved-{}.db", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    let path = path.to_string_lossy().into_owned();
+
+    let titles = |db: Database, criteria: Vec<DatabaseSearch>| async move {
+        let titles = db
+            .get_entries(
+                criteria,
+                10,
+                OffsetCursor::LatestTimestamp,
+                EntryOrder::Newest,
+            )
+            .await
+            .iter()
+            .map(|entry| (entry.entry.title().clone(), entry.saved))
+            .collect::<Vec<_>>();
+        db.close().await;
+        titles
+    };
+
+    {
+        let db = Database::new(&path).await.unwrap();
+        let mut ids = Vec::new();
+        for i in 0..3 {
+            let entry = slipfeed::EntryBuilder::new()
+                .title(format!("Entry {i}"))
+                .source_id(format!("entry-{i}"))
+                .source(format!("https://example.com/{i}"))
+                .date(slipfeed::DateTime::from_unix_timestamp_s(1_000 * i))
+                .build();
+            match db.insert_slipfeed_entry(&entry).await {
+                EntryInsertion::New(id) => ids.push(id),
+                insertion => panic!("Unexpected insertion: {insertion:?}"),
+            }
+        }
+
+        // Saving is separate from marking important.
+        db.toggle_saved(ids[0], true).await;
+        db.toggle_saved(ids[2], true).await;
+        db.toggle_important(ids[1], true).await;
+        db.toggle_important(ids[2], false).await;
+        db.toggle_saved(ids[2], false).await;
+        db.toggle_saved(ids[1], true).await;
+        db.toggle_important(ids[0], true).await;
+        db.close().await;
+    }
+
+    // Saved entries survive reopening the database.
+    let db = Database::new(&path).await.unwrap();
+    assert_eq!(
+        titles(db, vec![DatabaseSearch::Latest, DatabaseSearch::Saved]).await,
+        vec![("Entry 1".to_string(), true), ("Entry 0".to_string(), true)]
+    );
+    let db = Database::new(&path).await.unwrap();
+    assert_eq!(
+        titles(db, vec![DatabaseSearch::Latest]).await,
+        vec![
+            ("Entry 2".to_string(), false),
+            ("Entry 1".to_string(), true),
+            ("Entry 0".to_string(), true),
+        ]
+    );
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn jump_to_ends() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let mut state = InteractionState::default();
+    let mut entries = DatabaseEntryList::new(10);
+
+    // Jumping in an empty list does nothing.
+    state.select_bottom(&entries);
+    assert_eq!(state.selection, 0);
+
+    for i in 0..5 {
+        let entry = slipfeed::EntryBuilder::new()
+            .title(format!("Entry {i}"))
+            .build();
+        entries.add(DatabaseEntry::new(entry, i)).unwrap();
+    }
+    state.select_bottom(&entries);
+    assert_eq!(state.selection, 4);
+    state.select_top();
+    assert_eq!(state.selection, 0);
+
+    // Jumping is bound to g and G by default.
+    let config = ReadConfig::default();
+    assert!(matches!(
+        config.get_key_command(&TOP),
+        Commandish::Literal(ReadCommandLiteral::Top)
+    ));
+    assert!(matches!(
+        config.get_key_command(&BOTTOM),
+        Commandish::Literal(ReadCommandLiteral::Bottom)
+    ));
+}
+
+#[tokio::test]
+async fn tag_queries() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let db = Database::new(":memory:").await.unwrap();
+    for (title, tags) in [
+        ("Go", vec!["go", "async"]),
+        ("Golang", vec!["golang", "async"]),
+        ("Rust", vec!["rust", "Async"]),
+        ("Rust sync", vec!["rust"]),
+    ] {
+        let mut entry = slipfeed::EntryBuilder::new()
+            .title(title)
+            .source_id(title)
+            .source(format!("https://example.com/{title}"))
+            .build();
+        for tag in tags {
+            entry.add_tag(&slipfeed::Tag::new(tag));
+        }
+        assert!(db.insert_slipfeed_entry(&entry).await.is_new());
+    }
+    let titles = |matching: SearchMatch, tags: &str| {
+        let criteria = matching.tags(tags);
+        let db = &db;
+        async move {
+            let mut titles: Vec<String> = db
+                .get_entries(
+                    criteria,
+                    10,
+                    OffsetCursor::LatestTimestamp,
+                    EntryOrder::Newest,
+                )
+                .await
+                .iter()
+                .map(|entry| entry.entry.title().clone())
+                .collect();
+            titles.sort();
+            titles
+        }
+    };
+
+    // Tags match exactly by default.
+    assert_eq!(titles(SearchMatch::default(), "go").await, vec!["Go"]);
+    assert_eq!(titles(SearchMatch::Exact, "GO").await, vec!["Go"]);
+    assert_eq!(
+        titles(SearchMatch::Substring, "go").await,
+        vec!["Go", "Golang"]
+    );
+
+    // Every comma-separated tag must match.
+    assert_eq!(titles(SearchMatch::Exact, "rust,async").await, vec!["Rust"]);
+    assert_eq!(titles(SearchMatch::Exact, " go , async ").await, vec!["Go"]);
+    assert_eq!(
+        titles(SearchMatch::Substring, "go,async").await,
+        vec!["Go", "Golang"]
+    );
+    assert!(titles(SearchMatch::Exact, "go,rust").await.is_empty());
+
+    // The old behavior is opted into with a query.
+    let query: SearchMatch = serde_json::from_str("\"substring\"").unwrap();
+    assert_eq!(query, SearchMatch::Substring);
+}
+
+#[tokio::test]
+async fn exact_feed_search() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let db = Database::new(":memory:").await.unwrap();
+    for (feed, tag) in [
+        ("news", "o'brien"),
+        ("technews", "o'brien-fans"),
+        ("news-weekly", "weekly"),
+    ] {
+        let mut entry = slipfeed::EntryBuilder::new()
+            .title(feed)
+            .source(format!("https://example.com/{feed}"))
+            .build();
+        entry.add_feed(slipfeed::FeedRef {
+            id: slipfeed::FeedId::new(0),
+            name: Arc::new(feed.into()),
+        });
+        entry.add_tag(&slipfeed::Tag::new(tag));
+        assert!(db.insert_slipfeed_entry(&entry).await.is_new());
+    }
+    let titles = async |criteria: Vec<DatabaseSearch>| -> Vec<String> {
+        let mut titles: Vec<String> = db
+            .get_entries(
+                criteria,
+                10,
+                OffsetCursor::LatestTimestamp,
+                EntryOrder::Newest,
+            )
+            .await
+            .iter()
+            .map(|entry| entry.entry.title().clone())
+            .collect();
+        titles.sort();
+        titles
+    };
+
+    // Feeds and tags match exactly by default.
+    assert_eq!(
+        titles(vec![DatabaseSearch::Feed("news".into())]).await,
+        vec!["news"]
+    );
+    assert_eq!(
+        titles(vec![DatabaseSearch::NotFeed("news".into())]).await,
+        vec!["news-weekly", "technews"]
+    );
+    assert_eq!(
+        titles(vec![DatabaseSearch::Tag("O'Brien".into())]).await,
+        vec!["news"]
+    );
+
+    // Substring matching is explicit.
+    assert_eq!(
+        titles(vec![DatabaseSearch::FeedSubstring("news".into())]).await,
+        vec!["news", "news-weekly", "technews"]
+    );
+    assert_eq!(
+        titles(vec![DatabaseSearch::TagSubstring("o'brien".into())]).await,
+        vec!["news", "technews"]
+    );
+
+    // The reader's searches match the same way.
+    let mut search = SearchContext {
+        feed: vec!["news".into()],
+        ..SearchContext::default()
+    };
+    assert_eq!(titles(search.criteria()).await, vec!["news"]);
+    search.substring = true;
+    assert_eq!(titles(search.criteria()).await.len(), 3);
+}
+
+#[tokio::test]
+async fn aggregate_tag() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let dir = std::env::temp_dir()
+        .join(format!("slipstream-aggregate-tag-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let date = chrono::Utc::now().to_rfc3339();
+    let mut feeds = String::new();
+    for (name, tags) in [("blog", "tech"), ("news", "tech"), ("recipes", "")] {
+        let feed = dir.join(format!("{name}.atom"));
+        std::fs::write(
+            &feed,
+            include_str!("modes/serve/self_test/fixture.atom")
+                .replace("{date}", &date)
+                .replace("self-test", name)
+                .replace("Fixture", name),
+        )
+        .unwrap();
+        feeds.push_str(&format!(
+            "[feeds.{name}]\nurl = \"{}\"\ntags = [\"{tags}\"]\n",
+            feed.display()
+        ));
+    }
+
+    // The aggregate sorts before its sources, its own filters apply on top
+    // of its tags, and it tags its entries further.
+    let config: Config = toml::from_str(&format!(
+        r#"
+        [feeds.a-tech]
+        tag-allowlist = ["tech"]
+        tags = ["aggregated"]
+        exclude-title-words = ["two"]
+
+        {feeds}
+        "#
+    ))
+    .unwrap();
+    let updater = config.updater().await.unwrap();
+    let mut notifier = Notifier::new(&config.notify).unwrap();
+    update_step(
+        &updater.updater,
+        updater.entry_db.as_deref(),
+        &config,
+        &mut notifier,
+        &slipfeed::SystemClock,
+    )
+    .await;
+
+    let titles = async |criteria: Vec<DatabaseSearch>| -> Vec<String> {
+        let mut titles: Vec<String> = updater
+            .entry_db
+            .as_ref()
+            .unwrap()
+            .get_entries(
+                criteria,
+                10,
+                OffsetCursor::LatestTimestamp,
+                EntryOrder::Newest,
+            )
+            .await
+            .iter()
+            .map(|entry| entry.entry.title().clone())
+            .collect();
+        titles.sort();
+        titles
+    };
+    assert_eq!(titles(vec![DatabaseSearch::Latest]).await.len(), 6);
+    assert_eq!(
+        titles(vec![DatabaseSearch::Feed("a-tech".into())]).await,
+        vec!["blog entry one", "news entry one"]
+    );
+    assert_eq!(
+        titles(vec![DatabaseSearch::Tag("aggregated".into())]).await,
+        vec!["blog entry one", "news entry one"]
+    );
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[tokio::test]
+async fn serve_rate_limit() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let config: Config = toml::from_str(
+        r#"
+        [serve.rate-limit]
+        requests = 2
+        window = "1h"
+        trust-forwarded-for = true
+        "#,
+    )
+    .unwrap();
+    let server = TestServer::start(config).await;
+
+    let get = async |path: &str, forwarded_for: Option<&str>| {
+        let mut request = server.request(path);
+        if let Some(forwarded_for) = forwarded_for {
+            request = request.header("x-forwarded-for", forwarded_for);
+        }
+        request.send().await.unwrap()
+    };
+
+    // Health checks and robots.txt don't count against the limit.
+    for _ in 0..3 {
+        assert_eq!(get("/healthz", None).await.status(), 200);
+        assert_eq!(get("/robots.txt", None).await.status(), 200);
+    }
+
+    // Each client gets its own requests.
+    assert_eq!(get("/all", None).await.status(), 200);
+    assert_eq!(get("/all/feed", None).await.status(), 200);
+    let response = get("/all", None).await;
+    assert_eq!(response.status(), 429);
+    let retry_after = response.headers()[reqwest::header::RETRY_AFTER]
+        .to_str()
+        .unwrap()
+        .parse::<u64>()
+        .unwrap();
+    assert!(retry_after > 0 && retry_after <= 30 * 60);
+    assert_eq!(get("/healthz", None).await.status(), 200);
+
+    // Proxied clients are told apart by the address the proxy added.
+    let proxied = Some("10.0.0.1, 10.0.0.2");
+    assert_eq!(get("/all", proxied).await.status(), 200);
+    assert_eq!(get("/all", Some("10.0.0.9, 10.0.0.2")).await.status(), 200);
+    assert_eq!(get("/all", proxied).await.status(), 429);
+    assert_eq!(get("/all", Some("10.0.0.3")).await.status(), 200);
+
+    // IPv6 clients share the requests of their /64.
+    assert_eq!(get("/all", Some("2001:db8::1")).await.status(), 200);
+    assert_eq!(get("/all", Some("2001:db8::2")).await.status(), 200);
+    assert_eq!(get("/all", Some("2001:db8::3")).await.status(), 429);
+    assert_eq!(get("/all", Some("2001:db8:0:1::1")).await.status(), 200);
+
+    server.stop().await;
+
+    // Rate limiting is off by default.
+    assert!(Config::default().serve.rate_limit.is_none());
+}
+
+#[tokio::test]
+async fn route_cache_durations() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let config: Config = toml::from_str(
+        r#"
+        [serve]
+        cache = "2m"
+
+        [[serve.cache-routes]]
+        prefix = "/all"
+        cache = "60s"
+
+        [[serve.cache-routes]]
+        prefix = "/tag/niche"
+        cache = "10m"
+
+        [[serve.cache-routes]]
+        prefix = "/tag/"
+        cache = "5m"
+        "#,
+    )
+    .unwrap();
+    let durations = config.serve.cache_durations();
+    let seconds = |path: &str| durations.get(path).to_std().as_secs();
+    assert_eq!(seconds("/all"), 60);
+    assert_eq!(seconds("/all/feed?format=rss"), 60);
+    assert_eq!(seconds("/all?before=2025-01-01T00:00:00Z"), 60);
+    // The longest matching prefix wins, matching whole path segments.
+    assert_eq!(seconds("/tag/niche/feed.atom"), 600);
+    assert_eq!(seconds("/tag/nicheish"), 300);
+    assert_eq!(seconds("/tag/rust"), 300);
+    // Other routes use the global duration.
+    assert_eq!(seconds("/allergies"), 120);
+    assert_eq!(seconds("/feed/blog/feed"), 120);
+    let defaults = Config::default();
+    assert_eq!(
+        defaults.serve.cache_durations().get("/all"),
+        &defaults.serve.cache_duration()
+    );
+
+    // Cached entries expire by their route.
+    let cache = Cache::new(
+        CacheDurations::new(slipfeed::Duration::from_seconds(60))
+            .route("/fresh", slipfeed::Duration::from_seconds(0)),
+    );
+    let rebuilds = std::sync::atomic::AtomicUsize::new(0);
+    for uri in ["/fresh/feed", "/fresh/feed", "/all", "/all"] {
+        cache
+            .get(
+                uri,
+                async {
+                    rebuilds.fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+                },
+                CacheBehavior::UseOrWrite,
+            )
+            .await;
+    }
+    assert_eq!(rebuilds.load(std::sync::atomic::Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn serve_new_entries_expire_cache() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    // Caches expire entries when the generation changes.
+    let generation = Arc::new(std::sync::atomic::AtomicU64::new(0));
+    let cache = Cache::new(slipfeed::Duration::from_seconds(3600))
+        .invalidated_by(generation.clone());
+    let rebuilds = std::sync::atomic::AtomicUsize::new(0);
+    let get = async || {
+        cache
+            .get(
+                "/all",
+                async {
+                    rebuilds.fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+                },
+                CacheBehavior::UseOrWrite,
+            )
+            .await
+    };
+    assert_eq!(get().await, 0);
+    assert_eq!(get().await, 0);
+    generation.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    assert_eq!(get().await, 1);
+    assert_eq!(get().await, 1);
+
+    // Served feeds show new entries without waiting for the cache.
+    let path = std::env::temp_dir()
+        .join(format!("slipstream-generation-{}.atom", std::process::id()));
+    let fixture = include_str!("modes/serve/self_test/fixture.atom")
+        .replace("{date}", &chrono::Utc::now().to_rfc3339());
+    std::fs::write(&path, &fixture).unwrap();
+    let config: Config = toml::from_str(&format!(
+        r#"
+        freq = "1s"
+
+        [serve]
+        cache = "1h"
+
+        [feeds.local]
+        url = "{}"
+        freq = "0s"
+        "#,
+        path.display()
+    ))
+    .unwrap();
+    let server = TestServer::start(config).await;
+
+    let wait_for = async |path: &str, title: &str| {
+        for _ in 0..200 {
+            if server.text(path).await.contains(title) {
+                return true;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        false
+    };
+    assert!(wait_for("/all/feed", "Fixture entry one").await);
+    assert!(wait_for("/all", "Fixture entry one").await);
+
+    let entry = format!(
+        "<entry>
+    <title>Fixture entry three</title>
+    <id>urn:slipstream:self-test:3</id>
+    <link href=\"https://example.com/self-test/3\"/>
+    <updated>{}</updated>
+  </entry>
+  <entry>",
+        chrono::Utc::now().to_rfc3339()
+    );
+    std::fs::write(&path, fixture.replacen("<entry>", &entry, 1)).unwrap();
+    assert!(wait_for("/all/feed", "Fixture entry three").await);
+    assert!(wait_for("/all", "Fixture entry three").await);
+    assert!(wait_for("/feed/local/feed", "Fixture entry three").await);
+
+    server.stop().await;
+    std::fs::remove_file(&path).ok();
+}
+
+#[tokio::test]
+async fn like_search() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let path = std::env::temp_dir()
+        .join(format!("slipstream-like-search-{}.db", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    let path = path.to_string_lossy().into_owned();
+    let db = Database::new(&path).await.unwrap();
+    for (title, content, author) in [
+        ("Rust release", "It's 100% faster", "ferris"),
+        ("Zig release", "A new allocator", "ziggy"),
+    ] {
+        let entry = slipfeed::EntryBuilder::new()
+            .title(title)
+            .content(content)
+            .author(author)
+            .source_id(title)
+            .source(format!("https://example.com/{title}"))
+            .build();
+        assert!(db.insert_slipfeed_entry(&entry).await.is_new());
+    }
+    db.close().await;
+
+    // Without full-text search, the same columns are searched with LIKE.
+    let pool = sqlx::SqlitePool::connect(&format!("sqlite://{path}"))
+        .await
+        .unwrap();
+    sqlx::raw_sql(
+        "DROP TRIGGER entries_fts_insert;
+        DROP TRIGGER entries_fts_delete;
+        DROP TRIGGER entries_fts_update;
+        DROP TABLE entries_fts;",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    pool.close().await;
+    let db = Database::new(&path).await.unwrap();
+    let search = async |text: &str| -> Vec<String> {
+        db.get_entries(
+            vec![DatabaseSearch::Search(text.into())],
+            10,
+            OffsetCursor::LatestTimestamp,
+            EntryOrder::Newest,
+        )
+        .await
+        .iter()
+        .map(|entry| entry.entry.title().clone())
+        .collect()
+    };
+    assert_eq!(search("rust").await, vec!["Rust release"]);
+    assert_eq!(search("allocator").await, vec!["Zig release"]);
+    assert_eq!(search("100%").await, vec!["Rust release"]);
+    assert_eq!(search("ziggy").await, vec!["Zig release"]);
+    assert_eq!(search("release").await.len(), 2);
+    db.close().await;
+    std::fs::remove_file(&path).ok();
+}
+
+#[tokio::test]
+async fn database_upgrade() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let path = std::env::temp_dir()
+        .join(format!("slipstream-canonical-{}.db", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    let path = path.to_string_lossy().into_owned();
+    let entry = slipfeed::EntryBuilder::new()
+        .title("Video")
+        .source("https://www.youtube.com/watch?v=one&utm_source=feed")
+        .build();
+    let db = Database::new(&path).await.unwrap();
+    assert!(db.insert_slipfeed_entry(&entry).await.is_new());
+    db.close().await;
+
+    // Databases from v2.23.0 gain flags, feed state, search, and canonical
+    // links for their stored entries.
+    let pool = sqlx::SqlitePool::connect(&format!("sqlite://{path}"))
+        .await
+        .unwrap();
+    sqlx::raw_sql(
+        "DROP TABLE flags;
+        DROP TABLE feed_state;
+        DROP TRIGGER IF EXISTS entries_fts_insert;
+        DROP TRIGGER IF EXISTS entries_fts_delete;
+        DROP TRIGGER IF EXISTS entries_fts_update;
+        DROP TABLE IF EXISTS entries_fts;
+        DROP INDEX entries_canonical_link_idx;
+        ALTER TABLE entries DROP COLUMN canonical_link;
+        DELETE FROM version_history WHERE version = '2.24.0';",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    pool.close().await;
+    Database::new(&path).await.unwrap().close().await;
+    let pool = sqlx::SqlitePool::connect(&format!("sqlite://{path}"))
+        .await
+        .unwrap();
+    let (link,): (String,) =
+        sqlx::query_as("SELECT canonical_link FROM entries")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(link, "youtube.com/watch?v=one");
+    let (saved,): (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM flags WHERE saved = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(saved, 0);
+    let (version,): (String,) = sqlx::query_as(
+        "SELECT version FROM version_history ORDER BY id DESC LIMIT 1",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(version, "2.24.0");
+    pool.close().await;
+    std::fs::remove_file(&path).ok();
+}
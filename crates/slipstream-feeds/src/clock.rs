@@ -0,0 +1,58 @@
+//! Clocks for time-dependent logic.
+
+use super::*;
+
+/// Source of the current time.
+/// The updater reads time from a clock so tests can control it.
+pub trait Clock: std::fmt::Debug + Send + Sync {
+    /// Get the current DateTime.
+    fn now(&self) -> DateTime;
+}
+
+/// Clock using the system time.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime {
+        DateTime::now()
+    }
+}
+
+/// Clock that only moves when told to.
+#[derive(Clone, Debug)]
+pub struct ManualClock {
+    now: Arc<std::sync::Mutex<DateTime>>,
+}
+
+impl ManualClock {
+    /// Create a clock stopped at a time.
+    pub fn new(now: DateTime) -> Self {
+        Self {
+            now: Arc::new(std::sync::Mutex::new(now)),
+        }
+    }
+
+    /// Set the current time.
+    pub fn set(&self, now: DateTime) {
+        if let Ok(mut current) = self.now.lock() {
+            *current = now;
+        }
+    }
+
+    /// Move the clock forward.
+    pub fn advance(&self, duration: Duration) {
+        if let Ok(mut current) = self.now.lock() {
+            *current = current.clone() + duration;
+        }
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime {
+        match self.now.lock() {
+            Ok(now) => now.clone(),
+            Err(e) => e.into_inner().clone(),
+        }
+    }
+}
@@ -24,7 +24,12 @@ impl DateTime {
 
     /// Check whether or not this timestamp has passed.
     pub fn has_passed(&self, duration: &Duration) -> bool {
-        self.0 + duration.0 < DateTime::now().0
+        self.has_passed_at(duration, &DateTime::now())
+    }
+
+    /// Check whether or not this timestamp has passed at a time.
+    pub fn has_passed_at(&self, duration: &Duration, now: &DateTime) -> bool {
+        self.0 + duration.0 < now.0
     }
 
     // pub fn to_std(&self) -> std::time::Instant {
@@ -49,6 +54,44 @@ impl DateTime {
         }
     }
 
+    /// Describe how long ago this was relative to now, e.g. "5m ago".
+    /// This is `None` beyond a week, or for times in the future.
+    pub fn relative_to(&self, now: &DateTime) -> Option<String> {
+        let elapsed = now.0 - self.0;
+        if elapsed < -chrono::Duration::minutes(1)
+            || elapsed > chrono::Duration::weeks(1)
+        {
+            return None;
+        }
+        Some(if elapsed < chrono::Duration::minutes(1) {
+            "just now".to_string()
+        } else if elapsed < chrono::Duration::hours(1) {
+            format!("{}m ago", elapsed.num_minutes())
+        } else if elapsed < chrono::Duration::days(1) {
+            format!("{}h ago", elapsed.num_hours())
+        } else {
+            format!("{}d ago", elapsed.num_days())
+        })
+    }
+
+    /// Format in a timezone with a strftime-style format, e.g. "%H:%M".
+    /// Invalid formats fall back to ISO-8601.
+    pub fn format_in<Tz: chrono::TimeZone>(
+        &self,
+        tz: &Tz,
+        format: &str,
+    ) -> String
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        use std::fmt::Write;
+        let mut formatted = String::new();
+        match write!(formatted, "{}", self.0.with_timezone(tz).format(format)) {
+            Ok(()) => formatted,
+            Err(_) => self.to_iso8601(),
+        }
+    }
+
     /// Convert to ISO-8601 string.
     pub fn to_iso8601(&self) -> String {
         return self.0.format("%+").to_string();
@@ -154,17 +197,17 @@ impl TryFrom<&str> for DateTime {
         {
             return Ok(DateTime(parsed.to_utc()));
         }
-        if let Ok(parsed) = chrono::NaiveDateTime::parse_from_str(
-            date,
-            "%a, %d %b %Y %H:%M:%S %z",
-        ) {
-            return Ok(DateTime(chrono::DateTime::from_naive_utc_and_offset(
-                parsed,
-                chrono::Utc,
-            )));
+        if let Ok(parsed) =
+            chrono::DateTime::<chrono::FixedOffset>::parse_from_str(
+                date,
+                "%a, %d %b %Y %H:%M:%S %z",
+            )
+        {
+            return Ok(DateTime(parsed.to_utc()));
         }
         // https://github.com/chronotope/chrono/issues/1575 %Z GMT not yet supported.
-        let rfc2822_without_tz = date.replace("GMT", "+0000");
+        let rfc2822_without_tz =
+            date.trim().replace("GMT", "+0000").replace("UTC", "+0000");
         if let Ok(parsed) =
             chrono::DateTime::<chrono::FixedOffset>::parse_from_rfc2822(
                 &rfc2822_without_tz,
@@ -172,33 +215,48 @@ impl TryFrom<&str> for DateTime {
         {
             return Ok(DateTime(parsed.to_utc()));
         }
-        if let Ok(parsed) = chrono::NaiveDateTime::parse_from_str(
-            &rfc2822_without_tz,
-            "%a, %d %b %Y %H:%M:%S %z",
-        ) {
-            return Ok(DateTime(chrono::DateTime::from_naive_utc_and_offset(
-                parsed,
-                chrono::Utc,
-            )));
+        if let Ok(parsed) =
+            chrono::DateTime::<chrono::FixedOffset>::parse_from_str(
+                &rfc2822_without_tz,
+                "%a, %d %b %Y %H:%M:%S %z",
+            )
+        {
+            return Ok(DateTime(parsed.to_utc()));
         }
-        if rfc2822_without_tz.len() > 5 {
-            let rfc2822_without_tz_day = &rfc2822_without_tz[5..];
-
-            if let Ok(parsed) = chrono::NaiveDateTime::parse_from_str(
-                rfc2822_without_tz_day,
-                "%d %b %Y %H:%M:%S %z",
-            ) {
-                return Ok(DateTime(
-                    chrono::DateTime::from_naive_utc_and_offset(
-                        parsed,
-                        chrono::Utc,
-                    ),
-                ));
+        // Some feeds use the wrong (or a long) weekday, so skip it.
+        if let Some((_weekday, rfc2822_without_day)) =
+            rfc2822_without_tz.split_once(", ")
+        {
+            if let Ok(parsed) =
+                chrono::DateTime::<chrono::FixedOffset>::parse_from_str(
+                    rfc2822_without_day,
+                    "%d %b %Y %H:%M:%S %z",
+                )
+            {
+                return Ok(DateTime(parsed.to_utc()));
+            }
+            if let Ok(parsed) =
+                chrono::DateTime::<chrono::FixedOffset>::parse_from_str(
+                    rfc2822_without_day,
+                    "%d %b %Y %H:%M %z",
+                )
+            {
+                return Ok(DateTime(parsed.to_utc()));
             }
         }
 
         // iso8601 and variants:
 
+        for format in ["%Y-%m-%dT%H:%M:%S%.f%z", "%Y-%m-%d %H:%M:%S%.f %z"] {
+            if let Ok(parsed) =
+                chrono::DateTime::<chrono::FixedOffset>::parse_from_str(
+                    date, format,
+                )
+            {
+                return Ok(DateTime(parsed.to_utc()));
+            }
+        }
+
         if let Ok(parsed) =
             chrono::NaiveDateTime::parse_from_str(date, "%Y-%m-%dT%H:%M:%SZ")
         {
@@ -327,3 +385,83 @@ async fn if_modified_since() {
     let since = now.to_if_modified_since();
     assert!(DateTime::from_if_modified_since(&since).is_some());
 }
+
+#[tokio::test]
+async fn relative_times() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let now = DateTime::try_from("2025-06-12T12:00:00Z").unwrap();
+    let ago = |seconds: u64| {
+        (now.clone() - Duration::from_seconds(seconds)).relative_to(&now)
+    };
+    assert_eq!(ago(0).as_deref(), Some("just now"));
+    assert_eq!(ago(59).as_deref(), Some("just now"));
+    assert_eq!(ago(5 * 60).as_deref(), Some("5m ago"));
+    assert_eq!(ago(3 * 3600 + 59 * 60).as_deref(), Some("3h ago"));
+    assert_eq!(ago(2 * 86400).as_deref(), Some("2d ago"));
+    assert_eq!(ago(7 * 86400).as_deref(), Some("7d ago"));
+    assert_eq!(ago(8 * 86400), None);
+    assert_eq!(
+        (now.clone() + Duration::from_hours(1)).relative_to(&now),
+        None
+    );
+}
+
+#[tokio::test]
+async fn formatted_dates() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let dt = DateTime::try_from("2025-06-12T12:00:00Z").unwrap();
+    assert_eq!(
+        dt.format_in(&chrono::Utc, "%a %d %b %H:%M"),
+        "Thu 12 Jun 12:00"
+    );
+    let offset = chrono::FixedOffset::east_opt(-5 * 3600).unwrap();
+    assert_eq!(dt.format_in(&offset, "%H:%M %:z"), "07:00 -05:00");
+    assert_eq!(dt.format_in(&chrono::Utc, "%Q"), dt.to_iso8601());
+}
+
+#[tokio::test]
+async fn parse_dates() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let expected = DateTime::from_chrono(
+        chrono::Utc.with_ymd_and_hms(2025, 6, 2, 14, 30, 0).unwrap(),
+    );
+    for date in [
+        "2025-06-02T14:30:00Z",
+        "2025-06-02T14:30:00+00:00",
+        "2025-06-02T20:00:00+05:30",
+        "2025-06-02T14:30:00.000Z",
+        "2025-06-02T10:30:00-0400",
+        "2025-06-02 16:30:00 +0200",
+        "Mon, 02 Jun 2025 14:30:00 GMT",
+        "Mon, 02 Jun 2025 14:30:00 UTC",
+        "Mon, 02 Jun 2025 14:30:00 UT",
+        "Mon, 2 Jun 2025 14:30:00 +0000",
+        "Mon, 02 Jun 2025 10:30:00 EDT",
+        "Mon, 02 Jun 2025 07:30:00 PDT",
+        "Mon, 02 Jun 2025 23:30:00 +0900",
+        "Mon, 02 Jun 2025 04:30:00 -1000",
+        "Monday, 02 Jun 2025 14:30:00 GMT",
+        "Fri, 02 Jun 2025 14:30:00 GMT",
+        "Mon, 02 Jun 2025 14:30 +0000",
+    ] {
+        assert_eq!(DateTime::try_from(date), Ok(expected.clone()), "{date}");
+    }
+
+    assert_eq!(
+        DateTime::try_from("2025-06-02"),
+        Ok(DateTime::from_chrono(
+            chrono::Utc.with_ymd_and_hms(2025, 6, 2, 0, 0, 0).unwrap()
+        ))
+    );
+    for date in [
+        "",
+        "yesterday",
+        "02/06/2025",
+        "Mon, 32 Jun 2025 14:30:00 GMT",
+    ] {
+        assert!(DateTime::try_from(date).is_err(), "{date}");
+    }
+}
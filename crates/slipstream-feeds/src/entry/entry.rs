@@ -5,6 +5,14 @@ use super::*;
 use std::collections::BTreeSet;
 
 /// An entry from a feed.
+///
+/// Entries are identified by the first of these that exists:
+/// 1. The id provided by the source (e.g. an rss guid).
+/// 2. A hash of the (non-empty) content.
+/// 3. The title, author, and source url.
+///
+/// Equality and hashing only consider this identity, so entries from
+/// different feeds can be deduplicated in sets.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Entry {
     // Entry fields.
@@ -25,6 +33,9 @@ pub struct Entry {
     other_links: Vec<Link>,
     /// The icon link.
     icon: Option<Link>,
+    /// The language, as an ISO 639 code.
+    #[serde(default)]
+    language: Option<String>,
     // Meta information.
     /// The id provided by the source.
     source_id: Option<String>,
@@ -80,12 +91,39 @@ impl Entry {
         self.icon.as_ref()
     }
 
+    /// Get the language, as an ISO 639 code.
+    pub fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
+    /// Set the language, normalized to an ISO 639 code.
+    pub fn set_language(&mut self, language: impl AsRef<str>) {
+        let language = normalize_language(language);
+        self.language = match language.is_empty() {
+            true => None,
+            false => Some(language),
+        };
+    }
+
+    /// Replace the content.
+    pub fn set_content(&mut self, content: impl Into<String>) {
+        self.content = content.into();
+    }
+
+    /// Discard the content and extra links.
+    /// This keeps the title, date, and source.
+    pub fn strip_content(&mut self) {
+        self.content.clear();
+        self.other_links.clear();
+    }
+
     /// Get the primary feed, if it exists.
     pub fn set_icon(&mut self, url: impl Into<String>) {
         self.icon = Some(Link {
             url: url.into(),
             title: "Icon".into(),
             mime_type: None,
+            length: None,
         });
     }
 
@@ -169,42 +207,49 @@ impl Entry {
     }
 }
 
-impl PartialEq for Entry {
-    fn eq(&self, other: &Entry) -> bool {
-        if let (Some(id1), Some(id2)) = (&self.source_id, &other.source_id) {
-            return id1 == id2;
-        }
-        if self.title != other.title {
-            return false;
-        }
-        if self.author != other.author {
-            return false;
-        }
-        if self.content != other.content {
-            return false;
-        }
-        if self.source != other.source {
-            return false;
-        }
-        if self.comments != other.comments {
-            return false;
+impl Entry {
+    /// Get a hash of the identity of the entry.
+    pub(crate) fn identity_hash(&self) -> u64 {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.identity().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Get the identity of the entry.
+    fn identity(&self) -> EntryIdentity<'_> {
+        if let Some(source_id) = &self.source_id {
+            return EntryIdentity::SourceId(source_id);
         }
-        if self.other_links.len() != other.other_links.len() {
-            return false;
+        if !self.content.is_empty() {
+            use std::hash::{Hash, Hasher};
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            self.content.hash(&mut hasher);
+            return EntryIdentity::ContentHash(hasher.finish());
         }
-        for i in 0..self.other_links.len() {
-            if self.other_links[i] != other.other_links[i] {
-                return false;
-            }
+        EntryIdentity::Fields {
+            title: &self.title,
+            author: &self.author,
+            url: &self.source.url,
         }
-        if let EntryDate::Parsed(dt1) = &self.date {
-            if let EntryDate::Parsed(dt2) = &other.date {
-                if *dt1 != *dt2 {
-                    return false;
-                }
-            }
-        }
-        true
+    }
+}
+
+/// What uniquely identifies an entry.
+#[derive(PartialEq, Eq, Hash)]
+enum EntryIdentity<'a> {
+    SourceId(&'a str),
+    ContentHash(u64),
+    Fields {
+        title: &'a str,
+        author: &'a str,
+        url: &'a str,
+    },
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Entry) -> bool {
+        self.identity() == other.identity()
     }
 }
 
@@ -224,10 +269,7 @@ impl Ord for Entry {
 
 impl std::hash::Hash for Entry {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.title.hash(state);
-        self.author.hash(state);
-        self.content.hash(state);
-        self.source.url.hash(state);
+        self.identity().hash(state);
     }
 }
 
@@ -242,6 +284,7 @@ impl Default for Entry {
             comments: Link::new("", ""),
             other_links: Vec::new(),
             icon: None,
+            language: None,
             source_id: None,
             primary_feed: None,
             feeds: BTreeSet::new(),
@@ -291,6 +334,7 @@ pub struct EntryBuilder {
     comments: Option<Link>,
     other_links: Vec<Link>,
     icon: Option<Link>,
+    language: Option<String>,
     source_id: Option<String>,
 }
 
@@ -306,6 +350,7 @@ impl EntryBuilder {
             comments: None,
             other_links: Vec::new(),
             icon: None,
+            language: None,
             source_id: None,
         }
     }
@@ -340,6 +385,7 @@ impl EntryBuilder {
             url: url.into(),
             title: "Source".into(),
             mime_type: None,
+            length: None,
         });
         self
     }
@@ -350,6 +396,7 @@ impl EntryBuilder {
             url: url.into(),
             title: "Comments".into(),
             mime_type: None,
+            length: None,
         });
         self
     }
@@ -366,10 +413,30 @@ impl EntryBuilder {
             url: url.into(),
             title: "Icon".into(),
             mime_type: None,
+            length: None,
         });
         self
     }
 
+    /// Set the language, as declared by the source.
+    /// Entries without one have their language detected when built.
+    pub fn language(&mut self, language: impl AsRef<str>) -> &mut Self {
+        let language = normalize_language(language);
+        if !language.is_empty() {
+            self.language = Some(language);
+        }
+        self
+    }
+
+    /// Set the language declared by the feed, unless the entry declared its
+    /// own.
+    pub fn feed_language(&mut self, language: impl AsRef<str>) -> &mut Self {
+        if self.language.is_none() {
+            self.language(language);
+        }
+        self
+    }
+
     /// Set the source id.
     pub fn source_id(&mut self, source_id: impl Into<String>) -> &mut Self {
         self.source_id = Some(source_id.into());
@@ -378,14 +445,19 @@ impl EntryBuilder {
 
     /// Build into an entry.
     pub fn build(&self) -> Entry {
+        let title = self.title.clone().unwrap_or_default();
+        let content = self.content.clone().unwrap_or_default();
+        let language = self.language.clone().or_else(|| {
+            detect_language(&format!("{title}\n{content}")).map(String::from)
+        });
         Entry {
-            title: self.title.clone().unwrap_or_else(|| "".to_string()),
+            title,
             date: self
                 .date
                 .clone()
                 .unwrap_or_else(|| EntryDate::Parsed(DateTime::now())),
             author: self.author.clone().unwrap_or_else(|| "".to_string()),
-            content: self.content.clone().unwrap_or_else(|| "".to_string()),
+            content,
 
             source: self
                 .source
@@ -397,6 +469,7 @@ impl EntryBuilder {
                 .unwrap_or_else(|| Link::new("", "Comments")),
             other_links: self.other_links.clone(),
             icon: self.icon.clone(),
+            language,
 
             source_id: self.source_id.clone(),
             primary_feed: None,
@@ -421,6 +494,9 @@ pub struct Link {
     pub title: String,
     /// The link's mime-type.
     pub mime_type: Option<String>,
+    /// The length of the linked resource in bytes, for enclosures.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length: Option<u64>,
 }
 
 impl Link {
@@ -430,6 +506,7 @@ impl Link {
             url: url.into(),
             title: title.into(),
             mime_type: None,
+            length: None,
         }
     }
 
@@ -443,6 +520,7 @@ impl Link {
             url: url.into(),
             title: title.into(),
             mime_type: Some(mime_type.into()),
+            length: None,
         }
     }
 }
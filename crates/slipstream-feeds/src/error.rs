@@ -0,0 +1,59 @@
+//! Feed errors.
+
+/// Result of a single feed update, containing the number of entries parsed.
+pub type FeedResult = Result<usize, FeedError>;
+
+/// Errors a feed can encounter when updating.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FeedError {
+    /// The feed could not be fetched.
+    Network(String),
+    /// The feed file could not be read.
+    Io(String),
+    /// The feed could not be parsed.
+    Parse(String),
+    /// The feed took too long to update.
+    Timeout,
+    /// The feed source is rate limiting requests.
+    RateLimited,
+    /// The feed body could not be decoded.
+    Decode(String),
+    /// The feed is not configured correctly.
+    Config(String),
+    /// The feed body is larger than the limit, in bytes.
+    TooLarge(usize),
+}
+
+impl std::fmt::Display for FeedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FeedError::Network(e) => write!(f, "network error: {e}"),
+            FeedError::Io(e) => write!(f, "io error: {e}"),
+            FeedError::Parse(e) => write!(f, "parse error: {e}"),
+            FeedError::Timeout => write!(f, "timed out"),
+            FeedError::RateLimited => write!(f, "rate limited"),
+            FeedError::Decode(e) => write!(f, "decode error: {e}"),
+            FeedError::Config(e) => write!(f, "config error: {e}"),
+            FeedError::TooLarge(limit) => {
+                write!(f, "body is larger than {limit} bytes")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FeedError {}
+
+impl From<reqwest::Error> for FeedError {
+    fn from(value: reqwest::Error) -> Self {
+        if value.is_timeout() {
+            return FeedError::Timeout;
+        }
+        if value.is_decode() {
+            return FeedError::Decode(value.to_string());
+        }
+        if let Some(reqwest::StatusCode::TOO_MANY_REQUESTS) = value.status() {
+            return FeedError::RateLimited;
+        }
+        FeedError::Network(value.to_string())
+    }
+}
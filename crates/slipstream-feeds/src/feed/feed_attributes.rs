@@ -24,6 +24,31 @@ pub struct FeedAttributes {
     pub keep_empty: bool,
     /// Whether to apply tags from the source.
     pub apply_tags: bool,
+    /// Format to parse the feed as, instead of detecting it.
+    pub format: Option<FeedFormat>,
+    /// Charset to decode the feed with, instead of detecting it.
+    pub charset: Option<String>,
+    /// Timeout for each request, overriding the updater's default.
+    pub request_timeout: Option<Duration>,
+    /// Whether to only keep titles, discarding content and extra links.
+    pub titles_only: bool,
+    /// Largest body read from the feed, in bytes.
+    pub max_body_size: usize,
+    /// Whether to replace entry content with the full article from its
+    /// source.
+    pub full_content: bool,
+}
+
+/// Largest body read from a feed by default, in bytes.
+pub const DEFAULT_MAX_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+/// Formats a feed can be parsed as.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedFormat {
+    Rss,
+    Atom,
+    Json,
 }
 
 impl FeedAttributes {
@@ -39,6 +64,12 @@ impl FeedAttributes {
             filters: Vec::new(),
             keep_empty: false,
             apply_tags: true,
+            format: None,
+            charset: None,
+            request_timeout: None,
+            titles_only: false,
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+            full_content: false,
         }
     }
 
@@ -0,0 +1,13 @@
+//! Feed state.
+
+use super::*;
+
+/// State kept about a feed between updates, which lets fetches be
+/// conditional.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeedState {
+    /// When the feed was last updated successfully.
+    pub last_update: Option<DateTime>,
+    /// The ETag of the feed's last successful response.
+    pub etag: Option<String>,
+}
@@ -8,8 +8,15 @@ use downcast_rs::{DowncastSync, impl_downcast};
 #[feed_trait]
 pub trait Feed: std::fmt::Debug + Send + Sync + DowncastSync + 'static {
     /// Fetch items from the feed.
+    /// On success, this returns the number of entries sent to the updater.
     #[allow(unused_variables)]
-    async fn update(&mut self, ctx: &UpdaterContext, attr: &FeedAttributes) {}
+    async fn update(
+        &mut self,
+        ctx: &UpdaterContext,
+        attr: &FeedAttributes,
+    ) -> FeedResult {
+        Ok(0)
+    }
 
     /// Tag fetched entry. This serves as a method for other feeds to edit and claim
     /// ownership of other entries.
@@ -26,6 +33,21 @@ pub trait Feed: std::fmt::Debug + Send + Sync + DowncastSync + 'static {
             }
         }
     }
+
+    /// Get the url the feed is fetched from, if it has one.
+    fn url(&self) -> Option<&str> {
+        None
+    }
+
+    /// Get the ETag of the feed's last successful response, if any.
+    fn etag(&self) -> Option<&str> {
+        None
+    }
+
+    /// Set the ETag sent with the next request, such as one stored before a
+    /// restart.
+    #[allow(unused_variables)]
+    fn set_etag(&mut self, etag: Option<String>) {}
 }
 
 impl_downcast!(sync Feed);
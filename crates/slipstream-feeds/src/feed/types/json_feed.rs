@@ -0,0 +1,243 @@
+//! JSON Feed (https://www.jsonfeed.org).
+
+use super::*;
+
+/// A reference to a JSON Feed.
+#[derive(Clone, Debug)]
+pub struct JsonFeed {
+    /// The syndication used to read the feed body.
+    syndication: StandardSyndication,
+}
+
+impl JsonFeed {
+    /// Create a new json feed.
+    pub fn new(url: impl Into<String>) -> Box<Self> {
+        Box::new(Self {
+            syndication: *StandardSyndication::new(url),
+        })
+    }
+
+    /// Parse a json feed from the body text.
+    pub(crate) fn parse(
+        body: &str,
+        ctx: &UpdaterContext,
+        attr: &FeedAttributes,
+        tx: UnboundedSender<Entry>,
+    ) -> Result<(), FeedError> {
+        let document: JsonFeedDocument = match serde_json::from_str(body) {
+            Ok(document) => document,
+            Err(e) => {
+                tracing::warn!("Unable to parse json feed: {e}");
+                return Err(FeedError::Parse(e.to_string()));
+            }
+        };
+
+        let icon = document.icon.as_ref().or(document.favicon.as_ref());
+        for item in document.items.iter() {
+            let mut entry = JsonFeed::parse_item(item, &document, ctx, attr);
+            if !attr.keep_empty && entry.title().is_empty() {
+                continue;
+            }
+            if entry.icon().is_none() {
+                if let Some(icon) = icon {
+                    entry.set_icon(icon);
+                }
+            }
+            tx.send(entry).ok();
+        }
+
+        Ok(())
+    }
+
+    /// Parse a json feed item.
+    fn parse_item(
+        item: &JsonFeedItem,
+        document: &JsonFeedDocument,
+        ctx: &UpdaterContext,
+        attr: &FeedAttributes,
+    ) -> Entry {
+        let mut parsed = EntryBuilder::new();
+        parsed
+            .title(item.title.as_deref().unwrap_or(""))
+            .date('date: {
+                let dates = [&item.date_published, &item.date_modified];
+                for date in dates.into_iter().flatten() {
+                    match DateTime::try_from(date.as_str()) {
+                        Ok(dt) => break 'date dt,
+                        Err(_) => tracing::warn!(
+                            "Unable to parse date `{}` for `{}`",
+                            date,
+                            item.title.as_deref().unwrap_or("")
+                        ),
+                    }
+                }
+
+                ctx.parse_time.clone()
+            })
+            .author({
+                // Items inherit the feed authors if they have none.
+                let authors = match item.authors() {
+                    authors if authors.is_empty() => document.authors(),
+                    authors => authors,
+                };
+                authors
+                    .iter()
+                    .filter_map(|author| author.name.as_deref())
+                    .fold("".to_string(), |acc, name| format!("{acc} {name}"))
+                    .trim()
+                    .to_string()
+            })
+            .content(match (&item.content_html, &item.content_text) {
+                (Some(html), _) => htmd::convert(html).unwrap_or(html.clone()),
+                (None, Some(text)) => text.clone(),
+                (None, None) => item.summary.clone().unwrap_or_default(),
+            });
+        match (&item.url, &item.external_url) {
+            (Some(url), external_url) => {
+                parsed.source(url);
+                if let Some(external_url) = external_url {
+                    parsed.other_link(Link::new(external_url, "External"));
+                }
+            }
+            (None, Some(external_url)) => {
+                parsed.source(external_url);
+            }
+            (None, None) => {}
+        }
+        if let Some(id) = item.id() {
+            parsed.source_id(id);
+        }
+        if let Some(image) = &item.image {
+            parsed.icon(image);
+        }
+
+        let mut entry = parsed.build();
+
+        if attr.apply_tags {
+            for tag in item.tags.iter() {
+                entry.add_tag(&Tag::new(tag));
+            }
+        }
+
+        entry
+    }
+}
+
+/// Top-level json feed object.
+#[derive(Deserialize)]
+struct JsonFeedDocument {
+    #[serde(default)]
+    items: Vec<JsonFeedItem>,
+    #[serde(default)]
+    icon: Option<String>,
+    #[serde(default)]
+    favicon: Option<String>,
+    #[serde(default)]
+    authors: Vec<JsonFeedAuthor>,
+    /// Deprecated in 1.1, but still common.
+    #[serde(default)]
+    author: Option<JsonFeedAuthor>,
+}
+
+impl JsonFeedDocument {
+    /// Get the feed authors.
+    fn authors(&self) -> Vec<&JsonFeedAuthor> {
+        self.authors.iter().chain(self.author.iter()).collect()
+    }
+}
+
+/// Json feed item.
+#[derive(Deserialize)]
+struct JsonFeedItem {
+    /// The id is required, but some feeds use numbers.
+    #[serde(default)]
+    id: Option<serde_json::Value>,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    external_url: Option<String>,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    content_html: Option<String>,
+    #[serde(default)]
+    content_text: Option<String>,
+    #[serde(default)]
+    summary: Option<String>,
+    #[serde(default)]
+    image: Option<String>,
+    #[serde(default)]
+    date_published: Option<String>,
+    #[serde(default)]
+    date_modified: Option<String>,
+    #[serde(default)]
+    authors: Vec<JsonFeedAuthor>,
+    /// Deprecated in 1.1, but still common.
+    #[serde(default)]
+    author: Option<JsonFeedAuthor>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+impl JsonFeedItem {
+    /// Get the item id as a string.
+    fn id(&self) -> Option<String> {
+        match self.id.as_ref()? {
+            serde_json::Value::String(id) if !id.is_empty() => Some(id.clone()),
+            serde_json::Value::Number(id) => Some(id.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Get the item authors.
+    fn authors(&self) -> Vec<&JsonFeedAuthor> {
+        self.authors.iter().chain(self.author.iter()).collect()
+    }
+}
+
+/// Json feed author.
+#[derive(Deserialize)]
+struct JsonFeedAuthor {
+    #[serde(default)]
+    name: Option<String>,
+}
+
+impl Hash for JsonFeed {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.syndication.hash(state);
+    }
+}
+
+#[feed_trait]
+impl Feed for JsonFeed {
+    async fn update(
+        &mut self,
+        ctx: &UpdaterContext,
+        attr: &FeedAttributes,
+    ) -> FeedResult {
+        let (tx, rx) = unbounded_channel();
+        match self.syndication.read(ctx, attr).await? {
+            Some(body) => JsonFeed::parse(body.as_str(), ctx, attr, tx)?,
+            None => return Ok(0),
+        }
+        Ok(forward_entries(self, rx, ctx, attr))
+    }
+
+    fn url(&self) -> Option<&str> {
+        self.syndication.url()
+    }
+
+    fn etag(&self) -> Option<&str> {
+        self.syndication.etag()
+    }
+
+    fn set_etag(&mut self, etag: Option<String>) {
+        self.syndication.set_etag(etag);
+    }
+}
+
+impl std::fmt::Display for JsonFeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<JsonFeed url={}>", &self.syndication.url)
+    }
+}
@@ -48,6 +48,9 @@ struct MastodonTagSchema {
     name: String,
 }
 
+/// Default number of pages of statuses fetched per update.
+const DEFAULT_MAX_PAGES: usize = 3;
+
 /// A mastodon status feed.
 #[derive(Clone, Debug)]
 pub struct MastodonFeed {
@@ -57,6 +60,8 @@ pub struct MastodonFeed {
     feed_type: MastodonFeedType,
     /// The auth header token.
     token: Option<String>,
+    /// Maximum number of pages fetched per update.
+    max_pages: usize,
 }
 
 impl MastodonFeed {
@@ -67,75 +72,137 @@ impl MastodonFeed {
         token: Option<String>,
     ) -> Box<Self> {
         let mut instance_url: String = url.into();
-        if !instance_url.starts_with("https://") {
+        if !instance_url.contains("://") {
             instance_url = format!("https://{instance_url}");
         }
         return Box::new(Self {
             instance_url,
             feed_type,
             token,
+            max_pages: DEFAULT_MAX_PAGES,
         });
     }
 
-    /// Grab body from endpoint.
+    /// Set the maximum number of pages fetched per update.
+    pub fn set_max_pages(&mut self, max_pages: usize) {
+        self.max_pages = max_pages.max(1);
+    }
+
+    /// Find the `rel="next"` url of a `Link` header.
+    fn next_link(header: &str) -> Option<String> {
+        header.split(',').find_map(|link| {
+            let (url, params) = link.split_once(';')?;
+            let is_next = params
+                .split(';')
+                .any(|param| param.trim().replace('"', "") == "rel=next");
+            match is_next {
+                true => Some(
+                    url.trim()
+                        .trim_start_matches('<')
+                        .trim_end_matches('>')
+                        .into(),
+                ),
+                false => None,
+            }
+        })
+    }
+
+    /// Build the request headers, with the auth header if there is a token.
+    fn request_headers(
+        &self,
+        attr: &FeedAttributes,
+    ) -> reqwest::header::HeaderMap {
+        let mut headers = headers(attr);
+        if let Some(token) = &self.token {
+            let auth = reqwest::header::HeaderValue::from_str(&format!(
+                "Bearer {}",
+                token
+            ));
+            match auth {
+                Ok(auth) => {
+                    headers.insert(reqwest::header::AUTHORIZATION, auth);
+                }
+                Err(e) => {
+                    tracing::error!("Failed to set auth header: {e}");
+                }
+            }
+        }
+        headers
+    }
+
+    /// Grab body and the next page from endpoint, with the shared client.
     async fn fetch(
-        client: &mut reqwest::Client,
+        &self,
+        ctx: &UpdaterContext,
+        attr: &FeedAttributes,
         endpoint: &str,
-    ) -> Option<String> {
-        let request_builder = client.get(endpoint);
+    ) -> Result<(String, Option<String>), FeedError> {
+        let mut request_builder =
+            ctx.client.get(endpoint).headers(self.request_headers(attr));
+        if let Some(timeout) = ctx.request_timeout.as_ref() {
+            request_builder = request_builder.timeout(timeout.to_std());
+        }
         let request = match request_builder.build() {
             Ok(request) => request,
             Err(e) => {
                 tracing::error!("Unable to build request: {e}");
-                return None;
+                return Err(e.into());
             }
         };
 
-        return match client.execute(request).await {
-            Ok(resp) => match resp.text().await {
-                Ok(body) => Some(body),
-                Err(e) => {
-                    tracing::error!("Failed to parse body: {e}");
-                    return None;
-                }
-            },
+        let resp = match ctx.client.execute(request).await {
+            Ok(resp) => resp,
             Err(e) => {
                 tracing::error!("Failed to execute: {e}");
-                return None;
+                return Err(e.into());
             }
         };
+        if resp.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            tracing::warn!("Rate limited by {endpoint}");
+            return Err(FeedError::RateLimited);
+        }
+        let next = resp
+            .headers()
+            .get(reqwest::header::LINK)
+            .and_then(|link| link.to_str().ok())
+            .and_then(MastodonFeed::next_link);
+        match read_body(resp, attr.max_body_size, None).await {
+            Ok(body) => Ok((body, next)),
+            Err(e) => {
+                tracing::error!("Failed to parse body: {e}");
+                Err(e)
+            }
+        }
     }
 
     /// Fetch account id from username.
     async fn get_account_id(
         &mut self,
-        client: &mut reqwest::Client,
+        ctx: &UpdaterContext,
+        attr: &FeedAttributes,
         username: &str,
-    ) -> Option<String> {
-        if let Some(body) = MastodonFeed::fetch(
-            client,
-            &format!(
-                "{}/api/v1/accounts/search?q={}",
-                &self.instance_url, username
-            ),
-        )
-        .await
+    ) -> Result<String, FeedError> {
+        let endpoint = format!(
+            "{}/api/v1/accounts/search?q={}",
+            &self.instance_url, username
+        );
+        let (body, _) = self.fetch(ctx, attr, &endpoint).await?;
+        let accounts = match serde_json::from_str::<
+            MastodonAccountSearchResponseSchema,
+        >(&body)
         {
-            let accounts = match serde_json::from_str::<
-                MastodonAccountSearchResponseSchema,
-            >(&body)
-            {
-                Ok(data) => data,
-                Err(e) => {
-                    tracing::error!("Failed to parse the accounts: {e}");
-                    return None;
-                }
-            };
-            for account in accounts.0 {
-                return Some(account.id);
+            Ok(data) => data,
+            Err(e) => {
+                tracing::error!("Failed to parse the accounts: {e}");
+                return Err(FeedError::Parse(e.to_string()));
             }
+        };
+        match accounts.0.into_iter().next() {
+            Some(account) => Ok(account.id),
+            None => Err(FeedError::Parse(format!(
+                "No account found for {username}"
+            ))),
         }
-        return None;
     }
 
     /// Parse status to entry.
@@ -209,28 +276,35 @@ impl MastodonFeed {
     }
 
     /// Parse entries from response body.
+    /// Returns true once there are no newer statuses to page through.
     fn parse_statuses(
         &self,
         body: &str,
         ctx: &UpdaterContext,
         attr: &FeedAttributes,
-        tx: UnboundedSender<Entry>,
-    ) {
+        tx: &UnboundedSender<Entry>,
+    ) -> Result<bool, FeedError> {
         let statuses =
             match serde_json::from_str::<MastodonStatusReponseSchema>(body) {
                 Ok(data) => data,
                 Err(e) => {
                     tracing::error!("Failed to parse the statuses: {e}");
-                    return;
+                    return Err(FeedError::Parse(e.to_string()));
                 }
             };
 
         tracing::trace!("Parsed {:?} as mastodon", self);
+        let oldest = ctx.parse_time.clone() - attr.timeout.clone();
+        let mut reached_oldest = statuses.0.is_empty();
         for status in statuses.0.iter() {
+            if let Ok(date) = DateTime::try_from(&status.created_at) {
+                reached_oldest |= date < oldest;
+            }
             if let Some(entry) = self.parse_status(status, ctx, attr) {
                 tx.send(entry).ok();
             }
         }
+        Ok(reached_oldest)
     }
 }
 
@@ -242,85 +316,79 @@ impl Hash for MastodonFeed {
 
 #[feed_trait]
 impl Feed for MastodonFeed {
-    async fn update(&mut self, ctx: &UpdaterContext, attr: &FeedAttributes) {
-        // Generate request.
-        let mut client_builder = reqwest::ClientBuilder::new();
-
-        // Set auth header.
-        if let Some(token) = &self.token {
-            let auth = reqwest::header::HeaderValue::from_str(&format!(
-                "Bearer {}",
-                token
-            ));
-            match auth {
-                Ok(auth) => {
-                    let mut headers = reqwest::header::HeaderMap::new();
-                    headers.append(reqwest::header::AUTHORIZATION, auth);
-                    client_builder = client_builder.default_headers(headers);
-                }
-                Err(e) => {
-                    tracing::error!("Failed to set auth header: {e}");
-                }
-            }
+    async fn update(
+        &mut self,
+        ctx: &UpdaterContext,
+        attr: &FeedAttributes,
+    ) -> FeedResult {
+        // Private timelines can only be read with a token.
+        if self.feed_type.needs_token() && self.token.is_none() {
+            tracing::warn!("{} requires a token", self);
+            return Err(FeedError::Config(format!(
+                "mastodon {:?} requires a token",
+                self.feed_type
+            )));
         }
 
-        let mut client = match client_builder.build() {
-            Ok(client) => client,
-            Err(e) => {
-                tracing::warn!("Unable to build client: {e}");
-                return;
-            }
-        };
-
         // Execute request and parse.
         let (tx, mut rx) = unbounded_channel();
         let feed_type = self.feed_type.clone();
-        match &feed_type {
+        let endpoint = match &feed_type {
             MastodonFeedType::PublicTimeline => {
-                if let Some(body) = MastodonFeed::fetch(
-                    &mut client,
-                    &format!("{}/api/v1/timelines/public", &self.instance_url),
-                )
-                .await
-                {
-                    self.parse_statuses(&body, &ctx, attr, tx);
-                }
+                format!("{}/api/v1/timelines/public", &self.instance_url)
             }
             MastodonFeedType::HomeTimeline => {
-                if let Some(body) = MastodonFeed::fetch(
-                    &mut client,
-                    &format!("{}/api/v1/timelines/home", &self.instance_url),
-                )
-                .await
-                {
-                    self.parse_statuses(&body, &ctx, attr, tx);
-                }
+                format!("{}/api/v1/timelines/home", &self.instance_url)
             }
+            MastodonFeedType::HashtagTimeline { tag } => format!(
+                "{}/api/v1/timelines/tag/{}",
+                &self.instance_url,
+                tag.trim_start_matches('#')
+            ),
+            MastodonFeedType::ListTimeline { list_id } => format!(
+                "{}/api/v1/timelines/list/{}",
+                &self.instance_url, list_id
+            ),
             MastodonFeedType::UserStatuses { user, id } => {
                 let id: String = match id {
                     Some(id) => id.clone(),
-                    None => {
-                        match self.get_account_id(&mut client, user).await {
-                            Some(id) => id,
-                            None => return,
-                        }
-                    }
+                    None => self.get_account_id(ctx, attr, user).await?,
                 };
-                if let Some(body) = MastodonFeed::fetch(
-                    &mut client,
-                    &format!(
-                        "{}/api/v1/accounts/{}/statuses",
-                        &self.instance_url, &id
-                    ),
+                format!(
+                    "{}/api/v1/accounts/{}/statuses",
+                    &self.instance_url, &id
                 )
-                .await
-                {
-                    self.parse_statuses(&body, &ctx, attr, tx);
+            }
+        };
+
+        // Walk back through pages until reaching old statuses.
+        let mut next = Some(endpoint);
+        let mut pages: usize = 0;
+        while let Some(endpoint) = next.take() {
+            if pages >= self.max_pages {
+                tracing::debug!("{} reached the page limit", self);
+                break;
+            }
+            // Keep the statuses of earlier pages if a later one fails.
+            let page = match self.fetch(ctx, attr, &endpoint).await {
+                Ok((body, next_page)) => self
+                    .parse_statuses(&body, ctx, attr, &tx)
+                    .map(|reached_oldest| (reached_oldest, next_page)),
+                Err(e) => Err(e),
+            };
+            pages += 1;
+            match page {
+                Ok((false, next_page)) => next = next_page,
+                Ok((true, _)) => {}
+                Err(e) if pages > 1 => {
+                    tracing::warn!("Failed to read page {endpoint}: {e}");
                 }
+                Err(e) => return Err(e),
             }
         }
 
         // Forward the matching entries.
+        let mut count: usize = 0;
         while let Ok(entry) = rx.try_recv() {
             let too_old =
                 *entry.date() < ctx.parse_time.clone() - attr.timeout.clone();
@@ -342,7 +410,10 @@ impl Feed for MastodonFeed {
                     },
                 ))
                 .ok();
+            count += 1;
         }
+
+        Ok(count)
     }
 }
 
@@ -13,4 +13,17 @@ pub enum MastodonFeedType {
     PublicTimeline,
     HomeTimeline,
     UserStatuses { user: String, id: Option<String> },
+    HashtagTimeline { tag: String },
+    ListTimeline { list_id: String },
+}
+
+impl MastodonFeedType {
+    /// Check if the timeline is private to the account of the token.
+    pub fn needs_token(&self) -> bool {
+        matches!(
+            self,
+            MastodonFeedType::HomeTimeline
+                | MastodonFeedType::ListTimeline { .. }
+        )
+    }
 }
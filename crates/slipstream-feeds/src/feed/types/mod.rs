@@ -2,8 +2,14 @@
 
 use super::*;
 
+mod json_feed;
 mod mastodon;
+mod reddit;
 mod standard_syndication;
+mod youtube;
 
+pub use json_feed::*;
 pub use mastodon::*;
+pub use reddit::*;
 pub use standard_syndication::*;
+pub use youtube::*;
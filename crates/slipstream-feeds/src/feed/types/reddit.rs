@@ -0,0 +1,216 @@
+//! Reddit subreddit feeds.
+
+use super::*;
+
+/// User agent for reddit requests without one, since reddit rate limits
+/// generic user agents.
+const REDDIT_USER_AGENT: &str = concat!(
+    "slipfeed/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/HarrisonHall/slipstream)"
+);
+
+/// Sort order of a subreddit listing.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum RedditSort {
+    #[default]
+    Hot,
+    New,
+    Top,
+}
+
+impl std::fmt::Display for RedditSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RedditSort::Hot => write!(f, "hot"),
+            RedditSort::New => write!(f, "new"),
+            RedditSort::Top => write!(f, "top"),
+        }
+    }
+}
+
+/// A reference to a subreddit.
+#[derive(Clone, Debug)]
+pub struct RedditFeed {
+    /// The syndication used to read the listing.
+    syndication: StandardSyndication,
+}
+
+impl RedditFeed {
+    /// Create a new reddit feed.
+    /// The subreddit may be a name (`rust` or `r/rust`) or a listing url.
+    pub fn new(subreddit: impl AsRef<str>, sort: RedditSort) -> Box<Self> {
+        Box::new(Self {
+            syndication: *StandardSyndication::new(RedditFeed::listing_url(
+                subreddit.as_ref(),
+                sort,
+            )),
+        })
+    }
+
+    /// Get the json listing url of a subreddit.
+    pub fn listing_url(subreddit: &str, sort: RedditSort) -> String {
+        let subreddit = subreddit.trim().trim_end_matches('/');
+        if subreddit.contains("://") {
+            return subreddit.to_string();
+        }
+        let subreddit =
+            subreddit.trim_start_matches('/').trim_start_matches("r/");
+        format!("https://www.reddit.com/r/{subreddit}/{sort}.json")
+    }
+
+    /// Parse a subreddit listing from the body text.
+    /// Bodies that are not listings (like html error pages) produce no entries.
+    pub(crate) fn parse(
+        body: &str,
+        attr: &FeedAttributes,
+        tx: UnboundedSender<Entry>,
+    ) -> Result<(), FeedError> {
+        let listing: RedditListing = match serde_json::from_str(body) {
+            Ok(listing) => listing,
+            Err(e) => {
+                tracing::warn!("Unable to parse reddit listing: {e}");
+                return Ok(());
+            }
+        };
+        for child in listing.data.children.iter() {
+            let entry = RedditFeed::parse_post(&child.data, attr);
+            if !attr.keep_empty && entry.title().is_empty() {
+                continue;
+            }
+            tx.send(entry).ok();
+        }
+        Ok(())
+    }
+
+    /// Parse a reddit post.
+    fn parse_post(post: &RedditPost, attr: &FeedAttributes) -> Entry {
+        let permalink = format!("https://www.reddit.com{}", post.permalink);
+        let mut parsed = EntryBuilder::new();
+        parsed
+            .title(post.title.as_str())
+            .author(post.author.as_str())
+            .content(post.selftext.as_str())
+            .source(match &post.url {
+                Some(url) if !url.is_empty() => url.as_str(),
+                _ => permalink.as_str(),
+            })
+            .comments(permalink.as_str())
+            .source_id(post.name.as_str());
+        if let Some(created) = post.created_utc {
+            parsed.date(DateTime::from_unix_timestamp_s(created as u64));
+        }
+        if let Some(thumbnail) = &post.thumbnail {
+            if thumbnail.starts_with("http") {
+                parsed.icon(thumbnail);
+            }
+        }
+
+        let mut entry = parsed.build();
+
+        if attr.apply_tags {
+            if let Some(flair) = &post.link_flair_text {
+                if !flair.trim().is_empty() {
+                    entry.add_tag(&Tag::new(flair.trim()));
+                }
+            }
+        }
+
+        entry
+    }
+}
+
+/// Reddit listing.
+#[derive(Deserialize)]
+struct RedditListing {
+    data: RedditListingData,
+}
+
+/// Reddit listing data.
+#[derive(Deserialize)]
+struct RedditListingData {
+    #[serde(default)]
+    children: Vec<RedditChild>,
+}
+
+/// Reddit listing child.
+#[derive(Deserialize)]
+struct RedditChild {
+    data: RedditPost,
+}
+
+/// Reddit post.
+#[derive(Deserialize)]
+struct RedditPost {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    author: String,
+    #[serde(default)]
+    selftext: String,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    permalink: String,
+    #[serde(default)]
+    created_utc: Option<f64>,
+    #[serde(default)]
+    thumbnail: Option<String>,
+    #[serde(default)]
+    link_flair_text: Option<String>,
+}
+
+impl Hash for RedditFeed {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.syndication.hash(state);
+    }
+}
+
+#[feed_trait]
+impl Feed for RedditFeed {
+    async fn update(
+        &mut self,
+        ctx: &UpdaterContext,
+        attr: &FeedAttributes,
+    ) -> FeedResult {
+        let mut reddit_attr = attr.clone();
+        if !attr
+            .headers
+            .keys()
+            .any(|header| header.eq_ignore_ascii_case("user-agent"))
+        {
+            reddit_attr
+                .headers
+                .insert("user-agent".into(), REDDIT_USER_AGENT.into());
+        }
+        let (tx, rx) = unbounded_channel();
+        match self.syndication.read(ctx, &reddit_attr).await? {
+            Some(body) => RedditFeed::parse(body.as_str(), attr, tx)?,
+            None => return Ok(0),
+        }
+        Ok(forward_entries(self, rx, ctx, attr))
+    }
+
+    fn url(&self) -> Option<&str> {
+        self.syndication.url()
+    }
+
+    fn etag(&self) -> Option<&str> {
+        self.syndication.etag()
+    }
+
+    fn set_etag(&mut self, etag: Option<String>) {
+        self.syndication.set_etag(etag);
+    }
+}
+
+impl std::fmt::Display for RedditFeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<RedditFeed url={}>", &self.syndication.url)
+    }
+}
@@ -2,17 +2,196 @@
 
 use super::*;
 
+/// Longest `Retry-After`, in seconds, waited for within an update.
+/// Longer waits would hold up every other feed, so they're left to the next
+/// update instead.
+const MAX_RETRY_AFTER: u64 = 60;
+
 /// A reference to an RSS/Atom feed.
 #[derive(Clone, Debug)]
 pub struct StandardSyndication {
     /// The url of the feed.
-    url: String,
+    pub(crate) url: String,
+    /// The ETag of the last successful response.
+    etag: Option<String>,
 }
 
 impl StandardSyndication {
     /// Create a new standard syndication.
     pub fn new(url: impl Into<String>) -> Box<Self> {
-        return Box::new(Self { url: url.into() });
+        return Box::new(Self {
+            url: url.into(),
+            etag: None,
+        });
+    }
+
+    /// Get the local path of the feed, for `file://` urls and plain paths.
+    fn local_path(&self) -> Option<&str> {
+        match self.url.strip_prefix("file://") {
+            Some(path) => Some(path),
+            None if !self.url.contains("://") => Some(self.url.as_str()),
+            None => None,
+        }
+    }
+
+    /// Read the feed body from disk or over http.
+    /// This returns `None` if the feed has not been modified.
+    pub(crate) async fn read(
+        &mut self,
+        ctx: &UpdaterContext,
+        attr: &FeedAttributes,
+    ) -> Result<Option<String>, FeedError> {
+        if let Some(filename) = self.local_path() {
+            return match tokio::fs::read(filename).await {
+                Ok(buf) => match &attr.charset {
+                    Some(charset) => decode(&buf, charset).map(Some),
+                    None => match String::from_utf8(buf) {
+                        Ok(body) => Ok(Some(body)),
+                        Err(_) => {
+                            tracing::warn!(
+                                "Unable to read binary file `{filename}`."
+                            );
+                            Err(FeedError::Decode(format!(
+                                "binary file `{filename}`"
+                            )))
+                        }
+                    },
+                },
+                Err(e) => {
+                    tracing::warn!("Unable to read file `{filename}`: {e}");
+                    Err(FeedError::Io(e.to_string()))
+                }
+            };
+        }
+
+        // Execute request.
+        self.fetch(ctx, attr).await
+    }
+
+    /// Fetch the feed body, retrying transient failures.
+    /// This returns `None` if the feed has not been modified.
+    async fn fetch(
+        &mut self,
+        ctx: &UpdaterContext,
+        attr: &FeedAttributes,
+    ) -> Result<Option<String>, FeedError> {
+        let mut attempt: usize = 0;
+        loop {
+            let failure = match self.fetch_once(ctx, attr).await {
+                Ok(body) => return Ok(body),
+                Err(failure) => failure,
+            };
+            let delay = match failure.retry_after {
+                Some(delay) if attempt < ctx.retries => delay,
+                _ => return Err(failure.error),
+            };
+            attempt += 1;
+            tracing::debug!(
+                "Retrying {} ({}/{}): {}",
+                &self.url,
+                attempt,
+                ctx.retries,
+                failure.error
+            );
+            tokio::time::sleep(delay.to_std()).await;
+        }
+    }
+
+    /// Make a single request for the feed body.
+    async fn fetch_once(
+        &mut self,
+        ctx: &UpdaterContext,
+        attr: &FeedAttributes,
+    ) -> Result<Option<String>, FetchFailure> {
+        let mut request_builder =
+            ctx.client.get(&self.url).headers(headers(attr));
+        if let Some(timeout) = ctx.request_timeout.as_ref() {
+            request_builder = request_builder.timeout(timeout.to_std());
+        }
+        if let Some(last_update) = ctx.last_update.as_ref() {
+            request_builder = request_builder.header(
+                reqwest::header::IF_MODIFIED_SINCE,
+                last_update.to_if_modified_since(),
+            );
+        };
+        if let Some(etag) = self.etag.as_ref() {
+            request_builder =
+                request_builder.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        let request = match request_builder.build() {
+            Ok(request) => request,
+            Err(e) => {
+                tracing::warn!("Unable to build request: {e}");
+                return Err(FetchFailure::fatal(e.into()));
+            }
+        };
+
+        let response = match ctx.client.execute(request).await {
+            Ok(response) => response,
+            Err(e) => {
+                tracing::error!("Failed to execute: {e}");
+                let transient =
+                    e.is_timeout() || e.is_connect() || e.is_request();
+                return Err(FetchFailure {
+                    error: e.into(),
+                    retry_after: transient.then(|| ctx.retry_delay.clone()),
+                });
+            }
+        };
+
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_MODIFIED {
+            tracing::debug!("{} has not been modified", &self.url);
+            return Ok(None);
+        }
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            tracing::warn!(url = %self.url, "Rate limited");
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<u64>().ok());
+            return Err(FetchFailure {
+                error: FeedError::RateLimited,
+                retry_after: match retry_after {
+                    Some(seconds) if seconds > MAX_RETRY_AFTER => None,
+                    Some(seconds) => Some(Duration::from_seconds(seconds)),
+                    None => Some(ctx.retry_delay.clone()),
+                },
+            });
+        }
+        if status.is_server_error() {
+            tracing::warn!(url = %self.url, error = %status, "Server error");
+            return Err(FetchFailure {
+                error: FeedError::Network(status.to_string()),
+                retry_after: Some(ctx.retry_delay.clone()),
+            });
+        }
+        if status.is_client_error() {
+            tracing::warn!(url = %self.url, error = %status, "Client error");
+            return Err(FetchFailure::fatal(FeedError::Network(
+                status.to_string(),
+            )));
+        }
+
+        let etag = response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string());
+        let body =
+            read_body(response, attr.max_body_size, attr.charset.as_deref())
+                .await;
+        match body {
+            Ok(body) => {
+                self.etag = etag;
+                Ok(Some(body))
+            }
+            Err(e) => {
+                tracing::error!("Failed to get body from response: {e}");
+                Err(FetchFailure::fatal(e))
+            }
+        }
     }
 
     /// Parse a feed from the body text.
@@ -22,27 +201,47 @@ impl StandardSyndication {
         ctx: &UpdaterContext,
         attr: &FeedAttributes,
         tx: UnboundedSender<Entry>,
-    ) {
+    ) -> Result<(), FeedError> {
+        // Skip detection if the format is forced.
+        match attr.format {
+            Some(FeedFormat::Json) => {
+                return JsonFeed::parse(body, ctx, attr, tx);
+            }
+            Some(FeedFormat::Atom) => {
+                return StandardSyndication::parse_atom_feed(
+                    body, ctx, attr, tx,
+                )
+                .map_err(|e| {
+                    tracing::warn!("Unable to parse {:?} as atom: {e}", self);
+                    FeedError::Parse(e.to_string())
+                });
+            }
+            Some(FeedFormat::Rss) => {
+                return StandardSyndication::parse_rss_feed(
+                    body, ctx, attr, tx,
+                )
+                .map_err(|e| {
+                    tracing::warn!("Unable to parse {:?} as rss: {e}", self);
+                    FeedError::Parse(e.to_string())
+                });
+            }
+            None => {}
+        }
+
+        // Try to parse as json feed.
+        if self.url.ends_with(".json") || body.trim_start().starts_with('{') {
+            tracing::trace!("Parsing {:?} as json feed", self);
+            return JsonFeed::parse(body, ctx, attr, tx);
+        }
+
         let mut parse_error = String::new();
 
         // Try to parse as atom.
-        match body.parse::<atom_syndication::Feed>() {
-            Ok(atom_feed) => {
+        match StandardSyndication::parse_atom_feed(body, ctx, attr, tx.clone())
+        {
+            Ok(()) => {
                 tracing::trace!("Parsed {:?} as atom", self);
-                for atom_entry in atom_feed.entries() {
-                    let mut entry =
-                        StandardSyndication::parse_atom(atom_entry, ctx, attr);
-                    if !attr.keep_empty && entry.title().is_empty() {
-                        continue;
-                    }
-                    if entry.icon().is_none() {
-                        if let Some(icon) = atom_feed.icon() {
-                            entry.set_icon(icon);
-                        }
-                    }
-                    tx.send(entry).ok();
-                }
-                return;
+                return Ok(());
             }
             Err(e) => {
                 parse_error.push_str(&format!("\n{}", e));
@@ -50,23 +249,10 @@ impl StandardSyndication {
         }
 
         // Try to parse as rss.
-        match body.parse::<rss::Channel>() {
-            Ok(rss_feed) => {
+        match StandardSyndication::parse_rss_feed(body, ctx, attr, tx) {
+            Ok(()) => {
                 tracing::trace!("Parsed {:?} as rss", self);
-                for rss_entry in rss_feed.items() {
-                    let mut entry =
-                        StandardSyndication::parse_rss(rss_entry, ctx, attr);
-                    if !attr.keep_empty && entry.title().is_empty() {
-                        continue;
-                    }
-                    if entry.icon().is_none() {
-                        if let Some(icon) = rss_feed.image() {
-                            entry.set_icon(icon.url());
-                        }
-                    }
-                    tx.send(entry).ok();
-                }
-                return;
+                return Ok(());
             }
             Err(e) => {
                 parse_error.push_str(&format!("\n{}", e));
@@ -79,18 +265,92 @@ impl StandardSyndication {
             body,
             &parse_error
         );
+        Err(FeedError::Parse(parse_error.trim().to_string()))
     }
 
-    /// Parse an atom entry.
+    /// Parse an atom feed.
+    fn parse_atom_feed(
+        body: &str,
+        ctx: &UpdaterContext,
+        attr: &FeedAttributes,
+        tx: UnboundedSender<Entry>,
+    ) -> Result<(), atom_syndication::Error> {
+        let atom_feed = body.parse::<atom_syndication::Feed>()?;
+        for atom_entry in atom_feed.entries() {
+            let mut entry = StandardSyndication::parse_atom(
+                atom_entry,
+                atom_feed.lang(),
+                ctx,
+                attr,
+            );
+            if !attr.keep_empty && entry.title().is_empty() {
+                continue;
+            }
+            if entry.icon().is_none() {
+                if let Some(icon) = atom_feed.icon() {
+                    entry.set_icon(icon);
+                }
+            }
+            tx.send(entry).ok();
+        }
+        Ok(())
+    }
+
+    /// Parse an rss feed.
+    fn parse_rss_feed(
+        body: &str,
+        ctx: &UpdaterContext,
+        attr: &FeedAttributes,
+        tx: UnboundedSender<Entry>,
+    ) -> Result<(), rss::Error> {
+        let rss_feed = body.parse::<rss::Channel>()?;
+        for rss_entry in rss_feed.items() {
+            let mut entry = StandardSyndication::parse_rss(
+                rss_entry,
+                rss_feed.language(),
+                ctx,
+                attr,
+            );
+            if !attr.keep_empty && entry.title().is_empty() {
+                continue;
+            }
+            if entry.icon().is_none() {
+                if let Some(icon) = rss_feed.image() {
+                    entry.set_icon(icon.url());
+                }
+            }
+            tx.send(entry).ok();
+        }
+        Ok(())
+    }
+
+    /// Parse an atom entry, in the language of the feed unless the entry
+    /// declares its own.
     fn parse_atom(
         atom_entry: &atom_syndication::Entry,
+        feed_language: Option<&str>,
         _ctx: &UpdaterContext,
         attr: &FeedAttributes,
     ) -> Entry {
+        let mut parsed = StandardSyndication::atom_builder(atom_entry);
+        if let Some(language) = feed_language {
+            parsed.feed_language(language);
+        }
+        let entry = parsed.build();
+        StandardSyndication::tag_atom(entry, atom_entry, attr)
+    }
+
+    /// Build an entry from the common fields of an atom entry.
+    pub(crate) fn atom_builder(
+        atom_entry: &atom_syndication::Entry,
+    ) -> EntryBuilder {
         let mut parsed = EntryBuilder::new();
         parsed
             .title(atom_entry.title().to_string())
-            .date(DateTime::from_chrono(atom_entry.updated().to_utc()))
+            .date(DateTime::from_chrono(match atom_entry.published() {
+                Some(published) => published.to_utc(),
+                None => atom_entry.updated().to_utc(),
+            }))
             .author(
                 atom_entry
                     .authors()
@@ -112,25 +372,50 @@ impl StandardSyndication {
                     None => "".into(),
                 },
             });
-        for (i, link) in atom_entry.links().iter().enumerate() {
-            if i == 0 {
+        // The source is the first alternate link, and the rest, such as
+        // enclosures, are kept as other links.
+        let links = atom_entry.links();
+        let source = links
+            .iter()
+            .position(|link| link.rel() == "alternate")
+            .or_else(|| (!links.is_empty()).then_some(0));
+        for (i, link) in links.iter().enumerate() {
+            if Some(i) == source {
                 parsed.source(&link.href);
             } else {
-                parsed.other_link(Link::new_with_mime(
+                let mut other = Link::new_with_mime(
                     &link.href,
                     link.title().unwrap_or(""),
                     link.mime_type().unwrap_or(""),
-                ));
+                );
+                other.length = link.length().and_then(|len| len.parse().ok());
+                parsed.other_link(other);
             }
         }
+        if !atom_entry.id().is_empty() {
+            parsed.source_id(atom_entry.id());
+        }
         if let Some(source) = atom_entry.source() {
             if let Some(icon) = source.icon() {
                 parsed.icon(icon);
             }
         }
+        if let Some(language) = atom_entry
+            .content()
+            .and_then(|content| content.lang.as_ref())
+        {
+            parsed.language(language);
+        }
 
-        let mut entry = parsed.build();
+        parsed
+    }
 
+    /// Add the categories of an atom entry as tags.
+    pub(crate) fn tag_atom(
+        mut entry: Entry,
+        atom_entry: &atom_syndication::Entry,
+        attr: &FeedAttributes,
+    ) -> Entry {
         if attr.apply_tags {
             for category in atom_entry.categories() {
                 entry.add_tag(&Tag::new(String::from(category.term.clone())));
@@ -140,9 +425,47 @@ impl StandardSyndication {
         return entry;
     }
 
-    /// Parse an rss entry.
+    /// Get the enclosures of an rss entry: its `<enclosure>`, and any media rss
+    /// `<media:content>`, including those grouped in a `<media:group>`.
+    fn rss_enclosures(rss_entry: &rss::Item) -> Vec<Link> {
+        let mut enclosures = Vec::new();
+        if let Some(enclosure) = rss_entry.enclosure() {
+            let mut link =
+                Link::new_with_mime(enclosure.url(), "", enclosure.mime_type());
+            link.length = enclosure.length().parse().ok();
+            enclosures.push(link);
+        }
+        let Some(media) = rss_entry.extensions().get("media") else {
+            return enclosures;
+        };
+        let grouped = media
+            .get("group")
+            .into_iter()
+            .flatten()
+            .filter_map(|group| group.children().get("content"))
+            .flatten();
+        for content in media.get("content").into_iter().flatten().chain(grouped)
+        {
+            let attrs = content.attrs();
+            let Some(url) = attrs.get("url") else {
+                continue;
+            };
+            if enclosures.iter().any(|link: &Link| &link.url == url) {
+                continue;
+            }
+            let mut link = Link::new(url, "");
+            link.mime_type = attrs.get("type").cloned();
+            link.length =
+                attrs.get("fileSize").and_then(|len| len.parse().ok());
+            enclosures.push(link);
+        }
+        enclosures
+    }
+
+    /// Parse an rss entry, in the language of the channel.
     fn parse_rss(
         rss_entry: &rss::Item,
+        channel_language: Option<&str>,
         ctx: &UpdaterContext,
         attr: &FeedAttributes,
     ) -> Entry {
@@ -150,15 +473,25 @@ impl StandardSyndication {
         parsed
             .title(rss_entry.title().unwrap_or(""))
             .date('date: {
-                if let Ok(dt) =
-                    DateTime::try_from(rss_entry.pub_date().unwrap_or(""))
-                {
-                    break 'date dt;
+                if let Some(pub_date) = rss_entry.pub_date() {
+                    match DateTime::try_from(pub_date) {
+                        Ok(dt) => break 'date dt,
+                        Err(_) => tracing::warn!(
+                            "Unable to parse date `{}` for `{}`",
+                            pub_date,
+                            rss_entry.title().unwrap_or("")
+                        ),
+                    }
                 }
                 if let Some(dc) = rss_entry.dublin_core_ext() {
                     for date in dc.dates() {
-                        if let Ok(dt) = DateTime::try_from(date) {
-                            break 'date dt;
+                        match DateTime::try_from(date) {
+                            Ok(dt) => break 'date dt,
+                            Err(_) => tracing::warn!(
+                                "Unable to parse date `{}` for `{}`",
+                                date,
+                                rss_entry.title().unwrap_or("")
+                            ),
                         }
                     }
                 }
@@ -179,6 +512,17 @@ impl StandardSyndication {
         if let Some(comments) = rss_entry.comments() {
             parsed.comments(comments);
         }
+        for enclosure in StandardSyndication::rss_enclosures(rss_entry) {
+            parsed.other_link(enclosure);
+        }
+        if let Some(guid) = rss_entry.guid() {
+            if !guid.value().is_empty() {
+                parsed.source_id(guid.value());
+            }
+        }
+        if let Some(language) = channel_language {
+            parsed.feed_language(language);
+        }
         let mut entry = parsed.build();
         if attr.apply_tags {
             for category in rss_entry.categories() {
@@ -194,135 +538,178 @@ impl StandardSyndication {
     }
 }
 
-impl Hash for StandardSyndication {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        state.write(self.url.as_bytes());
-    }
-}
-
-#[feed_trait]
-impl Feed for StandardSyndication {
-    async fn update(&mut self, ctx: &UpdaterContext, attr: &FeedAttributes) {
-        let (tx, mut rx) = unbounded_channel();
-        if self.url.starts_with("file://") {
-            let filename = &self.url["file://".len()..];
-            match tokio::fs::read(filename).await {
-                Ok(buf) => {
-                    if let Ok(body) = str::from_utf8(buf.as_slice()) {
-                        self.parse(body, &ctx, attr, tx);
-                    } else {
-                        tracing::warn!(
-                            "Unable to read binary file `{filename}`."
-                        );
-                    }
-                }
+/// Build the request headers for a feed.
+pub(crate) fn headers(attr: &FeedAttributes) -> reqwest::header::HeaderMap {
+    let mut map = reqwest::header::HeaderMap::default();
+    'headers: for (header, value) in attr.headers.iter() {
+        let header_name =
+            match reqwest::header::HeaderName::from_bytes(header.as_bytes()) {
+                Ok(header_name) => header_name,
                 Err(e) => {
-                    tracing::warn!("Unable to read file `{filename}`: {e}");
+                    tracing::warn!("Unable to add header {header}: {e}");
+                    continue 'headers;
                 }
+            };
+        match value.parse() {
+            Ok(val) => {
+                tracing::trace!("Adding header {:?}={:?}", header_name, &val);
+                map.insert(header_name, val);
             }
-        } else {
-            // Generate client.
-            let mut client_builder = reqwest::ClientBuilder::new();
-
-            // Add headers.
-            let mut map = reqwest::header::HeaderMap::default();
-            'headers: for (header, value) in attr.headers.iter() {
-                let header_name =
-                    match reqwest::header::HeaderName::from_lowercase(
-                        header.as_bytes(),
-                    ) {
-                        Ok(header_name) => header_name,
-                        Err(e) => {
-                            tracing::warn!(
-                                "Unable to add header {header}: {e}"
-                            );
-                            continue 'headers;
-                        }
-                    };
-                match value.parse() {
-                    Ok(val) => {
-                        tracing::trace!(
-                            "Adding header {:?}={:?}",
-                            header_name,
-                            &val
-                        );
-                        map.insert(header_name, val);
-                    }
-                    Err(e) => {
-                        tracing::warn!(
-                            "Unable to use header {}={}: {}",
-                            header,
-                            value,
-                            e
-                        );
-                    }
-                };
+            Err(e) => {
+                tracing::warn!(
+                    "Unable to use header {}={}: {}",
+                    header,
+                    value,
+                    e
+                );
             }
-            client_builder = client_builder.default_headers(map);
+        };
+    }
+    map
+}
 
-            // Build the request.
-            let client = match client_builder.build() {
-                Ok(client) => client,
-                Err(e) => {
-                    tracing::warn!("Unable to build client: {e}");
-                    return;
-                }
-            };
-            let mut request_builder = client.get(&self.url);
-            if let Some(last_update) = ctx.last_update.as_ref() {
-                request_builder = request_builder.header(
-                    reqwest::header::IF_MODIFIED_SINCE,
-                    last_update.to_if_modified_since(),
-                );
-            };
-            let request = match request_builder.build() {
-                Ok(request) => request,
-                Err(e) => {
-                    tracing::warn!("Unable to build request: {e}");
-                    return;
+/// Decode a body with a charset label, e.g. `iso-8859-1`.
+fn decode(bytes: &[u8], charset: &str) -> Result<String, FeedError> {
+    match encoding_rs::Encoding::for_label(charset.trim().as_bytes()) {
+        Some(encoding) => Ok(encoding.decode(bytes).0.into_owned()),
+        None => Err(FeedError::Decode(format!("unknown charset `{charset}`"))),
+    }
+}
+
+/// Read a response body as text, failing once it is larger than the limit.
+/// The charset overrides the one in the response's content type.
+pub(crate) async fn read_body(
+    mut response: reqwest::Response,
+    limit: usize,
+    charset: Option<&str>,
+) -> Result<String, FeedError> {
+    let url = response.url().to_string();
+    if let Some(length) = response.content_length() {
+        if length > limit as u64 {
+            tracing::warn!(url = %url, length, limit, "Body too large");
+            return Err(FeedError::TooLarge(limit));
+        }
+    }
+    let response_charset = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| {
+            value.split(';').find_map(|param| {
+                let (key, value) = param.split_once('=')?;
+                match key.trim().eq_ignore_ascii_case("charset") {
+                    true => Some(value.trim().trim_matches('"').to_string()),
+                    false => None,
                 }
-            };
+            })
+        });
 
-            // Execute request and parse.
-            match client.execute(request).await {
-                Ok(req_result) => match req_result.text().await {
-                    Ok(body) => {
-                        self.parse(body.as_str(), &ctx, attr, tx);
-                    }
-                    Err(e) => {
-                        tracing::error!("Failed to get body from response: {e}")
-                    }
-                },
-                Err(e) => tracing::error!("Failed to execute: {e}"),
-            };
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() + chunk.len() > limit {
+            tracing::warn!(url = %url, limit, "Body too large");
+            return Err(FeedError::TooLarge(limit));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    match charset {
+        Some(charset) => decode(&body, charset),
+        None => {
+            let encoding = response_charset
+                .and_then(|c| encoding_rs::Encoding::for_label(c.as_bytes()))
+                .unwrap_or(encoding_rs::UTF_8);
+            Ok(encoding.decode(&body).0.into_owned())
         }
+    }
+}
 
-        // Forward the matching entries.
-        while let Ok(entry) = rx.try_recv() {
-            let too_old =
-                *entry.date() < ctx.parse_time.clone() - attr.timeout.clone();
-            if too_old {
-                continue;
-            }
+/// Forward parsed entries that are recent enough and pass the feed's filters.
+/// This returns the number of entries sent to the updater.
+pub(crate) fn forward_entries(
+    feed: &dyn Feed,
+    mut rx: UnboundedReceiver<Entry>,
+    ctx: &UpdaterContext,
+    attr: &FeedAttributes,
+) -> usize {
+    let mut count: usize = 0;
+    while let Ok(entry) = rx.try_recv() {
+        let too_old =
+            *entry.date() < ctx.parse_time.clone() - attr.timeout.clone();
+        if too_old {
+            continue;
+        }
 
-            let passes_filters = attr.passes_filters(self, &entry);
-            if !passes_filters {
-                continue;
-            }
+        let passes_filters = attr.passes_filters(feed, &entry);
+        if !passes_filters {
+            continue;
+        }
 
-            ctx.sender
-                .send((
-                    entry.clone(),
-                    FeedRef {
-                        id: ctx.feed_id,
-                        name: attr.display_name.clone(),
-                    },
-                ))
-                .ok();
+        ctx.sender
+            .send((
+                entry.clone(),
+                FeedRef {
+                    id: ctx.feed_id,
+                    name: attr.display_name.clone(),
+                },
+            ))
+            .ok();
+        count += 1;
+    }
+    count
+}
+
+/// A failed request for a feed body.
+struct FetchFailure {
+    /// The underlying error.
+    error: FeedError,
+    /// How long to wait before retrying, if the failure is transient.
+    retry_after: Option<Duration>,
+}
+
+impl FetchFailure {
+    /// A failure that should not be retried.
+    fn fatal(error: FeedError) -> Self {
+        Self {
+            error,
+            retry_after: None,
         }
     }
 }
 
+impl Hash for StandardSyndication {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        state.write(self.url.as_bytes());
+    }
+}
+
+#[feed_trait]
+impl Feed for StandardSyndication {
+    async fn update(
+        &mut self,
+        ctx: &UpdaterContext,
+        attr: &FeedAttributes,
+    ) -> FeedResult {
+        let (tx, rx) = unbounded_channel();
+        match self.read(ctx, attr).await? {
+            Some(body) => self.parse(body.as_str(), ctx, attr, tx)?,
+            None => return Ok(0),
+        }
+        Ok(forward_entries(self, rx, ctx, attr))
+    }
+
+    fn url(&self) -> Option<&str> {
+        Some(&self.url)
+    }
+
+    fn etag(&self) -> Option<&str> {
+        self.etag.as_deref()
+    }
+
+    fn set_etag(&mut self, etag: Option<String>) {
+        self.etag = etag;
+    }
+}
+
 impl std::fmt::Display for StandardSyndication {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "<StandardSyndication url={}>", &self.url)
@@ -0,0 +1,249 @@
+//! YouTube channel feeds.
+
+use super::*;
+
+/// Base url of youtube channel feeds.
+const YOUTUBE_FEED_URL: &str =
+    "https://www.youtube.com/feeds/videos.xml?channel_id=";
+
+/// A reference to a YouTube channel.
+#[derive(Clone, Debug)]
+pub struct YouTubeFeed {
+    /// The configured channel url, handle, or id.
+    channel: String,
+    /// The syndication used to read the channel feed, once resolved.
+    syndication: Option<StandardSyndication>,
+}
+
+impl YouTubeFeed {
+    /// Create a new youtube feed from a channel url, `@handle`, or `UC...` id.
+    pub fn new(channel: impl Into<String>) -> Box<Self> {
+        let channel: String = channel.into();
+        Box::new(Self {
+            syndication: YouTubeFeed::feed_url(&channel)
+                .map(|url| *StandardSyndication::new(url)),
+            channel,
+        })
+    }
+
+    /// Get the feed url of a channel without making any requests.
+    /// This returns `None` for handles, which must be resolved.
+    pub fn feed_url(channel: &str) -> Option<String> {
+        let channel = channel.trim().trim_end_matches('/');
+        // Feed urls and local files are used as-is.
+        if channel.contains("/feeds/videos.xml")
+            || channel.starts_with("file://")
+        {
+            return Some(channel.to_string());
+        }
+        if let Some(id) = YouTubeFeed::channel_id(channel) {
+            return Some(format!("{YOUTUBE_FEED_URL}{id}"));
+        }
+        let id = channel.rsplit_once("/channel/")?.1;
+        let id = id.split(['/', '?']).next()?;
+        YouTubeFeed::channel_id(id).map(|id| format!("{YOUTUBE_FEED_URL}{id}"))
+    }
+
+    /// Get the channel page url of a handle.
+    pub fn handle_url(channel: &str) -> Option<String> {
+        let channel = channel.trim().trim_end_matches('/');
+        let handle = match channel.rsplit_once("/@") {
+            Some((_, handle)) => handle,
+            None => channel.strip_prefix('@')?,
+        };
+        let handle = handle.split(['/', '?']).next()?;
+        if handle.is_empty() {
+            return None;
+        }
+        Some(format!("https://www.youtube.com/@{handle}"))
+    }
+
+    /// Find the channel id in the html of a channel page.
+    pub fn channel_id_from_page(body: &str) -> Option<String> {
+        for marker in [
+            "\"externalId\":\"",
+            "\"channelId\":\"",
+            "youtube.com/channel/",
+        ] {
+            for (start, _) in body.match_indices(marker) {
+                let id: String = body[start + marker.len()..]
+                    .chars()
+                    .take_while(|c| {
+                        c.is_ascii_alphanumeric() || *c == '-' || *c == '_'
+                    })
+                    .collect();
+                if let Some(id) = YouTubeFeed::channel_id(&id) {
+                    return Some(id.to_string());
+                }
+            }
+        }
+        None
+    }
+
+    /// Get the channel id if this is a `UC...` id.
+    fn channel_id(id: &str) -> Option<&str> {
+        let valid = id.len() == 24
+            && id.starts_with("UC")
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        valid.then_some(id)
+    }
+
+    /// Resolve the channel feed, looking up the channel id of handles.
+    async fn resolve(
+        &mut self,
+        ctx: &UpdaterContext,
+        attr: &FeedAttributes,
+    ) -> Result<(), FeedError> {
+        if self.syndication.is_none() {
+            let Some(handle_url) = YouTubeFeed::handle_url(&self.channel)
+            else {
+                tracing::warn!("Invalid youtube channel `{}`", self.channel);
+                return Err(FeedError::Parse(format!(
+                    "invalid youtube channel `{}`",
+                    self.channel
+                )));
+            };
+            // The channel page is fetched without conditional headers, since
+            // a 304 would leave nothing to find the id in.
+            let ctx = UpdaterContext {
+                last_update: None,
+                ..ctx.clone()
+            };
+            let page = StandardSyndication::new(&handle_url)
+                .read(&ctx, attr)
+                .await?
+                .unwrap_or_default();
+            let Some(id) = YouTubeFeed::channel_id_from_page(&page) else {
+                tracing::warn!("Unable to find channel id of `{handle_url}`");
+                return Err(FeedError::Parse(format!(
+                    "unable to find channel id of `{handle_url}`"
+                )));
+            };
+            tracing::debug!("Resolved {} to {}", self.channel, id);
+            self.syndication = Some(*StandardSyndication::new(format!(
+                "{YOUTUBE_FEED_URL}{id}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Parse a youtube channel feed from the body text.
+    pub(crate) fn parse(
+        body: &str,
+        attr: &FeedAttributes,
+        tx: UnboundedSender<Entry>,
+    ) -> Result<(), FeedError> {
+        let atom_feed = match body.parse::<atom_syndication::Feed>() {
+            Ok(atom_feed) => atom_feed,
+            Err(e) => {
+                tracing::warn!("Unable to parse youtube feed: {e}");
+                return Err(FeedError::Parse(e.to_string()));
+            }
+        };
+        for atom_entry in atom_feed.entries() {
+            let entry = YouTubeFeed::parse_video(atom_entry, attr);
+            if !attr.keep_empty && entry.title().is_empty() {
+                continue;
+            }
+            tx.send(entry).ok();
+        }
+        Ok(())
+    }
+
+    /// Parse a video, using its `media:group` for the content.
+    fn parse_video(
+        atom_entry: &atom_syndication::Entry,
+        attr: &FeedAttributes,
+    ) -> Entry {
+        let extension = |ns: &str, name: &str| {
+            atom_entry
+                .extensions()
+                .get(ns)
+                .and_then(|extensions| extensions.get(name))
+                .and_then(|extensions| extensions.first())
+        };
+        let group = extension("media", "group");
+        let child = |name: &str| {
+            group
+                .and_then(|group| group.children().get(name))
+                .and_then(|children| children.first())
+        };
+        let thumbnail = child("thumbnail")
+            .and_then(|thumbnail| thumbnail.attrs().get("url"))
+            .cloned();
+        let description = child("description")
+            .and_then(|description| description.value())
+            .unwrap_or("")
+            .trim()
+            .to_string();
+        let video_id = extension("yt", "videoId")
+            .and_then(|video_id| video_id.value())
+            .map(|video_id| video_id.to_string());
+
+        let mut parsed = StandardSyndication::atom_builder(atom_entry);
+        parsed.content(match &thumbnail {
+            Some(thumbnail) if description.is_empty() => {
+                format!("![]({thumbnail})")
+            }
+            Some(thumbnail) => format!("![]({thumbnail})\n\n{description}"),
+            None => description,
+        });
+        if let Some(video_id) = &video_id {
+            parsed
+                .source(format!("https://www.youtube.com/watch?v={video_id}"))
+                .source_id(video_id);
+        }
+        if let Some(thumbnail) = &thumbnail {
+            parsed.icon(thumbnail);
+        }
+        StandardSyndication::tag_atom(parsed.build(), atom_entry, attr)
+    }
+}
+
+impl Hash for YouTubeFeed {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.channel.hash(state);
+    }
+}
+
+#[feed_trait]
+impl Feed for YouTubeFeed {
+    async fn update(
+        &mut self,
+        ctx: &UpdaterContext,
+        attr: &FeedAttributes,
+    ) -> FeedResult {
+        let (tx, rx) = unbounded_channel();
+        self.resolve(ctx, attr).await?;
+        let Some(syndication) = self.syndication.as_mut() else {
+            return Ok(0);
+        };
+        match syndication.read(ctx, attr).await? {
+            Some(body) => YouTubeFeed::parse(body.as_str(), attr, tx)?,
+            None => return Ok(0),
+        }
+        Ok(forward_entries(self, rx, ctx, attr))
+    }
+
+    fn url(&self) -> Option<&str> {
+        self.syndication.as_ref()?.url()
+    }
+
+    fn etag(&self) -> Option<&str> {
+        self.syndication.as_ref()?.etag()
+    }
+
+    fn set_etag(&mut self, etag: Option<String>) {
+        if let Some(syndication) = self.syndication.as_mut() {
+            syndication.set_etag(etag);
+        }
+    }
+}
+
+impl std::fmt::Display for YouTubeFeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<YouTubeFeed channel={}>", &self.channel)
+    }
+}
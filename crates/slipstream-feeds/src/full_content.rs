@@ -0,0 +1,166 @@
+//! Full content extraction.
+
+use std::collections::{HashMap, VecDeque};
+
+use super::*;
+
+/// Number of articles fetched at a time.
+const FULL_CONTENT_WORKERS: usize = 4;
+
+/// Number of articles whose extracted content is remembered.
+const FULL_CONTENT_LIMIT: usize = 1_000;
+
+/// An article to fetch for an entry.
+pub(crate) struct ArticleRequest {
+    /// Url of the article.
+    pub url: String,
+    /// Headers of the entry's feed.
+    pub headers: reqwest::header::HeaderMap,
+    /// Timeout for the request.
+    pub timeout: Option<Duration>,
+    /// Largest body read, in bytes.
+    pub max_body_size: usize,
+}
+
+/// Full article content, extracted from entry sources and remembered by url.
+#[derive(Default)]
+pub(crate) struct FullContent {
+    /// Extracted articles by url.
+    cache: HashMap<String, Article>,
+    /// Urls in the cache, oldest first.
+    order: VecDeque<String>,
+}
+
+/// An article fetched for an entry.
+struct Article {
+    /// Extracted content, or none if it could not be extracted.
+    content: Option<String>,
+    /// When the article was fetched.
+    fetched: DateTime,
+}
+
+impl Article {
+    /// Whether the article doesn't need to be fetched again.
+    /// Failures are retried after an hour, in case they were temporary.
+    fn is_fresh(&self, now: &DateTime) -> bool {
+        self.content.is_some()
+            || self.fetched.clone() + Duration::from_hours(1) > *now
+    }
+}
+
+impl FullContent {
+    /// Fetch and extract the articles that aren't remembered yet.
+    /// This runs as part of an update, while the updater is borrowed.
+    pub(crate) async fn fetch(
+        &mut self,
+        client: &reqwest::Client,
+        requests: Vec<ArticleRequest>,
+        now: &DateTime,
+    ) {
+        use futures::StreamExt;
+
+        let mut requested = HashSet::new();
+        let requests: Vec<ArticleRequest> = requests
+            .into_iter()
+            .filter(|request| {
+                !self
+                    .cache
+                    .get(&request.url)
+                    .is_some_and(|article| article.is_fresh(now))
+            })
+            .filter(|request| requested.insert(request.url.clone()))
+            .collect();
+        let mut fetches = futures::stream::iter(requests)
+            .map(|request| async move {
+                let content = fetch_article(client, &request).await;
+                (request.url, content)
+            })
+            .buffer_unordered(FULL_CONTENT_WORKERS);
+        while let Some((url, content)) = fetches.next().await {
+            let article = Article {
+                content,
+                fetched: now.clone(),
+            };
+            // Retried failures keep their place.
+            if self.cache.insert(url.clone(), article).is_some() {
+                continue;
+            }
+            self.order.push_back(url);
+            if self.order.len() > FULL_CONTENT_LIMIT {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.cache.remove(&oldest);
+                }
+            }
+        }
+    }
+
+    /// Get the extracted content of an article, if it could be extracted.
+    pub(crate) fn get(&self, url: &str) -> Option<&String> {
+        self.cache.get(url)?.content.as_ref()
+    }
+}
+
+/// Fetch an article and extract its content as markdown.
+async fn fetch_article(
+    client: &reqwest::Client,
+    request: &ArticleRequest,
+) -> Option<String> {
+    let mut request_builder =
+        client.get(&request.url).headers(request.headers.clone());
+    if let Some(timeout) = request.timeout.as_ref() {
+        request_builder = request_builder.timeout(timeout.to_std());
+    }
+    let response = match request_builder.send().await {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::warn!(
+                url = %request.url,
+                error = %e,
+                "Failed to fetch article"
+            );
+            return None;
+        }
+    };
+    if !response.status().is_success() {
+        tracing::warn!(
+            url = %request.url,
+            error = %response.status(),
+            "Failed to fetch article"
+        );
+        return None;
+    }
+    let body = match read_body(response, request.max_body_size, None).await {
+        Ok(body) => body,
+        Err(e) => {
+            tracing::warn!(
+                url = %request.url,
+                error = %e,
+                "Failed to fetch article"
+            );
+            return None;
+        }
+    };
+    match extract_article(&body, &request.url) {
+        Ok(content) => Some(content),
+        Err(e) => {
+            tracing::warn!(
+                url = %request.url,
+                error = %e,
+                "Failed to extract article"
+            );
+            None
+        }
+    }
+}
+
+/// Extract the readable content of a page as markdown.
+fn extract_article(html: &str, url: &str) -> Result<String, String> {
+    let mut readability = dom_smoothie::Readability::new(html, Some(url), None)
+        .map_err(|e| e.to_string())?;
+    let article = readability.parse().map_err(|e| e.to_string())?;
+    let content = htmd::convert(&article.content).map_err(|e| e.to_string())?;
+    match content.trim().is_empty() {
+        true => Err("no content found".into()),
+        false => Ok(content),
+    }
+}
@@ -0,0 +1,144 @@
+//! Language detection.
+
+use super::*;
+
+/// Words counted when guessing the language of latin text.
+/// Codes are ISO 639-1.
+const COMMON_WORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "of", "to", "is", "in", "that", "for", "with", "on",
+            "are", "was", "this", "it", "by", "from", "be", "as", "at", "have",
+            "not", "you", "new", "how", "what",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "la", "los", "las", "de", "que", "y", "en", "un", "una",
+            "por", "con", "para", "es", "del", "se", "no", "al", "como", "más",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "de", "des", "et", "est", "un", "une", "du",
+            "en", "que", "pour", "dans", "pas", "sur", "au", "avec", "qui",
+            "ce",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "nicht", "ein", "eine", "zu",
+            "den", "von", "mit", "sich", "des", "auf", "für", "im", "dem",
+            "auch", "es",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "lo", "la", "di", "che", "e", "è", "un", "una", "per", "non",
+            "con", "del", "della", "sono", "le", "gli", "da", "nel", "si",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "a", "os", "as", "de", "que", "e", "do", "da", "em", "um",
+            "uma", "para", "com", "não", "por", "mais", "dos", "das", "se",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "de", "het", "een", "en", "van", "is", "dat", "niet", "op", "te",
+            "zijn", "voor", "met", "die", "in", "ook", "er", "aan", "maar",
+            "om",
+        ],
+    ),
+];
+
+/// Minimum common words before latin text is given a language.
+const MIN_COMMON_WORDS: usize = 2;
+
+/// Guess the language of some text, as an ISO 639-1 code.
+/// Text in a non-latin script is identified by its script, while latin text
+/// is identified by its most common words. This returns `None` when there is
+/// too little text to tell.
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    if let Some(language) = detect_script(text) {
+        return Some(language);
+    }
+
+    let text = text.to_lowercase();
+    let words: Vec<&str> = text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|word| !word.is_empty())
+        .collect();
+    let mut best: Option<(&'static str, usize)> = None;
+    let mut tied = false;
+    for (language, common) in COMMON_WORDS {
+        let count = words.iter().filter(|word| common.contains(word)).count();
+        match best {
+            Some((_, best_count)) if count < best_count => {}
+            Some((_, best_count)) if count == best_count => tied = true,
+            _ => {
+                best = Some((language, count));
+                tied = false;
+            }
+        }
+    }
+    match best {
+        Some((language, count)) if count >= MIN_COMMON_WORDS && !tied => {
+            Some(language)
+        }
+        _ => None,
+    }
+}
+
+/// Guess the language of text written mostly in a non-latin script.
+fn detect_script(text: &str) -> Option<&'static str> {
+    let mut letters = 0;
+    let mut scripts: BTreeMap<&'static str, usize> = BTreeMap::new();
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        letters += 1;
+        let language = match c as u32 {
+            0x3040..=0x30FF => "ja",
+            0xAC00..=0xD7AF | 0x1100..=0x11FF => "ko",
+            0x4E00..=0x9FFF | 0x3400..=0x4DBF => "zh",
+            0x0400..=0x04FF => "ru",
+            0x0370..=0x03FF => "el",
+            0x0590..=0x05FF => "he",
+            0x0600..=0x06FF => "ar",
+            0x0900..=0x097F => "hi",
+            0x0E00..=0x0E7F => "th",
+            _ => continue,
+        };
+        *scripts.entry(language).or_default() += 1;
+    }
+
+    // Japanese mixes kana with han characters.
+    if let Some(kana) = scripts.remove("ja") {
+        *scripts.entry("ja").or_default() +=
+            kana + scripts.remove("zh").unwrap_or(0);
+    }
+    let (language, count) =
+        scripts.into_iter().max_by_key(|(_, count)| *count)?;
+    match count * 2 >= letters {
+        true => Some(language),
+        false => None,
+    }
+}
+
+/// Normalize a language tag to its primary ISO 639 code.
+/// For example, `en-US` and `EN_gb` are both `en`.
+pub fn normalize_language(tag: impl AsRef<str>) -> String {
+    tag.as_ref()
+        .trim()
+        .split(['-', '_'])
+        .next()
+        .unwrap_or("")
+        .to_lowercase()
+}
@@ -1,9 +1,13 @@
 //! slipfeed feed management.
 
+mod clock;
 mod datetime;
 mod entry;
+mod error;
 mod feed;
 mod filter;
+mod full_content;
+mod language;
 pub mod prelude;
 mod tag;
 mod transform;
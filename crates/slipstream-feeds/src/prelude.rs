@@ -1,10 +1,13 @@
 use super::*;
 
 pub use async_trait::async_trait as feed_trait;
+pub use clock::*;
 pub use datetime::*;
 pub use entry::*;
+pub use error::*;
 pub use feed::*;
 pub use filter::*;
+pub use language::*;
 pub use tag::*;
 pub use transform::*;
 pub use updater::*;
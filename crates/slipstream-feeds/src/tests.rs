@@ -18,6 +18,12 @@ async fn standard_syndications() {
             keep_empty: false,
             apply_tags: true,
             headers: BTreeMap::new(),
+            format: None,
+            charset: None,
+            request_timeout: None,
+            titles_only: false,
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+            full_content: false,
         },
     );
     assert!(hn_id.0 == 1);
@@ -36,6 +42,12 @@ async fn standard_syndications() {
             keep_empty: false,
             apply_tags: true,
             headers: BTreeMap::new(),
+            format: None,
+            charset: None,
+            request_timeout: None,
+            titles_only: false,
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+            full_content: false,
         },
     );
     assert!(newsboat_id.0 == 1);
@@ -76,6 +88,12 @@ async fn mastodon() {
             keep_empty: false,
             apply_tags: true,
             headers: BTreeMap::new(),
+            format: None,
+            charset: None,
+            request_timeout: None,
+            titles_only: false,
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+            full_content: false,
         },
     );
 
@@ -83,6 +101,154 @@ async fn mastodon() {
     assert!(entries.len() > 0);
 }
 
+#[tokio::test]
+async fn mastodon_timelines() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let statuses = r#"[{
+        "id": "1",
+        "created_at": "2025-01-06T10:00:00.000Z",
+        "account": {"id": "2", "username": "ferris", "display_name": "Ferris"},
+        "url": "https://mastodon.example/@ferris/1",
+        "content": "<p>Hello #rust</p>",
+        "media_attachments": [],
+        "card": null,
+        "tags": [{"name": "rust"}]
+    }]"#;
+    let (url, requests) = mock_server(vec![http_response(
+        "200 OK",
+        &[("Content-Type", "application/json")],
+        statuses,
+    )])
+    .await;
+    let instance = url.trim_end_matches("/feed");
+
+    let mut updater = Updater::new(Duration::from_seconds(0), 10);
+    let hashtag = updater.add_feed(
+        MastodonFeed::new(
+            instance,
+            MastodonFeedType::HashtagTimeline {
+                tag: "#rust".into(),
+            },
+            None,
+        ),
+        local_attributes("Hashtag"),
+    );
+    let list = updater.add_feed(
+        MastodonFeed::new(
+            instance,
+            MastodonFeedType::ListTimeline {
+                list_id: "123".into(),
+            },
+            Some("secret".into()),
+        ),
+        local_attributes("List"),
+    );
+    // Lists are private, so they need a token.
+    let tokenless = updater.add_feed(
+        MastodonFeed::new(
+            instance,
+            MastodonFeedType::ListTimeline {
+                list_id: "456".into(),
+            },
+            None,
+        ),
+        local_attributes("Tokenless"),
+    );
+
+    let entries = updater.update().await;
+    assert_eq!(entries.len(), 1);
+    assert!(entries.as_slice()[0].has_tag(Tag::new("rust")));
+    assert_eq!(updater.result(hashtag), Some(&Ok(1)));
+    assert_eq!(updater.result(list), Some(&Ok(1)));
+    assert!(matches!(
+        updater.result(tokenless),
+        Some(Err(FeedError::Config(_)))
+    ));
+
+    let requests = requests.lock().unwrap().clone();
+    assert_eq!(requests.len(), 2);
+    let request = |path: &str| {
+        requests
+            .iter()
+            .find(|request| request.starts_with(&format!("GET {path} ")))
+            .cloned()
+    };
+    let hashtag_request = request("/api/v1/timelines/tag/rust").unwrap();
+    assert!(!hashtag_request.to_lowercase().contains("authorization"));
+    let list_request = request("/api/v1/timelines/list/123").unwrap();
+    assert!(list_request.contains("Bearer secret"));
+}
+
+#[tokio::test]
+async fn mastodon_pagination() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    // Each page links to the next, getting older after the second page.
+    let (url, requests) = mock_server_with(|served, request| {
+        let host = request
+            .lines()
+            .find_map(|line| line.strip_prefix("host: "))
+            .unwrap();
+        let date = match served % 3 {
+            2 => "2000-01-01T00:00:00.000Z",
+            _ => "2099-01-01T00:00:00.000Z",
+        };
+        let status = format!(
+            r#"[{{
+                "id": "{served}",
+                "created_at": "{date}",
+                "account": {{"id": "2", "username": "ferris", "display_name": "Ferris"}},
+                "url": "https://mastodon.example/@ferris/{served}",
+                "content": "<p>Status {served}</p>",
+                "media_attachments": [],
+                "card": null,
+                "tags": []
+            }}]"#
+        );
+        let link = format!(
+            "<http://{host}/api/v1/timelines/public?max_id={served}>; rel=\"next\", \
+            <http://{host}/api/v1/timelines/public?min_id={served}>; rel=\"prev\""
+        );
+        http_response("200 OK", &[("Link", &link)], &status)
+    })
+    .await;
+    let instance = url.trim_end_matches("/feed");
+
+    let mut updater = Updater::new(Duration::from_seconds(0), 10);
+    let mut attr = local_attributes("Public");
+    attr.timeout = Duration::from_days(30);
+    let public = updater.add_feed(
+        MastodonFeed::new(instance, MastodonFeedType::PublicTimeline, None),
+        attr,
+    );
+
+    // Paging stops at statuses older than the timeout.
+    let entries = updater.update().await;
+    assert_eq!(entries.len(), 2);
+    assert_eq!(updater.result(public), Some(&Ok(2)));
+    {
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(
+            requests[1].starts_with("GET /api/v1/timelines/public?max_id=0 ")
+        );
+        assert!(
+            requests[2].starts_with("GET /api/v1/timelines/public?max_id=1 ")
+        );
+    }
+
+    // Paging stops at the page limit.
+    let mut updater = Updater::new(Duration::from_seconds(0), 10);
+    let mut feed =
+        MastodonFeed::new(instance, MastodonFeedType::PublicTimeline, None);
+    feed.set_max_pages(1);
+    updater.add_feed(feed, local_attributes("Limited"));
+    let entries = updater.update().await;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(requests.lock().unwrap().len(), 4);
+}
+
 #[tokio::test]
 async fn parsing() {
     tracing_subscriber::fmt::try_init().ok();
@@ -102,6 +268,12 @@ async fn parsing() {
             keep_empty: false,
             apply_tags: true,
             headers: BTreeMap::new(),
+            format: None,
+            charset: None,
+            request_timeout: None,
+            titles_only: false,
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+            full_content: false,
         },
     );
 
@@ -112,3 +284,1530 @@ async fn parsing() {
         tracing::info!("ENTRY: {:?}", entry.date());
     }
 }
+
+#[tokio::test]
+async fn local_files() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let path =
+        format!("{}/../../test/feeds/local.rss", env!("CARGO_MANIFEST_DIR"));
+    for url in [format!("file://{path}"), path] {
+        let mut updater = Updater::new(Duration::from_seconds(1_000), 5);
+        let id = updater.add_feed(
+            StandardSyndication::new(&url),
+            local_attributes("Local"),
+        );
+        let entries = updater.update().await;
+        assert_eq!(updater.result(id), Some(&Ok(2)));
+        let titles: Vec<&str> = entries
+            .as_slice()
+            .iter()
+            .map(|e| e.title().as_str())
+            .collect();
+        assert_eq!(titles, vec!["Offline reading", "Fixtures"]);
+        assert!(entries.as_slice()[0].has_tag("local"));
+    }
+
+    // Missing files are reported as errors.
+    let mut updater = Updater::new(Duration::from_seconds(1_000), 5);
+    let id = updater.add_feed(
+        StandardSyndication::new("file:///nonexistent/feed.rss"),
+        local_attributes("Missing"),
+    );
+    updater.update().await;
+    assert!(matches!(updater.result(id), Some(Err(FeedError::Io(_)))));
+}
+
+#[tokio::test]
+async fn network_errors() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    // Nothing should be listening on the discard port.
+    let mut updater = Updater::new(Duration::from_seconds(1_000), 5);
+    let unreachable = StandardSyndication::new("http://127.0.0.1:9/feed.rss");
+    let unreachable_id = updater.add_feed(
+        unreachable,
+        FeedAttributes {
+            display_name: Arc::new("Unreachable".into()),
+            timeout: Duration::from_days(365),
+            freq: None,
+            step: 1,
+            tags: std::collections::HashSet::new(),
+            filters: vec![],
+            keep_empty: false,
+            apply_tags: true,
+            headers: BTreeMap::new(),
+            format: None,
+            charset: None,
+            request_timeout: None,
+            titles_only: false,
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+            full_content: false,
+        },
+    );
+
+    let entries = updater.update().await;
+    assert!(entries.len() == 0);
+    assert!(matches!(
+        updater.result(unreachable_id),
+        Some(Err(FeedError::Network(_)))
+    ));
+}
+
+/// Minimal rss body for local feeds.
+const RSS_BODY: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+<channel>
+<title>Local</title>
+<link>http://localhost</link>
+<description>Local feed</description>
+<item>
+<title>First</title>
+<link>http://localhost/first</link>
+<pubDate>Tue, 10 Jun 2025 04:00:00 GMT</pubDate>
+</item>
+<item>
+<title>Second</title>
+<link>http://localhost/second</link>
+<pubDate>Wed, 11 Jun 2025 04:00:00 GMT</pubDate>
+</item>
+</channel>
+</rss>"#;
+
+/// Build a raw http response.
+fn http_response(status: &str, headers: &[(&str, &str)], body: &str) -> String {
+    let mut response = format!("HTTP/1.1 {status}\r\n");
+    for (name, value) in headers {
+        response.push_str(&format!("{name}: {value}\r\n"));
+    }
+    response.push_str(&format!(
+        "Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    ));
+    response
+}
+
+/// Serve canned responses in order, repeating the last one.
+/// Returns the url of the server and the raw requests it received.
+async fn mock_server(
+    responses: Vec<String>,
+) -> (String, Arc<std::sync::Mutex<Vec<String>>>) {
+    mock_server_with(move |served, _request| {
+        responses[served.min(responses.len() - 1)].clone()
+    })
+    .await
+}
+
+/// Serve responses built from the number of requests served and the request.
+/// Returns the url of the server and the raw requests it received.
+async fn mock_server_with(
+    handler: impl Fn(usize, &str) -> String + Send + 'static,
+) -> (String, Arc<std::sync::Mutex<Vec<String>>>) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/feed", listener.local_addr().unwrap());
+    let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let received = requests.clone();
+    tokio::spawn(async move {
+        let mut served: usize = 0;
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                match stream.read(&mut buf).await {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => request.extend_from_slice(&buf[..n]),
+                }
+            }
+            let request = String::from_utf8_lossy(&request).into_owned();
+            let response = handler(served, &request);
+            received.lock().unwrap().push(request);
+            served += 1;
+            stream.write_all(response.as_bytes()).await.ok();
+            stream.shutdown().await.ok();
+        }
+    });
+    (url, requests)
+}
+
+/// Attributes for local test feeds.
+fn local_attributes(name: &str) -> FeedAttributes {
+    FeedAttributes {
+        display_name: Arc::new(name.into()),
+        timeout: Duration::from_days(365 * 100),
+        freq: None,
+        step: 1,
+        tags: std::collections::HashSet::new(),
+        filters: vec![],
+        keep_empty: false,
+        apply_tags: true,
+        headers: BTreeMap::new(),
+        format: None,
+        charset: None,
+        request_timeout: None,
+        titles_only: false,
+        max_body_size: DEFAULT_MAX_BODY_SIZE,
+        full_content: false,
+    }
+}
+
+#[tokio::test]
+async fn injected_clock() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let (url, requests) = mock_server(vec![http_response(
+        "200 OK",
+        &[("Content-Type", "application/rss+xml")],
+        RSS_BODY,
+    )])
+    .await;
+    let clock =
+        ManualClock::new(DateTime::try_from("2025-06-12T00:00:00Z").unwrap());
+    let mut updater = Updater::new(Duration::from_seconds(0), 5);
+    updater.set_clock(Arc::new(clock.clone()));
+    let mut attr = local_attributes("Clocked");
+    attr.freq = Some(Duration::from_hours(1));
+    attr.timeout = Duration::from_days(30);
+    updater.add_feed(StandardSyndication::new(url), attr);
+
+    let entries = updater.update().await;
+    assert_eq!(entries.len(), 2);
+    assert_eq!(requests.lock().unwrap().len(), 1);
+
+    // The feed is not due until the clock moves.
+    let entries = updater.update().await;
+    assert_eq!(entries.len(), 0);
+    assert_eq!(requests.lock().unwrap().len(), 1);
+
+    clock.advance(Duration::from_hours(2));
+    let entries = updater.update().await;
+    assert_eq!(entries.len(), 2);
+    assert_eq!(requests.lock().unwrap().len(), 2);
+
+    // Entries age out as the clock moves past them.
+    clock.set(DateTime::try_from("2025-07-10T12:00:00Z").unwrap());
+    let entries = updater.update().await;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(requests.lock().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn feed_freq() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let (url, requests) = mock_server_with(|_served, _request| {
+        http_response(
+            "200 OK",
+            &[("Content-Type", "application/rss+xml")],
+            RSS_BODY,
+        )
+    })
+    .await;
+    let clock =
+        ManualClock::new(DateTime::try_from("2025-06-12T00:00:00Z").unwrap());
+    let mut updater = Updater::new(Duration::from_seconds(0), 5);
+    updater.set_clock(Arc::new(clock.clone()));
+    updater.set_feed_freq(Some(Duration::from_hours(2)));
+    let mut hourly = local_attributes("Hourly");
+    hourly.freq = Some(Duration::from_hours(1));
+    hourly.timeout = Duration::from_days(30);
+    updater.add_feed(StandardSyndication::new(url.clone()), hourly);
+    let mut default = local_attributes("Default");
+    default.timeout = Duration::from_days(30);
+    updater.add_feed(StandardSyndication::new(url), default);
+
+    updater.update().await;
+    assert_eq!(requests.lock().unwrap().len(), 2);
+
+    // Neither feed is fetched again by an immediate update.
+    updater.update().await;
+    assert_eq!(requests.lock().unwrap().len(), 2);
+
+    // Feeds without a frequency fall back to the updater's.
+    clock.advance(Duration::from_seconds(90 * 60));
+    updater.update().await;
+    assert_eq!(requests.lock().unwrap().len(), 3);
+
+    clock.advance(Duration::from_seconds(40 * 60));
+    updater.update().await;
+    assert_eq!(requests.lock().unwrap().len(), 4);
+}
+
+#[tokio::test]
+async fn failure_backoff() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    // Fail three times, then recover.
+    let (url, requests) = mock_server_with(|served, _request| match served {
+        0..3 => http_response("503 Service Unavailable", &[], ""),
+        _ => http_response(
+            "200 OK",
+            &[("Content-Type", "application/rss+xml")],
+            RSS_BODY,
+        ),
+    })
+    .await;
+    let clock =
+        ManualClock::new(DateTime::try_from("2025-06-12T00:00:00Z").unwrap());
+    let mut updater = Updater::new(Duration::from_seconds(0), 5);
+    updater.set_clock(Arc::new(clock.clone()));
+    updater.set_max_backoff(Duration::from_seconds(200));
+    let mut attr = local_attributes("Flaky");
+    attr.freq = Some(Duration::from_seconds(60));
+    let id = updater.add_feed(StandardSyndication::new(url), attr);
+    let fetches = || requests.lock().unwrap().len();
+
+    updater.update().await;
+    assert_eq!(fetches(), 1);
+
+    // A single failure doesn't delay the next update.
+    clock.advance(Duration::from_seconds(61));
+    updater.update().await;
+    assert_eq!(fetches(), 2);
+
+    // Repeated failures double the interval.
+    clock.advance(Duration::from_seconds(61));
+    updater.update().await;
+    assert_eq!(fetches(), 2);
+    clock.advance(Duration::from_seconds(60));
+    updater.update().await;
+    assert_eq!(fetches(), 3);
+
+    // Up to the maximum backoff.
+    clock.advance(Duration::from_seconds(201));
+    updater.update().await;
+    assert_eq!(fetches(), 4);
+    assert!(updater.result(id).unwrap().is_ok());
+
+    // Success resets the interval.
+    clock.advance(Duration::from_seconds(61));
+    updater.update().await;
+    assert_eq!(fetches(), 5);
+}
+
+#[tokio::test]
+async fn failure_backoff_from_frequency() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let (url, requests) = mock_server_with(|_served, _request| {
+        http_response("503 Service Unavailable", &[], "")
+    })
+    .await;
+    let clock =
+        ManualClock::new(DateTime::try_from("2025-06-12T00:00:00Z").unwrap());
+    let mut updater = Updater::new(Duration::from_seconds(0), 5);
+    updater.set_clock(Arc::new(clock.clone()));
+    let mut attr = local_attributes("Flaky");
+    attr.freq = Some(Duration::from_hours(2));
+    updater.add_feed(StandardSyndication::new(url), attr);
+    let fetches = || requests.lock().unwrap().len();
+
+    updater.update().await;
+    clock.advance(Duration::from_seconds(2 * 60 * 60 + 1));
+    updater.update().await;
+    assert_eq!(fetches(), 2);
+
+    // Feeds updated less often than the maximum backoff still back off.
+    clock.advance(Duration::from_seconds(2 * 60 * 60 + 1));
+    updater.update().await;
+    assert_eq!(fetches(), 2);
+    clock.advance(Duration::from_seconds(2 * 60 * 60));
+    updater.update().await;
+    assert_eq!(fetches(), 3);
+
+    // The interval doesn't drop below the frequency when past the maximum.
+    updater.set_max_backoff(Duration::from_hours(1));
+    clock.advance(Duration::from_seconds(2 * 60 * 60 + 1));
+    updater.update().await;
+    assert_eq!(fetches(), 4);
+}
+
+#[tokio::test]
+async fn retries() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let unavailable = http_response("503 Service Unavailable", &[], "");
+    let ok = http_response(
+        "200 OK",
+        &[("Content-Type", "application/rss+xml")],
+        RSS_BODY,
+    );
+
+    // Fail twice, then succeed within the retry budget.
+    let (url, requests) =
+        mock_server(vec![unavailable.clone(), unavailable.clone(), ok]).await;
+    let mut updater = Updater::new(Duration::from_seconds(1_000), 5);
+    updater.set_retries(2, Duration::from_std(std::time::Duration::ZERO));
+    let id = updater
+        .add_feed(StandardSyndication::new(url), local_attributes("Flaky"));
+    let entries = updater.update().await;
+    assert_eq!(entries.len(), 2);
+    assert_eq!(updater.result(id), Some(&Ok(2)));
+    assert_eq!(requests.lock().unwrap().len(), 3);
+
+    // Client errors are not retried.
+    let (url, requests) =
+        mock_server(vec![http_response("404 Not Found", &[], "")]).await;
+    let mut updater = Updater::new(Duration::from_seconds(1_000), 5);
+    updater.set_retries(2, Duration::from_std(std::time::Duration::ZERO));
+    let id = updater
+        .add_feed(StandardSyndication::new(url), local_attributes("Missing"));
+    updater.update().await;
+    assert!(matches!(
+        updater.result(id),
+        Some(Err(FeedError::Network(_)))
+    ));
+    assert_eq!(requests.lock().unwrap().len(), 1);
+
+    // Rate limits are retried until the budget runs out.
+    let (url, requests) = mock_server(vec![http_response(
+        "429 Too Many Requests",
+        &[("Retry-After", "0")],
+        "",
+    )])
+    .await;
+    let mut updater = Updater::new(Duration::from_seconds(1_000), 5);
+    updater.set_retries(1, Duration::from_std(std::time::Duration::ZERO));
+    let id = updater
+        .add_feed(StandardSyndication::new(url), local_attributes("Limited"));
+    updater.update().await;
+    assert_eq!(updater.result(id), Some(&Err(FeedError::RateLimited)));
+    assert_eq!(requests.lock().unwrap().len(), 2);
+
+    // Long waits are left to the next update.
+    let (url, requests) = mock_server(vec![http_response(
+        "429 Too Many Requests",
+        &[("Retry-After", "86400")],
+        "",
+    )])
+    .await;
+    let mut updater = Updater::new(Duration::from_seconds(1_000), 5);
+    updater.set_retries(1, Duration::from_std(std::time::Duration::ZERO));
+    let id = updater
+        .add_feed(StandardSyndication::new(url), local_attributes("Limited"));
+    tokio::time::timeout(std::time::Duration::from_secs(5), updater.update())
+        .await
+        .unwrap();
+    assert_eq!(updater.result(id), Some(&Err(FeedError::RateLimited)));
+    assert_eq!(requests.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn publication_dates() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let atom_body = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+<title>Local</title>
+<id>urn:local</id>
+<updated>2025-06-20T00:00:00Z</updated>
+<entry>
+<title>Published</title>
+<id>urn:local:published</id>
+<published>2025-06-02T20:00:00+05:30</published>
+<updated>2025-06-20T00:00:00Z</updated>
+</entry>
+<entry>
+<title>Updated</title>
+<id>urn:local:updated</id>
+<updated>2025-06-03T09:00:00-05:00</updated>
+</entry>
+</feed>"#;
+    let rss_body = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+<channel>
+<title>Local</title>
+<link>http://localhost</link>
+<description>Local feed</description>
+<item>
+<title>Eastern</title>
+<pubDate>Mon, 02 Jun 2025 10:30:00 EDT</pubDate>
+</item>
+<item>
+<title>Malformed</title>
+<pubDate>sometime last week</pubDate>
+</item>
+</channel>
+</rss>"#;
+
+    let (atom_url, _) =
+        mock_server(vec![http_response("200 OK", &[], atom_body)]).await;
+    let (rss_url, _) =
+        mock_server(vec![http_response("200 OK", &[], rss_body)]).await;
+    let mut updater = Updater::new(Duration::from_seconds(1_000), 10);
+    updater
+        .add_feed(StandardSyndication::new(atom_url), local_attributes("Atom"));
+    updater
+        .add_feed(StandardSyndication::new(rss_url), local_attributes("Rss"));
+    let before = DateTime::now();
+    let entries = updater.update().await;
+    assert_eq!(entries.len(), 4);
+
+    let date_of = |title: &str| {
+        entries
+            .as_slice()
+            .iter()
+            .find(|entry| entry.title() == title)
+            .map(|entry| entry.date().clone())
+            .unwrap()
+    };
+    let expected = DateTime::try_from("2025-06-02T14:30:00Z").unwrap();
+    assert_eq!(date_of("Published"), expected);
+    assert_eq!(date_of("Eastern"), expected);
+    assert_eq!(
+        date_of("Updated"),
+        DateTime::try_from("2025-06-03T14:00:00Z").unwrap()
+    );
+
+    // Malformed dates fall back to the parse time, but are not dropped.
+    assert!(date_of("Malformed") >= before);
+}
+
+#[tokio::test]
+async fn enclosures() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let atom_body = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+<title>Local</title>
+<id>urn:local</id>
+<updated>2025-06-20T00:00:00Z</updated>
+<entry>
+<title>Episode</title>
+<id>urn:local:episode</id>
+<updated>2025-06-20T00:00:00Z</updated>
+<link rel="enclosure" type="audio/mpeg" length="1024" href="http://localhost/episode.mp3"/>
+<link href="http://localhost/episode"/>
+<link rel="enclosure" type="audio/ogg" href="http://localhost/episode.ogg"/>
+</entry>
+</feed>"#;
+    let rss_body = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:media="http://search.yahoo.com/mrss/">
+<channel>
+<title>Local</title>
+<link>http://localhost</link>
+<description>Local feed</description>
+<item>
+<title>Podcast</title>
+<link>http://localhost/podcast</link>
+<pubDate>Tue, 10 Jun 2025 04:00:00 GMT</pubDate>
+<enclosure url="http://localhost/podcast.mp3" length="2048" type="audio/mpeg"/>
+<media:content url="http://localhost/podcast.mp3" type="audio/mpeg"/>
+<media:group>
+<media:content url="http://localhost/podcast.m4a" fileSize="4096" type="audio/mp4"/>
+<media:content url="http://localhost/podcast.opus" type="audio/opus"/>
+</media:group>
+</item>
+</channel>
+</rss>"#;
+
+    let (atom_url, _) =
+        mock_server(vec![http_response("200 OK", &[], atom_body)]).await;
+    let (rss_url, _) =
+        mock_server(vec![http_response("200 OK", &[], rss_body)]).await;
+    let mut updater = Updater::new(Duration::from_seconds(1_000), 10);
+    updater
+        .add_feed(StandardSyndication::new(atom_url), local_attributes("Atom"));
+    updater
+        .add_feed(StandardSyndication::new(rss_url), local_attributes("Rss"));
+    let entries = updater.update().await;
+    let entry = |title: &str| {
+        entries
+            .as_slice()
+            .iter()
+            .find(|entry| entry.title() == title)
+            .cloned()
+            .unwrap()
+    };
+    let links = |entry: &Entry| {
+        entry
+            .other_links()
+            .iter()
+            .map(|link| (link.url.clone(), link.mime_type.clone(), link.length))
+            .collect::<Vec<_>>()
+    };
+    let link = |url: &str, mime_type: &str, length: Option<u64>| {
+        (url.to_string(), Some(mime_type.to_string()), length)
+    };
+
+    // Atom enclosures don't replace the alternate link as the source.
+    let episode = entry("Episode");
+    assert_eq!(episode.source().url, "http://localhost/episode");
+    assert_eq!(
+        links(&episode),
+        vec![
+            link("http://localhost/episode.mp3", "audio/mpeg", Some(1024)),
+            link("http://localhost/episode.ogg", "audio/ogg", None),
+        ]
+    );
+
+    // Rss enclosures and media content are all kept, once each.
+    let podcast = entry("Podcast");
+    assert_eq!(podcast.source().url, "http://localhost/podcast");
+    assert_eq!(
+        links(&podcast),
+        vec![
+            link("http://localhost/podcast.mp3", "audio/mpeg", Some(2048)),
+            link("http://localhost/podcast.m4a", "audio/mp4", Some(4096)),
+            link("http://localhost/podcast.opus", "audio/opus", None),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn etags() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let (url, requests) = mock_server_with(|_served, request| {
+        if request.to_lowercase().contains("if-none-match: \"v1\"") {
+            http_response("304 Not Modified", &[("ETag", "\"v1\"")], "")
+        } else {
+            http_response("200 OK", &[("ETag", "\"v1\"")], RSS_BODY)
+        }
+    })
+    .await;
+    let mut updater = Updater::new(Duration::from_seconds(0), 5);
+    let id = updater
+        .add_feed(StandardSyndication::new(url), local_attributes("Tagged"));
+
+    let entries = updater.update().await;
+    assert_eq!(entries.len(), 2);
+    assert_eq!(updater.result(id), Some(&Ok(2)));
+
+    // The stored ETag is echoed, so nothing new is parsed.
+    let entries = updater.update().await;
+    assert_eq!(entries.len(), 0);
+    assert_eq!(updater.result(id), Some(&Ok(0)));
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert!(!requests[0].to_lowercase().contains("if-none-match"));
+    assert!(requests[1].to_lowercase().contains("if-none-match: \"v1\""));
+}
+
+#[tokio::test]
+async fn restored_feed_state() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let (url, requests) = mock_server_with(|_served, request| {
+        if request.to_lowercase().contains("if-none-match: \"v1\"") {
+            http_response("304 Not Modified", &[("ETag", "\"v1\"")], "")
+        } else {
+            http_response("200 OK", &[("ETag", "\"v1\"")], RSS_BODY)
+        }
+    })
+    .await;
+
+    // Store the state of a first updater.
+    let mut updater = Updater::new(Duration::from_seconds(0), 5);
+    let id = updater.add_feed(
+        StandardSyndication::new(url.clone()),
+        local_attributes("Tagged"),
+    );
+    updater.update().await;
+    assert_eq!(updater.feed_url(id).await, Some(url.clone()));
+    let state = updater.feed_state(id).await.unwrap();
+    assert_eq!(state.etag.as_deref(), Some("\"v1\""));
+    assert!(state.last_update.is_some());
+
+    // A restarted updater makes a conditional request right away.
+    let mut updater = Updater::new(Duration::from_seconds(0), 5);
+    let id = updater
+        .add_feed(StandardSyndication::new(url), local_attributes("Tagged"));
+    updater.restore_feed_state(id, state.clone()).await;
+    assert_eq!(updater.feed_state(id).await, Some(state));
+    let entries = updater.update().await;
+    assert_eq!(entries.len(), 0);
+    assert_eq!(updater.result(id), Some(&Ok(0)));
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[1].to_lowercase().contains("if-none-match: \"v1\""));
+    assert!(requests[1].to_lowercase().contains("if-modified-since: "));
+}
+
+#[tokio::test]
+async fn carried_over_feed() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let (url, requests) = mock_server_with(|_served, _request| {
+        http_response("200 OK", &[("ETag", "\"v1\"")], RSS_BODY)
+    })
+    .await;
+    let mut attr = local_attributes("Tagged");
+    attr.freq = Some(Duration::from_seconds(3600));
+
+    let mut previous = Updater::new(Duration::from_seconds(0), 5);
+    previous.add_feed(StandardSyndication::new("unused"), attr.clone());
+    let previous_id =
+        previous.add_feed(StandardSyndication::new(url.clone()), attr.clone());
+    previous.update().await;
+    assert_eq!(requests.lock().unwrap().len(), 1);
+
+    // The replacement keeps the feed's schedule and state.
+    let mut updater = Updater::new(Duration::from_seconds(0), 5);
+    let id = updater.add_feed(StandardSyndication::new(url), attr);
+    updater.carry_over_feed(id, &previous, previous_id).await;
+    assert_ne!(id, previous_id);
+    assert_eq!(
+        updater.feed_state(id).await,
+        previous.feed_state(previous_id).await
+    );
+    assert_eq!(updater.result(id), previous.result(previous_id));
+    updater.update().await;
+    assert_eq!(requests.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn entry_identity() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    let hash = |entry: &Entry| {
+        let mut hasher = DefaultHasher::new();
+        entry.hash(&mut hasher);
+        hasher.finish()
+    };
+
+    // Same source id, different everything else.
+    let guid1 = EntryBuilder::new()
+        .title("Original")
+        .source_id("guid-1")
+        .content("First draft")
+        .build();
+    let guid2 = EntryBuilder::new()
+        .title("Edited")
+        .source_id("guid-1")
+        .content("Second draft")
+        .build();
+    assert_eq!(guid1, guid2);
+    assert_eq!(hash(&guid1), hash(&guid2));
+    let guid3 = EntryBuilder::new()
+        .title("Original")
+        .source_id("guid-2")
+        .content("First draft")
+        .build();
+    assert_ne!(guid1, guid3);
+
+    // Same content, different titles.
+    let content1 = EntryBuilder::new().title("A").content("Body").build();
+    let content2 = EntryBuilder::new().title("B").content("Body").build();
+    let content3 = EntryBuilder::new().title("A").content("Other").build();
+    assert_eq!(content1, content2);
+    assert_eq!(hash(&content1), hash(&content2));
+    assert_ne!(content1, content3);
+
+    // Fallback to title, author, and url.
+    let fields1 = EntryBuilder::new()
+        .title("Title")
+        .author("Author")
+        .source("https://example.com/1")
+        .build();
+    let fields2 = EntryBuilder::new()
+        .title("Title")
+        .author("Author")
+        .source("https://example.com/1")
+        .date(DateTime::epoch())
+        .build();
+    let fields3 = EntryBuilder::new()
+        .title("Title")
+        .author("Author")
+        .source("https://example.com/2")
+        .build();
+    assert_eq!(fields1, fields2);
+    assert_eq!(hash(&fields1), hash(&fields2));
+    assert_ne!(fields1, fields3);
+
+    // Entries can be deduplicated in sets.
+    let set: std::collections::HashSet<Entry> = [
+        guid1, guid2, guid3, content1, content2, content3, fields1, fields2,
+        fields3,
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(set.len(), 6);
+}
+
+#[tokio::test]
+async fn json_feeds() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let body = r#"{
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": "Example",
+        "authors": [{ "name": "Feed Author" }],
+        "items": [
+            {
+                "id": "1",
+                "title": "Html",
+                "content_html": "<p><b>Bold</b></p>",
+                "content_text": "Plain",
+                "url": "https://example.com/1",
+                "external_url": "https://elsewhere.com/1",
+                "date_published": "2024-01-02T03:04:05Z",
+                "authors": [{ "name": "Item Author" }],
+                "tags": ["rust"]
+            },
+            {
+                "id": 2,
+                "title": "Text",
+                "content_text": "Plain",
+                "external_url": "https://elsewhere.com/2"
+            }
+        ]
+    }"#;
+    let (url, _requests) = mock_server(vec![http_response(
+        "200 OK",
+        &[("Content-Type", "application/feed+json")],
+        body,
+    )])
+    .await;
+    let mut updater = Updater::new(Duration::from_seconds(0), 5);
+    let json_id =
+        updater.add_feed(JsonFeed::new(url.clone()), local_attributes("Json"));
+    let standard_id = updater
+        .add_feed(StandardSyndication::new(url), local_attributes("Standard"));
+
+    let entries = updater.update().await;
+    assert_eq!(updater.result(json_id), Some(&Ok(2)));
+    // Standard syndications detect json bodies.
+    assert_eq!(updater.result(standard_id), Some(&Ok(2)));
+    assert_eq!(entries.len(), 2);
+
+    let html = entries
+        .as_slice()
+        .iter()
+        .find(|e| e.title() == "Html")
+        .unwrap();
+    assert_eq!(html.content(), "**Bold**");
+    assert_eq!(html.author(), "Item Author");
+    assert_eq!(html.source().url, "https://example.com/1");
+    assert_eq!(html.source_id(), Some("1"));
+    assert_eq!(
+        html.date(),
+        &DateTime::try_from("2024-01-02T03:04:05Z").unwrap()
+    );
+    assert!(
+        html.other_links()
+            .iter()
+            .any(|link| link.url == "https://elsewhere.com/1")
+    );
+    assert!(html.has_tag("rust"));
+
+    // Missing dates fall back to the time the feed was parsed.
+    let text = entries
+        .as_slice()
+        .iter()
+        .find(|e| e.title() == "Text")
+        .unwrap();
+    assert_eq!(text.content(), "Plain");
+    assert_eq!(text.author(), "Feed Author");
+    assert_eq!(text.source().url, "https://elsewhere.com/2");
+    assert_eq!(text.source_id(), Some("2"));
+    assert!(!text.date().has_passed(&Duration::from_seconds(60)));
+}
+
+#[tokio::test]
+async fn youtube_channels() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let id = "UCabcdefghijklmnopqrstuv";
+    let feed_url =
+        format!("https://www.youtube.com/feeds/videos.xml?channel_id={id}");
+    for channel in [
+        id.to_string(),
+        format!("https://www.youtube.com/channel/{id}"),
+        format!("https://youtube.com/channel/{id}/videos"),
+        feed_url.clone(),
+    ] {
+        assert_eq!(YouTubeFeed::feed_url(&channel).as_ref(), Some(&feed_url));
+    }
+    // Handles must be resolved from the channel page.
+    for channel in ["@slipstream", "https://www.youtube.com/@slipstream/videos"]
+    {
+        assert_eq!(YouTubeFeed::feed_url(channel), None);
+        assert_eq!(
+            YouTubeFeed::handle_url(channel).as_deref(),
+            Some("https://www.youtube.com/@slipstream")
+        );
+    }
+    assert_eq!(YouTubeFeed::handle_url("slipstream"), None);
+    let page = format!(
+        r#"<script>{{"channelId":"UCshort","externalId":"{id}"}}</script>"#
+    );
+    assert_eq!(
+        YouTubeFeed::channel_id_from_page(&page).as_deref(),
+        Some(id)
+    );
+    assert_eq!(YouTubeFeed::channel_id_from_page("<html></html>"), None);
+
+    let path = format!(
+        "file://{}/../../test/feeds/youtube.xml",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    let mut updater = Updater::new(Duration::from_seconds(1_000), 5);
+    let feed_id =
+        updater.add_feed(YouTubeFeed::new(path), local_attributes("YouTube"));
+    let entries = updater.update().await;
+    assert_eq!(updater.result(feed_id), Some(&Ok(1)));
+    let video = &entries.as_slice()[0];
+    assert_eq!(video.title(), "Reading feeds offline");
+    assert_eq!(
+        video.source().url,
+        "https://www.youtube.com/watch?v=dQw4w9WgXcQ"
+    );
+    assert_eq!(video.source_id(), Some("dQw4w9WgXcQ"));
+    assert_eq!(
+        video.content(),
+        "![](https://i1.ytimg.com/vi/dQw4w9WgXcQ/hqdefault.jpg)\n\n\
+        How to read feeds without a network."
+    );
+    assert_eq!(
+        video.icon().map(|icon| icon.url.as_str()),
+        Some("https://i1.ytimg.com/vi/dQw4w9WgXcQ/hqdefault.jpg")
+    );
+}
+
+#[tokio::test]
+async fn reddit_listings() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    assert_eq!(
+        RedditFeed::listing_url("rust", RedditSort::default()),
+        "https://www.reddit.com/r/rust/hot.json"
+    );
+    assert_eq!(
+        RedditFeed::listing_url("/r/rust/", RedditSort::Top),
+        "https://www.reddit.com/r/rust/top.json"
+    );
+
+    let body = r#"{
+        "kind": "Listing",
+        "data": {
+            "children": [
+                {
+                    "kind": "t3",
+                    "data": {
+                        "name": "t3_abc",
+                        "title": "Link post",
+                        "author": "ferris",
+                        "selftext": "",
+                        "url": "https://example.com/article",
+                        "permalink": "/r/rust/comments/abc/link_post/",
+                        "created_utc": 1704164645.0,
+                        "thumbnail": "https://example.com/thumb.jpg",
+                        "link_flair_text": "news"
+                    }
+                },
+                {
+                    "kind": "t3",
+                    "data": {
+                        "name": "t3_def",
+                        "title": "Self post",
+                        "author": "ferris",
+                        "selftext": "Some **text**",
+                        "permalink": "/r/rust/comments/def/self_post/",
+                        "thumbnail": "self"
+                    }
+                }
+            ]
+        }
+    }"#;
+    let (url, requests) = mock_server(vec![http_response(
+        "200 OK",
+        &[("Content-Type", "application/json")],
+        body,
+    )])
+    .await;
+    let mut updater = Updater::new(Duration::from_seconds(0), 5);
+    let id = updater.add_feed(
+        RedditFeed::new(&url, RedditSort::New),
+        local_attributes("Reddit"),
+    );
+    let entries = updater.update().await;
+    assert_eq!(updater.result(id), Some(&Ok(2)));
+    // Reddit rate limits generic user agents.
+    assert!(
+        requests.lock().unwrap()[0]
+            .to_lowercase()
+            .contains("user-agent: slipfeed/")
+    );
+
+    let link = entries
+        .as_slice()
+        .iter()
+        .find(|e| e.title() == "Link post")
+        .unwrap();
+    assert_eq!(link.source().url, "https://example.com/article");
+    assert_eq!(
+        link.comments().url,
+        "https://www.reddit.com/r/rust/comments/abc/link_post/"
+    );
+    assert_eq!(link.author(), "ferris");
+    assert_eq!(link.source_id(), Some("t3_abc"));
+    assert_eq!(link.date(), &DateTime::from_unix_timestamp_s(1704164645));
+    assert!(link.has_tag("news"));
+
+    let text = entries
+        .as_slice()
+        .iter()
+        .find(|e| e.title() == "Self post")
+        .unwrap();
+    assert_eq!(text.content(), "Some **text**");
+    assert_eq!(
+        text.source().url,
+        "https://www.reddit.com/r/rust/comments/def/self_post/"
+    );
+    assert!(text.icon().is_none());
+
+    // Html error pages produce no entries.
+    let (url, _requests) = mock_server(vec![http_response(
+        "200 OK",
+        &[("Content-Type", "text/html")],
+        "<html><body>Too many requests</body></html>",
+    )])
+    .await;
+    let mut updater = Updater::new(Duration::from_seconds(0), 5);
+    let id = updater.add_feed(
+        RedditFeed::new(&url, RedditSort::Hot),
+        local_attributes("Reddit"),
+    );
+    assert_eq!(updater.update().await.len(), 0);
+    assert_eq!(updater.result(id), Some(&Ok(0)));
+}
+
+#[tokio::test]
+async fn snapshots() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let (url, _requests) =
+        mock_server(vec![http_response("200 OK", &[], RSS_BODY)]).await;
+    let mut attr = local_attributes("Snapshot");
+    attr.add_tag(Tag::new("saved"));
+    let mut updater = Updater::new(Duration::from_seconds(0), 5);
+    let id = updater.add_feed(StandardSyndication::new(&url), attr.clone());
+    updater.update().await;
+    let snapshot = updater.snapshot();
+    assert_eq!(snapshot.len(), 2);
+
+    // Persist and reload the snapshot.
+    let serialized = serde_json::to_string(&snapshot).unwrap();
+    let snapshot: Vec<Entry> = serde_json::from_str(&serialized).unwrap();
+
+    let mut restored = Updater::new(Duration::from_seconds(0), 5);
+    let restored_id = restored.add_feed(StandardSyndication::new(&url), attr);
+    assert_eq!(id, restored_id);
+    assert_eq!(restored.iter().count(), 0);
+    restored.restore(snapshot);
+
+    let original: Vec<&Entry> = updater.iter().collect();
+    let entries: Vec<&Entry> = restored.iter().collect();
+    assert_eq!(original, entries);
+    for (original, entry) in original.iter().zip(entries.iter()) {
+        assert_eq!(original.title(), entry.title());
+        assert_eq!(original.feeds(), entry.feeds());
+        assert_eq!(original.tags(), entry.tags());
+    }
+    assert_eq!(restored.from_feed(restored_id).count(), 2);
+    assert_eq!(restored.with_tags("saved").count(), 2);
+}
+
+#[tokio::test]
+async fn new_entry_hooks() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let path = format!(
+        "file://{}/../../test/feeds/local.rss",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    let mut updater = Updater::new(Duration::from_seconds(0), 5);
+    // Both feeds read the same entries.
+    updater.add_feed(StandardSyndication::new(&path), local_attributes("A"));
+    updater.add_feed(StandardSyndication::new(&path), local_attributes("B"));
+    let titles = Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+    {
+        let titles = titles.clone();
+        updater.on_new_entry(Box::new(move |entry: &Entry| {
+            titles.lock().unwrap().push(entry.title().clone());
+        }));
+    }
+    // Failing hooks do not stop the update or other hooks.
+    updater.on_new_entry(Box::new(|_entry: &Entry| panic!("hook failed")));
+
+    assert_eq!(updater.update().await.len(), 2);
+    let mut seen = titles.lock().unwrap().clone();
+    seen.sort();
+    assert_eq!(seen, vec!["Fixtures", "Offline reading"]);
+
+    // Entries from previous updates are not new.
+    assert_eq!(updater.update().await.len(), 2);
+    assert_eq!(titles.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn forced_formats() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let (url, _requests) = mock_server(vec![http_response(
+        "200 OK",
+        &[("Content-Type", "text/html")],
+        RSS_BODY,
+    )])
+    .await;
+    let mut updater = Updater::new(Duration::from_seconds(0), 5);
+
+    // A mislabeled feed parses when forced.
+    let mut attr = local_attributes("Forced rss");
+    attr.format = Some(FeedFormat::Rss);
+    let rss_id = updater.add_feed(StandardSyndication::new(&url), attr);
+
+    // Forcing an incompatible format is a parse error.
+    let mut attr = local_attributes("Forced atom");
+    attr.format = Some(FeedFormat::Atom);
+    let atom_id = updater.add_feed(StandardSyndication::new(&url), attr);
+    let mut attr = local_attributes("Forced json");
+    attr.format = Some(FeedFormat::Json);
+    let json_id = updater.add_feed(StandardSyndication::new(&url), attr);
+
+    let entries = updater.update().await;
+    assert_eq!(entries.len(), 2);
+    assert_eq!(updater.result(rss_id), Some(&Ok(2)));
+    assert!(matches!(
+        updater.result(atom_id),
+        Some(Err(FeedError::Parse(_)))
+    ));
+    assert!(matches!(
+        updater.result(json_id),
+        Some(Err(FeedError::Parse(_)))
+    ));
+
+    // A latin-1 file that is not valid utf-8.
+    let path = std::env::temp_dir().join("slipstream-forced-charset.rss");
+    let body = RSS_BODY.replace("First", "Café");
+    let (latin1, _, _) = encoding_rs::WINDOWS_1252.encode(&body);
+    std::fs::write(&path, &latin1).unwrap();
+    let url = format!("file://{}", path.display());
+    let mut updater = Updater::new(Duration::from_seconds(0), 5);
+    let detected_id = updater
+        .add_feed(StandardSyndication::new(&url), local_attributes("Detected"));
+    let mut attr = local_attributes("Forced charset");
+    attr.charset = Some("iso-8859-1".into());
+    let forced_id = updater.add_feed(StandardSyndication::new(&url), attr);
+
+    let entries = updater.update().await;
+    assert!(matches!(
+        updater.result(detected_id),
+        Some(Err(FeedError::Decode(_)))
+    ));
+    assert_eq!(updater.result(forced_id), Some(&Ok(2)));
+    assert!(entries.as_slice().iter().any(|e| e.title() == "Café"));
+    std::fs::remove_file(&path).ok();
+}
+
+/// Feed that tracks how many updates run at once.
+#[derive(Debug)]
+struct SlowFeed {
+    running: Arc<std::sync::atomic::AtomicUsize>,
+    most_running: Arc<std::sync::atomic::AtomicUsize>,
+}
+
+#[feed_trait]
+impl Feed for SlowFeed {
+    async fn update(
+        &mut self,
+        _ctx: &UpdaterContext,
+        _attr: &FeedAttributes,
+    ) -> FeedResult {
+        use std::sync::atomic::Ordering;
+        let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+        self.most_running.fetch_max(running, Ordering::SeqCst);
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        self.running.fetch_sub(1, Ordering::SeqCst);
+        Ok(0)
+    }
+}
+
+#[tokio::test]
+async fn concurrent_updates() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let running = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let most_running = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let mut updater = Updater::new(Duration::from_seconds(0), 5);
+    updater.set_workers(3);
+    for i in 0..8 {
+        updater.add_feed(
+            Box::new(SlowFeed {
+                running: running.clone(),
+                most_running: most_running.clone(),
+            }),
+            local_attributes(&format!("Slow {i}")),
+        );
+    }
+
+    // Feeds in the same step are updated concurrently, up to the number of
+    // workers.
+    updater.update().await;
+    assert_eq!(updater.results().len(), 8);
+    assert_eq!(most_running.load(std::sync::atomic::Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn request_timeouts() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    // Accept connections, but never respond.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let hung_url = format!("http://{}/feed", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let mut connections = Vec::new();
+        while let Ok((stream, _)) = listener.accept().await {
+            connections.push(stream);
+        }
+    });
+    let (url, _requests) =
+        mock_server(vec![http_response("200 OK", &[], RSS_BODY)]).await;
+
+    let mut updater = Updater::new(Duration::from_seconds(1_000), 5);
+    updater.set_request_timeout(None);
+    let mut attr = local_attributes("Hung");
+    attr.request_timeout =
+        Some(Duration::from_std(std::time::Duration::from_millis(100)));
+    let hung_id = updater.add_feed(StandardSyndication::new(hung_url), attr);
+    let ok_id =
+        updater.add_feed(StandardSyndication::new(url), local_attributes("Ok"));
+
+    // The hung feed times out without stopping the other feed.
+    let entries = updater.update().await;
+    assert_eq!(updater.result(hung_id), Some(&Err(FeedError::Timeout)));
+    assert_eq!(updater.result(ok_id), Some(&Ok(2)));
+    assert_eq!(entries.len(), 2);
+}
+
+#[tokio::test]
+async fn titles_only() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let body = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+<channel>
+<title>Local</title>
+<link>http://localhost</link>
+<description>Local feed</description>
+<item>
+<title>Headline</title>
+<link>http://localhost/headline</link>
+<description>A long article body.</description>
+<pubDate>Tue, 10 Jun 2025 04:00:00 GMT</pubDate>
+</item>
+</channel>
+</rss>"#;
+    let (full_url, _) =
+        mock_server(vec![http_response("200 OK", &[], body)]).await;
+    let (titles_url, _) =
+        mock_server(vec![http_response("200 OK", &[], body)]).await;
+    let mut updater = Updater::new(Duration::from_seconds(0), 10);
+    let full_id = updater.add_feed(
+        StandardSyndication::new(&full_url),
+        local_attributes("Full"),
+    );
+    let mut attr = local_attributes("Titles");
+    attr.titles_only = true;
+    let titles_id =
+        updater.add_feed(StandardSyndication::new(&titles_url), attr);
+    updater.update().await;
+
+    let full = updater.from_feed(full_id).next().unwrap();
+    assert_eq!(full.content(), "A long article body.");
+
+    // Titles-only entries keep the title, date, and url.
+    let titles = updater.from_feed(titles_id).next().unwrap();
+    assert_eq!(titles.title(), "Headline");
+    assert_eq!(titles.source().url, "http://localhost/headline");
+    assert_eq!(titles.date(), full.date());
+    assert!(titles.content().is_empty());
+    assert!(titles.other_links().is_empty());
+}
+
+#[test]
+fn language_detection() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    assert_eq!(
+        detect_language("What is new in the latest release of the compiler"),
+        Some("en")
+    );
+    assert_eq!(
+        detect_language(
+            "Les nouvelles fonctionnalités de la version sont dans le blog"
+        ),
+        Some("fr")
+    );
+    assert_eq!(
+        detect_language(
+            "Die neue Version ist auf der Webseite und nicht im Blog"
+        ),
+        Some("de")
+    );
+    assert_eq!(detect_language("Новая версия компилятора"), Some("ru"));
+    assert_eq!(detect_language("東京で新しい駅が開業しました"), Some("ja"));
+    assert_eq!(detect_language("새로운 버전이 출시되었습니다"), Some("ko"));
+    // Too little text to tell.
+    assert_eq!(detect_language("Rust 2.0"), None);
+    assert_eq!(detect_language(""), None);
+
+    assert_eq!(normalize_language("en-US"), "en");
+    assert_eq!(normalize_language(" PT_br "), "pt");
+
+    // Declared languages take precedence over detection.
+    let entry = EntryBuilder::new()
+        .title("What is new in the latest release")
+        .language("fr-CA")
+        .build();
+    assert_eq!(entry.language(), Some("fr"));
+}
+
+#[tokio::test]
+async fn declared_feed_languages() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let atom_body = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xml:lang="de">
+<title>Local</title>
+<id>urn:local</id>
+<updated>2025-06-20T00:00:00Z</updated>
+<entry>
+<title>What is new in the latest release of the compiler</title>
+<id>urn:local:atom</id>
+<updated>2025-06-20T00:00:00Z</updated>
+</entry>
+</feed>"#;
+    let rss_body = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+<channel>
+<title>Local</title>
+<link>http://localhost</link>
+<description>Local feed</description>
+<language>fr-CA</language>
+<item>
+<title>What is new in the latest release of the interpreter</title>
+</item>
+</channel>
+</rss>"#;
+
+    let (atom_url, _) =
+        mock_server(vec![http_response("200 OK", &[], atom_body)]).await;
+    let (rss_url, _) =
+        mock_server(vec![http_response("200 OK", &[], rss_body)]).await;
+    let mut updater = Updater::new(Duration::from_seconds(1_000), 10);
+    updater
+        .add_feed(StandardSyndication::new(atom_url), local_attributes("Atom"));
+    updater
+        .add_feed(StandardSyndication::new(rss_url), local_attributes("Rss"));
+    let entries = updater.update().await;
+    assert_eq!(entries.len(), 2);
+
+    // The channel's language wins over detection, which would guess english.
+    let language_of = |title: &str| {
+        entries
+            .as_slice()
+            .iter()
+            .find(|entry| entry.title().ends_with(title))
+            .and_then(|entry| entry.language().map(str::to_string))
+    };
+    assert_eq!(language_of("compiler").as_deref(), Some("de"));
+    assert_eq!(language_of("interpreter").as_deref(), Some("fr"));
+}
+
+#[tokio::test]
+async fn body_size_limit() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let (url, _requests) =
+        mock_server(vec![http_response("200 OK", &[], RSS_BODY)]).await;
+    let (small_url, _requests) =
+        mock_server(vec![http_response("200 OK", &[], RSS_BODY)]).await;
+    // Bodies without a length are cut off while reading.
+    let (chunked_url, _requests) = mock_server(vec![format!(
+        "HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n{RSS_BODY}"
+    )])
+    .await;
+
+    let mut updater = Updater::new(Duration::from_seconds(1_000), 5);
+    let ok_id =
+        updater.add_feed(StandardSyndication::new(url), local_attributes("Ok"));
+    let mut attr = local_attributes("Small");
+    attr.max_body_size = 64;
+    let small_id =
+        updater.add_feed(StandardSyndication::new(small_url), attr.clone());
+    let chunked_id =
+        updater.add_feed(StandardSyndication::new(chunked_url), attr);
+
+    let entries = updater.update().await;
+    assert_eq!(updater.result(ok_id), Some(&Ok(2)));
+    assert_eq!(
+        updater.result(small_id),
+        Some(&Err(FeedError::TooLarge(64)))
+    );
+    assert_eq!(
+        updater.result(chunked_id),
+        Some(&Err(FeedError::TooLarge(64)))
+    );
+    assert_eq!(entries.len(), 2);
+}
+
+#[tokio::test]
+async fn shared_client() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let (url, requests) =
+        mock_server_with(|_, request| match request.starts_with("GET /api/") {
+            true => http_response("200 OK", &[], "[]"),
+            false => http_response("200 OK", &[], RSS_BODY),
+        })
+        .await;
+    let instance = url.trim_end_matches("/feed");
+
+    let mut shared = reqwest::header::HeaderMap::new();
+    shared.insert("x-shared", "yes".parse().unwrap());
+    let mut updater = Updater::new(Duration::from_seconds(0), 10);
+    updater.set_client(
+        reqwest::Client::builder()
+            .default_headers(shared)
+            .build()
+            .unwrap(),
+    );
+    let mut attr = local_attributes("Home");
+    attr.headers
+        .insert("user-agent".into(), "home-agent".into());
+    let home = updater.add_feed(
+        MastodonFeed::new(
+            instance,
+            MastodonFeedType::HomeTimeline,
+            Some("secret".into()),
+        ),
+        attr,
+    );
+    let standard = updater
+        .add_feed(StandardSyndication::new(&url), local_attributes("Rss"));
+    updater.update().await;
+    assert_eq!(updater.result(home), Some(&Ok(0)));
+    assert_eq!(updater.result(standard), Some(&Ok(2)));
+
+    // Both feeds use the shared client, with their own headers on top.
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert!(requests.iter().all(|r| r.contains("x-shared: yes")));
+    let home = requests
+        .iter()
+        .find(|r| r.starts_with("GET /api/v1/timelines/home"))
+        .unwrap();
+    assert!(home.contains("authorization: Bearer secret"));
+    assert!(home.contains("user-agent: home-agent"));
+    let standard = requests
+        .iter()
+        .find(|r| r.starts_with("GET /feed"))
+        .unwrap();
+    assert!(!standard.contains("authorization"));
+}
+
+#[tokio::test]
+async fn full_content() {
+    tracing_subscriber::fmt::try_init().ok();
+
+    let paragraph = "The compiler now checks every crate in parallel, which \
+        cuts build times for large workspaces. This release also stabilizes \
+        several long awaited language features, improves diagnostics for \
+        common mistakes, and ships a faster linker by default on most \
+        platforms. ";
+    let article = format!(
+        "<html><head><title>Release</title></head><body>\
+        <nav><a href=\"/\">Home</a></nav>\
+        <article><h1>Release</h1><p>{}</p><p>{}</p></article>\
+        <footer>Copyright</footer></body></html>",
+        paragraph.repeat(3),
+        paragraph.repeat(2)
+    );
+    let (url, requests) = mock_server_with(move |_, request| {
+        let host = request
+            .lines()
+            .find_map(|line| line.strip_prefix("host: "))
+            .unwrap_or_default()
+            .to_string();
+        if request.starts_with("GET /article ") {
+            return http_response("200 OK", &[], &article);
+        }
+        if request.starts_with("GET /missing ") {
+            return http_response("404 Not Found", &[], "");
+        }
+        let feed = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+<channel>
+<title>Local</title>
+<link>http://{host}</link>
+<description>Local feed</description>
+<item>
+<title>Full</title>
+<link>http://{host}/article</link>
+<description>A summary.</description>
+<pubDate>Tue, 10 Jun 2025 04:00:00 GMT</pubDate>
+</item>
+<item>
+<title>Missing</title>
+<link>http://{host}/missing</link>
+<description>Another summary.</description>
+<pubDate>Tue, 10 Jun 2025 05:00:00 GMT</pubDate>
+</item>
+</channel>
+</rss>"#
+        );
+        http_response("200 OK", &[], &feed)
+    })
+    .await;
+
+    let clock =
+        ManualClock::new(DateTime::try_from("2025-06-12T00:00:00Z").unwrap());
+    let mut updater = Updater::new(Duration::from_seconds(0), 10);
+    updater.set_clock(Arc::new(clock.clone()));
+    let mut attr = local_attributes("Full");
+    attr.full_content = true;
+    updater.add_feed(StandardSyndication::new(&url), attr);
+    let content = |entries: &EntrySet, title: &str| {
+        entries
+            .as_slice()
+            .iter()
+            .find(|e| e.title() == title)
+            .unwrap()
+            .content()
+            .clone()
+    };
+
+    // Articles replace summaries, unless they can't be fetched.
+    let entries = updater.update().await;
+    let full = content(&entries, "Full");
+    assert!(
+        full.contains("The compiler now checks every crate"),
+        "{full}"
+    );
+    assert!(!full.contains("A summary."));
+    assert!(!full.contains("Copyright"));
+    assert_eq!(content(&entries, "Missing"), "Another summary.");
+    assert_eq!(requests.lock().unwrap().len(), 3);
+
+    // Articles are only fetched once.
+    let entries = updater.update().await;
+    assert_eq!(content(&entries, "Full"), full);
+    assert_eq!(requests.lock().unwrap().len(), 4);
+
+    // Failed articles are retried after a while.
+    clock.advance(Duration::from_hours(2));
+    let entries = updater.update().await;
+    assert_eq!(content(&entries, "Full"), full);
+    assert_eq!(content(&entries, "Missing"), "Another summary.");
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 6);
+    assert!(requests[5].starts_with("GET /missing "));
+}
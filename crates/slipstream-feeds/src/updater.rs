@@ -1,9 +1,16 @@
 //! Feed update handling.
 
+use super::full_content::*;
 use super::*;
 
 pub type BoxedFeed = Arc<RwLock<Box<dyn Feed>>>;
 
+/// Hook called with each new entry.
+pub type EntryHook = Box<dyn FnMut(&Entry) + Send>;
+
+/// Number of entry identities remembered to find new entries.
+const SEEN_LIMIT: usize = 100_000;
+
 /// Information the updater keeps about the feed.
 #[derive(Clone)]
 struct FeedInfo {
@@ -11,6 +18,11 @@ struct FeedInfo {
     feed: BoxedFeed,
     attr: FeedAttributes,
     last_update: Option<DateTime>,
+    /// Last successful update from before the updater started, used for
+    /// conditional requests until the feed is updated again.
+    restored_update: Option<DateTime>,
+    /// Consecutive failed updates.
+    failures: u32,
 }
 
 /// Object passed to feeds on update.
@@ -23,6 +35,14 @@ pub struct UpdaterContext {
     pub parse_time: DateTime,
     /// The last time the feed had been updated.
     pub last_update: Option<DateTime>,
+    /// Number of times to retry transient fetch failures.
+    pub retries: usize,
+    /// Delay between retries.
+    pub retry_delay: Duration,
+    /// Client shared by all feeds.
+    pub client: reqwest::Client,
+    /// Timeout for each request made by the feed.
+    pub request_timeout: Option<Duration>,
     /// A sender for parsed entries.
     pub sender: tokio::sync::mpsc::UnboundedSender<(Entry, FeedRef)>,
 }
@@ -38,12 +58,39 @@ pub struct Updater {
     last_update_check: Option<DateTime>,
     /// Update frequency.
     freq: Duration,
+    /// Update frequency of feeds without their own.
+    feed_freq: Option<Duration>,
+    /// Longest interval failing feeds are backed off to, unless their
+    /// frequency is longer.
+    max_backoff: Duration,
     /// Number of feeds to update/fetch at a time.
     workers: usize,
+    /// Number of times to retry transient fetch failures.
+    retries: usize,
+    /// Delay between retries.
+    retry_delay: Duration,
+    /// Client shared by all feeds, so connections are reused.
+    client: reqwest::Client,
+    /// Default timeout for requests, when feeds have none.
+    request_timeout: Option<Duration>,
     /// Current entries.
     entries: EntrySet,
+    /// Result of each feed's latest update.
+    results: BTreeMap<FeedId, FeedResult>,
+    /// When each feed was last updated successfully.
+    last_successes: BTreeMap<FeedId, DateTime>,
+    /// How long the latest update took.
+    last_update_duration: Option<Duration>,
     /// Next feed id.
     next_feed_id: usize,
+    /// Hooks called with each new entry.
+    hooks: std::sync::Mutex<Vec<EntryHook>>,
+    /// Identities of entries that have been seen, oldest first.
+    seen: SeenEntries,
+    /// Full articles extracted for feeds that want them.
+    full_content: FullContent,
+    /// Source of the current time.
+    clock: Arc<dyn Clock>,
 }
 
 impl Updater {
@@ -54,9 +101,22 @@ impl Updater {
             transforms: Vec::new(),
             last_update_check: None,
             freq,
+            feed_freq: None,
+            max_backoff: Duration::from_days(1),
             workers: 8,
+            retries: 0,
+            retry_delay: Duration::from_seconds(1),
+            client: reqwest::Client::new(),
+            request_timeout: Some(Duration::from_seconds(30)),
             entries: EntrySet::new(maximum),
+            results: BTreeMap::new(),
+            last_successes: BTreeMap::new(),
+            last_update_duration: None,
             next_feed_id: 1,
+            hooks: std::sync::Mutex::new(Vec::new()),
+            seen: SeenEntries::default(),
+            full_content: FullContent::default(),
+            clock: Arc::new(SystemClock),
         }
     }
 
@@ -65,6 +125,38 @@ impl Updater {
         self.workers = workers;
     }
 
+    /// Set the update frequency of feeds without their own.
+    pub fn set_feed_freq(&mut self, freq: Option<Duration>) {
+        self.feed_freq = freq;
+    }
+
+    /// Set the longest interval failing feeds are backed off to.
+    pub fn set_max_backoff(&mut self, max_backoff: Duration) {
+        self.max_backoff = max_backoff;
+    }
+
+    /// Set the number of retries for transient fetch failures.
+    pub fn set_retries(&mut self, retries: usize, retry_delay: Duration) {
+        self.retries = retries;
+        self.retry_delay = retry_delay;
+    }
+
+    /// Set the default request timeout, used by feeds without one.
+    pub fn set_request_timeout(&mut self, timeout: Option<Duration>) {
+        self.request_timeout = timeout;
+    }
+
+    /// Set the client shared by all feeds.
+    /// Feed headers and timeouts are set on each request, on top of it.
+    pub fn set_client(&mut self, client: reqwest::Client) {
+        self.client = client;
+    }
+
+    /// Set the clock used for update cadence and entry age.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
     /// Add a feed.
     pub fn add_feed(
         &mut self,
@@ -80,6 +172,8 @@ impl Updater {
                 feed: Arc::new(RwLock::new(feed)),
                 attr,
                 last_update: None,
+                restored_update: None,
+                failures: 0,
             },
         );
         feed_id
@@ -90,21 +184,32 @@ impl Updater {
         self.transforms.push(transform);
     }
 
+    /// Add a hook that is called with each new entry during updates.
+    /// Entries are new the first time they are seen by the updater, so
+    /// duplicates and entries from previous updates are skipped.
+    pub fn on_new_entry(&mut self, hook: EntryHook) {
+        if let Ok(hooks) = self.hooks.get_mut() {
+            hooks.push(hook);
+        }
+    }
+
     /// Update feeds.
     /// This is _not_ cancel-safe.
     pub async fn update(&mut self) -> EntrySet {
         let span = tracing::trace_span!("slipfeed::update");
         let _enter = span.enter();
-        let now = DateTime::now();
 
         // Wait until time to update.
         match &self.last_update_check {
             Some(last_time) => {
                 let next_time = last_time.clone() + self.freq.clone();
-                tokio::time::sleep_until(next_time.to_tokio()).await;
+                let wait = next_time - self.clock.now();
+                tokio::time::sleep(wait.to_std()).await;
             }
             None => {}
         };
+        let now = self.clock.now();
+        let started = std::time::Instant::now();
 
         // Perform updates.
         self.last_update_check = Some(now.clone());
@@ -124,9 +229,9 @@ impl Updater {
                 .filter(|(_id, feed_info)| {
                     // Check update time.
                     if let (Some(last_update), Some(freq)) =
-                        (&feed_info.last_update, &feed_info.attr.freq)
+                        (&feed_info.last_update, self.feed_interval(feed_info))
                     {
-                        if !last_update.has_passed(freq) {
+                        if !last_update.has_passed_at(&freq, &now) {
                             tracing::debug!(
                                 "Skipping feed {} (last updated at {}).",
                                 feed_info.attr.display_name,
@@ -168,34 +273,95 @@ impl Updater {
                         let ctx = UpdaterContext {
                             feed_id: id.clone(),
                             parse_time: now.clone(),
-                            last_update: feed_info.last_update.clone(),
+                            last_update: feed_info
+                                .last_update
+                                .clone()
+                                .or_else(|| feed_info.restored_update.clone()),
+                            retries: self.retries,
+                            retry_delay: self.retry_delay.clone(),
+                            client: self.client.clone(),
+                            request_timeout: feed_info
+                                .attr
+                                .request_timeout
+                                .clone()
+                                .or_else(|| self.request_timeout.clone()),
                             sender: tx.clone(),
                         };
 
                         async move {
                             let mut feed = feed.write().await;
-                            if let Err(_) = tokio::time::timeout(
+                            let result = match tokio::time::timeout(
                                 feed_info.attr.timeout.to_tokio(),
                                 feed.update(&ctx, &feed_info.attr),
                             )
                             .await
                             {
+                                Ok(result) => result,
+                                Err(_) => {
+                                    tracing::warn!(
+                                        feed = %feed_info.attr.display_name,
+                                        url = feed.url(),
+                                        "Update timed out"
+                                    );
+                                    Err(FeedError::Timeout)
+                                }
+                            };
+                            if let Err(e) = &result {
                                 tracing::warn!(
-                                    "Update timed out for {:?}",
-                                    feed
+                                    feed = %feed_info.attr.display_name,
+                                    url = feed.url(),
+                                    error = %e,
+                                    "Failed to update"
                                 );
                             }
+                            (id, result)
                         }
                     })
                     .buffer_unordered(self.workers);
 
                 // Wait for all updates.
                 tracing::info!("Gathering entries: step={}", step);
-                while let Some(_) = updates.next().await {}
+                let mut outcomes = Vec::new();
+                while let Some((id, result)) = updates.next().await {
+                    outcomes.push((id, result.is_ok()));
+                    if result.is_ok() {
+                        self.last_successes.insert(id, now.clone());
+                    }
+                    self.results.insert(id, result);
+                }
+                drop(updates);
+                for (id, success) in outcomes {
+                    self.count_failures(id, success);
+                }
+
+                // Gather entries, and fetch full articles for feeds that
+                // want them.
+                let mut received = Vec::new();
+                while let Ok(entry) = rx.try_recv() {
+                    received.push(entry);
+                }
+                let requests = self.article_requests(&received);
+                self.full_content
+                    .fetch(&self.client, requests, &self.clock.now())
+                    .await;
 
-                // Gather entries, tag, and transform.
+                // Tag and transform.
                 tracing::debug!("Applying tags: step={}", step);
-                while let Ok((mut entry, feed)) = rx.try_recv() {
+                for (mut entry, feed) in received {
+                    let attr = self.feeds.get(&feed.id).map(|f| &f.attr);
+                    if attr.is_some_and(|attr| attr.full_content) {
+                        if let Some(content) =
+                            self.full_content.get(&entry.source().url)
+                        {
+                            entry.set_content(content.clone());
+                        }
+                    }
+
+                    // Discard content from titles-only feeds.
+                    if attr.is_some_and(|attr| attr.titles_only) {
+                        entry.strip_content();
+                    }
+
                     // Add original feed.
                     entry.add_feed(feed);
 
@@ -211,6 +377,9 @@ impl Updater {
                         .iter()
                         .for_each(|transform| transform(&mut entry));
 
+                    if self.seen.insert(&entry) {
+                        Updater::run_hooks(&mut self.hooks, &entry);
+                    }
                     self.entries.add(entry);
                 }
             }
@@ -226,10 +395,56 @@ impl Updater {
             self.entries.len(),
             total_feeds_updated
         );
+        self.last_update_duration = Some(Duration::from_std(started.elapsed()));
 
         self.entries.clone()
     }
 
+    /// Get the articles to fetch for entries of feeds that want full content.
+    fn article_requests(
+        &self,
+        entries: &[(Entry, FeedRef)],
+    ) -> Vec<ArticleRequest> {
+        entries
+            .iter()
+            .filter_map(|(entry, feed)| {
+                let attr = &self.feeds.get(&feed.id)?.attr;
+                let url = &entry.source().url;
+                let is_web =
+                    url.starts_with("http://") || url.starts_with("https://");
+                if !attr.full_content || attr.titles_only || !is_web {
+                    return None;
+                }
+                Some(ArticleRequest {
+                    url: url.clone(),
+                    headers: headers(attr),
+                    timeout: attr
+                        .request_timeout
+                        .clone()
+                        .or_else(|| self.request_timeout.clone()),
+                    max_body_size: attr.max_body_size,
+                })
+            })
+            .collect()
+    }
+
+    /// Run the new entry hooks.
+    /// Panicking hooks are logged so the update can continue.
+    fn run_hooks(hooks: &mut std::sync::Mutex<Vec<EntryHook>>, entry: &Entry) {
+        let Ok(hooks) = hooks.get_mut() else {
+            return;
+        };
+        for hook in hooks.iter_mut() {
+            let res =
+                std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    hook(entry)
+                }));
+            if res.is_err() {
+                tracing::warn!("New entry hook failed for {}", entry.title());
+            }
+        }
+    }
+
     /// Iterate all entries.
     pub fn iter<'a>(&'a self) -> EntrySetIter<'a> {
         return EntrySetIter::All {
@@ -256,6 +471,145 @@ impl Updater {
         };
     }
 
+    /// Snapshot the current entries.
+    /// Feed references and tags are kept, so this can be persisted and
+    /// restored later.
+    pub fn snapshot(&self) -> Vec<Entry> {
+        self.entries.as_slice().to_vec()
+    }
+
+    /// Restore entries from a snapshot.
+    /// Feeds must be added in the same order as when the snapshot was taken
+    /// for feed ids to match.
+    pub fn restore(&mut self, entries: Vec<Entry>) {
+        for entry in entries {
+            self.seen.insert(&entry);
+            self.entries.add(entry);
+        }
+        self.entries.sort();
+    }
+
+    /// Get the result of each feed's latest update.
+    pub fn results(&self) -> &BTreeMap<FeedId, FeedResult> {
+        &self.results
+    }
+
+    /// Get the result of a feed's latest update.
+    pub fn result(&self, feed: FeedId) -> Option<&FeedResult> {
+        self.results.get(&feed)
+    }
+
+    /// Get when each feed was last updated successfully.
+    pub fn last_successes(&self) -> &BTreeMap<FeedId, DateTime> {
+        &self.last_successes
+    }
+
+    /// Get how long the latest update took.
+    pub fn last_update_duration(&self) -> Option<&Duration> {
+        self.last_update_duration.as_ref()
+    }
+
+    /// Get the url a feed is fetched from, if it has one.
+    pub async fn feed_url(&self, feed: FeedId) -> Option<String> {
+        let feed_info = self.feeds.get(&feed)?;
+        feed_info.feed.read().await.url().map(String::from)
+    }
+
+    /// Get the state kept about a feed between updates.
+    pub async fn feed_state(&self, feed: FeedId) -> Option<FeedState> {
+        let feed_info = self.feeds.get(&feed)?;
+        Some(FeedState {
+            last_update: self.last_successes.get(&feed).cloned(),
+            etag: feed_info.feed.read().await.etag().map(String::from),
+        })
+    }
+
+    /// Restore the state kept about a feed, such as one stored before a
+    /// restart, so its next fetch is conditional.
+    pub async fn restore_feed_state(&mut self, feed: FeedId, state: FeedState) {
+        let Some(feed_info) = self.feeds.get_mut(&feed) else {
+            return;
+        };
+        if let Some(last_update) = &state.last_update {
+            self.last_successes.insert(feed, last_update.clone());
+        }
+        feed_info.restored_update = state.last_update;
+        feed_info.feed.write().await.set_etag(state.etag);
+    }
+
+    /// Carry over the state kept about a feed from another updater, such as
+    /// one being replaced, so the feed keeps its update schedule.
+    pub async fn carry_over_feed(
+        &mut self,
+        feed: FeedId,
+        previous: &Updater,
+        previous_feed: FeedId,
+    ) {
+        let (Some(feed_info), Some(previous_info)) = (
+            self.feeds.get_mut(&feed),
+            previous.feeds.get(&previous_feed),
+        ) else {
+            return;
+        };
+        feed_info.last_update = previous_info.last_update.clone();
+        feed_info.restored_update = previous_info.restored_update.clone();
+        feed_info.failures = previous_info.failures;
+        let etag = previous_info.feed.read().await.etag().map(String::from);
+        feed_info.feed.write().await.set_etag(etag);
+        if let Some(last_success) = previous.last_successes.get(&previous_feed)
+        {
+            self.last_successes.insert(feed, last_success.clone());
+        }
+        if let Some(result) = previous.results.get(&previous_feed) {
+            self.results.insert(feed, result.clone());
+        }
+    }
+
+    /// How long to wait between updates of a feed.
+    /// Feeds that fail more than once in a row back off exponentially from
+    /// their frequency, so a single failure doesn't delay the next update.
+    fn feed_interval(&self, feed_info: &FeedInfo) -> Option<Duration> {
+        let freq = feed_info.attr.freq.as_ref().or(self.feed_freq.as_ref());
+        if feed_info.failures < 2 {
+            return freq.cloned();
+        }
+        let freq = freq.unwrap_or(&self.freq).to_chrono();
+        let backoff = freq * 2i32.pow((feed_info.failures - 1).min(16));
+        let max_backoff = self.max_backoff.to_chrono().max(freq);
+        Some(Duration::from_chrono(backoff.min(max_backoff)))
+    }
+
+    /// Count consecutive failures of a feed.
+    fn count_failures(&mut self, feed: FeedId, success: bool) {
+        let Some(feed_info) = self.feeds.get_mut(&feed) else {
+            return;
+        };
+        let failures = feed_info.failures;
+        feed_info.failures = match success {
+            true => 0,
+            false => failures + 1,
+        };
+        let Some(feed_info) = self.feeds.get(&feed) else {
+            return;
+        };
+        if success && failures > 0 {
+            tracing::debug!(
+                "Feed {} recovered after {} failures.",
+                feed_info.attr.display_name,
+                failures
+            );
+        } else if !success && feed_info.failures > 1 {
+            if let Some(interval) = self.feed_interval(feed_info) {
+                tracing::debug!(
+                    "Backing off feed {} to every {}s after {} failures.",
+                    feed_info.attr.display_name,
+                    interval.to_std().as_secs(),
+                    feed_info.failures
+                );
+            }
+        }
+    }
+
     /// Get a feed from the id.
     pub fn get_feed(&mut self, feed: FeedId) -> Option<&mut BoxedFeed> {
         if let Some(feed) = self.feeds.get_mut(&feed) {
@@ -272,10 +626,23 @@ impl Default for Updater {
             feeds: BTreeMap::default(),
             transforms: Vec::new(),
             workers: 8,
+            retries: 0,
+            retry_delay: Duration::from_seconds(1),
+            client: reqwest::Client::new(),
+            request_timeout: Some(Duration::from_seconds(30)),
             last_update_check: None,
             freq: Duration::from_seconds(10),
+            feed_freq: None,
+            max_backoff: Duration::from_days(1),
             entries: EntrySet::new(1_000),
+            results: BTreeMap::new(),
+            last_successes: BTreeMap::new(),
+            last_update_duration: None,
             next_feed_id: 0,
+            hooks: std::sync::Mutex::new(Vec::new()),
+            seen: SeenEntries::default(),
+            full_content: FullContent::default(),
+            clock: Arc::new(SystemClock),
         }
     }
 }
@@ -298,3 +665,27 @@ impl SteppedFeeds {
         }
     }
 }
+
+/// Identities of seen entries, limited to the most recent.
+#[derive(Default)]
+struct SeenEntries {
+    set: HashSet<u64>,
+    order: std::collections::VecDeque<u64>,
+}
+
+impl SeenEntries {
+    /// Mark an entry as seen, returning true if it had not been seen.
+    fn insert(&mut self, entry: &Entry) -> bool {
+        let identity = entry.identity_hash();
+        if !self.set.insert(identity) {
+            return false;
+        }
+        self.order.push_back(identity);
+        if self.order.len() > SEEN_LIMIT {
+            if let Some(oldest) = self.order.pop_front() {
+                self.set.remove(&oldest);
+            }
+        }
+        true
+    }
+}